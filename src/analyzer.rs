@@ -1,123 +1,377 @@
+use std::collections::HashMap;
 use std::path::Path;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
 use crate::engines::{
-    ArchiveEngine, DockerfileEngine, EngineState, EnvEngine, GitIgnoreEngine, HexEngine,
+    compression_for_ext, decompressor, is_requirements_file, modeline_language, tar_compression, ArchiveEngine, DiffEngine, DockerfileEngine, EngineState, EnvEngine, GitIgnoreEngine, HexEngine,
     HtmlEngine, ImageEngine, IniEngine, JsonlEngine, LockEngine, LogEngine, LogicEngine,
     MakefileEngine, SqliteEngine, SyntaxEngine, TableEngine, TextEngine, TreeEngine, XmlEngine,
 };
 
-pub fn analyze(path: &Path) -> Result<EngineState> {
+/// Bytes sniffed for parquet magic and binary detection
+const HEAD_LEN: usize = 8192;
+
+/// Largest decompressed size a single compressed file is opened at
+const MAX_DECOMPRESSED: u64 = 256 * 1024 * 1024;
+
+/// Header every Avro object container file starts with
+const AVRO_MAGIC: &[u8; 4] = b"Obj\x01";
+
+/// Header every SQLite 3 database file starts with
+const SQLITE_MAGIC: &[u8; 16] = b"SQLite format 3\0";
+
+/// Engine chosen for a file
+#[derive(Clone, Copy)]
+enum Kind {
+    Text,
+    Table,
+    Jsonl,
+    Tree,
+    Xml,
+    Sqlite,
+    Archive,
+    Image,
+    Ini,
+    Dockerfile,
+    Makefile,
+    Log,
+    Diff,
+    GitIgnore,
+    Logic,
+    Lock,
+    Env,
+    Html,
+    Syntax,
+    Hex,
+}
+
+impl Kind {
+    /// Canonical engine names, listed when `--engine` gets one it doesn't know
+    const NAMES: &'static [&'static str] = &[
+        "text", "table", "jsonl", "tree", "xml", "sqlite", "archive", "image", "ini", "dockerfile",
+        "makefile", "log", "diff", "gitignore", "logic", "lock", "env", "html", "syntax", "hex",
+    ];
+
+    /// Engine names accepted in config overrides and `--engine`
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name.to_lowercase().as_str() {
+            "text" => Kind::Text,
+            "table" | "csv" => Kind::Table,
+            "jsonl" => Kind::Jsonl,
+            "tree" | "json" | "yaml" | "toml" => Kind::Tree,
+            "xml" => Kind::Xml,
+            "sqlite" => Kind::Sqlite,
+            "archive" => Kind::Archive,
+            "image" => Kind::Image,
+            "ini" => Kind::Ini,
+            "dockerfile" => Kind::Dockerfile,
+            "makefile" => Kind::Makefile,
+            "log" => Kind::Log,
+            "diff" => Kind::Diff,
+            "gitignore" => Kind::GitIgnore,
+            "logic" => Kind::Logic,
+            "lock" => Kind::Lock,
+            "env" => Kind::Env,
+            "html" => Kind::Html,
+            "syntax" | "code" => Kind::Syntax,
+            "hex" => Kind::Hex,
+            _ => return None,
+        })
+    }
+}
+
+/// Pick and build the engine for a file. `overrides` maps an extension or file
+/// name to an engine name (see `Kind::from_name`) and wins over detection.
+pub fn analyze(path: &Path, overrides: &HashMap<String, String>) -> Result<EngineState> {
+    let kind = match override_kind(path, overrides)? {
+        Some(kind) => kind,
+        None => {
+            if let Some(engine) = analyze_compressed(path)? {
+                return Ok(engine);
+            }
+            detect(path, &read_head(path))
+        }
+    };
+    build(kind, path)
+}
+
+/// Build the named engine (see `Kind::from_name`) for a file, skipping detection
+pub fn analyze_as(path: &Path, name: &str) -> Result<EngineState> {
+    let kind = Kind::from_name(name).ok_or_else(|| {
+        anyhow!("Unknown engine \"{}\" (expected one of: {})", name, Kind::NAMES.join(", "))
+    })?;
+    build(kind, path)
+}
+
+fn build(kind: Kind, path: &Path) -> Result<EngineState> {
+    Ok(match kind {
+        Kind::Text => EngineState::new(TextEngine::from_path(path)?),
+        Kind::Table => EngineState::new(TableEngine::from_path(path)?),
+        Kind::Jsonl => EngineState::new(JsonlEngine::from_path(path)?),
+        Kind::Tree => EngineState::new(TreeEngine::from_path(path)?),
+        Kind::Xml => EngineState::new(XmlEngine::from_path(path)?),
+        Kind::Sqlite => EngineState::new(SqliteEngine::from_path(path)?),
+        Kind::Archive => EngineState::new(ArchiveEngine::from_path(path)?),
+        Kind::Image => EngineState::new(ImageEngine::from_path(path)?),
+        Kind::Ini => EngineState::new(IniEngine::from_path(path)?),
+        Kind::Dockerfile => EngineState::new(DockerfileEngine::from_path(path)?),
+        Kind::Makefile => EngineState::new(MakefileEngine::from_path(path)?),
+        Kind::Log => EngineState::new(LogEngine::from_path(path)?),
+        Kind::Diff => EngineState::new(DiffEngine::from_path(path)?),
+        Kind::GitIgnore => EngineState::new(GitIgnoreEngine::from_path(path)?),
+        Kind::Logic => EngineState::new(LogicEngine::from_path(path)?),
+        Kind::Lock => EngineState::new(LockEngine::from_path(path)?),
+        Kind::Env => EngineState::new(EnvEngine::from_path(path)?),
+        Kind::Html => EngineState::new(HtmlEngine::from_path(path)?),
+        Kind::Syntax => EngineState::new(SyntaxEngine::from_path(path)?),
+        Kind::Hex => EngineState::new(HexEngine::from_path(path)?),
+    })
+}
+
+/// Open a single compressed file like `data.json.gz` in the engine for what it
+/// decompresses to; None for anything else, including compressed tarballs
+fn analyze_compressed(path: &Path) -> Result<Option<EngineState>> {
+    use std::io::Read;
+
+    let file_name = path.file_name().and_then(|s| s.to_str()).unwrap_or("");
+    let Some((inner, ext)) = file_name.rsplit_once('.') else {
+        return Ok(None);
+    };
+    let Some(compression) = compression_for_ext(ext) else {
+        return Ok(None);
+    };
+    if inner.is_empty() || tar_compression(file_name).is_some() {
+        return Ok(None);
+    }
+
+    let file = std::fs::File::open(path)?;
+    let mut bytes = Vec::new();
+    decompressor(compression, std::io::BufReader::new(file))?
+        .take(MAX_DECOMPRESSED + 1)
+        .read_to_end(&mut bytes)
+        .map_err(|e| anyhow!("{}: {}", path.display(), e))?;
+    if bytes.len() as u64 > MAX_DECOMPRESSED {
+        return Err(anyhow!(
+            "{}: decompresses to more than {}MB",
+            path.display(),
+            MAX_DECOMPRESSED / (1024 * 1024)
+        ));
+    }
+    analyze_bytes(Path::new(inner), &bytes).map(Some)
+}
+
+/// Pick and build an engine from in-memory bytes, without touching the filesystem.
+/// `path` names the content (`data.json`, `Cargo.lock`, `makefile`) for detection
+/// and display; it is never read.
+pub fn analyze_bytes(path: &Path, bytes: &[u8]) -> Result<EngineState> {
+    let head = &bytes[..bytes.len().min(HEAD_LEN)];
+    Ok(match detect(path, head) {
+        Kind::Text => EngineState::new(TextEngine::from_bytes(path, bytes)?),
+        Kind::Table => EngineState::new(TableEngine::from_bytes(path, bytes)?),
+        Kind::Jsonl => EngineState::new(JsonlEngine::from_bytes(path, bytes)?),
+        Kind::Tree => EngineState::new(TreeEngine::from_bytes(path, bytes)?),
+        Kind::Xml => EngineState::new(XmlEngine::from_bytes(path, bytes)?),
+        Kind::Sqlite => EngineState::new(SqliteEngine::from_bytes(path, bytes)?),
+        Kind::Archive => EngineState::new(ArchiveEngine::from_bytes(path, bytes)?),
+        Kind::Image => EngineState::new(ImageEngine::from_bytes(path, bytes)?),
+        Kind::Ini => EngineState::new(IniEngine::from_bytes(path, bytes)?),
+        Kind::Dockerfile => EngineState::new(DockerfileEngine::from_bytes(path, bytes)?),
+        Kind::Makefile => EngineState::new(MakefileEngine::from_bytes(path, bytes)?),
+        Kind::Log => EngineState::new(LogEngine::from_bytes(path, bytes)?),
+        Kind::Diff => EngineState::new(DiffEngine::from_bytes(path, bytes)?),
+        Kind::GitIgnore => EngineState::new(GitIgnoreEngine::from_bytes(path, bytes)?),
+        Kind::Logic => EngineState::new(LogicEngine::from_bytes(path, bytes)?),
+        Kind::Lock => EngineState::new(LockEngine::from_bytes(path, bytes)?),
+        Kind::Env => EngineState::new(EnvEngine::from_bytes(path, bytes)?),
+        Kind::Html => EngineState::new(HtmlEngine::from_bytes(path, bytes)?),
+        Kind::Syntax => EngineState::new(SyntaxEngine::from_bytes(path, bytes)?),
+        Kind::Hex => EngineState::new(HexEngine::from_bytes(path, bytes)?),
+    })
+}
+
+/// Look up a user override: a key matches the whole file name or its
+/// trailing extension(s), and the longest matching key wins
+fn override_kind(path: &Path, overrides: &HashMap<String, String>) -> Result<Option<Kind>> {
+    let file_name = path.file_name().and_then(|s| s.to_str()).unwrap_or("");
+    let matched = overrides
+        .iter()
+        .filter(|(key, _)| {
+            let key = key.trim_start_matches('.');
+            file_name == key || file_name.ends_with(&format!(".{}", key))
+        })
+        .max_by_key(|(key, _)| key.len());
+    match matched {
+        Some((key, name)) => Kind::from_name(name)
+            .map(Some)
+            .ok_or_else(|| anyhow!("Unknown engine \"{}\" configured for \"{}\"", name, key)),
+        None => Ok(None),
+    }
+}
+
+/// Pick an engine from the file name and the first bytes of content
+fn detect(path: &Path, head: &[u8]) -> Kind {
     let file_name = path.file_name().and_then(|s| s.to_str()).unwrap_or("");
     let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("").to_lowercase();
 
-    // Check for parquet magic bytes (need to read first 4 bytes)
-    if ext == "parquet" || is_parquet_file(path) {
-        return TableEngine::from_path(path).map(EngineState::Table);
+    // Empty files have nothing to parse; the text view shows a placeholder.
+    // A zero-byte SQLite file is still a valid (empty) database.
+    if head.is_empty() && !matches!(ext.as_str(), "db" | "sqlite" | "sqlite3") {
+        return Kind::Text;
+    }
+
+    // Check for parquet magic bytes
+    if ext == "parquet" || head.starts_with(b"PAR1") {
+        return Kind::Table;
+    }
+
+    // Avro object container files and ORC files, by extension or magic
+    let orc_magic = head.starts_with(b"ORC") && is_binary(head);
+    if matches!(ext.as_str(), "avro" | "orc") || head.starts_with(AVRO_MAGIC) || orc_magic {
+        return Kind::Table;
+    }
+
+    // SQLite databases are often named without .db, so trust the header over the name
+    if head.starts_with(SQLITE_MAGIC) {
+        return Kind::Sqlite;
     }
 
     if matches!(ext.as_str(), "csv" | "tsv") {
-        return TableEngine::from_path(path).map(EngineState::Table);
+        return Kind::Table;
+    }
+
+    // HAR (HTTP Archive) network logs - one table row per request
+    if ext == "har" {
+        return Kind::Table;
     }
 
     // JSONL / NDJSON (JSON Lines) - each line is a separate JSON object
     if matches!(ext.as_str(), "jsonl" | "ndjson") {
-        return JsonlEngine::from_path(path).map(EngineState::Jsonl);
+        return Kind::Jsonl;
+    }
+
+    // pyproject.toml gets the dependency table when it declares any; other tool config stays a tree
+    if file_name == "pyproject.toml" && declares_dependencies(head) {
+        return Kind::Lock;
     }
 
     // Structured data formats - uses mmap + size checking
-    if matches!(ext.as_str(), "json" | "yaml" | "yml" | "toml" | "kdl") {
-        return TreeEngine::from_path(path).map(EngineState::Tree);
+    if matches!(ext.as_str(), "json" | "jsonc" | "json5" | "yaml" | "yml" | "toml" | "kdl") {
+        return Kind::Tree;
     }
 
     // XML files
     if ext == "xml" {
-        return XmlEngine::from_path(path).map(EngineState::Xml);
+        return Kind::Xml;
     }
 
     // SQLite database files
     if matches!(ext.as_str(), "db" | "sqlite" | "sqlite3") {
-        return SqliteEngine::from_path(path).map(EngineState::Sqlite);
+        return Kind::Sqlite;
     }
 
     // Archive files
-    if matches!(ext.as_str(), "zip" | "tar" | "tgz") || file_name.ends_with(".tar.gz") {
-        return ArchiveEngine::from_path(path).map(EngineState::Archive);
+    if matches!(ext.as_str(), "zip" | "tar") || tar_compression(file_name).is_some() {
+        return Kind::Archive;
     }
 
-    // Image files
-    if matches!(ext.as_str(), "jpg" | "jpeg" | "png" | "gif" | "webp" | "bmp" | "ico") {
-        return ImageEngine::from_path(path).map(EngineState::Image);
+    // Image files; SVGs also offer their element tree
+    if matches!(ext.as_str(), "jpg" | "jpeg" | "png" | "gif" | "webp" | "bmp" | "ico" | "svg") {
+        return Kind::Image;
     }
 
-    // INI/Properties config files
-    if matches!(ext.as_str(), "ini" | "cfg" | "properties" | "conf") {
-        return IniEngine::from_path(path).map(EngineState::Ini);
+    // INI/Properties config files; .editorconfig gets its properties checked
+    if matches!(ext.as_str(), "ini" | "cfg" | "properties" | "conf") || file_name == ".editorconfig" {
+        return Kind::Ini;
     }
 
     // Dockerfile
     if file_name == "Dockerfile" || file_name.starts_with("Dockerfile.") {
-        return DockerfileEngine::from_path(path).map(EngineState::Dockerfile);
+        return Kind::Dockerfile;
     }
 
     // Makefile
     if file_name == "Makefile" || file_name == "makefile" || file_name == "GNUmakefile" || ext == "mk" {
-        return MakefileEngine::from_path(path).map(EngineState::Makefile);
+        return Kind::Makefile;
     }
 
-    // Log files
-    if ext == "log" {
-        return LogEngine::from_path(path).map(EngineState::Log);
+    // Log files, including rotated copies (decompressed first when they're `.gz`/`.bz2`/`.zst`)
+    if is_log_file(file_name, &ext) {
+        return Kind::Log;
+    }
+
+    // Unified diffs / patches
+    if matches!(ext.as_str(), "diff" | "patch") {
+        return Kind::Diff;
     }
 
     // GitIgnore and similar
     if file_name == ".gitignore" || file_name == ".dockerignore" || file_name == ".npmignore" {
-        return GitIgnoreEngine::from_path(path).map(EngineState::GitIgnore);
+        return Kind::GitIgnore;
     }
 
     if is_logic_file(path, file_name) {
-        return LogicEngine::from_path(path).map(EngineState::Logic);
+        return Kind::Logic;
     }
 
     if is_lock_file(path, file_name) {
-        return LockEngine::from_path(path).map(EngineState::Lock);
+        return Kind::Lock;
     }
 
     // .env files and similar environment configs
     if is_env_file(file_name, &ext) {
-        return EnvEngine::from_path(path).map(EngineState::Env);
+        return Kind::Env;
     }
 
     if matches!(ext.as_str(), "html" | "htm") {
-        return HtmlEngine::from_path(path).map(EngineState::Html);
+        return Kind::Html;
     }
 
     if is_code_ext(&ext) {
-        return SyntaxEngine::from_path(path).map(EngineState::Syntax);
+        return Kind::Syntax;
     }
 
     // Check if binary file - fallback to hex viewer
-    if is_binary_file(path) {
-        return HexEngine::from_path(path).map(EngineState::Hex);
+    if is_binary(head) {
+        return Kind::Hex;
+    }
+
+    // Scripts without a known extension: any shebang, or a modeline naming the
+    // language. The code view picks the grammar from the interpreter itself.
+    if head.starts_with(b"#!") || modeline_language(&String::from_utf8_lossy(head)).is_some() {
+        return Kind::Syntax;
     }
 
-    TextEngine::from_path(path).map(EngineState::Text)
+    Kind::Text
 }
 
-fn is_parquet_file(path: &Path) -> bool {
-    use std::fs::File;
+/// Read the first bytes of a file for content sniffing (empty if unreadable)
+fn read_head(path: &Path) -> Vec<u8> {
     use std::io::Read;
 
-    let mut file = match File::open(path) {
-        Ok(f) => f,
-        Err(_) => return false,
+    let mut head = Vec::with_capacity(HEAD_LEN);
+    if let Ok(file) = std::fs::File::open(path) {
+        let _ = file.take(HEAD_LEN as u64).read_to_end(&mut head);
+    }
+    head
+}
+
+/// `app.log`, rotated copies like `app.log.1` or `app.log.2024-01-01`, and the
+/// system logs named without an extension (`syslog`, `messages.2`)
+fn is_log_file(file_name: &str, ext: &str) -> bool {
+    if ext == "log" {
+        return true;
+    }
+    let rotation = |suffix: &str| {
+        suffix
+            .split('.')
+            .all(|part| !part.is_empty() && part.chars().all(|c| c.is_ascii_digit() || c == '-'))
     };
-    let mut magic = [0u8; 4];
-    if file.read_exact(&mut magic).is_err() {
-        return false;
+    if let Some((stem, suffix)) = file_name.split_once(".log.") {
+        return !stem.is_empty() && rotation(suffix);
     }
-    &magic == b"PAR1"
+    let (base, suffix) = file_name.split_once('.').unwrap_or((file_name, ""));
+    matches!(base, "syslog" | "messages" | "dmesg") && (suffix.is_empty() || rotation(suffix))
 }
 
 fn is_logic_file(path: &Path, file_name: &str) -> bool {
@@ -143,6 +397,17 @@ fn is_lock_file(_path: &Path, file_name: &str) -> bool {
         || file_name == "package-lock.json"
         || file_name == "pnpm-lock.yaml"
         || file_name == "pnpm-lock.yml"
+        || file_name == "Pipfile"
+        || file_name == "Pipfile.lock"
+        || is_requirements_file(file_name)
+}
+
+/// A `dependencies` key or a Poetry dependency table near the top of a pyproject.toml
+fn declares_dependencies(head: &[u8]) -> bool {
+    String::from_utf8_lossy(head).lines().map(str::trim).any(|line| {
+        line.starts_with("dependencies")
+            || (line.starts_with("[tool.poetry") && line.ends_with("dependencies]"))
+    })
 }
 
 fn is_env_file(file_name: &str, ext: &str) -> bool {
@@ -159,26 +424,12 @@ fn is_code_ext(ext: &str) -> bool {
     )
 }
 
-fn is_binary_file(path: &Path) -> bool {
-    use std::fs::File;
-    use std::io::Read;
-
-    let mut file = match File::open(path) {
-        Ok(f) => f,
-        Err(_) => return false,
-    };
-
-    let mut buffer = [0u8; 8192];
-    let bytes_read = match file.read(&mut buffer) {
-        Ok(n) => n,
-        Err(_) => return false,
-    };
-
+fn is_binary(head: &[u8]) -> bool {
     // Check for null bytes or high proportion of non-printable characters
     let mut null_count = 0;
     let mut non_text_count = 0;
 
-    for &byte in &buffer[..bytes_read] {
+    for &byte in head {
         if byte == 0 {
             null_count += 1;
         }
@@ -194,7 +445,7 @@ fn is_binary_file(path: &Path) -> bool {
     }
 
     // If more than 30% non-text characters, likely binary
-    if bytes_read > 0 && non_text_count * 100 / bytes_read > 30 {
+    if !head.is_empty() && non_text_count * 100 / head.len() > 30 {
         return true;
     }
 
@@ -204,6 +455,7 @@ fn is_binary_file(path: &Path) -> bool {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
     use std::fs;
     use std::path::PathBuf;
 
@@ -232,16 +484,16 @@ mod tests {
     #[test]
     fn detects_tree_engine() {
         let path = write_temp_file("data.json", r#"{"a": 1}"#);
-        let engine = analyze(&path).unwrap();
-        assert!(matches!(engine, EngineState::Tree(_)));
+        let engine = analyze(&path, &HashMap::new()).unwrap();
+        assert_eq!(engine.name(), "TreeEngine");
         let _ = fs::remove_file(path);
     }
 
     #[test]
     fn detects_table_engine() {
         let path = write_temp_file("data.csv", "a,b\n1,2\n");
-        let engine = analyze(&path).unwrap();
-        assert!(matches!(engine, EngineState::Table(_)));
+        let engine = analyze(&path, &HashMap::new()).unwrap();
+        assert_eq!(engine.name(), "TableEngine");
         let _ = fs::remove_file(path);
     }
 
@@ -257,8 +509,116 @@ mod tests {
         fs::create_dir_all(&ssh_dir).unwrap();
         let path = ssh_dir.join("config");
         fs::write(&path, "Host example.com\n").unwrap();
-        let engine = analyze(&path).unwrap();
-        assert!(matches!(engine, EngineState::Logic(_)));
+        let engine = analyze(&path, &HashMap::new()).unwrap();
+        assert_eq!(engine.name(), "LogicEngine");
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn analyze_bytes_routes_in_memory_content() {
+        let log = analyze_bytes(Path::new("app.log"), b"2024-01-01 10:00:00 ERROR boom\n").unwrap();
+        assert_eq!(log.name(), "LogEngine");
+        let xml = analyze_bytes(Path::new("data.xml"), b"<root><a>1</a></root>").unwrap();
+        assert_eq!(xml.name(), "XmlEngine");
+
+        let svg = br#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 64 32"><rect/><path/></svg>"#;
+        let mut svg = analyze_bytes(Path::new("logo.svg"), svg).unwrap();
+        assert_eq!(svg.name(), "ImageEngine");
+        assert!(svg.breadcrumbs().contains("64x32"));
+        svg.handle_key(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::NONE));
+        assert_eq!(svg.name(), "XmlEngine");
+        assert_eq!(svg.content_height(), 3);
+
+        let csv = analyze_bytes(Path::new("data.csv"), b"a,b\n1,2\n").unwrap();
+        assert_eq!(csv.name(), "TableEngine");
+        let lock = analyze_bytes(Path::new("Cargo.lock"), b"[[package]]\nname = \"a\"\nversion = \"1.0.0\"\n").unwrap();
+        assert_eq!(lock.name(), "LockEngine");
+        let requirements = analyze_bytes(Path::new("requirements.txt"), b"requests==2.31.0\n").unwrap();
+        assert_eq!(requirements.name(), "LockEngine");
+        let pyproject = analyze_bytes(Path::new("pyproject.toml"), b"[tool.ruff]\nline-length = 100\n").unwrap();
+        assert_eq!(pyproject.name(), "TreeEngine");
+        let editorconfig = analyze_bytes(Path::new(".editorconfig"), b"root = true\n[*]\nindent_style = tab\n").unwrap();
+        assert_eq!(editorconfig.name(), "IniEngine");
+        let docker = analyze_bytes(Path::new("Dockerfile"), b"FROM rust\n").unwrap();
+        assert_eq!(docker.name(), "DockerfileEngine");
+        // Lowercase, extensionless names are file names too
+        let make = analyze_bytes(Path::new("makefile"), b"all:\n\techo hi\n").unwrap();
+        assert_eq!(make.name(), "MakefileEngine");
+        let binary = analyze_bytes(Path::new("blob.bin"), &[0u8, 1, 2, 3]).unwrap();
+        assert_eq!(binary.name(), "HexEngine");
+        let script = analyze_bytes(Path::new("Deploy"), b"#!/bin/bash\necho hi\n").unwrap();
+        assert_eq!(script.name(), "SyntaxEngine");
+        let env_script = analyze_bytes(Path::new("serve"), b"#!/usr/bin/env python3\nprint(1)\n").unwrap();
+        assert_eq!(env_script.name(), "SyntaxEngine");
+        // Interpreters the grammar table doesn't know are still scripts
+        let unknown = analyze_bytes(Path::new("run"), b"#!/opt/bin/fish\necho hi\n").unwrap();
+        assert_eq!(unknown.name(), "SyntaxEngine");
+    }
+
+    #[test]
+    fn detects_sqlite_by_magic_bytes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data");
+        let conn = rusqlite::Connection::open(&path).unwrap();
+        conn.execute_batch("CREATE TABLE t (id INTEGER);").unwrap();
+        drop(conn);
+        let engine = analyze(&path, &HashMap::new()).unwrap();
+        assert_eq!(engine.name(), "SqliteEngine");
+    }
+
+    #[test]
+    fn compressed_files_open_as_their_contents() {
+        use std::io::Write;
+
+        let dir = tempfile::tempdir().unwrap();
+        let json = dir.path().join("data.json.gz");
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(br#"{"a": 1}"#).unwrap();
+        fs::write(&json, encoder.finish().unwrap()).unwrap();
+        let engine = analyze(&json, &HashMap::new()).unwrap();
+        assert_eq!(engine.name(), "TreeEngine");
+
+        let log = dir.path().join("app.log.zst");
+        fs::write(&log, zstd::encode_all(&b"2024-01-01 10:00:00 INFO up\n"[..], 0).unwrap()).unwrap();
+        let engine = analyze(&log, &HashMap::new()).unwrap();
+        assert_eq!(engine.name(), "LogEngine");
+
+        // Rotated logs keep a numbered suffix before the compression extension
+        for name in ["app.log.1.gz", "syslog.2.gz"] {
+            let rotated = dir.path().join(name);
+            let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(b"Jan  1 10:00:00 host sshd[42]: error: bad login\n").unwrap();
+            fs::write(&rotated, encoder.finish().unwrap()).unwrap();
+            let engine = analyze(&rotated, &HashMap::new()).unwrap();
+            assert_eq!(engine.name(), "LogEngine", "{}", name);
+        }
+        assert!(!is_log_file("release.1.2", "2"));
+
+        assert_eq!(tar_compression("backup.tar.xz"), Some("xz"));
+        assert_eq!(tar_compression("backup.tbz2"), Some("bz2"));
+        assert_eq!(tar_compression("data.json.gz"), None);
+    }
+
+    #[test]
+    fn overrides_win_over_detection() {
+        let path = write_temp_file("app.conf", "[server]\nport = 80\n");
+        let overrides = HashMap::from([("conf".to_string(), "text".to_string())]);
+        let engine = analyze(&path, &overrides).unwrap();
+        assert_eq!(engine.name(), "TextEngine");
+
+        let overrides = HashMap::from([("conf".to_string(), "nope".to_string())]);
+        assert!(analyze(&path, &overrides).is_err());
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn analyze_as_skips_detection() {
+        let path = write_temp_file("data.json", r#"{"a": 1}"#);
+        let engine = analyze_as(&path, "syntax").unwrap();
+        assert_eq!(engine.name(), "SyntaxEngine");
+
+        let err = analyze_as(&path, "nope").err().unwrap().to_string();
+        assert!(err.contains("syntax") && err.contains("hex"));
         let _ = fs::remove_file(path);
     }
 }
@@ -1,6 +1,9 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
 use std::fs;
 use std::io::{self, IsTerminal, Write};
 use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, TryRecvError};
 use std::time::Duration;
 
 use anyhow::Result;
@@ -12,13 +15,17 @@ use crossterm::style::{
 use crossterm::terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen};
 use crossterm::{execute, terminal};
 use ratatui::backend::CrosstermBackend;
-use ratatui::layout::{Constraint, Direction, Layout};
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::{Style, Stylize};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph};
 use ratatui::Terminal;
 
-use crate::engines::EngineState;
+use crate::engines::{self, EngineState, ListPicker, PickerItem, PickerKey, ScrollAlign, TextLayout};
+use crate::state::State;
+
+/// Most bytes of streamed stdin appended per event-loop tick
+const STREAM_BATCH: usize = 1024 * 1024;
 
 struct InputState {
     active: bool,
@@ -27,6 +34,13 @@ struct InputState {
     is_filter: bool,
 }
 
+/// Recent files are listed most recent first, which breaks ties
+impl PickerItem for PathBuf {
+    fn label(&self) -> Cow<'_, str> {
+        Cow::Owned(display_path(self))
+    }
+}
+
 pub struct App {
     engine: EngineState,
     should_quit: bool,
@@ -36,16 +50,55 @@ pub struct App {
     file_path: String,
     /// Actual file path for raw mode (may differ from display path for stdin)
     source_path: PathBuf,
+    /// Raw-mode output for views with no file behind them (a generated diff)
+    raw_content: Option<Vec<u8>>,
+    /// Configured `gy` comment delimiters by extension or file name
+    comments: HashMap<String, String>,
     paging: Paging,
     force_raw: bool,
+    /// Page even when stdout isn't a terminal (`--force-tui`)
+    force_tui: bool,
+    /// Rendering width when stdout is not a TTY; formatted output instead of raw when set
+    width: Option<u16>,
+    /// File to write the formatted view to instead of showing it (`--output`)
+    output: Option<PathBuf>,
+    /// Draw the outer border, header and status footer (`--no-chrome`, `Ctrl+t`)
+    chrome: bool,
+    /// Wrap and tab width handed to the engine (`--wrap`, `--tabs`, `w`)
+    layout: TextLayout,
+    /// Lines still arriving on stdin, appended to the engine as they come in
+    stream: Option<Receiver<Vec<u8>>>,
+    /// Extra navigation bindings layered on top of the vim keys
+    keys: KeyPreset,
     /// Active filter query (shows only matching lines)
     filter: Option<String>,
     /// Show help overlay
     show_help: bool,
     /// Visual line mode: stores the starting selection index
     visual_start: Option<usize>,
+    /// Visual block mode: the column the block is anchored at (None when line-wise)
+    visual_block: Option<usize>,
+    /// Byte size of the visual selection, cached per (start, end) range
+    visual_bytes: Option<((usize, usize), usize)>,
     /// Track if 'y' was pressed (for 'yy' detection)
     pending_y: bool,
+    /// Track if 'g' was pressed (for 'gy' detection); the key still reaches
+    /// the engine so 'gg' keeps working
+    pending_g: bool,
+    /// Track if 'z' was pressed (for 'zz'/'zt'/'zb' and 'zh'/'zl')
+    pending_z: bool,
+    /// Columns the engine was last drawn in, for clamping horizontal scroll
+    view_width: usize,
+    /// Set when state changed and the next loop iteration should redraw
+    dirty: bool,
+    /// Open `Ctrl+o` recent-files picker
+    recent: Option<ListPicker<PathBuf>>,
+    /// The viewer was opened just to pick a recent file (`--recent`); closing the picker quits
+    recent_only: bool,
+    /// File picked from the recent list; the viewer quits so the caller can open it
+    reopen: Option<PathBuf>,
+    /// Set once `run` opens the interactive viewer, as opposed to printing the file
+    interactive: bool,
 }
 
 impl App {
@@ -55,6 +108,7 @@ impl App {
         source_path: PathBuf,
         paging: Paging,
         force_raw: bool,
+        keys: KeyPreset,
     ) -> Self {
         Self {
             engine,
@@ -67,19 +121,120 @@ impl App {
             status: None,
             file_path,
             source_path,
+            raw_content: None,
+            comments: HashMap::new(),
             paging,
             force_raw,
+            force_tui: false,
+            width: None,
+            output: None,
+            chrome: true,
+            layout: TextLayout::default(),
+            stream: None,
+            keys,
             filter: None,
             show_help: false,
             visual_start: None,
+            visual_block: None,
+            visual_bytes: None,
             pending_y: false,
+            pending_g: false,
+            pending_z: false,
+            view_width: 0,
+            dirty: true,
+            recent: None,
+            recent_only: false,
+            reopen: None,
+            interactive: false,
         }
     }
 
+    pub fn with_width(mut self, width: Option<u16>) -> Self {
+        self.width = width;
+        self
+    }
+
+    pub fn with_output(mut self, output: Option<PathBuf>) -> Self {
+        self.output = output;
+        self
+    }
+
+    pub fn with_chrome(mut self, chrome: bool) -> Self {
+        self.chrome = chrome;
+        self
+    }
+
+    pub fn with_layout(mut self, layout: TextLayout) -> Self {
+        self.layout = layout;
+        self.engine.set_layout(layout);
+        self
+    }
+
+    pub fn with_force_tui(mut self, force_tui: bool) -> Self {
+        self.force_tui = force_tui;
+        self
+    }
+
+    pub fn with_raw_content(mut self, content: Vec<u8>) -> Self {
+        self.raw_content = Some(content);
+        self
+    }
+
+    pub fn with_comments(mut self, comments: HashMap<String, String>) -> Self {
+        self.comments = comments;
+        self
+    }
+
+    pub fn with_stream(mut self, stream: Receiver<Vec<u8>>) -> Self {
+        self.stream = Some(stream);
+        self
+    }
+
+    /// Start with the recent-files picker open over an empty view, for `--recent`
+    pub fn with_recent_picker(mut self, files: Vec<PathBuf>) -> Self {
+        self.recent = Some(ListPicker::new(files));
+        self.recent_only = true;
+        self
+    }
+
+    /// The file picked from the recent list, when the viewer quit to open it
+    pub fn reopen(&self) -> Option<&Path> {
+        self.reopen.as_deref()
+    }
+
+    /// The engine as the session left it, e.g. to remember its folds
+    pub fn engine(&self) -> &EngineState {
+        &self.engine
+    }
+
+    /// Whether `run` opened the interactive viewer rather than printing or writing the file
+    pub fn interactive(&self) -> bool {
+        self.interactive
+    }
+
     pub fn run(&mut self) -> Result<()> {
-        // When stdout is piped (not a TTY) or --plain flag is set, output raw content
+        if let Some(output) = self.output.clone() {
+            // Terminal width when there is one to match, else a common default
+            let cols = self.width.or_else(|| terminal::size().ok().map(|(cols, _)| cols)).unwrap_or(80);
+            return self.write_plain_to(&output, cols);
+        }
+
+        if self.force_tui {
+            return self.run_tui();
+        }
+
+        // When stdout is piped (not a TTY) or --plain flag is set, output raw content,
+        // unless a width was given to format for
         if self.force_raw || !io::stdout().is_terminal() {
-            return self.run_raw();
+            return match self.width {
+                Some(cols) => self.run_plain(cols),
+                None => self.run_raw(),
+            };
+        }
+
+        // Streamed input has no end to measure yet
+        if self.stream.is_some() {
+            return self.run_tui();
         }
 
         let (cols, rows) = terminal::size()?;
@@ -88,11 +243,13 @@ impl App {
             Paging::Never => return self.run_plain(cols),
             Paging::Auto => {}
         }
-        let content_height = self.engine.content_height();
         let inner_width = cols.saturating_sub(2) as usize;
         let header_lines = self.plain_header_lines(inner_width).len();
-        let total_lines = content_height + header_lines + 2;
-        if total_lines <= rows as usize {
+        let content_rows = (rows as usize).saturating_sub(header_lines + 2);
+        // Wrapped lines take more than one row each, so count rows once the line count fits
+        if self.engine.fits_height(content_rows)
+            && (!self.layout.wrap || self.plain_content(inner_width).len() <= content_rows)
+        {
             return self.run_plain(cols);
         }
         self.run_tui()
@@ -101,10 +258,10 @@ impl App {
     /// Output raw file content without any formatting (for piping)
     /// Uses streaming to handle arbitrarily large files efficiently
     fn run_raw(&self) -> Result<()> {
-        let mut file = fs::File::open(&self.source_path)?;
         let mut stdout = io::stdout().lock();
+        let copied = self.copy_raw(&mut stdout);
         // Ignore broken pipe errors (e.g., when piping to head/tail)
-        if let Err(e) = io::copy(&mut file, &mut stdout) {
+        if let Err(e) = copied {
             if e.kind() != io::ErrorKind::BrokenPipe {
                 return Err(e.into());
             }
@@ -114,15 +271,53 @@ impl App {
     }
 
     fn run_plain(&mut self, cols: u16) -> Result<()> {
-        let inner_width = cols.saturating_sub(2) as usize;
-        let mut lines = self.plain_header_lines(inner_width);
-        lines.extend(self.engine.render_plain_lines(inner_width as u16));
-        let boxed = box_lines(lines, inner_width);
+        let boxed = self.boxed_lines(cols);
         write_plain(boxed)?;
         Ok(())
     }
 
+    /// The file (or the generated `raw_content`) unchanged
+    fn copy_raw(&self, out: &mut impl Write) -> io::Result<()> {
+        match &self.raw_content {
+            Some(content) => out.write_all(content),
+            None => io::copy(&mut fs::File::open(&self.source_path)?, out).map(|_| ()),
+        }
+    }
+
+    /// Write the boxed view to `path`, without colors since it isn't a terminal
+    fn write_plain_to(&mut self, path: &Path, cols: u16) -> Result<()> {
+        let boxed = self.boxed_lines(cols);
+        let mut file = io::BufWriter::new(fs::File::create(path)?);
+        for line in boxed {
+            for span in line.spans {
+                write!(file, "{}", span.content)?;
+            }
+            writeln!(file)?;
+        }
+        file.flush()?;
+        Ok(())
+    }
+
+    /// Header and content in a `cols`-wide box, as plain mode prints them
+    fn boxed_lines(&mut self, cols: u16) -> Vec<Line<'static>> {
+        let inner_width = cols.saturating_sub(2) as usize;
+        let mut lines = self.plain_header_lines(inner_width);
+        lines.extend(self.plain_content(inner_width));
+        box_lines(lines, inner_width)
+    }
+
+    /// Engine output for plain mode, split into `inner_width` rows when wrapping is on
+    /// (otherwise `box_lines` cuts long lines off)
+    fn plain_content(&mut self, inner_width: usize) -> Vec<Line<'static>> {
+        let lines = self.engine.render_plain_lines(inner_width as u16);
+        if !self.layout.wrap {
+            return lines;
+        }
+        lines.into_iter().flat_map(|line| engines::wrap_line(line, inner_width, 0)).collect()
+    }
+
     fn run_tui(&mut self) -> Result<()> {
+        self.interactive = true;
         enable_raw_mode()?;
         let mut stdout = io::stdout();
         execute!(stdout, EnterAlternateScreen)?;
@@ -135,12 +330,57 @@ impl App {
         res
     }
 
+    /// Hand the engine whatever stdin produced since the last tick, in one batch
+    /// so filters and level counts are refreshed once
+    fn drain_stream(&mut self) {
+        let Some(stream) = &self.stream else {
+            return;
+        };
+        let mut chunk = Vec::new();
+        let mut closed = false;
+        // Cap the batch so a fast producer can't starve key handling
+        while chunk.len() < STREAM_BATCH {
+            match stream.try_recv() {
+                Ok(line) => chunk.extend(line),
+                Err(TryRecvError::Empty) => break,
+                Err(TryRecvError::Disconnected) => {
+                    closed = true;
+                    break;
+                }
+            }
+        }
+        if !chunk.is_empty() {
+            self.engine.append(&chunk);
+            self.dirty = true;
+        }
+        if closed {
+            self.stream = None;
+            self.status = Some("End of input".to_string());
+            self.dirty = true;
+        }
+    }
+
     fn run_loop(&mut self, terminal: &mut Terminal<CrosstermBackend<io::Stdout>>) -> Result<()> {
         loop {
-            terminal.draw(|frame| self.draw(frame))?;
+            self.drain_stream();
+            // Only redraw when something changed; idle polling stays cheap
+            if self.dirty {
+                terminal.draw(|frame| self.draw(frame))?;
+                self.dirty = false;
+            }
             if event::poll(Duration::from_millis(200))? {
-                if let Event::Key(key) = event::read()? {
-                    self.handle_key(key);
+                match event::read()? {
+                    Event::Key(key) => {
+                        self.handle_key(key);
+                        self.dirty = true;
+                    }
+                    Event::Resize(_, _) => {
+                        // Draw picks up the new size and engines refresh their
+                        // view height (used by half-page jumps) on render
+                        terminal.autoresize()?;
+                        self.dirty = true;
+                    }
+                    _ => {}
                 }
             }
             if self.should_quit {
@@ -150,6 +390,7 @@ impl App {
         Ok(())
     }
 
+    #[allow(clippy::collapsible_match)] // bounds checks stay inside their key's arm, as in the engines
     fn handle_key(&mut self, key: KeyEvent) {
         // Handle help overlay first
         if self.show_help {
@@ -159,6 +400,11 @@ impl App {
             return;
         }
 
+        if self.recent.is_some() {
+            self.handle_recent_key(key);
+            return;
+        }
+
         if self.input.active {
             match key.code {
                 KeyCode::Esc => {
@@ -168,11 +414,14 @@ impl App {
                 KeyCode::Enter => {
                     let query = self.input.buffer.trim().to_string();
                     if !query.is_empty() {
-                        if self.input.is_filter {
+                        let found = if self.input.is_filter {
                             self.filter = Some(query.clone());
-                            self.engine.apply_filter(&query);
+                            self.engine.apply_filter(&query)
                         } else {
-                            self.engine.apply_search(&query);
+                            self.engine.apply_search(&query, true)
+                        };
+                        if !found {
+                            self.status = Some(format!("Pattern not found: {}", query));
                         }
                     }
                     self.input.active = false;
@@ -198,6 +447,64 @@ impl App {
             return;
         }
 
+        if self.engine.captures_input() {
+            self.engine.handle_key(key);
+            return;
+        }
+
+        // zz/zt/zb only move the view, so they work the same in visual mode; checked
+        // before the key presets so `zb` isn't read as a less-style page up
+        if std::mem::take(&mut self.pending_z) {
+            let align = match key.code {
+                KeyCode::Char('z') => Some(ScrollAlign::Center),
+                KeyCode::Char('t') => Some(ScrollAlign::Top),
+                KeyCode::Char('b') => Some(ScrollAlign::Bottom),
+                _ => None,
+            };
+            if let Some(align) = align {
+                self.engine.align_scroll(align);
+                return;
+            }
+            // zh/zl scroll one column, zH/zL half the view, as in vim
+            let half = (self.view_width / 2).max(1);
+            let cols = self.engine.h_scroll();
+            let scrolled = match key.code {
+                KeyCode::Char('h') => Some(cols.saturating_sub(1)),
+                KeyCode::Char('l') => Some(cols + 1),
+                KeyCode::Char('H') => Some(cols.saturating_sub(half)),
+                KeyCode::Char('L') => Some(cols + half),
+                _ => None,
+            };
+            if let Some(scrolled) = scrolled {
+                self.engine.set_h_scroll(scrolled);
+                self.clamp_h_scroll();
+                return;
+            }
+        } else if key.code == KeyCode::Char('z') && key.modifiers.is_empty() {
+            self.pending_z = true;
+            return;
+        }
+
+        // Presets turn their page keys into Ctrl+f/Ctrl+b so they page a whole view too
+        let key = self.keys.translate(key);
+
+        // Ctrl+f/Ctrl+b page a whole view; engines see them as Ctrl+d/Ctrl+u
+        if key.modifiers.contains(KeyModifiers::CONTROL) {
+            let half_page = match key.code {
+                KeyCode::Char('f') => Some('d'),
+                KeyCode::Char('b') => Some('u'),
+                _ => None,
+            };
+            if let Some(c) = half_page {
+                self.pending_g = false;
+                engines::full_page(|| self.engine.handle_key(ctrl_key(c)));
+                return;
+            }
+        }
+
+        let after_g = self.pending_g;
+        self.pending_g = key.code == KeyCode::Char('g') && !key.modifiers.contains(KeyModifiers::CONTROL) && !after_g;
+
         // Handle visual mode
         if self.visual_start.is_some() {
             match key.code {
@@ -205,21 +512,56 @@ impl App {
                     self.visual_start = None;
                     self.status = Some("Visual mode cancelled".to_string());
                 }
+                KeyCode::Char('y') if after_g => {
+                    // gy: copy selection with a location header
+                    if let Some(start) = self.visual_start {
+                        self.yank_with_location(start, self.engine.selection());
+                        self.visual_start = None;
+                    }
+                }
+                KeyCode::Char('y') if self.visual_block.is_some() => {
+                    if let (Some(start), Some(anchor)) = (self.visual_start, self.visual_block) {
+                        self.yank_block(start, anchor);
+                        self.visual_start = None;
+                    }
+                }
+                KeyCode::Char('v') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                    // Ctrl+v toggles between block and line selection
+                    if self.visual_block.take().is_some() {
+                        self.status = Some("-- VISUAL LINE --".to_string());
+                    } else if let Some(column) = self.engine.block_column() {
+                        self.visual_block = Some(column);
+                        self.status = Some("-- VISUAL BLOCK --".to_string());
+                    }
+                }
+                KeyCode::Char('v') => {
+                    self.visual_block = None;
+                }
+                KeyCode::Char('h' | 'l') | KeyCode::Left | KeyCode::Right if self.visual_block.is_some() => {
+                    self.engine.handle_key(key);
+                }
                 KeyCode::Char('y') => {
                     // Copy selection in visual mode
                     if let Some(start) = self.visual_start {
                         let end = self.engine.selection();
                         if let Some(content) = self.engine.get_lines_range(start, end) {
-                            if let Ok(mut clipboard) = Clipboard::new() {
-                                let line_count = if start <= end { end - start + 1 } else { start - end + 1 };
-                                if clipboard.set_text(content).is_ok() {
-                                    self.status = Some(format!("Yanked {} line(s)", line_count));
-                                }
+                            let line_count = if start <= end { end - start + 1 } else { start - end + 1 };
+                            if copy_to_clipboard(content) {
+                                self.status = Some(format!("Yanked {} line(s)", line_count));
+                            } else {
+                                self.status = Some(CLIPBOARD_UNAVAILABLE.to_string());
                             }
                         }
                         self.visual_start = None;
                     }
                 }
+                KeyCode::Char('Y') => {
+                    // Copy selected rows as a markdown table
+                    if let Some(start) = self.visual_start {
+                        self.yank_markdown_table(start, self.engine.selection());
+                        self.visual_start = None;
+                    }
+                }
                 KeyCode::Char('j') | KeyCode::Down => {
                     self.engine.handle_key(key);
                 }
@@ -240,6 +582,9 @@ impl App {
                 }
                 _ => {}
             }
+            if self.visual_start.is_none() {
+                self.visual_block = None;
+            }
             return;
         }
 
@@ -255,25 +600,71 @@ impl App {
             KeyCode::Char('?') => {
                 self.show_help = true;
             }
+            KeyCode::Char('y') if after_g => {
+                // gy: copy current line with a location header
+                let selection = self.engine.selection();
+                self.yank_with_location(selection, selection);
+                self.pending_y = false;
+            }
             KeyCode::Char('y') => {
                 if self.pending_y {
                     // yy: copy current line
                     if let Some(line) = self.engine.get_selected_line() {
-                        if let Ok(mut clipboard) = Clipboard::new() {
-                            if clipboard.set_text(line).is_ok() {
-                                self.status = Some("Yanked 1 line".to_string());
-                            }
+                        if copy_to_clipboard(line) {
+                            self.status = Some("Yanked 1 line".to_string());
+                        } else {
+                            self.status = Some(CLIPBOARD_UNAVAILABLE.to_string());
                         }
                     }
                     self.pending_y = false;
                 } else {
-                    // First 'y' press - wait for second 'y' or copy path for tree
+                    // First 'y' press: copy the selection's path where the view has
+                    // one, and wait for a second 'y' to copy the line instead
                     self.pending_y = true;
+                    if let Some(path) = self.engine.selected_path() {
+                        self.status = Some(if copy_to_clipboard(path.clone()) {
+                            format!("Copied {}", path)
+                        } else {
+                            CLIPBOARD_UNAVAILABLE.to_string()
+                        });
+                    }
                 }
             }
+            KeyCode::Char('Y') => {
+                let selection = self.engine.selection();
+                self.yank_markdown_table(selection, selection);
+            }
+            KeyCode::Char('g') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                let show = !engines::line_numbers();
+                engines::set_line_numbers(show);
+                self.status = Some(format!("Line numbers {}", if show { "on" } else { "off" }));
+            }
+            KeyCode::Char('t') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.chrome = !self.chrome;
+            }
+            KeyCode::Char('o') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.open_recent();
+            }
+            KeyCode::Char('w') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.layout.wrap = !self.layout.wrap;
+                self.engine.set_layout(self.layout);
+                self.status = Some(format!("Line wrap {}", if self.layout.wrap { "on" } else { "off" }));
+            }
+            KeyCode::Char('v') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                // Enter visual block mode in views with cells or byte columns
+                let Some(column) = self.engine.block_column() else {
+                    self.status = Some("Visual block needs a table or hex view".to_string());
+                    return;
+                };
+                self.visual_start = Some(self.engine.selection());
+                self.visual_block = Some(column);
+                self.visual_bytes = None;
+                self.status = Some("-- VISUAL BLOCK --".to_string());
+            }
             KeyCode::Char('v') => {
                 // Enter visual line mode
                 self.visual_start = Some(self.engine.selection());
+                self.visual_bytes = None;
                 self.status = Some("-- VISUAL LINE --".to_string());
             }
             KeyCode::Char('/') => {
@@ -290,45 +681,232 @@ impl App {
                     self.input.buffer.clear();
                 }
             }
+            KeyCode::Char(c @ ('*' | '#')) => {
+                // Like vim's * and #: the word under the column cursor, or the line's first
+                // word in views without one
+                let col = self.engine.cursor_col().unwrap_or(0);
+                let Some(word) = self.engine.get_selected_line().and_then(|line| word_at(&line, col)) else {
+                    self.status = Some("No identifier on this line".to_string());
+                    return;
+                };
+                // Whole words only, forward for `*` and backward for `#`
+                let query = format!("\\<{}\\>", word);
+                if !self.engine.apply_search(&query, c == '*') {
+                    self.status = Some(format!("Pattern not found: {}", word));
+                    return;
+                }
+                self.status = Some(format!("search: {}", query));
+            }
             KeyCode::Char('F') => {
                 // Clear filter
                 self.filter = None;
                 self.engine.clear_filter();
                 self.status = Some("Filter cleared".to_string());
             }
+            KeyCode::Char(c @ ('n' | 'N')) => {
+                // Searches wrap around; say so when the match is behind where we started
+                let before = self.engine.selection();
+                self.engine.handle_key(key);
+                let after = self.engine.selection();
+                if c == 'n' && after < before {
+                    self.status = Some("search hit BOTTOM, continuing at TOP".to_string());
+                } else if c == 'N' && after > before {
+                    self.status = Some("search hit TOP, continuing at BOTTOM".to_string());
+                }
+            }
             _ => {
                 self.engine.handle_key(key);
             }
         }
     }
 
-    fn draw(&mut self, frame: &mut ratatui::Frame) {
-        let outer = Block::default()
+    /// Show the recent-files picker, leaving out the file being viewed
+    fn open_recent(&mut self) {
+        let current = self.source_path.canonicalize().ok();
+        let files: Vec<PathBuf> = State::load()
+            .recent()
+            .into_iter()
+            .filter(|file| Some(file) != current.as_ref())
+            .collect();
+        if files.is_empty() {
+            self.status = Some("No recent files".to_string());
+            return;
+        }
+        self.recent = Some(ListPicker::new(files));
+    }
+
+    fn close_recent(&mut self) {
+        self.recent = None;
+        if self.recent_only {
+            self.should_quit = true;
+        }
+    }
+
+    /// Keys while the recent-files picker is open: typing narrows it, Enter opens the file
+    fn handle_recent_key(&mut self, key: KeyEvent) {
+        let Some(picker) = self.recent.as_mut() else {
+            return;
+        };
+        match picker.handle_key(key) {
+            PickerKey::Open => {}
+            PickerKey::Cancel => self.close_recent(),
+            PickerKey::Pick(file) => {
+                self.reopen = Some(file.clone());
+                self.recent = None;
+                self.should_quit = true;
+            }
+        }
+    }
+
+    fn render_recent(&self, frame: &mut ratatui::Frame, area: Rect) {
+        if let Some(picker) = &self.recent {
+            picker.render(frame, area, "Recent: ", "(no matching files)");
+        }
+    }
+
+    /// Copy lines `start..=end` prefixed with a `file:line` comment in the
+    /// file's comment syntax, e.g. `// main.rs:12-20`. Only for views whose
+    /// rows are the file's own lines, so the numbers can be pasted back.
+    fn yank_with_location(&mut self, start: usize, end: usize) {
+        // The engine saw the 'g' of 'gy' and is waiting for 'gg'; any other key clears it
+        self.engine.handle_key(KeyEvent::from(KeyCode::Null));
+
+        let (lo, hi) = if start <= end { (start, end) } else { (end, start) };
+        let Some(first) = self.engine.source_line(lo) else {
+            self.status = Some("gy: this view doesn't show the file's lines".to_string());
+            return;
+        };
+        // A filter (or skipped blank log lines) leaves gaps no single range describes
+        if (lo..=hi).any(|row| self.engine.source_line(row) != Some(first + row - lo)) {
+            self.status = Some("gy: the selection skips lines of the file".to_string());
+            return;
+        }
+        let Some(content) = self.engine.get_lines_range(lo, hi) else {
+            return;
+        };
+        let lines = if lo == hi {
+            format!("{}", first)
+        } else {
+            format!("{}-{}", first, first + hi - lo)
+        };
+        let (open, close) = comment_delimiters(Path::new(&self.file_path), &self.comments);
+        let header = format!("{} {}:{}{}", open, self.file_path, lines, close);
+        if copy_to_clipboard(format!("{}\n{}", header, content)) {
+            self.status = Some(format!("Yanked {} line(s) with location", hi - lo + 1));
+        } else {
+            self.status = Some(CLIPBOARD_UNAVAILABLE.to_string());
+        }
+    }
+
+    /// Copy the block between the visual anchor (`start` row, `anchor` column) and the cursor
+    fn yank_block(&mut self, start: usize, anchor: usize) {
+        let end = self.engine.selection();
+        let column = self.engine.block_column().unwrap_or(anchor);
+        let rows = (start.min(end), start.max(end));
+        let columns = (anchor.min(column), anchor.max(column));
+        let Some(content) = self.engine.get_block(rows, columns) else {
+            return;
+        };
+        self.status = Some(if copy_to_clipboard(content) {
+            format!("Yanked {}x{} block", rows.1 - rows.0 + 1, columns.1 - columns.0 + 1)
+        } else {
+            CLIPBOARD_UNAVAILABLE.to_string()
+        });
+    }
+
+    /// Copy rows `start..=end` of a table view as a markdown table, ready to paste
+    /// into an issue or PR comment
+    fn yank_markdown_table(&mut self, start: usize, end: usize) {
+        let Some(table) = self.engine.markdown_table(start, end) else {
+            self.status = Some("No table rows to copy here".to_string());
+            return;
+        };
+        // Header and separator lines come first
+        let rows = table.lines().count() - 2;
+        self.status = Some(if copy_to_clipboard(table) {
+            format!("Copied {} row(s) as a markdown table", rows)
+        } else {
+            CLIPBOARD_UNAVAILABLE.to_string()
+        });
+    }
+
+    /// Size of what `y` would copy for the visual range; only recomputed when the range moves
+    fn visual_selection_bytes(&mut self, start: usize, end: usize) -> usize {
+        let range = if start <= end { (start, end) } else { (end, start) };
+        match self.visual_bytes {
+            Some((cached, bytes)) if cached == range => bytes,
+            _ => {
+                let bytes = self
+                    .engine
+                    .get_lines_range(range.0, range.1)
+                    .map_or(0, |content| content.len());
+                self.visual_bytes = Some((range, bytes));
+                bytes
+            }
+        }
+    }
+
+    /// Stop horizontal scrolling once the widest line's end reaches the right edge
+    fn clamp_h_scroll(&mut self) {
+        let max = self.engine.content_width().saturating_sub(self.view_width);
+        let cols = self.engine.h_scroll().min(max);
+        self.engine.set_h_scroll(cols);
+    }
+
+    /// Format label and breadcrumbs above the content
+    fn draw_header(&self, frame: &mut ratatui::Frame, area: Rect) {
+        // Keep both ends of long breadcrumbs (root and current node) visible
+        let label = self.language_label();
+        let header_width = area.width.saturating_sub(2) as usize;
+        let crumb_width = header_width.saturating_sub(label.chars().count() + 3);
+        let header = Line::from(format!(
+            "{} · {}",
+            label,
+            truncate_middle(&self.engine.breadcrumbs(), crumb_width)
+        ))
+        .style(Style::default().bold());
+        let header_block = Block::default()
             .borders(Borders::ALL)
             .border_style(Style::default().fg(ratatui::style::Color::LightBlue));
-        let area = outer.inner(frame.size());
-        frame.render_widget(outer, frame.size());
+        frame.render_widget(Paragraph::new(header).block(header_block), area);
+    }
+
+    fn draw(&mut self, frame: &mut ratatui::Frame) {
+        let area = if self.chrome {
+            let outer = Block::default()
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(ratatui::style::Color::LightBlue));
+            let area = outer.inner(frame.size());
+            frame.render_widget(outer, frame.size());
+            area
+        } else {
+            frame.size()
+        };
+        if area.width == 0 || area.height == 0 {
+            return;
+        }
 
-        let footer_height = if self.input.active { 2 } else { 1 };
+        // Without chrome the footer only appears for the search prompt and visual mode
+        let footer_height = if self.input.active {
+            2
+        } else if self.chrome || self.visual_start.is_some() {
+            1
+        } else {
+            0
+        };
+        let header_height = if self.chrome { 3 } else { 0 };
         let chunks = Layout::default()
             .direction(Direction::Vertical)
             .constraints([
-                Constraint::Length(3),
+                Constraint::Length(header_height),
                 Constraint::Min(1),
                 Constraint::Length(footer_height),
             ])
             .split(area);
 
-        let header = Line::from(format!(
-            "{}  {}",
-            self.engine.name(),
-            self.engine.breadcrumbs()
-        ))
-        .style(Style::default().bold());
-        let header_block = Block::default()
-            .borders(Borders::ALL)
-            .border_style(Style::default().fg(ratatui::style::Color::LightBlue));
-        frame.render_widget(Paragraph::new(header).block(header_block), chunks[0]);
+        if self.chrome {
+            self.draw_header(frame, chunks[0]);
+        }
 
         // Set visual range for highlighting
         if let Some(start) = self.visual_start {
@@ -337,7 +915,20 @@ impl App {
         } else {
             self.engine.set_visual_range(None);
         }
+        let columns = self.visual_block.map(|anchor| {
+            let column = self.engine.block_column().unwrap_or(anchor);
+            (anchor.min(column), anchor.max(column))
+        });
+        self.engine.set_visual_columns(columns);
 
+        // A wider terminal may now show the end of the widest line; measuring only
+        // on resize keeps huge text files from being rescanned every frame
+        if chunks[1].width as usize != self.view_width {
+            self.view_width = chunks[1].width as usize;
+            if self.engine.h_scroll() > 0 {
+                self.clamp_h_scroll();
+            }
+        }
         self.engine.render(frame, chunks[1]);
 
         if self.input.active {
@@ -353,7 +944,7 @@ impl App {
                 ),
                 Span::styled(" ", Style::default()),
                 Span::styled(
-                    format!("{}", self.input.buffer),
+                    self.input.buffer.to_string(),
                     Style::default()
                         .fg(ratatui::style::Color::White)
                         .bold(),
@@ -384,14 +975,24 @@ impl App {
             let start = self.visual_start.unwrap();
             let end = self.engine.selection();
             let line_count = if start <= end { end - start + 1 } else { start - end + 1 };
-            let range_text = if line_count == 1 {
-                "1 line".to_string()
+            let block_columns = self.visual_block.map(|anchor| {
+                let column = self.engine.block_column().unwrap_or(anchor);
+                anchor.abs_diff(column) + 1
+            });
+            let (label, range_text, extend) = if let Some(columns) = block_columns {
+                (" ▌ VISUAL BLOCK ", format!("{} rows × {} columns", line_count, columns), "h/j/k/l")
             } else {
-                format!("{} lines", line_count)
+                let bytes = self.visual_selection_bytes(start, end);
+                let range_text = if line_count == 1 {
+                    format!("1 line · {} bytes", bytes)
+                } else {
+                    format!("{} lines · {} bytes", line_count, bytes)
+                };
+                (" ▌ VISUAL ", range_text, "j/k")
             };
             let visual_line = Line::from(vec![
                 Span::styled(
-                    " ▌ VISUAL ",
+                    label,
                     Style::default()
                         .fg(ratatui::style::Color::Black)
                         .bg(ratatui::style::Color::LightMagenta)
@@ -411,7 +1012,7 @@ impl App {
                 ),
                 Span::styled(" yank  ", Style::default().fg(ratatui::style::Color::Gray)),
                 Span::styled(
-                    "j/k",
+                    extend,
                     Style::default().fg(ratatui::style::Color::White).bold(),
                 ),
                 Span::styled(" extend  ", Style::default().fg(ratatui::style::Color::Gray)),
@@ -436,6 +1037,8 @@ impl App {
             frame.render_widget(footer, chunks[2]);
         }
 
+        self.render_recent(frame, chunks[1]);
+
         // Help overlay
         if self.show_help {
             self.render_help_overlay(frame);
@@ -445,7 +1048,7 @@ impl App {
     fn render_help_overlay(&self, frame: &mut ratatui::Frame) {
         use ratatui::widgets::Clear;
 
-        let help_text = vec![
+        let mut help_text = vec![
             Line::from(Span::styled("Keyboard Shortcuts", Style::default().bold().fg(ratatui::style::Color::LightCyan))),
             Line::from(""),
             Line::from(vec![
@@ -455,6 +1058,12 @@ impl App {
             Line::from("  gg           Jump to top"),
             Line::from("  G            Jump to bottom"),
             Line::from("  Ctrl+u/d     Half-page up/down"),
+            Line::from("  Ctrl+b/f     Full page up/down"),
+            Line::from("  zz/zt/zb     Line to middle/top/bottom"),
+            Line::from("  zh/zl, zH/zL Scroll left/right (unwrapped text)"),
+        ];
+        help_text.extend(self.keys.help_lines());
+        help_text.extend([
             Line::from(""),
             Line::from(vec![
                 Span::styled("Search & Filter", Style::default().bold()),
@@ -463,24 +1072,38 @@ impl App {
             Line::from("  f            Filter (show only matches)"),
             Line::from("  F            Clear filter"),
             Line::from("  n/N          Next/previous match"),
+            Line::from("  */#          Search the word at the cursor forward/back"),
             Line::from(""),
             Line::from(vec![
                 Span::styled("Actions", Style::default().bold()),
             ]),
-            Line::from("  Enter        Expand/collapse (tree/json)"),
+            Line::from("  y            Copy the selection's path (key path, XPath, INI key)"),
             Line::from("  yy           Copy current line"),
+            Line::from("  gy           Copy with file:line header"),
+            Line::from("  Y            Copy table row(s) as a markdown table"),
             Line::from("  v            Enter visual line mode"),
-            Line::from("  s            Toggle sidebar/schema"),
-            Line::from("  e            Next section/heading"),
+            Line::from("  Ctrl+v       Enter visual block mode (table cells, hex bytes)"),
             Line::from(""),
             Line::from(vec![
                 Span::styled("General", Style::default().bold()),
             ]),
+            Line::from("  Ctrl+g       Show/hide line numbers"),
+            Line::from("  Ctrl+w       Wrap/cut long lines (text and code)"),
+            Line::from("  Ctrl+t       Show/hide border, header and footer"),
+            Line::from("  Ctrl+o       Reopen a recently viewed file"),
             Line::from("  ?            Show/hide this help"),
             Line::from("  q            Quit"),
-            Line::from(""),
-            Line::from(Span::styled("Press ? or Esc to close", Style::default().fg(ratatui::style::Color::DarkGray))),
-        ];
+        ]);
+        let engine_lines = self.engine.help_lines();
+        if !engine_lines.is_empty() {
+            help_text.push(Line::from(""));
+            help_text.push(Line::from(vec![
+                Span::styled(format!("{} keys", self.engine.name()), Style::default().bold()),
+            ]));
+            help_text.extend(engine_lines);
+        }
+        help_text.push(Line::from(""));
+        help_text.push(Line::from(Span::styled("Press ? or Esc to close", Style::default().fg(ratatui::style::Color::DarkGray))));
 
         let block = Block::default()
             .title(" Help ")
@@ -500,16 +1123,17 @@ impl App {
         frame.render_widget(Paragraph::new(help_text).block(block), popup_area);
     }
 
+    /// Format shown in the headers
+    fn language_label(&self) -> &'static str {
+        language_label(Path::new(&self.file_path), self.engine.name())
+    }
+
     fn plain_header_lines(&self, inner_width: usize) -> Vec<Line<'static>> {
         let file_name = Path::new(&self.file_path)
             .file_name()
             .and_then(|s| s.to_str())
             .unwrap_or(&self.file_path);
-        let ext = Path::new(&self.file_path)
-            .extension()
-            .and_then(|s| s.to_str())
-            .unwrap_or("");
-        let language = language_label(ext);
+        let language = self.language_label();
         let header_text = format!(" {}  ({}) ", file_name, language);
         let padded = format!("{:width$}", header_text, width = inner_width);
         let header_line = Line::from(Span::styled(
@@ -525,6 +1149,73 @@ impl App {
     }
 }
 
+/// Status shown when neither the system clipboard nor OSC 52 could take a yank
+const CLIPBOARD_UNAVAILABLE: &str = "Clipboard unavailable: no system clipboard and no terminal for OSC 52";
+
+/// Copy `text` to the system clipboard, falling back to an OSC 52 escape when there's
+/// no clipboard to talk to (SSH, headless); the terminal then sets its own clipboard
+fn copy_to_clipboard(text: String) -> bool {
+    if let Ok(mut clipboard) = Clipboard::new() {
+        if clipboard.set_text(text.clone()).is_ok() {
+            return true;
+        }
+    }
+    let mut stdout = io::stdout();
+    if !stdout.is_terminal() {
+        return false;
+    }
+    write!(stdout, "\x1b]52;c;{}\x07", base64_encode(text.as_bytes()))
+        .and_then(|_| stdout.flush())
+        .is_ok()
+}
+
+/// Standard padded base64, as OSC 52 expects
+fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let n = chunk.iter().enumerate().fold(0u32, |n, (i, &b)| n | ((b as u32) << (16 - 8 * i)));
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(ALPHABET[((n >> (18 - 6 * i)) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// The run of letters, digits and underscores around char `col`, or the first one
+/// after it
+fn word_at(line: &str, col: usize) -> Option<String> {
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    let chars: Vec<char> = line.chars().collect();
+    let mut start = (col.min(chars.len())..chars.len()).find(|&idx| is_word(chars[idx]))?;
+    while start > 0 && is_word(chars[start - 1]) {
+        start -= 1;
+    }
+    Some(chars[start..].iter().take_while(|&&c| is_word(c)).collect())
+}
+
+/// Shorten `value` to at most `max` chars by replacing its middle with `…`
+fn truncate_middle(value: &str, max: usize) -> String {
+    let len = value.chars().count();
+    if len <= max {
+        return value.to_string();
+    }
+    if max == 0 {
+        return String::new();
+    }
+    let keep = max - 1;
+    let head = keep / 2;
+    let tail = keep - head;
+    let mut out: String = value.chars().take(head).collect();
+    out.push('…');
+    out.extend(value.chars().skip(len - tail));
+    out
+}
+
 fn write_plain(lines: Vec<Line<'static>>) -> Result<()> {
     let mut stdout = io::stdout();
     for line in lines {
@@ -638,8 +1329,42 @@ fn to_ct_color(color: ratatui::style::Color) -> CtColor {
     }
 }
 
-fn language_label(ext: &str) -> &'static str {
-    match ext.to_lowercase().as_str() {
+/// Format label for the headers, driven by the engine that opened the file; the
+/// general-purpose views (code, trees, tables, text) fall back to the extension
+fn language_label(path: &Path, engine: &str) -> &'static str {
+    let file_name = path.file_name().and_then(|s| s.to_str()).unwrap_or("").to_lowercase();
+    // `data.json.gz` opens in the view for `data.json`
+    let inner = match file_name.rsplit_once('.') {
+        Some((stem, "gz" | "zst" | "xz" | "bz2")) => stem,
+        _ => file_name.as_str(),
+    };
+    let ext = inner.rsplit_once('.').map_or("", |(_, ext)| ext);
+    match engine {
+        "JsonlEngine" => return "JSON Lines",
+        "XmlEngine" => return if ext == "svg" { "SVG" } else { "XML" },
+        "SqliteEngine" => return "SQLite",
+        "ArchiveEngine" => return "Archive",
+        "ImageEngine" => return "Image",
+        "IniEngine" => {
+            return match ext {
+                "properties" => "Java Properties",
+                _ if inner == ".editorconfig" => "EditorConfig",
+                _ => "INI",
+            }
+        }
+        "DockerfileEngine" => return "Dockerfile",
+        "MakefileEngine" => return "Makefile",
+        "LogEngine" => return "Log",
+        "DiffEngine" => return "Diff",
+        "GitIgnoreEngine" => return "Git Ignore",
+        "LogicEngine" => return if inner == "config" { "SSH Config" } else { "Config" },
+        "LockEngine" => return "Lockfile",
+        "EnvEngine" => return "Environment",
+        "HtmlEngine" => return "HTML",
+        "HexEngine" => return "Binary",
+        _ => {}
+    }
+    match ext {
         "rs" => "Rust",
         "js" => "JavaScript",
         "jsx" => "JavaScript (React)",
@@ -648,21 +1373,221 @@ fn language_label(ext: &str) -> &'static str {
         "py" => "Python",
         "css" | "tcss" => "CSS",
         "md" => "Markdown",
-        "json" => "JSON",
+        "json" | "jsonc" | "json5" => "JSON",
         "yaml" | "yml" => "YAML",
         "toml" => "TOML",
         "kdl" => "KDL",
         "csv" => "CSV",
         "tsv" => "TSV",
+        "har" => "HAR",
         "parquet" => "Parquet",
-        "html" => "HTML",
+        "avro" => "Avro",
+        "orc" => "ORC",
+        "html" | "htm" => "HTML",
+        "xml" => "XML",
+        "go" => "Go",
+        "c" | "h" => "C",
+        "cpp" | "cc" | "hpp" => "C++",
+        "java" => "Java",
+        "rb" => "Ruby",
+        "sh" | "bash" | "zsh" => "Shell",
+        "sql" => "SQL",
         _ => "Text",
     }
 }
 
+/// `path` with the home directory shortened to `~`
+fn display_path(path: &Path) -> String {
+    match dirs::home_dir().and_then(|home| path.strip_prefix(home).ok().map(Path::to_path_buf)) {
+        Some(relative) => format!("~/{}", relative.display()),
+        None => path.display().to_string(),
+    }
+}
+
+/// Comment delimiters (open, close) for a file's `gy` header: a configured
+/// entry matching the file name or its trailing extension(s) wins, the longest
+/// key first, then the built-in table. `close` keeps its leading space.
+fn comment_delimiters(path: &Path, custom: &HashMap<String, String>) -> (String, String) {
+    let file_name = path.file_name().and_then(|s| s.to_str()).unwrap_or("");
+    let configured = custom
+        .iter()
+        .filter(|(key, _)| {
+            let key = key.trim_start_matches('.');
+            file_name == key || file_name.ends_with(&format!(".{}", key))
+        })
+        .max_by_key(|(key, _)| key.len());
+    if let Some((_, delimiters)) = configured {
+        let mut parts = delimiters.split_whitespace();
+        let open = parts.next().unwrap_or("#").to_string();
+        let close = parts.map(|part| format!(" {}", part)).collect();
+        return (open, close);
+    }
+    // Extensionless files like Dockerfile/Makefile are matched by name
+    let ext = path
+        .extension()
+        .or_else(|| path.file_name())
+        .and_then(|s| s.to_str())
+        .unwrap_or("");
+    let (open, close) = comment_style(ext.trim_start_matches('.'));
+    (open.to_string(), close.to_string())
+}
+
+/// Built-in line comment delimiters (open, close) used for `gy` location headers
+fn comment_style(ext: &str) -> (&'static str, &'static str) {
+    match ext.to_lowercase().as_str() {
+        "py" | "sh" | "bash" | "zsh" | "rb" | "pl" | "r" | "yaml" | "yml" | "toml" | "ini"
+        | "cfg" | "conf" | "env" | "mk" | "makefile" | "dockerfile" | "gitignore" | "log" => ("#", ""),
+        "sql" | "lua" | "hs" => ("--", ""),
+        "html" | "htm" | "xml" | "md" | "svg" => ("<!--", " -->"),
+        "css" | "tcss" => ("/*", " */"),
+        _ => ("//", ""),
+    }
+}
+
 #[derive(Clone, Copy, Debug)]
 pub enum Paging {
     Auto,
     Always,
     Never,
 }
+
+/// Navigation key presets; each adds aliases that translate to the vim keys
+/// engines already understand
+#[derive(Clone, Copy, Debug)]
+pub enum KeyPreset {
+    Vim,
+    Less,
+    Emacs,
+}
+
+impl KeyPreset {
+    fn translate(self, key: KeyEvent) -> KeyEvent {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        let alt = key.modifiers.contains(KeyModifiers::ALT);
+        let mapped = match self {
+            KeyPreset::Vim => None,
+            KeyPreset::Less => match key.code {
+                KeyCode::Char(' ') | KeyCode::PageDown => Some(ctrl_key('f')),
+                KeyCode::Char('b') | KeyCode::PageUp => Some(ctrl_key('b')),
+                _ => None,
+            },
+            KeyPreset::Emacs => match key.code {
+                KeyCode::Char('n') if ctrl => Some(KeyEvent::from(KeyCode::Down)),
+                KeyCode::Char('p') if ctrl => Some(KeyEvent::from(KeyCode::Up)),
+                KeyCode::Char('v') if ctrl => Some(ctrl_key('f')),
+                KeyCode::Char('v') if alt => Some(ctrl_key('b')),
+                KeyCode::Char('>') if alt => Some(KeyEvent::from(KeyCode::Char('G'))),
+                _ => None,
+            },
+        };
+        mapped.unwrap_or(key)
+    }
+
+    fn help_lines(self) -> Vec<Line<'static>> {
+        match self {
+            KeyPreset::Vim => Vec::new(),
+            KeyPreset::Less => vec![
+                Line::from("  Space/b      Page down/up"),
+                Line::from("  PgDn/PgUp    Page down/up"),
+            ],
+            KeyPreset::Emacs => vec![
+                Line::from("  Ctrl+n/p     Move down/up"),
+                Line::from("  Ctrl+v/Alt+v Page down/up"),
+                Line::from("  Alt+>        Jump to bottom"),
+            ],
+        }
+    }
+}
+
+fn ctrl_key(c: char) -> KeyEvent {
+    KeyEvent::new(KeyCode::Char(c), KeyModifiers::CONTROL)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn output_file_holds_the_boxed_view_with_or_without_plain() {
+        let dir = tempfile::tempdir().unwrap();
+        let source = dir.path().join("notes.txt");
+        fs::write(&source, "first\tline\nsecond\n").unwrap();
+        let write = |force_raw: bool, name: &str| {
+            let output = dir.path().join(name);
+            let engine = EngineState::new(engines::TextEngine::from_path(&source).unwrap());
+            App::new(engine, "notes.txt".to_string(), source.clone(), Paging::Auto, force_raw, KeyPreset::Vim)
+                .with_output(Some(output.clone()))
+                .run()
+                .unwrap();
+            fs::read_to_string(output).unwrap()
+        };
+        for (force_raw, name) in [(false, "formatted.txt"), (true, "plain.txt")] {
+            let formatted = write(force_raw, name);
+            assert!(formatted.contains("second") && formatted.contains('│'), "{}", formatted);
+        }
+    }
+
+    #[test]
+    fn recent_picker_ranks_closer_matches_first() {
+        let files = ["/srv/app/config.yaml", "/srv/logs/app.log", "/srv/cfg.yml"].map(PathBuf::from).to_vec();
+        let mut picker = ListPicker::new(files.clone());
+        // No query keeps the most recent first
+        assert_eq!(picker.matches(), files.iter().collect::<Vec<_>>());
+        for c in "cfg".chars() {
+            picker.handle_key(KeyEvent::from(KeyCode::Char(c)));
+        }
+        assert_eq!(picker.matches(), vec![&files[2], &files[0]]);
+    }
+
+    #[test]
+    fn preset_page_keys_page_a_whole_view() {
+        let key = |code| KeyPreset::Less.translate(KeyEvent::from(code));
+        assert_eq!(key(KeyCode::Char(' ')), ctrl_key('f'));
+        assert_eq!(key(KeyCode::PageUp), ctrl_key('b'));
+        let alt_v = KeyEvent::new(KeyCode::Char('v'), KeyModifiers::ALT);
+        assert_eq!(KeyPreset::Emacs.translate(alt_v), ctrl_key('b'));
+        assert_eq!(KeyPreset::Vim.translate(KeyEvent::from(KeyCode::Char(' '))), KeyEvent::from(KeyCode::Char(' ')));
+    }
+
+    #[test]
+    fn configured_comments_win_over_the_built_in_table() {
+        let custom = HashMap::from([
+            ("vue".to_string(), "<!-- -->".to_string()),
+            ("test.ts".to_string(), "#".to_string()),
+        ]);
+        let delimiters = |name: &str| comment_delimiters(Path::new(name), &custom);
+        assert_eq!(delimiters("App.vue"), ("<!--".to_string(), " -->".to_string()));
+        assert_eq!(delimiters("api.test.ts"), ("#".to_string(), String::new()));
+        assert_eq!(delimiters("api.ts"), ("//".to_string(), String::new()));
+        assert_eq!(delimiters("Dockerfile"), ("#".to_string(), String::new()));
+    }
+
+    #[test]
+    fn star_and_hash_search_whole_words_in_each_direction() {
+        let text = b"foo = 1\nfoobar()\nfoo(2)\nfood\n";
+        let engine = EngineState::new(engines::TextEngine::from_bytes(Path::new("a.txt"), text).unwrap());
+        let mut app = App::new(engine, "a.txt".to_string(), PathBuf::new(), Paging::Always, false, KeyPreset::Vim);
+        let press = |app: &mut App, c| {
+            app.handle_key(KeyEvent::from(KeyCode::Char(c)));
+            app.engine().selection()
+        };
+        // `foobar` and `food` don't count as `foo`
+        assert_eq!(press(&mut app, '*'), 2);
+        assert_eq!(press(&mut app, '*'), 0);
+        assert_eq!(press(&mut app, '#'), 2);
+        assert_eq!(press(&mut app, 'j'), 3);
+        // `food` occurs once: `#` wraps around to itself
+        assert_eq!(press(&mut app, '#'), 3);
+    }
+
+    #[test]
+    fn w_moves_by_word_and_ctrl_w_toggles_wrap() {
+        let engine = EngineState::new(engines::TextEngine::from_bytes(Path::new("a.txt"), b"let total = 1;
+").unwrap());
+        let mut app = App::new(engine, "a.txt".to_string(), PathBuf::new(), Paging::Always, false, KeyPreset::Vim);
+        app.handle_key(KeyEvent::from(KeyCode::Char('w')));
+        assert_eq!((app.engine().cursor_col(), app.layout.wrap), (Some(4), false));
+        app.handle_key(ctrl_key('w'));
+        assert_eq!((app.engine().cursor_col(), app.layout.wrap), (Some(4), true));
+    }
+}
@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+
+/// User settings read from `<config dir>/vat/config.toml`
+#[derive(Debug, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Extension or file name → engine name, e.g. `conf = "ini"` or `"txt.log" = "log"`
+    pub engines: HashMap<String, String>,
+    /// Rows of context kept above and below the selection while scrolling, like vim's `scrolloff`
+    pub scroll_off: usize,
+    /// Draw the line-number gutter; `Ctrl+g` toggles it at runtime
+    pub line_numbers: bool,
+    /// Open JSON/YAML/TOML trees folded below this many levels; `--depth` overrides it
+    pub tree_depth: Option<usize>,
+    /// Share of the view `Ctrl+d`/`Ctrl+u` move; `Ctrl+f`/`Ctrl+b` always move a full page
+    pub half_page: f64,
+    /// Characters kept by cell and value previews before they're cut; None keeps each view's default
+    pub preview_len: Option<usize>,
+    /// Extension or file name → comment delimiters for `gy` headers, the closing
+    /// one after a space, e.g. `sql = "--"` or `vue = "<!-- -->"`
+    pub comments: HashMap<String, String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            engines: HashMap::new(),
+            scroll_off: 0,
+            line_numbers: true,
+            tree_depth: None,
+            half_page: 0.5,
+            preview_len: None,
+            comments: HashMap::new(),
+        }
+    }
+}
+
+impl Config {
+    /// Load the config file; a missing file gives the defaults
+    pub fn load() -> Result<Self> {
+        let Some(path) = Self::path() else {
+            return Ok(Self::default());
+        };
+        match std::fs::read_to_string(&path) {
+            Ok(raw) => toml::from_str(&raw).map_err(|e| anyhow!("{}: {}", path.display(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(anyhow!("{}: {}", path.display(), e)),
+        }
+    }
+
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("vat").join("config.toml"))
+    }
+}
@@ -1,5 +1,5 @@
 use std::fs::File;
-use std::io::{BufReader, Read};
+use std::io::{BufReader, Cursor, Read, Seek};
 use std::path::Path;
 
 use anyhow::{anyhow, Result};
@@ -8,6 +8,10 @@ use ratatui::layout::Rect;
 use ratatui::style::{Color, Style, Stylize};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph};
+use serde_json::json;
+
+use super::util::{format_size, search_matches};
+use super::Engine;
 
 #[derive(Clone)]
 struct ArchiveEntry {
@@ -37,6 +41,15 @@ pub struct ArchiveEngine {
 
 impl ArchiveEngine {
     pub fn from_path(path: &Path) -> Result<Self> {
+        Self::from_reader(path, BufReader::new(File::open(path)?))
+    }
+
+    /// Create from in-memory bytes; `path` only supplies the name and extension
+    pub fn from_bytes(path: &Path, bytes: &[u8]) -> Result<Self> {
+        Self::from_reader(path, Cursor::new(bytes))
+    }
+
+    fn from_reader<R: Read + Seek>(path: &Path, reader: R) -> Result<Self> {
         let file_name = path
             .file_name()
             .and_then(|s| s.to_str())
@@ -46,16 +59,18 @@ impl ArchiveEngine {
         let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("").to_lowercase();
 
         let (entries, archive_type) = match ext.as_str() {
-            "zip" => (parse_zip(path)?, "ZIP".to_string()),
-            "tar" => (parse_tar(path, None)?, "TAR".to_string()),
-            "gz" | "tgz" => {
-                if file_name.ends_with(".tar.gz") || ext == "tgz" {
-                    (parse_tar(path, Some("gz"))?, "TAR.GZ".to_string())
-                } else {
-                    return Err(anyhow!("Single gzip files not supported, use tar.gz"));
+            "zip" => (parse_zip(reader)?, "ZIP".to_string()),
+            "tar" => (parse_tar(reader, None)?, "TAR".to_string()),
+            _ => match tar_compression(&file_name) {
+                Some(compression) => (
+                    parse_tar(reader, Some(compression))?,
+                    format!("TAR.{}", compression.to_uppercase()),
+                ),
+                None if compression_for_ext(&ext).is_some() => {
+                    return Err(anyhow!("Single compressed files open in the view for their contents, not as archives"));
                 }
-            }
-            _ => return Err(anyhow!("Unsupported archive format")),
+                None => return Err(anyhow!("Unsupported archive format")),
+            },
         };
 
         let total_size: u64 = entries.iter().map(|e| e.size).sum();
@@ -77,16 +92,53 @@ impl ArchiveEngine {
         })
     }
 
-    pub fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+    fn search_next(&mut self, query: &str, forward: bool) -> bool {
+        let lower = query.to_lowercase();
+        let mut found = false;
+        let total = self.entries.len().max(1);
+        let start = if forward {
+            (self.selection + 1) % total
+        } else {
+            (self.selection + total - 1) % total
+        };
+
+        for offset in 0..total {
+            let idx = if forward {
+                (start + offset) % total
+            } else {
+                (start + total - offset % total) % total
+            };
+            if search_matches(&self.entries[idx].path, &lower) {
+                self.selection = idx;
+                found = true;
+                break;
+            }
+        }
+        self.last_match = Some(query.to_string());
+        found
+    }
+}
+
+impl Engine for ArchiveEngine {
+    fn name(&self) -> &'static str {
+        "ArchiveEngine"
+    }
+
+    fn set_visual_range(&mut self, range: Option<(usize, usize)>) {
+        self.visual_range = range;
+    }
+
+    fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
         let height = area.height as usize;
         self.last_view_height = height;
 
-        if self.selection < self.scroll {
-            self.scroll = self.selection;
-        } else if self.selection >= self.scroll + height {
-            self.scroll = self.selection.saturating_sub(height - 1);
+        if self.entries.is_empty() {
+            super::render_placeholder(frame, area, "(empty archive)");
+            return;
         }
 
+        self.scroll = super::follow_selection(self.scroll, self.selection, height, self.entries.len());
+
         let visible: Vec<Line> = self.entries
             .iter()
             .skip(self.scroll)
@@ -156,7 +208,7 @@ impl ArchiveEngine {
                     Style::default().fg(Color::Cyan).bold()
                 } else {
                     // Color by extension
-                    let ext = entry.path.split('.').last().unwrap_or("");
+                    let ext = entry.path.split('.').next_back().unwrap_or("");
                     match ext {
                         "rs" | "py" | "js" | "ts" | "go" | "java" | "c" | "cpp" | "h" => Style::default().fg(Color::Green),
                         "json" | "yaml" | "yml" | "toml" | "xml" => Style::default().fg(Color::Yellow),
@@ -174,7 +226,8 @@ impl ArchiveEngine {
         frame.render_widget(Paragraph::new(visible).block(block), area);
     }
 
-    pub fn handle_key(&mut self, key: KeyEvent) {
+    #[allow(clippy::collapsible_match)]
+    fn handle_key(&mut self, key: KeyEvent) {
         match key.code {
             KeyCode::Char('g') => {
                 if self.pending_g {
@@ -201,12 +254,10 @@ impl ArchiveEngine {
                 self.selection = self.selection.saturating_sub(1);
             }
             KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let jump = page_jump(self.last_view_height).min(self.selection);
-                self.selection = self.selection.saturating_sub(jump);
+                self.selection = super::half_page_up(self.selection, self.last_view_height);
             }
             KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let jump = page_jump(self.last_view_height).min(total.saturating_sub(1));
-                self.selection = (self.selection + jump).min(total.saturating_sub(1));
+                self.selection = super::half_page_down(self.selection, self.last_view_height, total);
             }
             KeyCode::Char('G') => {
                 if total > 0 {
@@ -227,25 +278,26 @@ impl ArchiveEngine {
         }
     }
 
-    pub fn apply_search(&mut self, query: &str) {
+    fn apply_search(&mut self, query: &str, forward: bool) -> bool {
         let trimmed = query.trim();
         if trimmed.is_empty() {
-            return;
+            return false;
         }
         self.last_query = Some(trimmed.to_string());
-        self.search_next(trimmed, true);
+        let found = self.search_next(trimmed, forward);
         self.last_match = Some(trimmed.to_string());
+        found
     }
 
-    pub fn apply_filter(&mut self, query: &str) {
-        self.apply_search(query);
+    fn apply_filter(&mut self, query: &str) -> bool {
+        self.apply_search(query, true)
     }
 
-    pub fn clear_filter(&mut self) {
+    fn clear_filter(&mut self) {
         self.last_query = None;
     }
 
-    pub fn breadcrumbs(&self) -> String {
+    fn breadcrumbs(&self) -> String {
         let ratio = if self.total_size > 0 {
             (self.total_compressed as f64 / self.total_size as f64 * 100.0) as u64
         } else {
@@ -262,7 +314,7 @@ impl ArchiveEngine {
         )
     }
 
-    pub fn status_line(&self) -> String {
+    fn status_line(&self) -> String {
         let query = self
             .last_query
             .as_ref()
@@ -274,20 +326,24 @@ impl ArchiveEngine {
         )
     }
 
-    #[allow(dead_code)]
-    pub fn selected_path(&self) -> Option<String> {
+    /// Keys specific to this view, listed in the help overlay
+    fn help_lines(&self) -> Vec<Line<'static>> {
+        Vec::new()
+    }
+
+    fn selected_path(&self) -> Option<String> {
         self.entries.get(self.selection).map(|e| e.path.clone())
     }
 
     /// Get the content of the currently selected line
-    pub fn get_selected_line(&self) -> Option<String> {
+    fn get_selected_line(&self) -> Option<String> {
         self.entries.get(self.selection).map(|e| {
             format!("{}\t{}\t{}", e.path, format_size(e.size), format_size(e.compressed_size.unwrap_or(e.size)))
         })
     }
 
     /// Get lines in a range (inclusive), joined by newlines
-    pub fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
+    fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
         let (start, end) = if start <= end { (start, end) } else { (end, start) };
         let total = self.entries.len();
         if start >= total { return None; }
@@ -299,15 +355,46 @@ impl ArchiveEngine {
     }
 
     /// Get current selection index (for visual mode)
-    pub fn selection(&self) -> usize {
+    fn selection(&self) -> usize {
         self.selection
     }
 
-    pub fn content_height(&self) -> usize {
+    fn select(&mut self, row: usize) {
+        self.selection = row.min(self.entries.len().saturating_sub(1));
+    }
+
+    /// Scroll so the selection sits at the top, middle or bottom of the view (`zt`/`zz`/`zb`)
+    fn align_scroll(&mut self, align: super::ScrollAlign) {
+        self.scroll = super::aligned_scroll(self.selection, self.last_view_height, align);
+    }
+
+    fn content_height(&mut self) -> usize {
         self.entries.len()
     }
 
-    pub fn render_plain_lines(&self, _width: u16) -> Vec<Line<'static>> {
+    /// Parsed structure for `--dump`
+    fn to_json(&mut self) -> serde_json::Value {
+        let entries: Vec<_> = self
+            .entries
+            .iter()
+            .map(|e| {
+                json!({
+                    "path": e.path,
+                    "size": e.size,
+                    "compressed_size": e.compressed_size,
+                    "is_dir": e.is_dir,
+                })
+            })
+            .collect();
+        json!({
+            "format": self.archive_type,
+            "total_size": self.total_size,
+            "total_compressed": self.total_compressed,
+            "entries": entries,
+        })
+    }
+
+    fn render_plain_lines(&mut self, _width: u16) -> Vec<Line<'static>> {
         self.entries
             .iter()
             .map(|entry| {
@@ -321,34 +408,9 @@ impl ArchiveEngine {
             })
             .collect()
     }
-
-    fn search_next(&mut self, query: &str, forward: bool) {
-        let lower = query.to_lowercase();
-        let total = self.entries.len().max(1);
-        let start = if forward {
-            (self.selection + 1) % total
-        } else {
-            self.selection.saturating_sub(1)
-        };
-
-        for offset in 0..total {
-            let idx = if forward {
-                (start + offset) % total
-            } else {
-                (start + total - offset % total) % total
-            };
-            if self.entries[idx].path.to_lowercase().contains(&lower) {
-                self.selection = idx;
-                break;
-            }
-        }
-        self.last_match = Some(query.to_string());
-    }
 }
 
-fn parse_zip(path: &Path) -> Result<Vec<ArchiveEntry>> {
-    let file = File::open(path)?;
-    let reader = BufReader::new(file);
+fn parse_zip<R: Read + Seek>(reader: R) -> Result<Vec<ArchiveEntry>> {
     let mut archive = zip::ZipArchive::new(reader)?;
 
     let mut entries = Vec::new();
@@ -367,15 +429,46 @@ fn parse_zip(path: &Path) -> Result<Vec<ArchiveEntry>> {
     Ok(entries)
 }
 
-fn parse_tar(path: &Path, compression: Option<&str>) -> Result<Vec<ArchiveEntry>> {
-    let file = File::open(path)?;
+/// Compression named by a file extension: `gz`, `zst`, `xz` or `bz2`
+pub fn compression_for_ext(ext: &str) -> Option<&'static str> {
+    match ext.to_lowercase().as_str() {
+        "gz" => Some("gz"),
+        "zst" => Some("zst"),
+        "xz" => Some("xz"),
+        "bz2" => Some("bz2"),
+        _ => None,
+    }
+}
 
-    let reader: Box<dyn Read> = match compression {
-        Some("gz") => {
-            let decoder = flate2::read::GzDecoder::new(file);
-            Box::new(decoder)
-        }
-        _ => Box::new(file),
+/// Compression of a tarball name like `a.tar.zst` or `a.tgz` (None for plain or non-tar files)
+pub fn tar_compression(file_name: &str) -> Option<&'static str> {
+    let lower = file_name.to_lowercase();
+    let (stem, ext) = lower.rsplit_once('.')?;
+    match ext {
+        "tgz" => Some("gz"),
+        "tzst" => Some("zst"),
+        "txz" => Some("xz"),
+        "tbz" | "tbz2" => Some("bz2"),
+        _ if stem.ends_with(".tar") => compression_for_ext(ext),
+        _ => None,
+    }
+}
+
+/// Wrap `reader` in a streaming decoder for a compression from `compression_for_ext`
+pub fn decompressor<'a, R: Read + 'a>(compression: &str, reader: R) -> Result<Box<dyn Read + 'a>> {
+    Ok(match compression {
+        "gz" => Box::new(flate2::read::MultiGzDecoder::new(reader)),
+        "zst" => Box::new(zstd::stream::read::Decoder::new(reader)?),
+        "xz" => Box::new(xz2::read::XzDecoder::new(reader)),
+        "bz2" => Box::new(bzip2::read::MultiBzDecoder::new(reader)),
+        _ => return Err(anyhow!("Unsupported compression: {}", compression)),
+    })
+}
+
+fn parse_tar<'a, R: Read + 'a>(reader: R, compression: Option<&str>) -> Result<Vec<ArchiveEntry>> {
+    let reader: Box<dyn Read + 'a> = match compression {
+        Some(compression) => decompressor(compression, reader)?,
+        None => Box::new(reader),
     };
 
     let mut archive = tar::Archive::new(reader);
@@ -399,24 +492,3 @@ fn parse_tar(path: &Path, compression: Option<&str>) -> Result<Vec<ArchiveEntry>
     entries.sort_by(|a, b| a.path.cmp(&b.path));
     Ok(entries)
 }
-
-fn format_size(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
-
-    if bytes >= GB {
-        format!("{:.1}G", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.1}M", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.1}K", bytes as f64 / KB as f64)
-    } else {
-        format!("{}B", bytes)
-    }
-}
-
-fn page_jump(view_height: usize) -> usize {
-    let half = view_height / 2;
-    if half == 0 { 1 } else { half }
-}
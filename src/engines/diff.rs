@@ -0,0 +1,559 @@
+use std::path::Path;
+
+use anyhow::Result;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Paragraph};
+use serde_json::json;
+
+use super::util::search_matches;
+use super::Engine;
+
+#[derive(Clone)]
+enum DiffLine {
+    /// `diff`, `index`, `---` and `+++` lines
+    FileHeader(String),
+    Hunk(String),
+    Context { old_no: usize, new_no: usize, text: String },
+    Added { new_no: usize, text: String },
+    Removed { old_no: usize, text: String },
+    /// Anything else, e.g. `\ No newline at end of file`
+    Other(String),
+}
+
+/// DiffEngine renders unified diffs (`.diff`/`.patch` files and `--diff` output)
+/// with old/new line numbers and colored additions/deletions.
+pub struct DiffEngine {
+    lines: Vec<(String, DiffLine)>, // (raw, parsed)
+    selection: usize,
+    scroll: usize,
+    file_name: String,
+    last_query: Option<String>,
+    pending_g: bool,
+    last_view_height: usize,
+    last_match: Option<String>,
+    /// Visual selection range (start, end) for highlighting
+    pub visual_range: Option<(usize, usize)>,
+}
+
+impl DiffEngine {
+    pub fn from_path(path: &Path) -> Result<Self> {
+        Self::from_bytes(path, &std::fs::read(path)?)
+    }
+
+    /// Create from in-memory bytes; `path` only supplies the name and extension
+    pub fn from_bytes(path: &Path, bytes: &[u8]) -> Result<Self> {
+        let content = std::str::from_utf8(bytes)?;
+        let file_name = path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or("")
+            .to_string();
+
+        Ok(Self {
+            lines: parse_unified_diff(content),
+            selection: 0,
+            scroll: 0,
+            file_name,
+            last_query: None,
+            pending_g: false,
+            last_view_height: 0,
+            last_match: None,
+            visual_range: None,
+        })
+    }
+
+    /// Override the name shown in the header (e.g. "old.json → new.json")
+    pub fn with_title(mut self, title: String) -> Self {
+        self.file_name = title;
+        self
+    }
+
+    fn line_no_width(&self) -> usize {
+        let max_no = self.lines.iter().fold(0, |max, (_, l)| match l {
+            DiffLine::Context { old_no, new_no, .. } => max.max(*old_no).max(*new_no),
+            DiffLine::Added { new_no, .. } => max.max(*new_no),
+            DiffLine::Removed { old_no, .. } => max.max(*old_no),
+            _ => max,
+        });
+        max_no.max(1).to_string().len().max(2)
+    }
+
+    fn search_next(&mut self, query: &str, forward: bool) -> bool {
+        let lower = query.to_lowercase();
+        let mut found = false;
+        let total = self.lines.len().max(1);
+        let start = if forward {
+            (self.selection + 1) % total
+        } else {
+            (self.selection + total - 1) % total
+        };
+
+        for offset in 0..total {
+            let idx = if forward {
+                (start + offset) % total
+            } else {
+                (start + total - offset % total) % total
+            };
+            if self.lines.get(idx).is_some_and(|(raw, _)| search_matches(raw, &lower)) {
+                self.selection = idx;
+                found = true;
+                break;
+            }
+        }
+        self.last_match = Some(query.to_string());
+        found
+    }
+}
+
+impl Engine for DiffEngine {
+    fn name(&self) -> &'static str {
+        "DiffEngine"
+    }
+
+    fn set_visual_range(&mut self, range: Option<(usize, usize)>) {
+        self.visual_range = range;
+    }
+
+    fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+        let height = area.height as usize;
+        self.last_view_height = height;
+
+        if self.lines.is_empty() {
+            super::render_placeholder(frame, area, "(no differences)");
+            return;
+        }
+
+        self.scroll = super::follow_selection(self.scroll, self.selection, height, self.lines.len());
+
+        let no_width = self.line_no_width();
+
+        let visible: Vec<Line> = self.lines
+            .iter()
+            .skip(self.scroll)
+            .take(height)
+            .enumerate()
+            .map(|(idx, (_, parsed))| {
+                let row = self.scroll + idx;
+                let selected = row == self.selection;
+                let in_visual = self.visual_range.is_some_and(|(start, end)| {
+                    let (lo, hi) = if start <= end { (start, end) } else { (end, start) };
+                    row >= lo && row <= hi
+                });
+
+                let mut spans = diff_line_spans(parsed, no_width);
+                if selected {
+                    for span in spans.iter_mut() {
+                        span.style = Style::default().fg(Color::Black).bg(Color::LightBlue);
+                    }
+                } else if in_visual {
+                    for span in spans.iter_mut() {
+                        span.style = Style::default().fg(Color::Black).bg(Color::LightYellow);
+                    }
+                }
+                Line::from(spans)
+            })
+            .collect();
+
+        let block = Block::default().borders(Borders::NONE);
+        frame.render_widget(Paragraph::new(visible).block(block), area);
+    }
+
+    #[allow(clippy::collapsible_match)]
+    fn handle_key(&mut self, key: KeyEvent) {
+        match key.code {
+            KeyCode::Char('g') => {
+                if self.pending_g {
+                    self.selection = 0;
+                    self.pending_g = false;
+                } else {
+                    self.pending_g = true;
+                }
+                return;
+            }
+            _ => {
+                self.pending_g = false;
+            }
+        }
+
+        let total = self.lines.len();
+        match key.code {
+            KeyCode::Char('j') | KeyCode::Down => {
+                if self.selection + 1 < total {
+                    self.selection += 1;
+                }
+            }
+            KeyCode::Char('k') | KeyCode::Up => {
+                self.selection = self.selection.saturating_sub(1);
+            }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.selection = super::half_page_up(self.selection, self.last_view_height);
+            }
+            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.selection = super::half_page_down(self.selection, self.last_view_height, total);
+            }
+            KeyCode::Char('G') => {
+                if total > 0 {
+                    self.selection = total - 1;
+                }
+            }
+            KeyCode::Char('e') => {
+                // Jump to next hunk
+                for i in (self.selection + 1)..total {
+                    if matches!(self.lines[i].1, DiffLine::Hunk(_)) {
+                        self.selection = i;
+                        break;
+                    }
+                }
+            }
+            KeyCode::Char('E') => {
+                // Jump to previous hunk
+                for i in (0..self.selection).rev() {
+                    if matches!(self.lines[i].1, DiffLine::Hunk(_)) {
+                        self.selection = i;
+                        break;
+                    }
+                }
+            }
+            KeyCode::Char('n') => {
+                if let Some(query) = self.last_match.clone() {
+                    self.search_next(&query, true);
+                }
+            }
+            KeyCode::Char('N') => {
+                if let Some(query) = self.last_match.clone() {
+                    self.search_next(&query, false);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn apply_search(&mut self, query: &str, forward: bool) -> bool {
+        let trimmed = query.trim();
+        if trimmed.is_empty() {
+            return false;
+        }
+        self.last_query = Some(trimmed.to_string());
+        let found = self.search_next(trimmed, forward);
+        self.last_match = Some(trimmed.to_string());
+        found
+    }
+
+    fn apply_filter(&mut self, query: &str) -> bool {
+        self.apply_search(query, true)
+    }
+
+    fn clear_filter(&mut self) {
+        self.last_query = None;
+    }
+
+    fn breadcrumbs(&self) -> String {
+        let hunks = self.lines.iter().filter(|(_, l)| matches!(l, DiffLine::Hunk(_))).count();
+        let current = self.lines[..(self.selection + 1).min(self.lines.len())]
+            .iter()
+            .filter(|(_, l)| matches!(l, DiffLine::Hunk(_)))
+            .count();
+        let (added, removed) = self.lines.iter().fold((0, 0), |(a, r), (_, l)| match l {
+            DiffLine::Added { .. } => (a + 1, r),
+            DiffLine::Removed { .. } => (a, r + 1),
+            _ => (a, r),
+        });
+        format!(
+            "{} hunk {}/{} +{} -{}",
+            self.file_name, current, hunks, added, removed
+        )
+    }
+
+    fn status_line(&self) -> String {
+        let query = self
+            .last_query
+            .as_ref()
+            .map(|q| format!(" | search: {}", q))
+            .unwrap_or_default();
+        format!(
+            "j/k move | gg/G jump | Ctrl+u/d half-page | e/E next/prev hunk | n/N next/prev | / search{}",
+            query
+        )
+    }
+
+    /// Keys specific to this view, listed in the help overlay
+    fn help_lines(&self) -> Vec<Line<'static>> {
+        vec![Line::from("  e/E          Next/previous hunk")]
+    }
+
+    fn selected_path(&self) -> Option<String> {
+        None
+    }
+
+    /// Get the content of the currently selected line
+    fn get_selected_line(&self) -> Option<String> {
+        self.lines.get(self.selection).map(|(raw, _)| raw.clone())
+    }
+
+    /// Get lines in a range (inclusive), joined by newlines
+    fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
+        let (start, end) = if start <= end { (start, end) } else { (end, start) };
+        let total = self.lines.len();
+        if start >= total { return None; }
+        let end = end.min(total.saturating_sub(1));
+        let lines: Vec<String> = self.lines[start..=end].iter().map(|(raw, _)| raw.clone()).collect();
+        if lines.is_empty() { None } else { Some(lines.join("\n")) }
+    }
+
+    /// Get current selection index (for visual mode)
+    fn selection(&self) -> usize {
+        self.selection
+    }
+
+    fn select(&mut self, row: usize) {
+        self.selection = row.min(self.lines.len().saturating_sub(1));
+    }
+
+    /// Scroll so the selection sits at the top, middle or bottom of the view (`zt`/`zz`/`zb`)
+    fn align_scroll(&mut self, align: super::ScrollAlign) {
+        self.scroll = super::aligned_scroll(self.selection, self.last_view_height, align);
+    }
+
+    fn content_height(&mut self) -> usize {
+        self.lines.len()
+    }
+
+    /// Parsed structure for `--dump`
+    fn to_json(&mut self) -> serde_json::Value {
+        let lines: Vec<_> = self
+            .lines
+            .iter()
+            .map(|(_, line)| match line {
+                DiffLine::FileHeader(text) => json!({ "kind": "header", "text": text }),
+                DiffLine::Hunk(text) => json!({ "kind": "hunk", "text": text }),
+                DiffLine::Context { old_no, new_no, text } => {
+                    json!({ "kind": "context", "old": old_no, "new": new_no, "text": text })
+                }
+                DiffLine::Added { new_no, text } => json!({ "kind": "added", "new": new_no, "text": text }),
+                DiffLine::Removed { old_no, text } => json!({ "kind": "removed", "old": old_no, "text": text }),
+                DiffLine::Other(text) => json!({ "kind": "other", "text": text }),
+            })
+            .collect();
+        json!({ "lines": lines })
+    }
+
+    fn render_plain_lines(&mut self, _width: u16) -> Vec<Line<'static>> {
+        let no_width = self.line_no_width();
+        self.lines
+            .iter()
+            .map(|(_, parsed)| Line::from(diff_line_spans(parsed, no_width)))
+            .collect()
+    }
+}
+
+/// Gutter (old/new line numbers), sign and text for one diff line
+fn diff_line_spans(parsed: &DiffLine, no_width: usize) -> Vec<Span<'static>> {
+    let gutter = |no: Option<usize>| {
+        no.map(|n| format!("{:>width$}", n, width = no_width))
+            .unwrap_or_else(|| " ".repeat(no_width))
+    };
+    let gutter_style = Style::default().fg(Color::DarkGray);
+    let numbered = |old: Option<usize>, new: Option<usize>, sign: &str, text: &str, style: Style| {
+        vec![
+            Span::styled(format!("{} {} ", gutter(old), gutter(new)), gutter_style),
+            Span::styled("│ ", gutter_style),
+            Span::styled(format!("{}{}", sign, text), style),
+        ]
+    };
+    let blank = " ".repeat(no_width * 2 + 2);
+
+    match parsed {
+        DiffLine::FileHeader(text) => vec![
+            Span::styled(blank, gutter_style),
+            Span::styled("│ ", gutter_style),
+            Span::styled(text.clone(), Style::default().fg(Color::White).bold()),
+        ],
+        DiffLine::Hunk(text) => vec![
+            Span::styled(blank, gutter_style),
+            Span::styled("│ ", gutter_style),
+            Span::styled(text.clone(), Style::default().fg(Color::LightCyan)),
+        ],
+        DiffLine::Context { old_no, new_no, text } => {
+            numbered(Some(*old_no), Some(*new_no), " ", text, Style::default().fg(Color::Gray))
+        }
+        DiffLine::Added { new_no, text } => {
+            numbered(None, Some(*new_no), "+", text, Style::default().fg(Color::LightGreen))
+        }
+        DiffLine::Removed { old_no, text } => {
+            numbered(Some(*old_no), None, "-", text, Style::default().fg(Color::LightRed))
+        }
+        DiffLine::Other(text) => vec![
+            Span::styled(blank, gutter_style),
+            Span::styled("│ ", gutter_style),
+            Span::styled(text.clone(), Style::default().fg(Color::DarkGray).italic()),
+        ],
+    }
+}
+
+fn parse_unified_diff(content: &str) -> Vec<(String, DiffLine)> {
+    let mut lines = Vec::new();
+    let mut old_no = 0;
+    let mut new_no = 0;
+    let mut in_hunk = false;
+
+    for line in content.lines() {
+        let parsed = if line.starts_with("@@") {
+            let (old_start, new_start) = parse_hunk_header(line).unwrap_or((1, 1));
+            old_no = old_start;
+            new_no = new_start;
+            in_hunk = true;
+            DiffLine::Hunk(line.to_string())
+        } else if !in_hunk
+            || line.starts_with("diff ")
+            || line.starts_with("--- ")
+            || line.starts_with("+++ ")
+        {
+            in_hunk = false;
+            DiffLine::FileHeader(line.to_string())
+        } else if let Some(text) = line.strip_prefix('+') {
+            new_no += 1;
+            DiffLine::Added { new_no: new_no - 1, text: text.to_string() }
+        } else if let Some(text) = line.strip_prefix('-') {
+            old_no += 1;
+            DiffLine::Removed { old_no: old_no - 1, text: text.to_string() }
+        } else if let Some(text) = line.strip_prefix(' ').or((line.is_empty()).then_some("")) {
+            old_no += 1;
+            new_no += 1;
+            DiffLine::Context { old_no: old_no - 1, new_no: new_no - 1, text: text.to_string() }
+        } else {
+            DiffLine::Other(line.to_string())
+        };
+        lines.push((line.to_string(), parsed));
+    }
+
+    lines
+}
+
+/// Parse `@@ -a,b +c,d @@` into the old and new start lines
+fn parse_hunk_header(line: &str) -> Option<(usize, usize)> {
+    let mut parts = line.split_whitespace().skip(1);
+    let old = parts.next()?.strip_prefix('-')?;
+    let new = parts.next()?.strip_prefix('+')?;
+    let start = |range: &str| range.split(',').next()?.parse::<usize>().ok();
+    Some((start(old)?, start(new)?))
+}
+
+enum Op {
+    Equal(usize),
+    Delete(usize),
+    Insert(usize),
+}
+
+/// Build a unified diff (3 lines of context) between two texts
+pub fn unified_diff(old_name: &str, old: &str, new_name: &str, new: &str) -> String {
+    const CONTEXT: usize = 3;
+    let a: Vec<&str> = old.lines().collect();
+    let b: Vec<&str> = new.lines().collect();
+    let ops = diff_ops(&a, &b);
+
+    // Old/new line positions before each op
+    let mut positions = Vec::with_capacity(ops.len() + 1);
+    let (mut old_pos, mut new_pos) = (0, 0);
+    for op in &ops {
+        positions.push((old_pos, new_pos));
+        match op {
+            Op::Equal(_) => { old_pos += 1; new_pos += 1; }
+            Op::Delete(_) => old_pos += 1,
+            Op::Insert(_) => new_pos += 1,
+        }
+    }
+
+    // Group changes whose context windows overlap into hunks
+    let changes: Vec<usize> = ops.iter().enumerate()
+        .filter(|(_, op)| !matches!(op, Op::Equal(_)))
+        .map(|(i, _)| i)
+        .collect();
+    let mut hunks: Vec<(usize, usize)> = Vec::new();
+    for &i in &changes {
+        let start = i.saturating_sub(CONTEXT);
+        let end = (i + CONTEXT + 1).min(ops.len());
+        match hunks.last_mut() {
+            Some(last) if start <= last.1 => last.1 = end,
+            _ => hunks.push((start, end)),
+        }
+    }
+
+    let mut out = String::new();
+    if hunks.is_empty() {
+        return out;
+    }
+    out.push_str(&format!("--- {}\n+++ {}\n", old_name, new_name));
+    for (start, end) in hunks {
+        let (old_start, new_start) = positions[start];
+        let old_len = ops[start..end].iter().filter(|op| !matches!(op, Op::Insert(_))).count();
+        let new_len = ops[start..end].iter().filter(|op| !matches!(op, Op::Delete(_))).count();
+        let range = |pos: usize, len: usize| {
+            let first = if len == 0 { pos } else { pos + 1 };
+            if len == 1 { first.to_string() } else { format!("{},{}", first, len) }
+        };
+        out.push_str(&format!("@@ -{} +{} @@\n", range(old_start, old_len), range(new_start, new_len)));
+        for op in &ops[start..end] {
+            let line = match op {
+                Op::Equal(i) => format!(" {}", a[*i]),
+                Op::Delete(i) => format!("-{}", a[*i]),
+                Op::Insert(j) => format!("+{}", b[*j]),
+            };
+            out.push_str(&line);
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Shortest edit script over lines; `similar`'s Myers runs in linear space, so large,
+/// dissimilar files don't keep a copy of the search frontier per edit
+fn diff_ops(a: &[&str], b: &[&str]) -> Vec<Op> {
+    use similar::{Algorithm, DiffTag};
+
+    let mut ops = Vec::new();
+    for op in similar::capture_diff_slices(Algorithm::Myers, a, b) {
+        let (tag, old, new) = op.as_tag_tuple();
+        match tag {
+            DiffTag::Equal => ops.extend(old.map(Op::Equal)),
+            DiffTag::Delete => ops.extend(old.map(Op::Delete)),
+            DiffTag::Insert => ops.extend(new.map(Op::Insert)),
+            DiffTag::Replace => {
+                ops.extend(old.map(Op::Delete));
+                ops.extend(new.map(Op::Insert));
+            }
+        }
+    }
+    ops
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_hunk_line_numbers() {
+        let diff = "--- a.json\n+++ b.json\n@@ -2,3 +2,3 @@\n   \"a\": 1,\n-  \"b\": 2\n+  \"b\": 3\n }\n";
+        let lines = parse_unified_diff(diff);
+        assert!(matches!(lines[0].1, DiffLine::FileHeader(_)));
+        assert!(matches!(lines[2].1, DiffLine::Hunk(_)));
+        assert!(matches!(lines[3].1, DiffLine::Context { old_no: 2, new_no: 2, .. }));
+        assert!(matches!(lines[4].1, DiffLine::Removed { old_no: 3, .. }));
+        assert!(matches!(lines[5].1, DiffLine::Added { new_no: 3, .. }));
+        assert!(matches!(lines[6].1, DiffLine::Context { old_no: 4, new_no: 4, .. }));
+    }
+
+    #[test]
+    fn unified_diff_groups_changes_into_hunks() {
+        let old = "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n";
+        let new = "a\nB\nc\nd\ne\nf\ng\nh\ni\nj\nk\n";
+        let diff = unified_diff("old", old, "new", new);
+        assert_eq!(
+            diff,
+            "--- old\n+++ new\n@@ -1,5 +1,5 @@\n a\n-b\n+B\n c\n d\n e\n@@ -8,3 +8,4 @@\n h\n i\n j\n+k\n"
+        );
+        assert_eq!(unified_diff("old", old, "new", old), "");
+    }
+}
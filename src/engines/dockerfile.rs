@@ -6,6 +6,10 @@ use ratatui::layout::Rect;
 use ratatui::style::{Color, Style, Stylize};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph};
+use serde_json::json;
+
+use super::util::{search_matches, truncate};
+use super::Engine;
 
 #[derive(Clone)]
 enum DockerLine {
@@ -33,14 +37,19 @@ pub struct DockerfileEngine {
 
 impl DockerfileEngine {
     pub fn from_path(path: &Path) -> Result<Self> {
-        let content = std::fs::read_to_string(path)?;
+        Self::from_bytes(path, &std::fs::read(path)?)
+    }
+
+    /// Create from in-memory bytes; `path` only supplies the name and extension
+    pub fn from_bytes(path: &Path, bytes: &[u8]) -> Result<Self> {
+        let content = std::str::from_utf8(bytes)?;
         let file_name = path
             .file_name()
             .and_then(|s| s.to_str())
             .unwrap_or("")
             .to_string();
 
-        let lines = parse_dockerfile(&content);
+        let lines = parse_dockerfile(content);
 
         Ok(Self {
             lines,
@@ -55,16 +64,66 @@ impl DockerfileEngine {
         })
     }
 
-    pub fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+    fn search_next(&mut self, query: &str, forward: bool) -> bool {
+        let lower = query.to_lowercase();
+        let mut found = false;
+        let total = self.lines.len().max(1);
+        let start = if forward {
+            (self.selection + 1) % total
+        } else {
+            (self.selection + total - 1) % total
+        };
+
+        for offset in 0..total {
+            let idx = if forward {
+                (start + offset) % total
+            } else {
+                (start + total - offset % total) % total
+            };
+            let text = match &self.lines[idx].2 {
+                DockerLine::From { image, alias, .. } => {
+                    format!("FROM {} {}", image, alias.as_deref().unwrap_or(""))
+                }
+                DockerLine::Instruction { cmd, args } => format!("{} {}", cmd, args),
+                DockerLine::Comment(text) => text.clone(),
+                DockerLine::Arg { name, default } => {
+                    format!("ARG {} {}", name, default.as_deref().unwrap_or(""))
+                }
+                DockerLine::Env { key, value } => format!("ENV {}={}", key, value),
+                DockerLine::Label { key, value } => format!("LABEL {}={}", key, value),
+                DockerLine::Empty => String::new(),
+            };
+            if search_matches(&text, &lower) {
+                self.selection = idx;
+                found = true;
+                break;
+            }
+        }
+        self.last_match = Some(query.to_string());
+        found
+    }
+}
+
+impl Engine for DockerfileEngine {
+    fn name(&self) -> &'static str {
+        "DockerfileEngine"
+    }
+
+    fn set_visual_range(&mut self, range: Option<(usize, usize)>) {
+        self.visual_range = range;
+    }
+
+    fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
         let height = area.height as usize;
         self.last_view_height = height;
 
-        if self.selection < self.scroll {
-            self.scroll = self.selection;
-        } else if self.selection >= self.scroll + height {
-            self.scroll = self.selection.saturating_sub(height - 1);
+        if self.lines.is_empty() {
+            super::render_placeholder(frame, area, "(empty file)");
+            return;
         }
 
+        self.scroll = super::follow_selection(self.scroll, self.selection, height, self.lines.len());
+
         let line_no_width = self.lines.len().max(1).to_string().len().max(2);
 
         let visible: Vec<Line> = self.lines
@@ -83,8 +142,10 @@ impl DockerfileEngine {
                 } else {
                     Style::default().fg(Color::LightYellow)
                 };
-                spans.push(Span::styled(line_no_str, line_no_style));
-                spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
+                if super::line_numbers() {
+                    spans.push(Span::styled(line_no_str, line_no_style));
+                    spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
+                }
 
                 match parsed {
                     DockerLine::From { image, alias, stage_num } => {
@@ -123,7 +184,7 @@ impl DockerfileEngine {
                             Style::default().fg(Color::Yellow)
                         };
                         spans.push(Span::styled(format!("{} ", cmd), cmd_style));
-                        spans.push(Span::styled(truncate(args, 60), args_style));
+                        spans.push(Span::styled(truncate(args, super::preview_len(60)), args_style));
                     }
                     DockerLine::Arg { name, default } => {
                         let cmd_style = if selected {
@@ -179,7 +240,8 @@ impl DockerfileEngine {
         frame.render_widget(Paragraph::new(visible).block(block), area);
     }
 
-    pub fn handle_key(&mut self, key: KeyEvent) {
+    #[allow(clippy::collapsible_match)]
+    fn handle_key(&mut self, key: KeyEvent) {
         match key.code {
             KeyCode::Char('g') => {
                 if self.pending_g {
@@ -206,12 +268,10 @@ impl DockerfileEngine {
                 self.selection = self.selection.saturating_sub(1);
             }
             KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let jump = page_jump(self.last_view_height).min(self.selection);
-                self.selection = self.selection.saturating_sub(jump);
+                self.selection = super::half_page_up(self.selection, self.last_view_height);
             }
             KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let jump = page_jump(self.last_view_height).min(total.saturating_sub(1));
-                self.selection = (self.selection + jump).min(total.saturating_sub(1));
+                self.selection = super::half_page_down(self.selection, self.last_view_height, total);
             }
             KeyCode::Char('G') => {
                 if total > 0 {
@@ -227,6 +287,15 @@ impl DockerfileEngine {
                     }
                 }
             }
+            KeyCode::Char('E') => {
+                // Jump to previous FROM (stage)
+                for i in (0..self.selection).rev() {
+                    if matches!(self.lines[i].2, DockerLine::From { .. }) {
+                        self.selection = i;
+                        break;
+                    }
+                }
+            }
             KeyCode::Char('n') => {
                 if let Some(query) = self.last_match.clone() {
                     self.search_next(&query, true);
@@ -241,25 +310,26 @@ impl DockerfileEngine {
         }
     }
 
-    pub fn apply_search(&mut self, query: &str) {
+    fn apply_search(&mut self, query: &str, forward: bool) -> bool {
         let trimmed = query.trim();
         if trimmed.is_empty() {
-            return;
+            return false;
         }
         self.last_query = Some(trimmed.to_string());
-        self.search_next(trimmed, true);
+        let found = self.search_next(trimmed, forward);
         self.last_match = Some(trimmed.to_string());
+        found
     }
 
-    pub fn apply_filter(&mut self, query: &str) {
-        self.apply_search(query);
+    fn apply_filter(&mut self, query: &str) -> bool {
+        self.apply_search(query, true)
     }
 
-    pub fn clear_filter(&mut self) {
+    fn clear_filter(&mut self) {
         self.last_query = None;
     }
 
-    pub fn breadcrumbs(&self) -> String {
+    fn breadcrumbs(&self) -> String {
         // Find current stage
         let mut stage = 0;
         for i in (0..=self.selection).rev() {
@@ -271,30 +341,36 @@ impl DockerfileEngine {
         format!("{} stage {} line {}", self.file_name, stage, self.selection + 1)
     }
 
-    pub fn status_line(&self) -> String {
+    fn status_line(&self) -> String {
         let query = self
             .last_query
             .as_ref()
             .map(|q| format!(" | search: {}", q))
             .unwrap_or_default();
         format!(
-            "j/k move | gg/G jump | Ctrl+u/d half-page | e next stage | n/N next/prev | / search{}",
+            "j/k move | gg/G jump | Ctrl+u/d half-page | e/E next/prev stage | n/N next/prev | / search{}",
             query
         )
     }
 
-    #[allow(dead_code)]
-    pub fn selected_path(&self) -> Option<String> {
+    /// Keys specific to this view, listed in the help overlay
+    fn help_lines(&self) -> Vec<Line<'static>> {
+        vec![
+            Line::from("  e/E          Next/previous build stage"),
+        ]
+    }
+
+    fn selected_path(&self) -> Option<String> {
         None
     }
 
     /// Get the content of the currently selected line
-    pub fn get_selected_line(&self) -> Option<String> {
+    fn get_selected_line(&self) -> Option<String> {
         self.lines.get(self.selection).map(|(_, raw, _)| raw.clone())
     }
 
     /// Get lines in a range (inclusive), joined by newlines
-    pub fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
+    fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
         let (start, end) = if start <= end { (start, end) } else { (end, start) };
         let total = self.lines.len();
         if start >= total { return None; }
@@ -304,25 +380,57 @@ impl DockerfileEngine {
     }
 
     /// Get current selection index (for visual mode)
-    pub fn selection(&self) -> usize {
+    fn selection(&self) -> usize {
         self.selection
     }
 
-    pub fn content_height(&self) -> usize {
+    fn select(&mut self, row: usize) {
+        self.selection = row.min(self.lines.len().saturating_sub(1));
+    }
+
+    /// Scroll so the selection sits at the top, middle or bottom of the view (`zt`/`zz`/`zb`)
+    fn align_scroll(&mut self, align: super::ScrollAlign) {
+        self.scroll = super::aligned_scroll(self.selection, self.last_view_height, align);
+    }
+
+    fn content_height(&mut self) -> usize {
         self.lines.len()
     }
 
-    pub fn render_plain_lines(&self, _width: u16) -> Vec<Line<'static>> {
+    /// Parsed structure for `--dump`
+    fn to_json(&mut self) -> serde_json::Value {
+        let mut stages = Vec::new();
+        let mut instructions = Vec::new();
+        for (line_no, _, parsed) in &self.lines {
+            let (instruction, args) = match parsed {
+                DockerLine::From { image, alias, stage_num } => {
+                    stages.push(json!({ "stage": stage_num, "image": image, "alias": alias, "line": line_no }));
+                    ("FROM".to_string(), json!({ "image": image, "alias": alias }))
+                }
+                DockerLine::Instruction { cmd, args } => (cmd.to_uppercase(), json!(args)),
+                DockerLine::Arg { name, default } => ("ARG".to_string(), json!({ "name": name, "default": default })),
+                DockerLine::Env { key, value } => ("ENV".to_string(), json!({ "key": key, "value": value })),
+                DockerLine::Label { key, value } => ("LABEL".to_string(), json!({ "key": key, "value": value })),
+                DockerLine::Comment(_) | DockerLine::Empty => continue,
+            };
+            instructions.push(json!({ "line": line_no, "instruction": instruction, "args": args }));
+        }
+        json!({ "stages": stages, "instructions": instructions })
+    }
+
+    fn render_plain_lines(&mut self, _width: u16) -> Vec<Line<'static>> {
         let line_no_width = self.lines.len().max(1).to_string().len().max(2);
         self.lines
             .iter()
             .map(|(line_no, _raw, parsed)| {
                 let mut spans = Vec::new();
-                spans.push(Span::styled(
-                    format!("{:>width$} ", line_no, width = line_no_width),
-                    Style::default().fg(Color::LightYellow),
-                ));
-                spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
+                if super::line_numbers() {
+                    spans.push(Span::styled(
+                        format!("{:>width$} ", line_no, width = line_no_width),
+                        Style::default().fg(Color::LightYellow),
+                    ));
+                    spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
+                }
 
                 match parsed {
                     DockerLine::From { image, alias, stage_num } => {
@@ -348,42 +456,6 @@ impl DockerfileEngine {
             })
             .collect()
     }
-
-    fn search_next(&mut self, query: &str, forward: bool) {
-        let lower = query.to_lowercase();
-        let total = self.lines.len().max(1);
-        let start = if forward {
-            (self.selection + 1) % total
-        } else {
-            self.selection.saturating_sub(1)
-        };
-
-        for offset in 0..total {
-            let idx = if forward {
-                (start + offset) % total
-            } else {
-                (start + total - offset % total) % total
-            };
-            let text = match &self.lines[idx].2 {
-                DockerLine::From { image, alias, .. } => {
-                    format!("FROM {} {}", image, alias.as_deref().unwrap_or(""))
-                }
-                DockerLine::Instruction { cmd, args } => format!("{} {}", cmd, args),
-                DockerLine::Comment(text) => text.clone(),
-                DockerLine::Arg { name, default } => {
-                    format!("ARG {} {}", name, default.as_deref().unwrap_or(""))
-                }
-                DockerLine::Env { key, value } => format!("ENV {}={}", key, value),
-                DockerLine::Label { key, value } => format!("LABEL {}={}", key, value),
-                DockerLine::Empty => String::new(),
-            };
-            if text.to_lowercase().contains(&lower) {
-                self.selection = idx;
-                break;
-            }
-        }
-        self.last_match = Some(query.to_string());
-    }
 }
 
 
@@ -398,11 +470,11 @@ fn parse_dockerfile(content: &str) -> Vec<(usize, String, DockerLine)> {
         let trimmed = line.trim();
 
         // Handle line continuation
-        if trimmed.ends_with('\\') {
+        if let Some(head) = trimmed.strip_suffix('\\') {
             if continued_line.is_empty() {
                 continued_start = line_no;
             }
-            continued_line.push_str(&trimmed[..trimmed.len() - 1]);
+            continued_line.push_str(head);
             continued_line.push(' ');
             continue;
         }
@@ -490,17 +562,3 @@ fn parse_dockerfile(content: &str) -> Vec<(usize, String, DockerLine)> {
 
     lines
 }
-
-fn truncate(value: &str, max: usize) -> String {
-    if value.len() <= max {
-        return value.to_string();
-    }
-    let mut out = value.chars().take(max.saturating_sub(3)).collect::<String>();
-    out.push_str("...");
-    out
-}
-
-fn page_jump(view_height: usize) -> usize {
-    let half = view_height / 2;
-    if half == 0 { 1 } else { half }
-}
@@ -1,3 +1,4 @@
+use std::collections::HashSet;
 use std::path::Path;
 
 use anyhow::Result;
@@ -6,6 +7,10 @@ use ratatui::layout::{Constraint, Rect};
 use ratatui::style::{Color, Style, Stylize};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Cell, Row, Table, TableState};
+use serde_json::json;
+
+use super::util::{search_matches, truncate};
+use super::Engine;
 
 #[derive(Clone)]
 struct EnvEntry {
@@ -14,6 +19,18 @@ struct EnvEntry {
     category: String,
     is_secret: bool,
     line_no: usize,
+    drift: Drift,
+}
+
+/// How a key compares against the `--compare` file
+#[derive(Clone, Copy, PartialEq)]
+enum Drift {
+    /// Declared in both files (or no comparison)
+    Shared,
+    /// Only in this file
+    Extra,
+    /// Only in the other file; the row shows its value and line there
+    Missing,
 }
 
 pub struct EnvEngine {
@@ -26,20 +43,27 @@ pub struct EnvEngine {
     last_view_height: usize,
     last_match: Option<String>,
     show_secrets: bool,
+    /// Name of the file given to `--compare`
+    compared_with: Option<String>,
     /// Visual selection range (start, end) for highlighting
     pub visual_range: Option<(usize, usize)>,
 }
 
 impl EnvEngine {
     pub fn from_path(path: &Path) -> Result<Self> {
-        let content = std::fs::read_to_string(path)?;
+        Self::from_bytes(path, &std::fs::read(path)?)
+    }
+
+    /// Create from in-memory bytes; `path` only supplies the name and extension
+    pub fn from_bytes(path: &Path, bytes: &[u8]) -> Result<Self> {
+        let content = std::str::from_utf8(bytes)?;
         let file_name = path
             .file_name()
             .and_then(|s| s.to_str())
             .unwrap_or("")
             .to_string();
 
-        let entries = parse_env(&content);
+        let entries = parse_env(content);
 
         Ok(Self {
             entries,
@@ -51,20 +75,106 @@ impl EnvEngine {
             last_view_height: 0,
             last_match: None,
             show_secrets: false,
+            compared_with: None,
             visual_range: None,
         })
     }
 
-    pub fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+    /// Mark keys missing from or extra to `other` (e.g. `.env` against `.env.example`);
+    /// missing keys are appended with the other file's value
+    pub fn compare_with(&mut self, other: &Path) -> Result<()> {
+        let other_entries = parse_env(&std::fs::read_to_string(other)?);
+        let other_keys: HashSet<&str> = other_entries.iter().map(|e| e.key.as_str()).collect();
+        for entry in &mut self.entries {
+            if !other_keys.contains(entry.key.as_str()) {
+                entry.drift = Drift::Extra;
+            }
+        }
+        let own_keys: HashSet<String> = self.entries.iter().map(|e| e.key.clone()).collect();
+        for mut entry in other_entries {
+            if !own_keys.contains(&entry.key) {
+                entry.drift = Drift::Missing;
+                self.entries.push(entry);
+            }
+        }
+        self.compared_with = Some(
+            other.file_name().and_then(|s| s.to_str()).unwrap_or("").to_string(),
+        );
+        Ok(())
+    }
+
+    fn drift_count(&self, drift: Drift) -> usize {
+        self.entries.iter().filter(|e| e.drift == drift).count()
+    }
+
+    /// Move to the next or previous key that differs from the compared file, wrapping around
+    fn jump_to_drift(&mut self, forward: bool) {
+        let total = self.entries.len();
+        let found = (1..=total)
+            .map(|offset| {
+                if forward {
+                    (self.selection + offset) % total
+                } else {
+                    (self.selection + total - offset) % total
+                }
+            })
+            .find(|&idx| self.entries[idx].drift != Drift::Shared);
+        if let Some(idx) = found {
+            self.selection = idx;
+        }
+    }
+
+    fn search_next(&mut self, query: &str, forward: bool) -> bool {
+        let lower = query.to_lowercase();
+        let mut found = false;
+        let total = self.entries.len().max(1);
+        let start = if forward {
+            (self.selection + 1) % total
+        } else {
+            (self.selection + total - 1) % total
+        };
+
+        for offset in 0..total {
+            let idx = if forward {
+                (start + offset) % total
+            } else {
+                (start + total - offset % total) % total
+            };
+            let entry = &self.entries[idx];
+            if search_matches(&entry.key, &lower)
+                || search_matches(&entry.value, &lower)
+                || search_matches(&entry.category, &lower)
+            {
+                self.selection = idx;
+                found = true;
+                break;
+            }
+        }
+        self.last_match = Some(query.to_string());
+        found
+    }
+}
+
+impl Engine for EnvEngine {
+    fn name(&self) -> &'static str {
+        "EnvEngine"
+    }
+
+    fn set_visual_range(&mut self, range: Option<(usize, usize)>) {
+        self.visual_range = range;
+    }
+
+    fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
         self.last_view_height = area.height as usize;
         let height = area.height.saturating_sub(1) as usize;
 
-        if self.selection < self.scroll {
-            self.scroll = self.selection;
-        } else if self.selection >= self.scroll + height {
-            self.scroll = self.selection.saturating_sub(height - 1);
+        if self.entries.is_empty() {
+            super::render_placeholder(frame, area, "(empty file)");
+            return;
         }
 
+        self.scroll = super::follow_selection(self.scroll, self.selection, height, self.entries.len());
+
         let slice = if self.entries.is_empty() {
             &[][..]
         } else {
@@ -76,13 +186,19 @@ impl EnvEngine {
             .fg(Color::Black)
             .bg(Color::LightBlue)
             .bold();
-        let headers = vec![
+        let comparing = self.compared_with.is_some();
+        let mut headers = vec![
             Cell::from("#").style(header_style),
             Cell::from("│").style(Style::default().fg(Color::LightBlue)),
+        ];
+        if comparing {
+            headers.push(Cell::from("Drift").style(header_style));
+        }
+        headers.extend([
             Cell::from("Category").style(header_style),
             Cell::from("Key").style(header_style),
             Cell::from("Value").style(header_style),
-        ];
+        ]);
         let header = Row::new(headers);
 
         let mut rows = Vec::new();
@@ -90,7 +206,7 @@ impl EnvEngine {
             let display_value = if entry.is_secret && !self.show_secrets {
                 "••••••••".to_string()
             } else {
-                truncate(&entry.value, 50)
+                truncate(&entry.value, super::preview_len(50))
             };
 
             // Smart value coloring
@@ -109,26 +225,34 @@ impl EnvEngine {
                 }
             };
 
-            let cells = vec![
+            let mut cells = vec![
                 Cell::from(entry.line_no.to_string())
                     .style(Style::default().fg(Color::DarkGray)),
                 Cell::from("│").style(Style::default().fg(Color::DarkGray)),
+            ];
+            if comparing {
+                let (label, color) = drift_label(entry.drift);
+                cells.push(Cell::from(label).style(Style::default().fg(color).bold()));
+            }
+            cells.extend([
                 Cell::from(entry.category.clone())
                     .style(Style::default().fg(Color::Magenta)),
                 Cell::from(entry.key.clone())
                     .style(Style::default().fg(Color::White).bold()),
                 Cell::from(display_value).style(value_style),
-            ];
+            ]);
             rows.push(Row::new(cells));
         }
 
-        let widths = vec![
-            Constraint::Length(5),
-            Constraint::Length(2),
+        let mut widths = vec![Constraint::Length(5), Constraint::Length(2)];
+        if comparing {
+            widths.push(Constraint::Length(8));
+        }
+        widths.extend([
             Constraint::Length(12),
             Constraint::Length(28),
             Constraint::Min(20),
-        ];
+        ]);
 
         let table = Table::new(rows, widths)
             .header(header)
@@ -143,7 +267,8 @@ impl EnvEngine {
         frame.render_stateful_widget(table, area, &mut state);
     }
 
-    pub fn handle_key(&mut self, key: KeyEvent) {
+    #[allow(clippy::collapsible_match)]
+    fn handle_key(&mut self, key: KeyEvent) {
         match key.code {
             KeyCode::Char('g') => {
                 if self.pending_g {
@@ -169,12 +294,10 @@ impl EnvEngine {
                 self.selection = self.selection.saturating_sub(1);
             }
             KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let jump = page_jump(self.last_view_height).min(self.selection);
-                self.selection = self.selection.saturating_sub(jump);
+                self.selection = super::half_page_up(self.selection, self.last_view_height);
             }
             KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let jump = page_jump(self.last_view_height).min(self.entries.len().saturating_sub(1));
-                self.selection = (self.selection + jump).min(self.entries.len().saturating_sub(1));
+                self.selection = super::half_page_down(self.selection, self.last_view_height, self.entries.len());
             }
             KeyCode::Char('G') => {
                 if !self.entries.is_empty() {
@@ -184,6 +307,8 @@ impl EnvEngine {
             KeyCode::Char('s') => {
                 self.show_secrets = !self.show_secrets;
             }
+            KeyCode::Char('e') => self.jump_to_drift(true),
+            KeyCode::Char('E') => self.jump_to_drift(false),
             KeyCode::Char('n') => {
                 if let Some(query) = self.last_match.clone() {
                     self.search_next(&query, true);
@@ -198,48 +323,65 @@ impl EnvEngine {
         }
     }
 
-    pub fn apply_search(&mut self, query: &str) {
+    fn apply_search(&mut self, query: &str, forward: bool) -> bool {
         let trimmed = query.trim();
         if trimmed.is_empty() {
-            return;
+            return false;
         }
         self.last_query = Some(trimmed.to_string());
-        self.search_next(trimmed, true);
+        let found = self.search_next(trimmed, forward);
         self.last_match = Some(trimmed.to_string());
+        found
     }
 
-    pub fn apply_filter(&mut self, query: &str) {
-        self.apply_search(query);
+    fn apply_filter(&mut self, query: &str) -> bool {
+        self.apply_search(query, true)
     }
 
-    pub fn clear_filter(&mut self) {
+    fn clear_filter(&mut self) {
         self.last_query = None;
     }
 
-    pub fn breadcrumbs(&self) -> String {
+    fn breadcrumbs(&self) -> String {
         format!("{} row {}/{}", self.file_name, self.selection + 1, self.entries.len())
     }
 
-    pub fn status_line(&self) -> String {
+    fn status_line(&self) -> String {
         let secrets = if self.show_secrets { "shown" } else { "hidden" };
         let query = self
             .last_query
             .as_ref()
             .map(|q| format!(" | search: {}", q))
             .unwrap_or_default();
+        let drift = match &self.compared_with {
+            Some(other) => format!(
+                " | vs {}: {} missing, {} extra (e/E jump)",
+                other,
+                self.drift_count(Drift::Missing),
+                self.drift_count(Drift::Extra)
+            ),
+            None => String::new(),
+        };
         format!(
-            "j/k move | gg/G jump | Ctrl+u/d half-page | n/N next/prev | s toggle secrets ({}) | / search | f filter{}",
-            secrets, query
+            "j/k move | gg/G jump | Ctrl+u/d half-page | n/N next/prev | s toggle secrets ({}) | / search | f filter{}{}",
+            secrets, drift, query
         )
     }
 
-    #[allow(dead_code)]
-    pub fn selected_path(&self) -> Option<String> {
+    /// Keys specific to this view, listed in the help overlay
+    fn help_lines(&self) -> Vec<Line<'static>> {
+        vec![
+            Line::from("  s            Show/hide secret values"),
+            Line::from("  e/E          Next/prev key missing or extra (--compare)"),
+        ]
+    }
+
+    fn selected_path(&self) -> Option<String> {
         None
     }
 
     /// Get the content of the currently selected line
-    pub fn get_selected_line(&self) -> Option<String> {
+    fn get_selected_line(&self) -> Option<String> {
         if self.selection == 0 {
             Some("Category\tKey\tValue".to_string())
         } else {
@@ -251,7 +393,7 @@ impl EnvEngine {
     }
 
     /// Get lines in a range (inclusive), joined by newlines
-    pub fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
+    fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
         let (start, end) = if start <= end { (start, end) } else { (end, start) };
         let total = self.entries.len() + 1;
         if start >= total { return None; }
@@ -272,24 +414,61 @@ impl EnvEngine {
     }
 
     /// Get current selection index (for visual mode)
-    pub fn selection(&self) -> usize {
+    fn selection(&self) -> usize {
         self.selection
     }
 
-    pub fn content_height(&self) -> usize {
+    fn select(&mut self, row: usize) {
+        self.selection = row.min(self.entries.len().saturating_sub(1));
+    }
+
+    /// Scroll so the selection sits at the top, middle or bottom of the view (`zt`/`zz`/`zb`)
+    fn align_scroll(&mut self, align: super::ScrollAlign) {
+        self.scroll = super::aligned_scroll(self.selection, self.last_view_height, align);
+    }
+
+    fn content_height(&mut self) -> usize {
         self.entries.len() + 1
     }
 
-    pub fn render_plain_lines(&self, _width: u16) -> Vec<Line<'static>> {
+    /// Parsed structure for `--dump`
+    fn to_json(&mut self) -> serde_json::Value {
+        let variables: Vec<_> = self
+            .entries
+            .iter()
+            .map(|e| {
+                let mut variable = json!({
+                    "key": e.key,
+                    "value": e.value,
+                    "category": e.category,
+                    "secret": e.is_secret,
+                    "line": e.line_no,
+                });
+                if self.compared_with.is_some() {
+                    variable["drift"] = json!(drift_label(e.drift).0);
+                }
+                variable
+            })
+            .collect();
+        json!({ "variables": variables })
+    }
+
+    fn render_plain_lines(&mut self, _width: u16) -> Vec<Line<'static>> {
         let mut lines = Vec::new();
         let header_style = Style::default().fg(Color::Black).bg(Color::LightBlue);
-        let headers = vec![
+        let comparing = self.compared_with.is_some();
+        let mut headers = vec![
             Span::styled("#", header_style),
             Span::styled("│", Style::default().fg(Color::LightBlue)),
+        ];
+        if comparing {
+            headers.push(Span::styled("Drift", header_style));
+        }
+        headers.extend([
             Span::styled("Category", header_style),
             Span::styled("Key", header_style),
             Span::styled("Value", header_style),
-        ];
+        ]);
         lines.push(Line::from(join_with_sep(headers, "  ")));
 
         for entry in &self.entries {
@@ -299,44 +478,23 @@ impl EnvEngine {
                 entry.value.clone()
             };
 
-            let spans = vec![
+            let mut spans = vec![
                 Span::styled(entry.line_no.to_string(), Style::default().fg(Color::LightYellow)),
                 Span::styled("│", Style::default().fg(Color::LightBlue)),
+            ];
+            if comparing {
+                let (label, color) = drift_label(entry.drift);
+                spans.push(Span::styled(label, Style::default().fg(color).bold()));
+            }
+            spans.extend([
                 Span::styled(entry.category.clone(), Style::default().fg(Color::LightMagenta)),
                 Span::styled(entry.key.clone(), Style::default().fg(Color::LightCyan)),
                 Span::styled(display_value, Style::default().fg(Color::LightGreen)),
-            ];
+            ]);
             lines.push(Line::from(join_with_sep(spans, "  ")));
         }
         lines
     }
-
-    fn search_next(&mut self, query: &str, forward: bool) {
-        let lower = query.to_lowercase();
-        let total = self.entries.len().max(1);
-        let start = if forward {
-            (self.selection + 1) % total
-        } else {
-            self.selection.saturating_sub(1)
-        };
-
-        for offset in 0..total {
-            let idx = if forward {
-                (start + offset) % total
-            } else {
-                (start + total - offset % total) % total
-            };
-            let entry = &self.entries[idx];
-            if entry.key.to_lowercase().contains(&lower)
-                || entry.value.to_lowercase().contains(&lower)
-                || entry.category.to_lowercase().contains(&lower)
-            {
-                self.selection = idx;
-                break;
-            }
-        }
-        self.last_match = Some(query.to_string());
-    }
 }
 
 fn parse_env(content: &str) -> Vec<EnvEntry> {
@@ -371,6 +529,7 @@ fn parse_env(content: &str) -> Vec<EnvEntry> {
                 category,
                 is_secret,
                 line_no: line_no + 1,
+                drift: Drift::Shared,
             });
         }
     }
@@ -378,6 +537,14 @@ fn parse_env(content: &str) -> Vec<EnvEntry> {
     entries
 }
 
+fn drift_label(drift: Drift) -> (&'static str, Color) {
+    match drift {
+        Drift::Shared => ("ok", Color::DarkGray),
+        Drift::Extra => ("extra", Color::Yellow),
+        Drift::Missing => ("missing", Color::Red),
+    }
+}
+
 fn categorize_key(key: &str) -> String {
     let upper = key.to_uppercase();
 
@@ -424,20 +591,6 @@ fn is_secret_key(key: &str) -> bool {
         || upper.contains("PRIVATE")
 }
 
-fn truncate(value: &str, max: usize) -> String {
-    if value.len() <= max {
-        return value.to_string();
-    }
-    let mut out = value.chars().take(max.saturating_sub(3)).collect::<String>();
-    out.push_str("...");
-    out
-}
-
-fn page_jump(view_height: usize) -> usize {
-    let half = view_height / 2;
-    if half == 0 { 1 } else { half }
-}
-
 fn join_with_sep(mut spans: Vec<Span<'static>>, sep: &str) -> Vec<Span<'static>> {
     if spans.is_empty() {
         return spans;
@@ -451,3 +604,29 @@ fn join_with_sep(mut spans: Vec<Span<'static>>, sep: &str) -> Vec<Span<'static>>
     }
     joined
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn compare_flags_missing_and_extra_keys() {
+        let mut example = tempfile::NamedTempFile::new().unwrap();
+        std::io::Write::write_all(&mut example, b"DATABASE_URL=postgres://\nAPI_KEY=\nPORT=8080\n").unwrap();
+        let mut engine = EnvEngine::from_bytes(Path::new(".env"), b"PORT=3000\nDEBUG=1\n").unwrap();
+        engine.compare_with(example.path()).unwrap();
+
+        let drift: Vec<(&str, &str)> =
+            engine.entries.iter().map(|e| (e.key.as_str(), drift_label(e.drift).0)).collect();
+        assert_eq!(
+            drift,
+            [("PORT", "ok"), ("DEBUG", "extra"), ("DATABASE_URL", "missing"), ("API_KEY", "missing")]
+        );
+        assert!(engine.status_line().contains("2 missing, 1 extra"));
+
+        engine.jump_to_drift(true);
+        assert_eq!(engine.selection, 1);
+        engine.jump_to_drift(false);
+        assert_eq!(engine.selection, 3);
+    }
+}
@@ -6,6 +6,10 @@ use ratatui::layout::Rect;
 use ratatui::style::{Color, Style, Stylize};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph};
+use serde_json::json;
+
+use super::util::search_matches;
+use super::Engine;
 
 #[derive(Clone)]
 enum GitIgnoreLine {
@@ -16,6 +20,8 @@ enum GitIgnoreLine {
 
 pub struct GitIgnoreEngine {
     lines: Vec<(usize, String, GitIgnoreLine)>,  // (line_no, raw, parsed)
+    /// Lint warning for each line, parallel to `lines`
+    warnings: Vec<Option<String>>,
     selection: usize,
     scroll: usize,
     file_name: String,
@@ -29,17 +35,24 @@ pub struct GitIgnoreEngine {
 
 impl GitIgnoreEngine {
     pub fn from_path(path: &Path) -> Result<Self> {
-        let content = std::fs::read_to_string(path)?;
+        Self::from_bytes(path, &std::fs::read(path)?)
+    }
+
+    /// Create from in-memory bytes; `path` only supplies the name and extension
+    pub fn from_bytes(path: &Path, bytes: &[u8]) -> Result<Self> {
+        let content = std::str::from_utf8(bytes)?;
         let file_name = path
             .file_name()
             .and_then(|s| s.to_str())
             .unwrap_or("")
             .to_string();
 
-        let lines = parse_gitignore(&content);
+        let lines = parse_gitignore(content);
+        let warnings = lint_gitignore(&lines);
 
         Ok(Self {
             lines,
+            warnings,
             selection: 0,
             scroll: 0,
             file_name,
@@ -51,16 +64,58 @@ impl GitIgnoreEngine {
         })
     }
 
-    pub fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+    fn search_next(&mut self, query: &str, forward: bool) -> bool {
+        let lower = query.to_lowercase();
+        let mut found = false;
+        let total = self.lines.len().max(1);
+        let start = if forward {
+            (self.selection + 1) % total
+        } else {
+            (self.selection + total - 1) % total
+        };
+
+        for offset in 0..total {
+            let idx = if forward {
+                (start + offset) % total
+            } else {
+                (start + total - offset % total) % total
+            };
+            let text = match &self.lines[idx].2 {
+                GitIgnoreLine::Pattern { pattern, .. } => pattern.clone(),
+                GitIgnoreLine::Comment(text) => text.clone(),
+                GitIgnoreLine::Empty => String::new(),
+            };
+            if search_matches(&text, &lower) {
+                self.selection = idx;
+                found = true;
+                break;
+            }
+        }
+        self.last_match = Some(query.to_string());
+        found
+    }
+}
+
+impl Engine for GitIgnoreEngine {
+    fn name(&self) -> &'static str {
+        "GitIgnoreEngine"
+    }
+
+    fn set_visual_range(&mut self, range: Option<(usize, usize)>) {
+        self.visual_range = range;
+    }
+
+    fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
         let height = area.height as usize;
         self.last_view_height = height;
 
-        if self.selection < self.scroll {
-            self.scroll = self.selection;
-        } else if self.selection >= self.scroll + height {
-            self.scroll = self.selection.saturating_sub(height - 1);
+        if self.lines.is_empty() {
+            super::render_placeholder(frame, area, "(empty file)");
+            return;
         }
 
+        self.scroll = super::follow_selection(self.scroll, self.selection, height, self.lines.len());
+
         let line_no_width = self.lines.len().max(1).to_string().len().max(2);
 
         let visible: Vec<Line> = self.lines
@@ -79,8 +134,10 @@ impl GitIgnoreEngine {
                 } else {
                     Style::default().fg(Color::DarkGray)
                 };
-                spans.push(Span::styled(line_no_str, line_no_style));
-                spans.push(Span::styled("│ ", Style::default().fg(Color::DarkGray)));
+                if super::line_numbers() {
+                    spans.push(Span::styled(line_no_str, line_no_style));
+                    spans.push(Span::styled("│ ", Style::default().fg(Color::DarkGray)));
+                }
 
                 match parsed {
                     GitIgnoreLine::Pattern { pattern, is_negated, is_dir } => {
@@ -121,6 +178,15 @@ impl GitIgnoreEngine {
                             };
                             spans.push(Span::styled(format!("  # {}", hint), hint_style));
                         }
+
+                        if let Some(warning) = &self.warnings[row] {
+                            let warn_style = if selected {
+                                Style::default().fg(Color::Black).bg(Color::LightBlue).bold()
+                            } else {
+                                Style::default().fg(Color::Yellow).bold()
+                            };
+                            spans.push(Span::styled(format!("  ⚠ {}", warning), warn_style));
+                        }
                     }
                     GitIgnoreLine::Comment(text) => {
                         let style = if selected {
@@ -141,7 +207,8 @@ impl GitIgnoreEngine {
         frame.render_widget(Paragraph::new(visible).block(block), area);
     }
 
-    pub fn handle_key(&mut self, key: KeyEvent) {
+    #[allow(clippy::collapsible_match)]
+    fn handle_key(&mut self, key: KeyEvent) {
         match key.code {
             KeyCode::Char('g') => {
                 if self.pending_g {
@@ -168,18 +235,34 @@ impl GitIgnoreEngine {
                 self.selection = self.selection.saturating_sub(1);
             }
             KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let jump = page_jump(self.last_view_height).min(self.selection);
-                self.selection = self.selection.saturating_sub(jump);
+                self.selection = super::half_page_up(self.selection, self.last_view_height);
             }
             KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let jump = page_jump(self.last_view_height).min(total.saturating_sub(1));
-                self.selection = (self.selection + jump).min(total.saturating_sub(1));
+                self.selection = super::half_page_down(self.selection, self.last_view_height, total);
             }
             KeyCode::Char('G') => {
                 if total > 0 {
                     self.selection = total - 1;
                 }
             }
+            KeyCode::Char('e') => {
+                // Jump to next warning
+                for i in (self.selection + 1)..total {
+                    if self.warnings[i].is_some() {
+                        self.selection = i;
+                        break;
+                    }
+                }
+            }
+            KeyCode::Char('E') => {
+                // Jump to previous warning
+                for i in (0..self.selection).rev() {
+                    if self.warnings[i].is_some() {
+                        self.selection = i;
+                        break;
+                    }
+                }
+            }
             KeyCode::Char('n') => {
                 if let Some(query) = self.last_match.clone() {
                     self.search_next(&query, true);
@@ -194,52 +277,64 @@ impl GitIgnoreEngine {
         }
     }
 
-    pub fn apply_search(&mut self, query: &str) {
+    fn apply_search(&mut self, query: &str, forward: bool) -> bool {
         let trimmed = query.trim();
         if trimmed.is_empty() {
-            return;
+            return false;
         }
         self.last_query = Some(trimmed.to_string());
-        self.search_next(trimmed, true);
+        let found = self.search_next(trimmed, forward);
         self.last_match = Some(trimmed.to_string());
+        found
     }
 
-    pub fn apply_filter(&mut self, query: &str) {
-        self.apply_search(query);
+    fn apply_filter(&mut self, query: &str) -> bool {
+        self.apply_search(query, true)
     }
 
-    pub fn clear_filter(&mut self) {
+    fn clear_filter(&mut self) {
         self.last_query = None;
     }
 
-    pub fn breadcrumbs(&self) -> String {
+    fn breadcrumbs(&self) -> String {
         format!("{} line {}", self.file_name, self.selection + 1)
     }
 
-    pub fn status_line(&self) -> String {
+    fn status_line(&self) -> String {
         let query = self
             .last_query
             .as_ref()
             .map(|q| format!(" | search: {}", q))
             .unwrap_or_default();
+        let warnings = match self.warnings.iter().flatten().count() {
+            0 => String::new(),
+            1 => " | 1 warning".to_string(),
+            n => format!(" | {} warnings", n),
+        };
         format!(
-            "j/k move | gg/G jump | Ctrl+u/d half-page | n/N next/prev | / search{}",
-            query
+            "j/k move | gg/G jump | e/E next/prev warning | n/N next/prev | / search{}{}",
+            warnings, query
         )
     }
 
-    #[allow(dead_code)]
-    pub fn selected_path(&self) -> Option<String> {
+    /// Keys specific to this view, listed in the help overlay
+    fn help_lines(&self) -> Vec<Line<'static>> {
+        vec![
+            Line::from("  e/E          Next/previous warning"),
+        ]
+    }
+
+    fn selected_path(&self) -> Option<String> {
         None
     }
 
     /// Get the content of the currently selected line
-    pub fn get_selected_line(&self) -> Option<String> {
+    fn get_selected_line(&self) -> Option<String> {
         self.lines.get(self.selection).map(|(_, raw, _)| raw.clone())
     }
 
     /// Get lines in a range (inclusive), joined by newlines
-    pub fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
+    fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
         let (start, end) = if start <= end { (start, end) } else { (end, start) };
         let total = self.lines.len();
         if start >= total { return None; }
@@ -249,25 +344,57 @@ impl GitIgnoreEngine {
     }
 
     /// Get current selection index (for visual mode)
-    pub fn selection(&self) -> usize {
+    fn selection(&self) -> usize {
         self.selection
     }
 
-    pub fn content_height(&self) -> usize {
+    fn select(&mut self, row: usize) {
+        self.selection = row.min(self.lines.len().saturating_sub(1));
+    }
+
+    /// Scroll so the selection sits at the top, middle or bottom of the view (`zt`/`zz`/`zb`)
+    fn align_scroll(&mut self, align: super::ScrollAlign) {
+        self.scroll = super::aligned_scroll(self.selection, self.last_view_height, align);
+    }
+
+    fn content_height(&mut self) -> usize {
         self.lines.len()
     }
 
-    pub fn render_plain_lines(&self, _width: u16) -> Vec<Line<'static>> {
+    /// Parsed structure for `--dump`
+    fn to_json(&mut self) -> serde_json::Value {
+        let rules: Vec<_> = self
+            .lines
+            .iter()
+            .zip(&self.warnings)
+            .filter_map(|((line_no, _, parsed), warning)| match parsed {
+                GitIgnoreLine::Pattern { pattern, is_negated, is_dir } => Some(json!({
+                    "line": line_no,
+                    "pattern": pattern,
+                    "negated": is_negated,
+                    "directory": is_dir,
+                    "warning": warning,
+                })),
+                _ => None,
+            })
+            .collect();
+        json!({ "rules": rules })
+    }
+
+    fn render_plain_lines(&mut self, _width: u16) -> Vec<Line<'static>> {
         let line_no_width = self.lines.len().max(1).to_string().len().max(2);
         self.lines
             .iter()
-            .map(|(line_no, _raw, parsed)| {
+            .zip(&self.warnings)
+            .map(|((line_no, _raw, parsed), warning)| {
                 let mut spans = Vec::new();
-                spans.push(Span::styled(
-                    format!("{:>width$} ", line_no, width = line_no_width),
-                    Style::default().fg(Color::DarkGray),
-                ));
-                spans.push(Span::styled("│ ", Style::default().fg(Color::DarkGray)));
+                if super::line_numbers() {
+                    spans.push(Span::styled(
+                        format!("{:>width$} ", line_no, width = line_no_width),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                    spans.push(Span::styled("│ ", Style::default().fg(Color::DarkGray)));
+                }
 
                 match parsed {
                     GitIgnoreLine::Pattern { pattern, is_negated, .. } => {
@@ -276,6 +403,9 @@ impl GitIgnoreEngine {
                         }
                         let color = if *is_negated { Color::LightGreen } else { Color::LightRed };
                         spans.push(Span::styled(pattern.clone(), Style::default().fg(color)));
+                        if let Some(warning) = warning {
+                            spans.push(Span::styled(format!("  ⚠ {}", warning), Style::default().fg(Color::Yellow)));
+                        }
                     }
                     GitIgnoreLine::Comment(text) => {
                         spans.push(Span::styled(text.clone(), Style::default().fg(Color::DarkGray)));
@@ -287,34 +417,6 @@ impl GitIgnoreEngine {
             })
             .collect()
     }
-
-    fn search_next(&mut self, query: &str, forward: bool) {
-        let lower = query.to_lowercase();
-        let total = self.lines.len().max(1);
-        let start = if forward {
-            (self.selection + 1) % total
-        } else {
-            self.selection.saturating_sub(1)
-        };
-
-        for offset in 0..total {
-            let idx = if forward {
-                (start + offset) % total
-            } else {
-                (start + total - offset % total) % total
-            };
-            let text = match &self.lines[idx].2 {
-                GitIgnoreLine::Pattern { pattern, .. } => pattern.clone(),
-                GitIgnoreLine::Comment(text) => text.clone(),
-                GitIgnoreLine::Empty => String::new(),
-            };
-            if text.to_lowercase().contains(&lower) {
-                self.selection = idx;
-                break;
-            }
-        }
-        self.last_match = Some(query.to_string());
-    }
 }
 
 fn parse_gitignore(content: &str) -> Vec<(usize, String, GitIgnoreLine)> {
@@ -355,6 +457,185 @@ fn parse_gitignore(content: &str) -> Vec<(usize, String, GitIgnoreLine)> {
     lines
 }
 
+/// A pattern line split into `/`-separated components for the lint pass
+struct Rule<'a> {
+    line_no: usize,
+    parts: Vec<&'a str>,
+    /// A slash at the start or middle ties the pattern to the ignore file's directory
+    anchored: bool,
+    is_dir: bool,
+    is_negated: bool,
+}
+
+impl Rule<'_> {
+    /// Whether this rule matches `path` (components from the ignore file's directory)
+    fn matches(&self, path: &[&str]) -> bool {
+        if self.anchored {
+            self.parts.len() == path.len() && self.parts.iter().zip(path).all(|(p, c)| covers(p, c))
+        } else {
+            path.last().is_some_and(|c| covers(self.parts[0], c))
+        }
+    }
+
+    /// Whether this rule already ignores everything `other` would
+    fn shadows(&self, other: &Rule) -> bool {
+        let dir_only_mismatch = self.is_dir && !other.is_dir;
+        if self.parts == other.parts && self.anchored == other.anchored {
+            return !dir_only_mismatch;
+        }
+        if self.parts.contains(&"**") || other.parts.contains(&"**") {
+            return false;
+        }
+        // Files inside a directory this rule excludes
+        if other.anchored && (1..other.parts.len()).any(|k| self.matches(&other.parts[..k])) {
+            return true;
+        }
+        // A directory-only rule can't cover one that also matches files
+        if dir_only_mismatch || (self.anchored && !other.anchored) {
+            return false;
+        }
+        self.matches(&other.parts)
+    }
+}
+
+/// Flag invalid patterns, rules that can't ignore anything new, and negations
+/// that git can never apply because a parent directory is excluded
+fn lint_gitignore(lines: &[(usize, String, GitIgnoreLine)]) -> Vec<Option<String>> {
+    let mut rules: Vec<Rule> = Vec::new();
+    lines
+        .iter()
+        .map(|(line_no, raw, parsed)| {
+            let GitIgnoreLine::Pattern { pattern, is_negated, is_dir } = parsed else {
+                return None;
+            };
+            if let Some(problem) = syntax_problem(raw, pattern) {
+                return Some(problem);
+            }
+            let trimmed = pattern.strip_prefix('/').unwrap_or(pattern);
+            let rule = Rule {
+                line_no: *line_no,
+                parts: trimmed.split('/').collect(),
+                anchored: pattern.contains('/'),
+                is_dir: *is_dir,
+                is_negated: *is_negated,
+            };
+            let warning = if rule.is_negated {
+                excluded_parent(&rules, &rule).map(|line| {
+                    format!("never re-included: a parent directory is excluded by line {}", line)
+                })
+            } else {
+                // A negation in between may re-include part of an earlier rule; stay quiet then
+                let earlier = rules.iter().rev().take_while(|r| !r.is_negated);
+                earlier
+                    .filter(|r| r.shadows(&rule))
+                    .last()
+                    .map(|r| format!("redundant: already ignored by line {}", r.line_no))
+            };
+            rules.push(rule);
+            warning
+        })
+        .collect()
+}
+
+/// Line of the rule that leaves a parent directory of `negation` excluded, if any
+fn excluded_parent(rules: &[Rule], negation: &Rule) -> Option<usize> {
+    if !negation.anchored || negation.parts.contains(&"**") {
+        return None;
+    }
+    (1..negation.parts.len()).find_map(|k| {
+        let dir = &negation.parts[..k];
+        let mut excluded_by = None;
+        for rule in rules.iter().filter(|r| !r.parts.contains(&"**") && r.matches(dir)) {
+            excluded_by = (!rule.is_negated).then_some(rule.line_no);
+        }
+        excluded_by
+    })
+}
+
+/// Describe a pattern git can't use as written
+fn syntax_problem(raw: &str, pattern: &str) -> Option<String> {
+    if pattern.is_empty() || pattern == "/" {
+        return Some("empty pattern matches nothing".to_string());
+    }
+    // An escaped trailing space ("foo\ ") is fine; a bare trailing backslash is not
+    let trailing_backslashes = pattern.chars().rev().take_while(|&c| c == '\\').count();
+    if trailing_backslashes % 2 == 1 && raw.trim_end() == raw {
+        return Some("trailing backslash: pattern never matches".to_string());
+    }
+    let mut chars = pattern.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                chars.next();
+            }
+            '[' => {
+                // `]` right after `[` or `[!` is a literal member of the class
+                chars.next_if(|&c| c == '!' || c == '^');
+                chars.next_if_eq(&']');
+                if !chars.by_ref().any(|c| c == ']') {
+                    return Some("unclosed [ character class".to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+    let stray_stars = pattern
+        .split('/')
+        .any(|part| part.contains("**") && part != "**");
+    if stray_stars {
+        return Some("** is only special as a whole path component; here it acts like *".to_string());
+    }
+    None
+}
+
+/// Whether glob component `pattern` matches every name `name` can stand for
+fn covers(pattern: &str, name: &str) -> bool {
+    pattern == name || (!name.contains(['*', '?', '[', '\\']) && glob_match(pattern, name))
+}
+
+/// Match one path component against a gitignore glob (`*`, `?`, `[...]`, `\` escapes)
+pub(crate) fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+    glob_match_at(&pattern, &name)
+}
+
+fn glob_match_at(pattern: &[char], name: &[char]) -> bool {
+    match pattern.first() {
+        None => name.is_empty(),
+        Some('*') => (0..=name.len()).any(|skip| glob_match_at(&pattern[1..], &name[skip..])),
+        Some('?') => !name.is_empty() && glob_match_at(&pattern[1..], &name[1..]),
+        Some('[') => {
+            let Some((&c, rest)) = name.split_first() else {
+                return false;
+            };
+            let negated = matches!(pattern.get(1), Some('!' | '^'));
+            let start = if negated { 2 } else { 1 };
+            // The first `]` of a class is a literal member
+            let Some(close) = pattern.iter().skip(start + 1).position(|&p| p == ']') else {
+                return false;
+            };
+            let class = &pattern[start..start + 1 + close];
+            let mut hit = false;
+            let mut i = 0;
+            while i < class.len() {
+                if i + 2 < class.len() && class[i + 1] == '-' {
+                    hit |= class[i] <= c && c <= class[i + 2];
+                    i += 3;
+                } else {
+                    hit |= class[i] == c;
+                    i += 1;
+                }
+            }
+            hit != negated && glob_match_at(&pattern[start + 2 + close..], rest)
+        }
+        Some('\\') if pattern.len() > 1 => {
+            name.first() == Some(&pattern[1]) && glob_match_at(&pattern[2..], &name[1..])
+        }
+        Some(&p) => name.first() == Some(&p) && glob_match_at(&pattern[1..], &name[1..]),
+    }
+}
+
 fn categorize_pattern(pattern: &str) -> &'static str {
     if pattern.starts_with("*.") {
         return "extension";
@@ -386,7 +667,39 @@ fn categorize_pattern(pattern: &str) -> &'static str {
     ""
 }
 
-fn page_jump(view_height: usize) -> usize {
-    let half = view_height / 2;
-    if half == 0 { 1 } else { half }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn warnings(content: &str) -> Vec<Option<String>> {
+        lint_gitignore(&parse_gitignore(content))
+    }
+
+    #[test]
+    fn negation_under_excluded_directory_is_flagged() {
+        let found = warnings("build/\n!build/keep.txt\nlogs/*\n!logs/.gitkeep\n");
+        assert!(found[1].as_deref().unwrap().contains("line 1"));
+        assert_eq!(found[3], None);
+    }
+
+    #[test]
+    fn shadowed_and_invalid_rules_are_flagged() {
+        let found = warnings("*.log\ndebug.log\ntarget/\n/target/debug\nfoo\\\n[abc\nsrc/*.rs\n*.rs\n");
+        assert_eq!(found[0], None);
+        assert!(found[1].as_deref().unwrap().contains("line 1"));
+        assert!(found[3].as_deref().unwrap().contains("line 3"));
+        assert!(found[4].as_deref().unwrap().contains("backslash"));
+        assert!(found[5].as_deref().unwrap().contains("unclosed"));
+        // The broader rule comes later, so the earlier one isn't redundant
+        assert_eq!(found[6], None);
+        assert_eq!(found[7], None);
+    }
+
+    #[test]
+    fn glob_components_match() {
+        assert!(glob_match("*.log", "debug.log"));
+        assert!(glob_match("file[0-9].txt", "file7.txt"));
+        assert!(!glob_match("file[!0-9].txt", "file7.txt"));
+        assert!(!glob_match("?.rs", "ab.rs"));
+    }
 }
@@ -8,14 +8,21 @@ use ratatui::layout::Rect;
 use ratatui::style::{Color, Style, Stylize};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph};
+use serde_json::json;
+
+use super::util::format_size;
+use super::Engine;
 
 const BYTES_PER_LINE: usize = 16;
 const MAX_FILE_SIZE: u64 = 100 * 1024 * 1024; // 100MB limit
 
 pub struct HexEngine {
     file_path: std::path::PathBuf,
+    /// In-memory content; when None, bytes are read from `file_path` on demand
+    data: Option<Vec<u8>>,
     file_size: u64,
-    selection: usize,
+    /// Byte offset of the cursor; the selected row is derived from it
+    cursor: usize,
     scroll: usize,
     file_name: String,
     last_query: Option<String>,
@@ -25,23 +32,34 @@ pub struct HexEngine {
     cache_start: usize,
     /// Visual selection range (start, end) for highlighting
     pub visual_range: Option<(usize, usize)>,
+    /// Byte columns of a visual block selection; None highlights whole rows
+    visual_columns: Option<(usize, usize)>,
 }
 
 impl HexEngine {
     pub fn from_path(path: &Path) -> Result<Self> {
+        let metadata = std::fs::metadata(path)?;
+        Ok(Self::new(path, None, metadata.len()))
+    }
+
+    /// Create from in-memory bytes; `path` only supplies the displayed name
+    pub fn from_bytes(path: &Path, bytes: &[u8]) -> Result<Self> {
+        let len = bytes.len() as u64;
+        Ok(Self::new(path, Some(bytes.to_vec()), len))
+    }
+
+    fn new(path: &Path, data: Option<Vec<u8>>, len: u64) -> Self {
         let file_name = path
             .file_name()
             .and_then(|s| s.to_str())
             .unwrap_or("")
             .to_string();
 
-        let metadata = std::fs::metadata(path)?;
-        let file_size = metadata.len().min(MAX_FILE_SIZE);
-
-        Ok(Self {
+        Self {
             file_path: path.to_path_buf(),
-            file_size,
-            selection: 0,
+            data,
+            file_size: len.min(MAX_FILE_SIZE),
+            cursor: 0,
             scroll: 0,
             file_name,
             last_query: None,
@@ -50,11 +68,46 @@ impl HexEngine {
             cached_lines: Vec::new(),
             cache_start: 0,
             visual_range: None,
-        })
+            visual_columns: None,
+        }
+    }
+
+    /// Read up to `len` bytes at `offset`, from memory or from the file
+    fn read_at(&self, offset: usize, len: usize) -> Option<Vec<u8>> {
+        if let Some(data) = &self.data {
+            let start = offset.min(data.len());
+            let end = offset.saturating_add(len).min(data.len());
+            return Some(data[start..end].to_vec());
+        }
+        let mut file = File::open(&self.file_path).ok()?;
+        file.seek(SeekFrom::Start(offset as u64)).ok()?;
+        let mut buffer = vec![0u8; len];
+        let read = file.read(&mut buffer).ok()?;
+        buffer.truncate(read);
+        Some(buffer)
     }
 
     fn total_lines(&self) -> usize {
-        ((self.file_size as usize) + BYTES_PER_LINE - 1) / BYTES_PER_LINE
+        (self.file_size as usize).div_ceil(BYTES_PER_LINE)
+    }
+
+    fn last_offset(&self) -> usize {
+        (self.file_size as usize).saturating_sub(1)
+    }
+
+    /// Move the cursor by whole rows, keeping its column where the row allows
+    fn move_rows(&mut self, rows: isize) {
+        let target = self.cursor as isize + rows * BYTES_PER_LINE as isize;
+        if target < 0 {
+            self.cursor %= BYTES_PER_LINE;
+        } else if target as usize > self.last_offset() {
+            // Stay put unless the move lands on a (shorter) last row
+            if self.cursor / BYTES_PER_LINE != self.last_offset() / BYTES_PER_LINE {
+                self.cursor = self.last_offset();
+            }
+        } else {
+            self.cursor = target as usize;
+        }
     }
 
     fn load_lines(&mut self, start: usize, count: usize) {
@@ -66,21 +119,14 @@ impl HexEngine {
 
         // Load new cache
         let offset = (start * BYTES_PER_LINE) as u64;
-        let bytes_to_read = (count * BYTES_PER_LINE).min(self.file_size as usize - offset as usize);
-
-        if let Ok(mut file) = File::open(&self.file_path) {
-            if file.seek(SeekFrom::Start(offset)).is_ok() {
-                let mut buffer = vec![0u8; bytes_to_read];
-                if let Ok(read) = file.read(&mut buffer) {
-                    buffer.truncate(read);
+        let bytes_to_read = (count * BYTES_PER_LINE).min((self.file_size as usize).saturating_sub(offset as usize));
 
-                    self.cached_lines.clear();
-                    self.cache_start = start;
+        if let Some(buffer) = self.read_at(offset as usize, bytes_to_read) {
+            self.cached_lines.clear();
+            self.cache_start = start;
 
-                    for (i, chunk) in buffer.chunks(BYTES_PER_LINE).enumerate() {
-                        self.cached_lines.push((start + i, chunk.to_vec()));
-                    }
-                }
+            for (i, chunk) in buffer.chunks(BYTES_PER_LINE).enumerate() {
+                self.cached_lines.push((start + i, chunk.to_vec()));
             }
         }
     }
@@ -93,21 +139,32 @@ impl HexEngine {
             None
         }
     }
+}
+
+impl Engine for HexEngine {
+    fn name(&self) -> &'static str {
+        "HexEngine"
+    }
 
-    pub fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+    fn set_visual_range(&mut self, range: Option<(usize, usize)>) {
+        self.visual_range = range;
+    }
+
+    fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
         let height = area.height as usize;
         self.last_view_height = height;
 
         let total = self.total_lines();
-        if self.selection >= total && total > 0 {
-            self.selection = total - 1;
-        }
 
-        if self.selection < self.scroll {
-            self.scroll = self.selection;
-        } else if self.selection >= self.scroll + height {
-            self.scroll = self.selection.saturating_sub(height - 1);
+        if total == 0 {
+            super::render_placeholder(frame, area, "(empty file)");
+            return;
         }
+        self.cursor = self.cursor.min(self.last_offset());
+        let selection = self.selection();
+
+        self.scroll = super::follow_selection(self.scroll, selection, height, total);
+        let cursor_col = self.cursor % BYTES_PER_LINE;
 
         // Load visible lines into cache
         self.load_lines(self.scroll, height + 10);
@@ -122,7 +179,10 @@ impl HexEngine {
                 }
 
                 let offset = line_idx * BYTES_PER_LINE;
-                let selected = line_idx == self.selection;
+                let selected = line_idx == selection;
+                let in_visual = self.visual_range.is_some_and(|(start, end)| {
+                    line_idx >= start.min(end) && line_idx <= start.max(end)
+                });
 
                 let bytes = self.get_line(line_idx).cloned().unwrap_or_default();
 
@@ -146,7 +206,11 @@ impl HexEngine {
                         spans.push(Span::raw(" "));
                     }
 
-                    let byte_style = if selected {
+                    let byte_style = if selected && i == cursor_col {
+                        cursor_style()
+                    } else if in_visual && self.visual_columns.is_none_or(|(lo, hi)| i >= lo && i <= hi) {
+                        Style::default().fg(Color::Black).bg(Color::LightYellow)
+                    } else if selected {
                         Style::default().fg(Color::Black).bg(Color::LightBlue)
                     } else if byte == 0 {
                         Style::default().fg(Color::DarkGray)
@@ -187,7 +251,17 @@ impl HexEngine {
                     .map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' })
                     .collect();
 
-                spans.push(Span::styled(ascii, ascii_style));
+                if selected && cursor_col < bytes.len() {
+                    // Byte columns are ASCII, so char index equals byte index
+                    spans.push(Span::styled(ascii[..cursor_col].to_string(), ascii_style));
+                    spans.push(Span::styled(
+                        ascii[cursor_col..cursor_col + 1].to_string(),
+                        cursor_style(),
+                    ));
+                    spans.push(Span::styled(ascii[cursor_col + 1..].to_string(), ascii_style));
+                } else {
+                    spans.push(Span::styled(ascii, ascii_style));
+                }
 
                 Some(Line::from(spans))
             })
@@ -197,11 +271,11 @@ impl HexEngine {
         frame.render_widget(Paragraph::new(visible).block(block), area);
     }
 
-    pub fn handle_key(&mut self, key: KeyEvent) {
+    fn handle_key(&mut self, key: KeyEvent) {
         match key.code {
             KeyCode::Char('g') => {
                 if self.pending_g {
-                    self.selection = 0;
+                    self.cursor = 0;
                     self.pending_g = false;
                 } else {
                     self.pending_g = true;
@@ -213,45 +287,43 @@ impl HexEngine {
             }
         }
 
-        let total = self.total_lines();
         match key.code {
-            KeyCode::Char('j') | KeyCode::Down => {
-                if self.selection + 1 < total {
-                    self.selection += 1;
-                }
+            KeyCode::Char('j') | KeyCode::Down => self.move_rows(1),
+            KeyCode::Char('k') | KeyCode::Up => self.move_rows(-1),
+            KeyCode::Char('h') | KeyCode::Left => {
+                self.cursor = self.cursor.saturating_sub(1);
             }
-            KeyCode::Char('k') | KeyCode::Up => {
-                self.selection = self.selection.saturating_sub(1);
+            KeyCode::Char('l') | KeyCode::Right => {
+                self.cursor = (self.cursor + 1).min(self.last_offset());
             }
             KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let jump = page_jump(self.last_view_height).min(self.selection);
-                self.selection = self.selection.saturating_sub(jump);
+                self.move_rows(-(super::page_jump(self.last_view_height) as isize));
             }
             KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let jump = page_jump(self.last_view_height).min(total.saturating_sub(1));
-                self.selection = (self.selection + jump).min(total.saturating_sub(1));
+                self.move_rows(super::page_jump(self.last_view_height) as isize);
             }
             KeyCode::Char('G') => {
-                if total > 0 {
-                    self.selection = total - 1;
-                }
+                self.cursor = self.last_offset();
             }
             _ => {}
         }
     }
 
-    pub fn apply_search(&mut self, _query: &str) {
+    fn apply_search(&mut self, _query: &str, _forward: bool) -> bool {
         // TODO: Implement hex search
+        false
     }
 
-    pub fn apply_filter(&mut self, _query: &str) {}
+    fn apply_filter(&mut self, _query: &str) -> bool {
+        false
+    }
 
-    pub fn clear_filter(&mut self) {
+    fn clear_filter(&mut self) {
         self.last_query = None;
     }
 
-    pub fn breadcrumbs(&self) -> String {
-        let offset = self.selection * BYTES_PER_LINE;
+    fn breadcrumbs(&self) -> String {
+        let offset = self.cursor;
         format!(
             "{} offset 0x{:X} ({}/{})",
             self.file_name,
@@ -261,119 +333,142 @@ impl HexEngine {
         )
     }
 
-    pub fn status_line(&self) -> String {
-        format!(
-            "j/k move | gg/G jump | Ctrl+u/d half-page | {} bytes | {} lines",
-            self.file_size,
-            self.total_lines()
-        )
+    fn status_line(&self) -> String {
+        let decoded = self
+            .read_at(self.cursor, 4)
+            .map(|bytes| decode_bytes(&bytes))
+            .unwrap_or_default();
+        if decoded.is_empty() {
+            return format!("h/j/k/l move | gg/G jump | {} bytes", self.file_size);
+        }
+        format!("{} | h/j/k/l move | {} bytes", decoded, self.file_size)
     }
 
-    #[allow(dead_code)]
-    pub fn selected_path(&self) -> Option<String> {
+    /// Keys specific to this view, listed in the help overlay
+    fn help_lines(&self) -> Vec<Line<'static>> {
+        vec![
+            Line::from("  h/l          Previous / next byte"),
+        ]
+    }
+
+    fn selected_path(&self) -> Option<String> {
         None
     }
 
+    fn block_column(&self) -> Option<usize> {
+        Some(self.cursor % BYTES_PER_LINE)
+    }
+
+    fn set_visual_columns(&mut self, columns: Option<(usize, usize)>) {
+        self.visual_columns = columns;
+    }
+
+    /// The bytes in the block as hex, one row per line
+    fn get_block(&self, rows: (usize, usize), columns: (usize, usize)) -> Option<String> {
+        if rows.0 >= self.total_lines() {
+            return None;
+        }
+        let end = rows.1.min(self.total_lines() - 1);
+        let lines: Vec<String> = (rows.0..=end)
+            .filter_map(|row| {
+                let bytes = self.read_at(row * BYTES_PER_LINE + columns.0, columns.1 - columns.0 + 1)?;
+                Some(bytes.iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(" "))
+            })
+            .collect();
+        Some(lines.join("\n"))
+    }
+
     /// Get the content of the currently selected line
-    pub fn get_selected_line(&self) -> Option<String> {
-        let offset = self.selection * BYTES_PER_LINE;
+    fn get_selected_line(&self) -> Option<String> {
+        let offset = self.selection() * BYTES_PER_LINE;
         if offset >= self.file_size as usize {
             return None;
         }
         // Read the bytes for this line
-        if let Ok(mut file) = File::open(&self.file_path) {
-            use std::io::{Seek, SeekFrom, Read};
-            if file.seek(SeekFrom::Start(offset as u64)).is_ok() {
-                let mut buffer = vec![0u8; BYTES_PER_LINE];
-                if let Ok(read) = file.read(&mut buffer) {
-                    buffer.truncate(read);
-                    let hex: String = buffer.iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(" ");
-                    let ascii: String = buffer.iter().map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' }).collect();
-                    return Some(format!("{:08X}: {} | {}", offset, hex, ascii));
-                }
-            }
-        }
-        None
+        let buffer = self.read_at(offset, BYTES_PER_LINE)?;
+        let hex: String = buffer.iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(" ");
+        let ascii: String = buffer.iter().map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' }).collect();
+        Some(format!("{:08X}: {} | {}", offset, hex, ascii))
     }
 
     /// Get lines in a range (inclusive), joined by newlines
-    pub fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
+    fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
         let (start, end) = if start <= end { (start, end) } else { (end, start) };
         let total = self.total_lines();
         if start >= total { return None; }
         let end = end.min(total.saturating_sub(1));
 
-        if let Ok(mut file) = File::open(&self.file_path) {
-            use std::io::{Seek, SeekFrom, Read};
-            let start_offset = start * BYTES_PER_LINE;
-            if file.seek(SeekFrom::Start(start_offset as u64)).is_ok() {
-                let byte_count = (end - start + 1) * BYTES_PER_LINE;
-                let mut buffer = vec![0u8; byte_count];
-                if let Ok(read) = file.read(&mut buffer) {
-                    buffer.truncate(read);
-                    let lines: Vec<String> = buffer.chunks(BYTES_PER_LINE).enumerate().map(|(i, chunk)| {
-                        let offset = (start + i) * BYTES_PER_LINE;
-                        let hex: String = chunk.iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(" ");
-                        let ascii: String = chunk.iter().map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' }).collect();
-                        format!("{:08X}: {} | {}", offset, hex, ascii)
-                    }).collect();
-                    return Some(lines.join("\n"));
-                }
-            }
-        }
-        None
+        let start_offset = start * BYTES_PER_LINE;
+        let byte_count = (end - start + 1) * BYTES_PER_LINE;
+        let buffer = self.read_at(start_offset, byte_count)?;
+        let lines: Vec<String> = buffer.chunks(BYTES_PER_LINE).enumerate().map(|(i, chunk)| {
+            let offset = (start + i) * BYTES_PER_LINE;
+            let hex: String = chunk.iter().map(|b| format!("{:02X}", b)).collect::<Vec<_>>().join(" ");
+            let ascii: String = chunk.iter().map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' }).collect();
+            format!("{:08X}: {} | {}", offset, hex, ascii)
+        }).collect();
+        Some(lines.join("\n"))
     }
 
     /// Get current selection index (for visual mode)
-    pub fn selection(&self) -> usize {
-        self.selection
+    fn selection(&self) -> usize {
+        self.cursor / BYTES_PER_LINE
+    }
+
+    fn select(&mut self, row: usize) {
+        self.cursor = (row * BYTES_PER_LINE).min(self.last_offset());
     }
 
-    pub fn content_height(&self) -> usize {
+    /// Scroll so the selection sits at the top, middle or bottom of the view (`zt`/`zz`/`zb`)
+    fn align_scroll(&mut self, align: super::ScrollAlign) {
+        self.scroll = super::aligned_scroll(self.selection(), self.last_view_height, align);
+    }
+
+    fn content_height(&mut self) -> usize {
         self.total_lines()
     }
 
-    pub fn render_plain_lines(&self, _width: u16) -> Vec<Line<'static>> {
+    /// Parsed structure for `--dump`
+    fn to_json(&mut self) -> serde_json::Value {
+        json!({ "size": self.file_size })
+    }
+
+    fn render_plain_lines(&mut self, _width: u16) -> Vec<Line<'static>> {
         let mut lines = Vec::new();
         let addr_width = format!("{:08X}", self.file_size).len();
 
         // Only show first 100 lines in plain mode
-        if let Ok(mut file) = File::open(&self.file_path) {
-            let mut buffer = vec![0u8; 100 * BYTES_PER_LINE];
-            if let Ok(read) = file.read(&mut buffer) {
-                buffer.truncate(read);
-
-                for (line_idx, chunk) in buffer.chunks(BYTES_PER_LINE).enumerate() {
-                    let offset = line_idx * BYTES_PER_LINE;
-
-                    let hex: String = chunk
-                        .iter()
-                        .enumerate()
-                        .map(|(i, b)| {
-                            if i == 8 {
-                                format!(" {:02X}", b)
-                            } else {
-                                format!("{:02X} ", b)
-                            }
-                        })
-                        .collect();
-
-                    let ascii: String = chunk
-                        .iter()
-                        .map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' })
-                        .collect();
-
-                    lines.push(Line::from(vec![
-                        Span::styled(
-                            format!("{:0width$X}  ", offset, width = addr_width),
-                            Style::default().fg(Color::DarkGray),
-                        ),
-                        Span::styled("│ ", Style::default().fg(Color::DarkGray)),
-                        Span::styled(hex, Style::default().fg(Color::Cyan)),
-                        Span::styled(" │ ", Style::default().fg(Color::DarkGray)),
-                        Span::styled(ascii, Style::default().fg(Color::White)),
-                    ]));
-                }
+        if let Some(buffer) = self.read_at(0, 100 * BYTES_PER_LINE) {
+            for (line_idx, chunk) in buffer.chunks(BYTES_PER_LINE).enumerate() {
+                let offset = line_idx * BYTES_PER_LINE;
+
+                let hex: String = chunk
+                    .iter()
+                    .enumerate()
+                    .map(|(i, b)| {
+                        if i == 8 {
+                            format!(" {:02X}", b)
+                        } else {
+                            format!("{:02X} ", b)
+                        }
+                    })
+                    .collect();
+
+                let ascii: String = chunk
+                    .iter()
+                    .map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '.' })
+                    .collect();
+
+                lines.push(Line::from(vec![
+                    Span::styled(
+                        format!("{:0width$X}  ", offset, width = addr_width),
+                        Style::default().fg(Color::DarkGray),
+                    ),
+                    Span::styled("│ ", Style::default().fg(Color::DarkGray)),
+                    Span::styled(hex, Style::default().fg(Color::Cyan)),
+                    Span::styled(" │ ", Style::default().fg(Color::DarkGray)),
+                    Span::styled(ascii, Style::default().fg(Color::White)),
+                ]));
             }
         }
 
@@ -391,23 +486,81 @@ impl AsciiPrintable for u8 {
     }
 }
 
-fn format_size(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
-
-    if bytes >= GB {
-        format!("{:.1}GB", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.1}MB", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.1}KB", bytes as f64 / KB as f64)
+fn cursor_style() -> Style {
+    Style::default().fg(Color::Black).bg(Color::Yellow).bold()
+}
+
+/// Decode the byte at the cursor plus the little/big-endian words starting there,
+/// e.g. `0x41 = 'A' = 65 (u8) | i8 65 | u16 LE 16961 BE 16706`
+fn decode_bytes(bytes: &[u8]) -> String {
+    let Some(&byte) = bytes.first() else {
+        return String::new();
+    };
+    let ch = if byte.is_ascii() {
+        format!("'{}'", std::ascii::escape_default(byte))
     } else {
-        format!("{}B", bytes)
+        "non-ASCII".to_string()
+    };
+    let mut parts = vec![
+        format!("0x{:02X} = {} = {} (u8)", byte, ch, byte),
+        format!("i8 {}", byte as i8),
+    ];
+    if let Some(word) = bytes.get(..2) {
+        let word = [word[0], word[1]];
+        parts.push(format!("u16 LE {} BE {}", u16::from_le_bytes(word), u16::from_be_bytes(word)));
     }
+    if let Some(dword) = bytes.get(..4) {
+        let dword = [dword[0], dword[1], dword[2], dword[3]];
+        parts.push(format!("u32 LE {} BE {}", u32::from_le_bytes(dword), u32::from_be_bytes(dword)));
+    }
+    parts.join(" | ")
 }
 
-fn page_jump(view_height: usize) -> usize {
-    let half = view_height / 2;
-    if half == 0 { 1 } else { half }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(c: char) -> KeyEvent {
+        KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE)
+    }
+
+    #[test]
+    fn block_copies_the_same_bytes_of_each_row() {
+        let bytes: Vec<u8> = (0..40).collect();
+        let mut engine = HexEngine::from_bytes(Path::new("data.bin"), &bytes).unwrap();
+        engine.handle_key(key('l'));
+        assert_eq!(engine.block_column(), Some(1));
+        assert_eq!(engine.get_block((0, 2), (1, 2)).unwrap(), "01 02\n11 12\n21 22");
+    }
+
+    #[test]
+    fn decodes_byte_and_words_at_cursor() {
+        assert_eq!(
+            decode_bytes(&[0x41, 0x42, 0x00, 0x00]),
+            "0x41 = 'A' = 65 (u8) | i8 65 | u16 LE 16961 BE 16706 | u32 LE 16961 BE 1094844416"
+        );
+        assert_eq!(decode_bytes(&[0xFF]), "0xFF = non-ASCII = 255 (u8) | i8 -1");
+        assert_eq!(decode_bytes(&[]), "");
+    }
+
+    #[test]
+    fn cursor_moves_by_byte_and_row() {
+        let bytes: Vec<u8> = (0..40).collect();
+        let mut engine = HexEngine::from_bytes(Path::new("data.bin"), &bytes).unwrap();
+        engine.handle_key(key('l'));
+        engine.handle_key(key('j'));
+        assert_eq!(engine.cursor, 17);
+        assert_eq!(engine.selection(), 1);
+        // The last row only holds 8 bytes, so moving down clamps to the final byte
+        for _ in 0..8 {
+            engine.handle_key(key('l'));
+        }
+        engine.handle_key(key('j'));
+        assert_eq!(engine.cursor, 39);
+        engine.handle_key(key('k'));
+        assert_eq!(engine.cursor, 23);
+        engine.handle_key(key('h'));
+        assert_eq!(engine.cursor, 22);
+        assert!(engine.status_line().starts_with("0x16 = '\\x16' = 22 (u8)"));
+    }
 }
@@ -7,6 +7,10 @@ use ratatui::style::{Color, Style, Stylize};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Cell, Row, Table, TableState};
 use scraper::{ElementRef, Html};
+use serde_json::json;
+
+use super::util::{search_matches, truncate};
+use super::{Engine, SyntaxEngine, TextLayout};
 
 struct HtmlRow {
     depth: usize,
@@ -14,6 +18,8 @@ struct HtmlRow {
     id: String,
     class: String,
     text: String,
+    /// Inline `<script>`/`<style>` source and the extension it highlights as
+    code: Option<(&'static str, String)>,
 }
 
 pub struct HtmlEngine {
@@ -26,14 +32,23 @@ pub struct HtmlEngine {
     pending_g: bool,
     last_view_height: usize,
     last_match: Option<String>,
+    /// Inline script or style opened as highlighted code; keys go to it until Esc
+    nested: Option<(String, Box<SyntaxEngine>)>,
+    /// Handed on to `nested` when it opens
+    layout: TextLayout,
     /// Visual selection range (start, end) for highlighting
     pub visual_range: Option<(usize, usize)>,
 }
 
 impl HtmlEngine {
     pub fn from_path(path: &Path) -> Result<Self> {
-        let content = std::fs::read_to_string(path)?;
-        let doc = Html::parse_document(&content);
+        Self::from_bytes(path, &std::fs::read(path)?)
+    }
+
+    /// Create from in-memory bytes; `path` only supplies the name and extension
+    pub fn from_bytes(path: &Path, bytes: &[u8]) -> Result<Self> {
+        let content = std::str::from_utf8(bytes)?;
+        let doc = Html::parse_document(content);
         let mut rows = Vec::new();
         let root = doc.root_element();
         collect_elements(root, 0, &mut rows);
@@ -52,19 +67,43 @@ impl HtmlEngine {
             pending_g: false,
             last_view_height: 0,
             last_match: None,
+            nested: None,
+            layout: TextLayout::default(),
             visual_range: None,
         })
     }
+}
+
+impl Engine for HtmlEngine {
+    fn name(&self) -> &'static str {
+        "HtmlEngine"
+    }
+
+    fn set_visual_range(&mut self, range: Option<(usize, usize)>) {
+        self.visual_range = range;
+    }
+
+    fn set_layout(&mut self, layout: TextLayout) {
+        self.layout = layout;
+        if let Some((_, code)) = self.nested.as_mut() {
+            code.set_layout(layout);
+        }
+    }
 
-    pub fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+    fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+        if let Some((_, code)) = self.nested.as_mut() {
+            code.render(frame, area);
+            return;
+        }
         self.last_view_height = area.height as usize;
         let visible = self.visible_rows();
-        let height = area.height.saturating_sub(1) as usize;
-        if self.selection < self.scroll {
-            self.scroll = self.selection;
-        } else if self.selection >= self.scroll + height {
-            self.scroll = self.selection.saturating_sub(height - 1);
+
+        if visible.is_empty() {
+            super::render_placeholder(frame, area, "(empty document)");
+            return;
         }
+        let height = area.height.saturating_sub(1) as usize;
+        self.scroll = super::follow_selection(self.scroll, self.selection, height, visible.len());
 
         let slice = if visible.is_empty() {
             &[][..]
@@ -89,16 +128,16 @@ impl HtmlEngine {
         let mut rows = Vec::new();
         for (idx, row_idx) in slice.iter().enumerate() {
             let row = &self.rows[*row_idx];
-            let mut cells = Vec::new();
-            cells.push(
+            let text_color = if row.code.is_some() { Color::LightMagenta } else { Color::Yellow };
+            let cells = vec![
                 Cell::from((self.scroll + idx + 1).to_string())
                     .style(Style::default().fg(Color::DarkGray)),
-            );
-            cells.push(Cell::from("│").style(Style::default().fg(Color::DarkGray)));
-            cells.push(Cell::from(indent_tag(row.depth, &row.tag)).style(Style::default().fg(Color::Cyan).bold()));
-            cells.push(Cell::from(row.id.clone()).style(Style::default().fg(Color::Magenta)));
-            cells.push(Cell::from(row.class.clone()).style(Style::default().fg(Color::Green)));
-            cells.push(Cell::from(row.text.clone()).style(Style::default().fg(Color::Yellow)));
+                Cell::from("│").style(Style::default().fg(Color::DarkGray)),
+                Cell::from(indent_tag(row.depth, &row.tag)).style(Style::default().fg(Color::Cyan).bold()),
+                Cell::from(row.id.clone()).style(Style::default().fg(Color::Magenta)),
+                Cell::from(row.class.clone()).style(Style::default().fg(Color::Green)),
+                Cell::from(row.text.clone()).style(Style::default().fg(text_color)),
+            ];
             rows.push(Row::new(cells));
         }
 
@@ -123,7 +162,16 @@ impl HtmlEngine {
         frame.render_stateful_widget(table, area, &mut state);
     }
 
-    pub fn handle_key(&mut self, key: KeyEvent) {
+    #[allow(clippy::collapsible_match)]
+    fn handle_key(&mut self, key: KeyEvent) {
+        if let Some((_, code)) = self.nested.as_mut() {
+            if key.code == KeyCode::Esc {
+                self.nested = None;
+            } else {
+                code.handle_key(key);
+            }
+            return;
+        }
         match key.code {
             KeyCode::Char('g') => {
                 if self.pending_g {
@@ -150,15 +198,13 @@ impl HtmlEngine {
             KeyCode::Char('u')
                 if key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) =>
             {
-                let jump = page_jump(self.last_view_height).min(self.selection);
-                self.selection = self.selection.saturating_sub(jump);
+                self.selection = super::half_page_up(self.selection, self.last_view_height);
             }
             KeyCode::Char('d')
                 if key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) =>
             {
                 let visible_len = self.visible_rows().len();
-                let jump = page_jump(self.last_view_height).min(visible_len.saturating_sub(1));
-                self.selection = (self.selection + jump).min(visible_len.saturating_sub(1));
+                self.selection = super::half_page_down(self.selection, self.last_view_height, visible_len);
             }
             KeyCode::Char('G') => {
                 let visible_len = self.visible_rows().len();
@@ -185,51 +231,79 @@ impl HtmlEngine {
                     }
                 }
             }
+            KeyCode::Char('o') => self.open_code(),
+            KeyCode::Char('e') => self.jump_to_code(true),
+            KeyCode::Char('E') => self.jump_to_code(false),
             _ => {}
         }
     }
 
-    pub fn apply_search(&mut self, query: &str) {
+    fn apply_search(&mut self, query: &str, forward: bool) -> bool {
+        if let Some((_, code)) = self.nested.as_mut() {
+            return code.apply_search(query, forward);
+        }
         let trimmed = query.trim();
         if trimmed.is_empty() {
-            return;
+            return false;
         }
         self.last_query = Some(trimmed.to_string());
-        self.search_next(trimmed, true);
+        let found = self.search_next(trimmed, forward);
         self.last_match = Some(trimmed.to_string());
+        found
     }
 
-    pub fn breadcrumbs(&self) -> String {
+    fn breadcrumbs(&self) -> String {
+        if let Some((label, code)) = &self.nested {
+            return format!(
+                "{} row {} · {} > {}",
+                self.file_name,
+                self.selection + 1,
+                label,
+                code.breadcrumbs()
+            );
+        }
         format!("{} row {}", self.file_name, self.selection + 1)
     }
 
-    pub fn status_line(&self) -> String {
+    fn status_line(&self) -> String {
+        if let Some((_, code)) = &self.nested {
+            return format!("Esc back to elements | {}", code.status_line());
+        }
         let query = self
             .last_query
             .as_ref()
             .map(|q| format!(" | search: {}", q))
             .unwrap_or_default();
         format!(
-            "j/k move | gg/G jump | Ctrl+u/d half-page | n/N next/prev | Enter fold | / search | f filter{}",
+            "j/k move | gg/G jump | Ctrl+u/d half-page | n/N next/prev | Enter fold | e/E script/style | o open | / search | f filter{}",
             query
         )
     }
 
-    pub fn apply_filter(&mut self, query: &str) {
-        self.apply_search(query);
+    fn apply_filter(&mut self, query: &str) -> bool {
+        self.apply_search(query, true)
     }
 
-    pub fn clear_filter(&mut self) {
+    fn clear_filter(&mut self) {
         self.last_query = None;
     }
 
-    #[allow(dead_code)]
-    pub fn selected_path(&self) -> Option<String> {
+    /// Keys specific to this view, listed in the help overlay
+    fn help_lines(&self) -> Vec<Line<'static>> {
+        vec![
+            Line::from("  Enter        Expand/collapse element"),
+            Line::from("  e/E          Next/prev inline script or style"),
+            Line::from("  o            Open script/style as highlighted code"),
+            Line::from("  Esc          Close script/style"),
+        ]
+    }
+
+    fn selected_path(&self) -> Option<String> {
         None
     }
 
     /// Get the content of the currently selected line
-    pub fn get_selected_line(&self) -> Option<String> {
+    fn get_selected_line(&self) -> Option<String> {
         let visible = self.visible_rows();
         visible.get(self.selection).map(|&idx| {
             let row = &self.rows[idx];
@@ -238,7 +312,7 @@ impl HtmlEngine {
     }
 
     /// Get lines in a range (inclusive), skipping children of selected parents
-    pub fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
+    fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
         let (start, end) = if start <= end { (start, end) } else { (end, start) };
         let visible = self.visible_rows();
         let total = visible.len();
@@ -276,15 +350,51 @@ impl HtmlEngine {
     }
 
     /// Get current selection index (for visual mode)
-    pub fn selection(&self) -> usize {
+    fn selection(&self) -> usize {
         self.selection
     }
 
-    pub fn content_height(&self) -> usize {
+    fn select(&mut self, row: usize) {
+        if let Some((_, code)) = self.nested.as_mut() {
+            code.select(row);
+            return;
+        }
+        self.selection = row.min(self.visible_rows().len().saturating_sub(1));
+    }
+
+    /// Scroll so the selection sits at the top, middle or bottom of the view (`zt`/`zz`/`zb`)
+    fn align_scroll(&mut self, align: super::ScrollAlign) {
+        if let Some((_, code)) = self.nested.as_mut() {
+            code.align_scroll(align);
+            return;
+        }
+        self.scroll = super::aligned_scroll(self.selection, self.last_view_height, align);
+    }
+
+    fn content_height(&mut self) -> usize {
         self.visible_rows().len() + 1
     }
 
-    pub fn render_plain_lines(&self, width: u16) -> Vec<Line<'static>> {
+    /// Parsed structure for `--dump`
+    fn to_json(&mut self) -> serde_json::Value {
+        let elements: Vec<_> = self
+            .rows
+            .iter()
+            .map(|row| {
+                json!({
+                    "depth": row.depth,
+                    "tag": row.tag,
+                    "id": row.id,
+                    "class": row.class,
+                    "text": row.text,
+                    "code": row.code.as_ref().map(|(_, source)| source),
+                })
+            })
+            .collect();
+        json!({ "elements": elements })
+    }
+
+    fn render_plain_lines(&mut self, width: u16) -> Vec<Line<'static>> {
         let inner_width = width as usize;
         let (w_num, w_sep, w_tag, w_id, w_class, w_text) = html_column_widths(inner_width);
         let mut lines = Vec::new();
@@ -334,6 +444,39 @@ impl HtmlEngine {
         }
         visible
     }
+
+    /// Open the selected inline `<script>`/`<style>` in a syntax-highlighted view
+    fn open_code(&mut self) {
+        let Some(row) = self.visible_rows().get(self.selection).map(|&idx| &self.rows[idx]) else {
+            return;
+        };
+        let Some((ext, source)) = &row.code else {
+            return;
+        };
+        let name = format!("inline.{}", ext);
+        if let Ok(mut code) = SyntaxEngine::from_bytes(Path::new(&name), source.as_bytes()) {
+            code.set_layout(self.layout);
+            self.nested = Some((format!("<{}>", row.tag), Box::new(code)));
+        }
+    }
+
+    /// Move to the next or previous row holding inline script or style, wrapping around
+    fn jump_to_code(&mut self, forward: bool) {
+        let visible = self.visible_rows();
+        let total = visible.len();
+        let found = (1..=total)
+            .map(|offset| {
+                if forward {
+                    (self.selection + offset) % total
+                } else {
+                    (self.selection + total - offset) % total
+                }
+            })
+            .find(|&idx| self.rows[visible[idx]].code.is_some());
+        if let Some(idx) = found {
+            self.selection = idx;
+        }
+    }
 }
 
 fn collect_elements(node: ElementRef<'_>, depth: usize, rows: &mut Vec<HtmlRow>) {
@@ -342,13 +485,15 @@ fn collect_elements(node: ElementRef<'_>, depth: usize, rows: &mut Vec<HtmlRow>)
     let class = node.value().attr("class").unwrap_or("").to_string();
     let text = node.text().collect::<Vec<_>>().join(" ");
     let text = text.trim().to_string();
-    let text = truncate_text(&text, 60);
+    let text = truncate(&text, super::preview_len(60));
+    let code = embedded_code(node);
     rows.push(HtmlRow {
         depth,
         tag,
         id,
         class,
         text,
+        code,
     });
     for child in node.children() {
         if let Some(element) = ElementRef::wrap(child) {
@@ -357,20 +502,35 @@ fn collect_elements(node: ElementRef<'_>, depth: usize, rows: &mut Vec<HtmlRow>)
     }
 }
 
+/// Source of an inline `<script>` or `<style>`, with the extension to highlight it as;
+/// external scripts and non-JavaScript script types (JSON, templates) are skipped
+fn embedded_code(node: ElementRef<'_>) -> Option<(&'static str, String)> {
+    let element = node.value();
+    let ext = match element.name() {
+        "style" => "css",
+        "script" if element.attr("src").is_none() => {
+            let kind = element.attr("type").unwrap_or("").trim().to_ascii_lowercase();
+            if !matches!(kind.as_str(), "" | "module" | "text/javascript" | "application/javascript") {
+                return None;
+            }
+            "js"
+        }
+        _ => return None,
+    };
+    let source: String = node.text().collect();
+    if source.trim().is_empty() {
+        return None;
+    }
+    // Drop the blank line after the opening tag and the indentation before the closing one
+    let source = source.trim_start_matches(['\r', '\n']).trim_end();
+    Some((ext, source.to_string()))
+}
+
 fn indent_tag(depth: usize, tag: &str) -> String {
     let indent = "  ".repeat(depth);
     format!("{}<{}>", indent, tag)
 }
 
-fn truncate_text(text: &str, max: usize) -> String {
-    if text.len() <= max {
-        return text.to_string();
-    }
-    let mut out = text.chars().take(max.saturating_sub(3)).collect::<String>();
-    out.push_str("...");
-    out
-}
-
 fn html_column_widths(inner_width: usize) -> (usize, usize, usize, usize, usize, usize) {
     let w_num = 5;
     let w_sep = 2;
@@ -396,24 +556,20 @@ fn pad_cell(value: &str, width: usize) -> String {
     out
 }
 
-fn page_jump(view_height: usize) -> usize {
-    let half = view_height / 2;
-    if half == 0 { 1 } else { half }
-}
-
 impl HtmlEngine {
-    fn search_next(&mut self, query: &str, forward: bool) {
+    fn search_next(&mut self, query: &str, forward: bool) -> bool {
         let trimmed = query.trim();
         if trimmed.is_empty() {
-            return;
+            return false;
         }
         let lower = trimmed.to_lowercase();
+        let mut found = false;
         let visible = self.visible_rows();
         let total = visible.len().max(1);
         let start = if forward {
             (self.selection + 1) % total
         } else {
-            self.selection.saturating_sub(1)
+            (self.selection + total - 1) % total
         };
         for offset in 0..visible.len() {
             let idx = if forward {
@@ -422,15 +578,48 @@ impl HtmlEngine {
                 (start + total - offset % total) % total
             };
             let row = &self.rows[visible[idx]];
-            if row.tag.to_lowercase().contains(&lower)
-                || row.id.to_lowercase().contains(&lower)
-                || row.class.to_lowercase().contains(&lower)
-                || row.text.to_lowercase().contains(&lower)
+            if search_matches(&row.tag, &lower)
+                || search_matches(&row.id, &lower)
+                || search_matches(&row.class, &lower)
+                || search_matches(&row.text, &lower)
             {
                 self.selection = idx;
+                found = true;
                 break;
             }
         }
         self.last_match = Some(trimmed.to_string());
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossterm::event::KeyModifiers;
+
+    #[test]
+    fn inline_script_and_style_open_as_code() {
+        let html = br#"<html><head><style>
+  body { color: red; }
+</style><script src="app.js"></script><script type="application/ld+json">{}</script></head>
+<body><p>hi</p><script>
+const answer = 42;
+</script></body></html>"#;
+        let mut engine = HtmlEngine::from_bytes(Path::new("page.html"), html).unwrap();
+        let code: Vec<(&str, &str)> = engine
+            .rows
+            .iter()
+            .filter_map(|row| row.code.as_ref().map(|(ext, source)| (*ext, source.as_str())))
+            .collect();
+        assert_eq!(code, [("css", "  body { color: red; }"), ("js", "const answer = 42;")]);
+
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        engine.handle_key(key(KeyCode::Char('e')));
+        engine.handle_key(key(KeyCode::Char('e')));
+        engine.handle_key(key(KeyCode::Char('o')));
+        assert!(engine.breadcrumbs().contains("<script> > inline.js"));
+        engine.handle_key(key(KeyCode::Esc));
+        assert!(engine.nested.is_none());
     }
 }
@@ -7,6 +7,10 @@ use ratatui::layout::Rect;
 use ratatui::style::{Color, Style, Stylize};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph};
+use serde_json::json;
+
+use super::util::format_size;
+use super::{Engine, XmlEngine};
 
 #[derive(Clone)]
 struct ImageInfo {
@@ -35,20 +39,30 @@ pub struct ImageEngine {
     last_view_height: usize,
     /// Visual selection range (start, end) for highlighting
     pub visual_range: Option<(usize, usize)>,
+    /// Element tree of an SVG, shown instead of the image info while `show_source` is set
+    source: Option<Box<XmlEngine>>,
+    show_source: bool,
 }
 
 impl ImageEngine {
     pub fn from_path(path: &Path) -> Result<Self> {
+        Self::from_bytes(path, &std::fs::read(path)?)
+    }
+
+    /// Create from in-memory bytes; `path` only supplies the name and extension
+    pub fn from_bytes(path: &Path, bytes: &[u8]) -> Result<Self> {
         let file_name = path
             .file_name()
             .and_then(|s| s.to_str())
             .unwrap_or("")
             .to_string();
 
-        let metadata = std::fs::metadata(path)?;
-        let file_size = metadata.len();
+        let file_size = bytes.len() as u64;
+        if path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("svg")) {
+            return Self::from_svg(path, bytes, file_name);
+        }
 
-        let img = image::open(path).map_err(|e| anyhow!("Failed to open image: {}", e))?;
+        let img = image::load_from_memory(bytes).map_err(|e| anyhow!("Failed to open image: {}", e))?;
 
         let (width, height) = img.dimensions();
         let color = img.color();
@@ -83,18 +97,97 @@ impl ImageEngine {
             pending_g: false,
             last_view_height: 0,
             visual_range: None,
+            source: None,
+            show_source: false,
+        })
+    }
+
+    /// SVGs are XML, so their size comes from the root's `width`/`height` (or
+    /// `viewBox`) and `s` switches to the element tree
+    fn from_svg(path: &Path, bytes: &[u8], file_name: String) -> Result<Self> {
+        let content = std::str::from_utf8(bytes)?;
+        let doc = roxmltree::Document::parse(content).map_err(|e| anyhow!("Failed to parse SVG: {}", e))?;
+        let root = doc.root_element();
+        let view_box: Vec<f64> = root
+            .attribute("viewBox")
+            .map(|v| v.split([' ', ',']).filter_map(|n| n.parse().ok()).collect())
+            .unwrap_or_default();
+        let length = |name: &str, fallback: usize| {
+            root.attribute(name)
+                .and_then(svg_length)
+                .or_else(|| view_box.get(fallback).copied())
+                .unwrap_or(0.0)
+                .round() as u32
+        };
+        let info = ImageInfo {
+            width: length("width", 2),
+            height: length("height", 3),
+            format: "SVG".to_string(),
+            color_type: "Vector".to_string(),
+            file_size: bytes.len() as u64,
+            bits_per_pixel: 0,
+        };
+        let elements: Vec<_> = root.descendants().filter(|n| n.is_element()).collect();
+        let count = |tags: &[&str]| elements.iter().filter(|n| tags.contains(&n.tag_name().name())).count();
+        let lines = build_svg_info_lines(
+            &info,
+            &file_name,
+            root.attribute("viewBox"),
+            &[
+                ("Elements", elements.len()),
+                ("Paths", count(&["path"])),
+                ("Shapes", count(&["rect", "circle", "ellipse", "line", "polyline", "polygon"])),
+                ("Text", count(&["text"])),
+                ("Groups", count(&["g"])),
+            ],
+        );
+
+        Ok(Self {
+            info,
+            lines,
+            selection: 0,
+            scroll: 0,
+            file_name,
+            last_query: None,
+            pending_g: false,
+            last_view_height: 0,
+            visual_range: None,
+            source: Some(Box::new(XmlEngine::from_bytes(path, bytes)?)),
+            show_source: false,
         })
     }
 
-    pub fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+    /// The SVG element tree while it is shown
+    fn source_view(&self) -> Option<&XmlEngine> {
+        self.source.as_deref().filter(|_| self.show_source)
+    }
+
+    fn source_view_mut(&mut self) -> Option<&mut XmlEngine> {
+        self.source.as_deref_mut().filter(|_| self.show_source)
+    }
+}
+
+impl Engine for ImageEngine {
+    fn name(&self) -> &'static str {
+        self.source_view().map_or("ImageEngine", |xml| xml.name())
+    }
+
+    fn set_visual_range(&mut self, range: Option<(usize, usize)>) {
+        if let Some(xml) = self.source_view_mut() {
+            xml.set_visual_range(range);
+        }
+        self.visual_range = range;
+    }
+
+    fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+        if let Some(xml) = self.source_view_mut() {
+            xml.render(frame, area);
+            return;
+        }
         let height = area.height as usize;
         self.last_view_height = height;
 
-        if self.selection < self.scroll {
-            self.scroll = self.selection;
-        } else if self.selection >= self.scroll + height {
-            self.scroll = self.selection.saturating_sub(height - 1);
-        }
+        self.scroll = super::follow_selection(self.scroll, self.selection, height, self.lines.len());
 
         let visible: Vec<Line> = self.lines
             .iter()
@@ -134,7 +227,16 @@ impl ImageEngine {
         frame.render_widget(Paragraph::new(visible).block(block), area);
     }
 
-    pub fn handle_key(&mut self, key: KeyEvent) {
+    #[allow(clippy::collapsible_match)]
+    fn handle_key(&mut self, key: KeyEvent) {
+        if key.code == KeyCode::Char('s') && self.source.is_some() {
+            self.show_source = !self.show_source;
+            return;
+        }
+        if let Some(xml) = self.source_view_mut() {
+            xml.handle_key(key);
+            return;
+        }
         match key.code {
             KeyCode::Char('g') => {
                 if self.pending_g {
@@ -161,12 +263,10 @@ impl ImageEngine {
                 self.selection = self.selection.saturating_sub(1);
             }
             KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let jump = page_jump(self.last_view_height).min(self.selection);
-                self.selection = self.selection.saturating_sub(jump);
+                self.selection = super::half_page_up(self.selection, self.last_view_height);
             }
             KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let jump = page_jump(self.last_view_height).min(total.saturating_sub(1));
-                self.selection = (self.selection + jump).min(total.saturating_sub(1));
+                self.selection = super::half_page_down(self.selection, self.last_view_height, total);
             }
             KeyCode::Char('G') => {
                 if total > 0 {
@@ -177,17 +277,26 @@ impl ImageEngine {
         }
     }
 
-    pub fn apply_search(&mut self, _query: &str) {
-        // No search for image metadata
+    fn apply_search(&mut self, query: &str, forward: bool) -> bool {
+        // Image metadata has no search; an SVG's element tree does
+        self.source_view_mut().is_some_and(|xml| xml.apply_search(query, forward))
     }
 
-    pub fn apply_filter(&mut self, _query: &str) {}
+    fn apply_filter(&mut self, query: &str) -> bool {
+        self.source_view_mut().is_some_and(|xml| xml.apply_filter(query))
+    }
 
-    pub fn clear_filter(&mut self) {
+    fn clear_filter(&mut self) {
+        if let Some(xml) = self.source_view_mut() {
+            xml.clear_filter();
+        }
         self.last_query = None;
     }
 
-    pub fn breadcrumbs(&self) -> String {
+    fn breadcrumbs(&self) -> String {
+        if let Some(xml) = self.source_view() {
+            return xml.breadcrumbs();
+        }
         format!(
             "{} {}x{} {}",
             self.file_name,
@@ -197,7 +306,16 @@ impl ImageEngine {
         )
     }
 
-    pub fn status_line(&self) -> String {
+    fn status_line(&self) -> String {
+        if let Some(xml) = self.source_view() {
+            return format!("s image info | {}", xml.status_line());
+        }
+        if self.source.is_some() {
+            return format!(
+                "j/k move | gg/G jump | Ctrl+u/d half-page | s element tree | {}x{} SVG",
+                self.info.width, self.info.height
+            );
+        }
         format!(
             "j/k move | gg/G jump | Ctrl+u/d half-page | {}x{} {} {}bpp",
             self.info.width,
@@ -207,20 +325,33 @@ impl ImageEngine {
         )
     }
 
-    #[allow(dead_code)]
-    pub fn selected_path(&self) -> Option<String> {
+    /// Keys specific to this view, listed in the help overlay
+    fn help_lines(&self) -> Vec<Line<'static>> {
+        if self.source.is_none() {
+            return Vec::new();
+        }
+        vec![Line::from("  s            Toggle SVG info / element tree")]
+    }
+
+    fn selected_path(&self) -> Option<String> {
         None
     }
 
     /// Get the content of the currently selected line
-    pub fn get_selected_line(&self) -> Option<String> {
+    fn get_selected_line(&self) -> Option<String> {
+        if let Some(xml) = self.source_view() {
+            return xml.get_selected_line();
+        }
         self.lines.get(self.selection).map(|line| {
             format!("{}: {}", line.label, line.value)
         })
     }
 
     /// Get lines in a range (inclusive), joined by newlines
-    pub fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
+    fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
+        if let Some(xml) = self.source_view() {
+            return xml.get_lines_range(start, end);
+        }
         let (start, end) = if start <= end { (start, end) } else { (end, start) };
         let total = self.lines.len();
         if start >= total { return None; }
@@ -230,15 +361,51 @@ impl ImageEngine {
     }
 
     /// Get current selection index (for visual mode)
-    pub fn selection(&self) -> usize {
-        self.selection
+    fn selection(&self) -> usize {
+        self.source_view().map_or(self.selection, |xml| xml.selection())
     }
 
-    pub fn content_height(&self) -> usize {
+    fn select(&mut self, row: usize) {
+        if let Some(xml) = self.source_view_mut() {
+            xml.select(row);
+            return;
+        }
+        self.selection = row.min(self.lines.len().saturating_sub(1));
+    }
+
+    /// Scroll so the selection sits at the top, middle or bottom of the view (`zt`/`zz`/`zb`)
+    fn align_scroll(&mut self, align: super::ScrollAlign) {
+        if let Some(xml) = self.source_view_mut() {
+            xml.align_scroll(align);
+            return;
+        }
+        self.scroll = super::aligned_scroll(self.selection, self.last_view_height, align);
+    }
+
+    fn content_height(&mut self) -> usize {
+        if let Some(xml) = self.source_view_mut() {
+            return xml.content_height();
+        }
         self.lines.len()
     }
 
-    pub fn render_plain_lines(&self, _width: u16) -> Vec<Line<'static>> {
+    /// Parsed structure for `--dump`
+    fn to_json(&mut self) -> serde_json::Value {
+        let info = &self.info;
+        json!({
+            "width": info.width,
+            "height": info.height,
+            "format": info.format,
+            "color_type": info.color_type,
+            "bits_per_pixel": info.bits_per_pixel,
+            "file_size": info.file_size,
+        })
+    }
+
+    fn render_plain_lines(&mut self, width: u16) -> Vec<Line<'static>> {
+        if let Some(xml) = self.source_view_mut() {
+            return xml.render_plain_lines(width);
+        }
         self.lines
             .iter()
             .map(|line| {
@@ -337,6 +504,41 @@ fn build_info_lines(info: &ImageInfo, file_name: &str) -> Vec<InfoLine> {
     lines
 }
 
+/// Info rows for an SVG: size, view box and how many elements of each kind it draws
+fn build_svg_info_lines(
+    info: &ImageInfo,
+    file_name: &str,
+    view_box: Option<&str>,
+    counts: &[(&str, usize)],
+) -> Vec<InfoLine> {
+    let row = |label: &str, value: String| InfoLine { label: label.to_string(), value };
+    let mut lines = vec![
+        row("--- Basic Info ---", String::new()),
+        row("File", file_name.to_string()),
+        row("Format", info.format.clone()),
+        row("Dimensions", format!("{} x {} (scalable)", info.width, info.height)),
+    ];
+    if info.width > 0 && info.height > 0 {
+        lines.push(row("Aspect Ratio", calculate_aspect_ratio(info.width, info.height)));
+    }
+    if let Some(view_box) = view_box {
+        lines.push(row("View Box", view_box.to_string()));
+    }
+    lines.push(row("", String::new()));
+    lines.push(row("--- Content ---", String::new()));
+    lines.extend(counts.iter().map(|(label, count)| row(label, count.to_string())));
+    lines.push(row("", String::new()));
+    lines.push(row("--- File Info ---", String::new()));
+    lines.push(row("File Size", format_size(info.file_size)));
+    lines
+}
+
+/// An SVG length such as `120`, `120px` or `12.5`; relative units like `%` or `em`
+/// have no fixed size
+fn svg_length(value: &str) -> Option<f64> {
+    value.trim().trim_end_matches("px").parse().ok()
+}
+
 fn calculate_aspect_ratio(width: u32, height: u32) -> String {
     let gcd = gcd(width, height);
     let w = width / gcd;
@@ -365,23 +567,57 @@ fn gcd(a: u32, b: u32) -> u32 {
     if b == 0 { a } else { gcd(b, a % b) }
 }
 
-fn format_size(bytes: u64) -> String {
-    const KB: u64 = 1024;
-    const MB: u64 = KB * 1024;
-    const GB: u64 = MB * 1024;
-
-    if bytes >= GB {
-        format!("{:.2} GB", bytes as f64 / GB as f64)
-    } else if bytes >= MB {
-        format!("{:.2} MB", bytes as f64 / MB as f64)
-    } else if bytes >= KB {
-        format!("{:.2} KB", bytes as f64 / KB as f64)
-    } else {
-        format!("{} bytes", bytes)
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SVG: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" width="120px" height="40" viewBox="0 0 64 32"><g><rect/><circle/><path/></g><text>hi</text></svg>"#;
+
+    fn value<'a>(engine: &'a ImageEngine, label: &str) -> &'a str {
+        &engine.lines.iter().find(|line| line.label == label).unwrap().value
     }
-}
 
-fn page_jump(view_height: usize) -> usize {
-    let half = view_height / 2;
-    if half == 0 { 1 } else { half }
+    #[test]
+    fn svg_size_prefers_attributes_over_view_box() {
+        let engine = ImageEngine::from_bytes(Path::new("logo.svg"), SVG.as_bytes()).unwrap();
+        assert_eq!((engine.info.width, engine.info.height), (120, 40));
+        assert_eq!(engine.info.format, "SVG");
+        assert_eq!(value(&engine, "View Box"), "0 0 64 32");
+
+        let bare = r#"<svg viewBox="0 0 64 32" width="100%"><rect/></svg>"#;
+        let engine = ImageEngine::from_bytes(Path::new("logo.svg"), bare.as_bytes()).unwrap();
+        assert_eq!((engine.info.width, engine.info.height), (64, 32));
+        assert_eq!(svg_length(" 12.5px "), Some(12.5));
+        assert_eq!(svg_length("100%"), None);
+    }
+
+    #[test]
+    fn svg_info_counts_elements_by_kind() {
+        let mut engine = ImageEngine::from_bytes(Path::new("logo.svg"), SVG.as_bytes()).unwrap();
+        assert_eq!(value(&engine, "Elements"), "6");
+        assert_eq!(value(&engine, "Paths"), "1");
+        assert_eq!(value(&engine, "Shapes"), "2");
+        assert_eq!(value(&engine, "Text"), "1");
+        assert_eq!(value(&engine, "Groups"), "1");
+        assert_eq!(engine.to_json()["color_type"], "Vector");
+        assert_eq!(engine.to_json()["width"], 120);
+    }
+
+    #[test]
+    fn s_toggles_the_element_tree_and_search_follows_it() {
+        let mut engine = ImageEngine::from_bytes(Path::new("logo.svg"), SVG.as_bytes()).unwrap();
+        assert!(!engine.help_lines().is_empty());
+        assert!(!engine.apply_search("circle", true));
+
+        engine.handle_key(KeyEvent::from(KeyCode::Char('s')));
+        assert_eq!(engine.name(), "XmlEngine");
+        assert!(engine.status_line().starts_with("s image info"));
+        assert_eq!(engine.content_height(), 6);
+        assert!(engine.apply_search("circle", true));
+        assert!(!engine.apply_search("ellipse", true));
+
+        engine.handle_key(KeyEvent::from(KeyCode::Char('s')));
+        assert_eq!(engine.name(), "ImageEngine");
+        assert_eq!(engine.content_height(), engine.lines.len());
+    }
 }
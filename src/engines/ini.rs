@@ -6,6 +6,10 @@ use ratatui::layout::Rect;
 use ratatui::style::{Color, Style, Stylize};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph};
+use serde_json::json;
+
+use super::util::search_matches;
+use super::Engine;
 
 #[derive(Clone)]
 enum IniLine {
@@ -17,6 +21,10 @@ enum IniLine {
 
 pub struct IniEngine {
     lines: Vec<(usize, String, IniLine)>, // (line_no, raw, parsed)
+    /// `.editorconfig`: known properties are highlighted and checked
+    editorconfig: bool,
+    /// Validation warning for each line, parallel to `lines` (`.editorconfig` only)
+    warnings: Vec<Option<String>>,
     selection: usize,
     scroll: usize,
     file_name: String,
@@ -30,17 +38,35 @@ pub struct IniEngine {
 
 impl IniEngine {
     pub fn from_path(path: &Path) -> Result<Self> {
-        let content = std::fs::read_to_string(path)?;
+        Self::from_bytes(path, &std::fs::read(path)?)
+    }
+
+    /// Create from in-memory bytes; `path` only supplies the name and extension
+    pub fn from_bytes(path: &Path, bytes: &[u8]) -> Result<Self> {
+        let content = std::str::from_utf8(bytes)?;
         let file_name = path
             .file_name()
             .and_then(|s| s.to_str())
             .unwrap_or("")
             .to_string();
 
-        let lines = parse_ini(&content);
+        let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("");
+        let lines = if ext.eq_ignore_ascii_case("properties") {
+            parse_properties(content)
+        } else {
+            parse_ini(content)
+        };
+        let editorconfig = file_name == ".editorconfig";
+        let warnings = if editorconfig {
+            lint_editorconfig(&lines)
+        } else {
+            vec![None; lines.len()]
+        };
 
         Ok(Self {
             lines,
+            editorconfig,
+            warnings,
             selection: 0,
             scroll: 0,
             file_name,
@@ -52,16 +78,59 @@ impl IniEngine {
         })
     }
 
-    pub fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+    fn search_next(&mut self, query: &str, forward: bool) -> bool {
+        let lower = query.to_lowercase();
+        let mut found = false;
+        let total = self.lines.len().max(1);
+        let start = if forward {
+            (self.selection + 1) % total
+        } else {
+            (self.selection + total - 1) % total
+        };
+
+        for offset in 0..total {
+            let idx = if forward {
+                (start + offset) % total
+            } else {
+                (start + total - offset % total) % total
+            };
+            let text = match &self.lines[idx].2 {
+                IniLine::Section(name) => name.clone(),
+                IniLine::KeyValue { key, value } => format!("{} = {}", key, value),
+                IniLine::Comment(text) => text.clone(),
+                IniLine::Empty => String::new(),
+            };
+            if search_matches(&text, &lower) {
+                self.selection = idx;
+                found = true;
+                break;
+            }
+        }
+        self.last_match = Some(query.to_string());
+        found
+    }
+}
+
+impl Engine for IniEngine {
+    fn name(&self) -> &'static str {
+        "IniEngine"
+    }
+
+    fn set_visual_range(&mut self, range: Option<(usize, usize)>) {
+        self.visual_range = range;
+    }
+
+    fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
         let height = area.height as usize;
         self.last_view_height = height;
 
-        if self.selection < self.scroll {
-            self.scroll = self.selection;
-        } else if self.selection >= self.scroll + height {
-            self.scroll = self.selection.saturating_sub(height - 1);
+        if self.lines.is_empty() {
+            super::render_placeholder(frame, area, "(empty file)");
+            return;
         }
 
+        self.scroll = super::follow_selection(self.scroll, self.selection, height, self.lines.len());
+
         let line_no_width = self.lines.len().max(1).to_string().len().max(2);
 
         let visible: Vec<Line> = self.lines
@@ -80,8 +149,10 @@ impl IniEngine {
                 } else {
                     Style::default().fg(Color::LightYellow)
                 };
-                spans.push(Span::styled(line_no_str, line_no_style));
-                spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
+                if super::line_numbers() {
+                    spans.push(Span::styled(line_no_str, line_no_style));
+                    spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
+                }
 
                 match parsed {
                     IniLine::Section(name) => {
@@ -102,6 +173,8 @@ impl IniEngine {
                     IniLine::KeyValue { key, value } => {
                         let key_style = if selected {
                             Style::default().fg(Color::Black).bg(Color::LightBlue).bold()
+                        } else if self.editorconfig && editorconfig_values(key).is_some() {
+                            Style::default().fg(Color::LightCyan).bold()
                         } else {
                             Style::default().fg(Color::White).bold()
                         };
@@ -139,6 +212,14 @@ impl IniEngine {
                     }
                     IniLine::Empty => {}
                 }
+                if let Some(warning) = &self.warnings[row] {
+                    let warn_style = if selected {
+                        Style::default().fg(Color::Black).bg(Color::LightBlue).bold()
+                    } else {
+                        Style::default().fg(Color::Yellow).bold()
+                    };
+                    spans.push(Span::styled(format!("  ⚠ {}", warning), warn_style));
+                }
 
                 Line::from(spans)
             })
@@ -148,7 +229,8 @@ impl IniEngine {
         frame.render_widget(Paragraph::new(visible).block(block), area);
     }
 
-    pub fn handle_key(&mut self, key: KeyEvent) {
+    #[allow(clippy::collapsible_match)]
+    fn handle_key(&mut self, key: KeyEvent) {
         match key.code {
             KeyCode::Char('g') => {
                 if self.pending_g {
@@ -175,12 +257,10 @@ impl IniEngine {
                 self.selection = self.selection.saturating_sub(1);
             }
             KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let jump = page_jump(self.last_view_height).min(self.selection);
-                self.selection = self.selection.saturating_sub(jump);
+                self.selection = super::half_page_up(self.selection, self.last_view_height);
             }
             KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let jump = page_jump(self.last_view_height).min(total.saturating_sub(1));
-                self.selection = (self.selection + jump).min(total.saturating_sub(1));
+                self.selection = super::half_page_down(self.selection, self.last_view_height, total);
             }
             KeyCode::Char('G') => {
                 if total > 0 {
@@ -196,6 +276,15 @@ impl IniEngine {
                     }
                 }
             }
+            KeyCode::Char('E') => {
+                // Jump to previous section
+                for i in (0..self.selection).rev() {
+                    if matches!(self.lines[i].2, IniLine::Section(_)) {
+                        self.selection = i;
+                        break;
+                    }
+                }
+            }
             KeyCode::Char('n') => {
                 if let Some(query) = self.last_match.clone() {
                     self.search_next(&query, true);
@@ -210,25 +299,26 @@ impl IniEngine {
         }
     }
 
-    pub fn apply_search(&mut self, query: &str) {
+    fn apply_search(&mut self, query: &str, forward: bool) -> bool {
         let trimmed = query.trim();
         if trimmed.is_empty() {
-            return;
+            return false;
         }
         self.last_query = Some(trimmed.to_string());
-        self.search_next(trimmed, true);
+        let found = self.search_next(trimmed, forward);
         self.last_match = Some(trimmed.to_string());
+        found
     }
 
-    pub fn apply_filter(&mut self, query: &str) {
-        self.apply_search(query);
+    fn apply_filter(&mut self, query: &str) -> bool {
+        self.apply_search(query, true)
     }
 
-    pub fn clear_filter(&mut self) {
+    fn clear_filter(&mut self) {
         self.last_query = None;
     }
 
-    pub fn breadcrumbs(&self) -> String {
+    fn breadcrumbs(&self) -> String {
         // Find current section
         let mut section = "root".to_string();
         for i in (0..=self.selection).rev() {
@@ -240,30 +330,54 @@ impl IniEngine {
         format!("{} [{}] line {}", self.file_name, section, self.selection + 1)
     }
 
-    pub fn status_line(&self) -> String {
+    fn status_line(&self) -> String {
         let query = self
             .last_query
             .as_ref()
             .map(|q| format!(" | search: {}", q))
             .unwrap_or_default();
+        let warnings = match self.warnings.iter().flatten().count() {
+            0 => String::new(),
+            1 => " | 1 warning".to_string(),
+            n => format!(" | {} warnings", n),
+        };
         format!(
-            "j/k move | gg/G jump | Ctrl+u/d half-page | e next section | n/N next/prev | / search{}",
-            query
+            "j/k move | gg/G jump | Ctrl+u/d half-page | e/E next/prev section | n/N next/prev | y copy key | / search{}{}",
+            warnings, query
         )
     }
 
-    #[allow(dead_code)]
-    pub fn selected_path(&self) -> Option<String> {
-        None
+    /// Keys specific to this view, listed in the help overlay
+    fn help_lines(&self) -> Vec<Line<'static>> {
+        vec![
+            Line::from("  e/E          Next/previous section"),
+        ]
+    }
+
+    /// `section.key` for a key (just `key` before the first section), or the section name
+    fn selected_path(&self) -> Option<String> {
+        let key = match &self.lines.get(self.selection)?.2 {
+            IniLine::Section(name) => return Some(name.clone()),
+            IniLine::KeyValue { key, .. } => key,
+            IniLine::Comment(_) | IniLine::Empty => return None,
+        };
+        let section = self.lines[..self.selection].iter().rev().find_map(|(_, _, parsed)| match parsed {
+            IniLine::Section(name) => Some(name),
+            _ => None,
+        });
+        Some(match section {
+            Some(section) => format!("{}.{}", section, key),
+            None => key.clone(),
+        })
     }
 
     /// Get the content of the currently selected line
-    pub fn get_selected_line(&self) -> Option<String> {
+    fn get_selected_line(&self) -> Option<String> {
         self.lines.get(self.selection).map(|(_, raw, _)| raw.clone())
     }
 
     /// Get lines in a range (inclusive), joined by newlines
-    pub fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
+    fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
         let (start, end) = if start <= end { (start, end) } else { (end, start) };
         let total = self.lines.len();
         if start >= total { return None; }
@@ -273,25 +387,60 @@ impl IniEngine {
     }
 
     /// Get current selection index (for visual mode)
-    pub fn selection(&self) -> usize {
+    fn selection(&self) -> usize {
         self.selection
     }
 
-    pub fn content_height(&self) -> usize {
+    fn select(&mut self, row: usize) {
+        self.selection = row.min(self.lines.len().saturating_sub(1));
+    }
+
+    /// Scroll so the selection sits at the top, middle or bottom of the view (`zt`/`zz`/`zb`)
+    fn align_scroll(&mut self, align: super::ScrollAlign) {
+        self.scroll = super::aligned_scroll(self.selection, self.last_view_height, align);
+    }
+
+    fn content_height(&mut self) -> usize {
         self.lines.len()
     }
 
-    pub fn render_plain_lines(&self, _width: u16) -> Vec<Line<'static>> {
+    /// Parsed structure for `--dump`
+    fn to_json(&mut self) -> serde_json::Value {
+        // Keys before the first header belong to an unnamed section
+        let mut sections = vec![(String::new(), Vec::new())];
+        for (line_no, _, parsed) in &self.lines {
+            match parsed {
+                IniLine::Section(name) => sections.push((name.clone(), Vec::new())),
+                IniLine::KeyValue { key, value } => {
+                    if let Some((_, keys)) = sections.last_mut() {
+                        keys.push(json!({ "key": key, "value": value, "line": line_no }));
+                    }
+                }
+                IniLine::Comment(_) | IniLine::Empty => {}
+            }
+        }
+        let sections: Vec<_> = sections
+            .into_iter()
+            .filter(|(name, keys)| !name.is_empty() || !keys.is_empty())
+            .map(|(name, keys)| json!({ "name": name, "keys": keys }))
+            .collect();
+        json!({ "sections": sections })
+    }
+
+    fn render_plain_lines(&mut self, _width: u16) -> Vec<Line<'static>> {
         let line_no_width = self.lines.len().max(1).to_string().len().max(2);
         self.lines
             .iter()
-            .map(|(line_no, _raw, parsed)| {
+            .zip(&self.warnings)
+            .map(|((line_no, _raw, parsed), warning)| {
                 let mut spans = Vec::new();
-                spans.push(Span::styled(
-                    format!("{:>width$} ", line_no, width = line_no_width),
-                    Style::default().fg(Color::LightYellow),
-                ));
-                spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
+                if super::line_numbers() {
+                    spans.push(Span::styled(
+                        format!("{:>width$} ", line_no, width = line_no_width),
+                        Style::default().fg(Color::LightYellow),
+                    ));
+                    spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
+                }
 
                 match parsed {
                     IniLine::Section(name) => {
@@ -310,40 +459,14 @@ impl IniEngine {
                     }
                     IniLine::Empty => {}
                 }
+                if let Some(warning) = warning {
+                    spans.push(Span::styled(format!("  ⚠ {}", warning), Style::default().fg(Color::Yellow)));
+                }
 
                 Line::from(spans)
             })
             .collect()
     }
-
-    fn search_next(&mut self, query: &str, forward: bool) {
-        let lower = query.to_lowercase();
-        let total = self.lines.len().max(1);
-        let start = if forward {
-            (self.selection + 1) % total
-        } else {
-            self.selection.saturating_sub(1)
-        };
-
-        for offset in 0..total {
-            let idx = if forward {
-                (start + offset) % total
-            } else {
-                (start + total - offset % total) % total
-            };
-            let text = match &self.lines[idx].2 {
-                IniLine::Section(name) => name.clone(),
-                IniLine::KeyValue { key, value } => format!("{} = {}", key, value),
-                IniLine::Comment(text) => text.clone(),
-                IniLine::Empty => String::new(),
-            };
-            if text.to_lowercase().contains(&lower) {
-                self.selection = idx;
-                break;
-            }
-        }
-        self.last_match = Some(query.to_string());
-    }
 }
 
 fn parse_ini(content: &str) -> Vec<(usize, String, IniLine)> {
@@ -379,7 +502,214 @@ fn parse_ini(content: &str) -> Vec<(usize, String, IniLine)> {
     lines
 }
 
-fn page_jump(view_height: usize) -> usize {
-    let half = view_height / 2;
-    if half == 0 { 1 } else { half }
+/// Parse Java `.properties`: `#`/`!` comments, `=`/`:`/whitespace separators,
+/// backslash line continuations and `\uXXXX` escapes
+fn parse_properties(content: &str) -> Vec<(usize, String, IniLine)> {
+    let mut lines = Vec::new();
+    let mut physical = content.lines().enumerate();
+
+    while let Some((idx, line)) = physical.next() {
+        let line_no = idx + 1;
+        let trimmed = line.trim_start();
+
+        if trimmed.is_empty() {
+            lines.push((line_no, line.to_string(), IniLine::Empty));
+            continue;
+        }
+        if trimmed.starts_with('#') || trimmed.starts_with('!') {
+            lines.push((line_no, line.to_string(), IniLine::Comment(trimmed.to_string())));
+            continue;
+        }
+
+        // Join continuation lines: an odd number of trailing backslashes escapes the newline
+        let mut raw = line.to_string();
+        let mut logical = trimmed.to_string();
+        while ends_with_continuation(&logical) {
+            logical.pop();
+            match physical.next() {
+                Some((_, next)) => {
+                    raw.push('\n');
+                    raw.push_str(next);
+                    logical.push_str(next.trim_start());
+                }
+                None => break,
+            }
+        }
+
+        let (key, value) = split_property(&logical);
+        lines.push((line_no, raw, IniLine::KeyValue { key: unescape_property(key), value: unescape_property(value) }));
+    }
+
+    lines
+}
+
+fn ends_with_continuation(line: &str) -> bool {
+    line.chars().rev().take_while(|&c| c == '\\').count() % 2 == 1
+}
+
+/// Split at the first unescaped `=`, `:` or whitespace; one `=`/`:` after
+/// whitespace still counts as the separator
+fn split_property(line: &str) -> (&str, &str) {
+    let mut escaped = false;
+    for (pos, c) in line.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if c == '\\' {
+            escaped = true;
+        } else if c == '=' || c == ':' || c.is_whitespace() {
+            let key = &line[..pos];
+            let mut rest = line[pos..].trim_start();
+            if c.is_whitespace() {
+                rest = rest.strip_prefix(['=', ':']).unwrap_or(rest).trim_start();
+            } else {
+                rest = line[pos + 1..].trim_start();
+            }
+            return (key, rest);
+        }
+    }
+    (line, "")
+}
+
+fn unescape_property(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some('f') => out.push('\u{c}'),
+            Some('u') => {
+                let hex: String = chars.by_ref().take(4).collect();
+                match u32::from_str_radix(&hex, 16).ok().and_then(char::from_u32) {
+                    Some(decoded) => out.push(decoded),
+                    None => {
+                        out.push_str("\\u");
+                        out.push_str(&hex);
+                    }
+                }
+            }
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out
+}
+
+/// Allowed values for a standard EditorConfig property, or None for unknown properties.
+/// Numeric properties (see `is_numeric_property`) also take a number.
+fn editorconfig_values(key: &str) -> Option<&'static [&'static str]> {
+    Some(match key.to_ascii_lowercase().as_str() {
+        "indent_style" => &["tab", "space"],
+        "indent_size" => &["tab"],
+        "tab_width" => &[],
+        "end_of_line" => &["lf", "cr", "crlf"],
+        "charset" => &["latin1", "utf-8", "utf-8-bom", "utf-16be", "utf-16le"],
+        "trim_trailing_whitespace" | "insert_final_newline" | "root" => &["true", "false"],
+        "max_line_length" => &["off"],
+        _ => return None,
+    })
+}
+
+/// Properties whose value is a number, optionally one of the listed words instead
+fn is_numeric_property(key: &str) -> bool {
+    matches!(key.to_ascii_lowercase().as_str(), "indent_size" | "tab_width" | "max_line_length")
+}
+
+/// Check `.editorconfig` properties: unknown names, bad values, and `root` or
+/// properties outside where EditorConfig reads them
+fn lint_editorconfig(lines: &[(usize, String, IniLine)]) -> Vec<Option<String>> {
+    let mut in_section = false;
+    lines
+        .iter()
+        .map(|(_, _, parsed)| match parsed {
+            IniLine::Section(glob) => {
+                in_section = true;
+                glob.is_empty().then(|| "empty section glob matches nothing".to_string())
+            }
+            IniLine::KeyValue { key, value } => {
+                let is_root = key.eq_ignore_ascii_case("root");
+                if is_root && in_section {
+                    return Some("`root` only counts before the first section".to_string());
+                }
+                if !is_root && !in_section {
+                    return Some("properties before the first section are ignored".to_string());
+                }
+                let Some(allowed) = editorconfig_values(key) else {
+                    return Some(format!("unknown property `{}`", key));
+                };
+                let value = value.to_ascii_lowercase();
+                let numeric = is_numeric_property(key) && value.parse::<u32>().is_ok();
+                if numeric || value == "unset" || allowed.contains(&value.as_str()) {
+                    return None;
+                }
+                let mut expected: Vec<&str> = allowed.to_vec();
+                if is_numeric_property(key) {
+                    expected.insert(0, "a number");
+                }
+                Some(format!("expected {}", expected.join(", ")))
+            }
+            IniLine::Comment(_) | IniLine::Empty => None,
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selected_path_joins_section_and_key() {
+        let content = b"top = 1\n[server]\n; comment\nport = 80\n";
+        let mut engine = IniEngine::from_bytes(Path::new("app.ini"), content).unwrap();
+        let path_at = |engine: &mut IniEngine, row| {
+            engine.selection = row;
+            engine.selected_path()
+        };
+        assert_eq!(path_at(&mut engine, 0).as_deref(), Some("top"));
+        assert_eq!(path_at(&mut engine, 1).as_deref(), Some("server"));
+        assert_eq!(path_at(&mut engine, 2), None);
+        assert_eq!(path_at(&mut engine, 3).as_deref(), Some("server.port"));
+    }
+
+    #[test]
+    fn properties_decode_escapes_and_join_continuations() {
+        let content = "! comment\ngreeting = caf\\u00e9\npath:/usr/\\\n    local\nspaced\\ key name value\n";
+        let lines = parse_properties(content);
+        let pairs: Vec<(String, String)> = lines
+            .iter()
+            .filter_map(|(_, _, parsed)| match parsed {
+                IniLine::KeyValue { key, value } => Some((key.clone(), value.clone())),
+                _ => None,
+            })
+            .collect();
+        assert!(matches!(lines[0].2, IniLine::Comment(_)));
+        assert_eq!(pairs[0], ("greeting".to_string(), "café".to_string()));
+        assert_eq!(pairs[1], ("path".to_string(), "/usr/local".to_string()));
+        assert_eq!(pairs[2], ("spaced key".to_string(), "name value".to_string()));
+        assert_eq!(lines.len(), 4);
+    }
+
+    #[test]
+    fn editorconfig_flags_unknown_properties_and_bad_values() {
+        let content = "root = true\nindent_size = 2\n\n[*.py]\nindent_style = spaces\nindent_size = 4\n\
+                       max_line_length = off\ncharset = UTF-8\ncolour = red\nroot = false\n";
+        let engine = IniEngine::from_bytes(Path::new(".editorconfig"), content.as_bytes()).unwrap();
+        let flagged: Vec<usize> = engine
+            .lines
+            .iter()
+            .zip(&engine.warnings)
+            .filter(|(_, warning)| warning.is_some())
+            .map(|((line_no, _, _), _)| *line_no)
+            .collect();
+        assert_eq!(flagged, [2, 5, 9, 10]);
+        assert_eq!(engine.warnings[4].as_deref(), Some("expected tab, space"));
+
+        let plain = IniEngine::from_bytes(Path::new("setup.cfg"), content.as_bytes()).unwrap();
+        assert!(plain.warnings.iter().all(Option::is_none));
+    }
 }
@@ -1,24 +1,31 @@
-use std::collections::HashSet;
-use std::fs::File;
+use std::collections::{BTreeSet, HashMap};
 use std::path::Path;
 
 use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-use memmap2::Mmap;
 use ratatui::layout::Rect;
 use ratatui::style::{Color, Style, Stylize};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph};
+use rayon::prelude::*;
+use serde_json::json;
+
+use super::util::{line_text, search_matches, truncate, Source};
+use super::Engine;
+
+/// Records read for the `s` schema summary
+const SCHEMA_SAMPLE: usize = 1000;
 
 /// JsonlEngine uses memory-mapped files for efficient streaming of JSON Lines files.
 /// Each line is parsed on-demand, only when visible.
 pub struct JsonlEngine {
-    /// Memory-mapped file content
-    mmap: Mmap,
+    /// File content (memory-mapped when read from disk)
+    data: Source,
     /// Byte offsets for the start of each line
     line_offsets: Vec<usize>,
-    /// Which lines are expanded (show full JSON tree)
-    expanded: HashSet<usize>,
+    /// Expanded lines (showing the full JSON tree) → rows that tree takes,
+    /// counted once on expanding so scrolling never re-parses records
+    expanded: HashMap<usize, usize>,
     /// Cached parsed previews for visible lines
     selection: usize,
     scroll: usize,
@@ -31,13 +38,22 @@ pub struct JsonlEngine {
     filtered_indices: Option<Vec<usize>>,
     /// Visual selection range (start, end) for highlighting
     pub visual_range: Option<(usize, usize)>,
+    /// Open schema popup: (title, one line per key)
+    schema: Option<(String, String)>,
 }
 
 impl JsonlEngine {
     pub fn from_path(path: &Path) -> Result<Self> {
-        let file = File::open(path)?;
-        let mmap = unsafe { Mmap::map(&file)? };
-        let line_offsets = build_line_offsets(&mmap);
+        Ok(Self::from_source(path, Source::map(path)?))
+    }
+
+    /// Create from in-memory bytes; `path` only supplies the displayed name
+    pub fn from_bytes(path: &Path, bytes: &[u8]) -> Result<Self> {
+        Ok(Self::from_source(path, Source::Owned(bytes.to_vec())))
+    }
+
+    fn from_source(path: &Path, data: Source) -> Self {
+        let line_offsets = build_line_offsets(&data);
 
         let file_name = path
             .file_name()
@@ -45,10 +61,10 @@ impl JsonlEngine {
             .unwrap_or("")
             .to_string();
 
-        Ok(Self {
-            mmap,
+        Self {
+            data,
             line_offsets,
-            expanded: HashSet::new(),
+            expanded: HashMap::new(),
             selection: 0,
             scroll: 0,
             file_name,
@@ -58,7 +74,8 @@ impl JsonlEngine {
             last_match: None,
             filtered_indices: None,
             visual_range: None,
-        })
+            schema: None,
+        }
     }
 
     /// Get line content at given index (zero-copy from mmap)
@@ -70,18 +87,9 @@ impl JsonlEngine {
         let end = if idx + 1 < self.line_offsets.len() {
             self.line_offsets[idx + 1]
         } else {
-            self.mmap.len()
+            self.data.len()
         };
-
-        let mut line_end = end;
-        if line_end > start && self.mmap.get(line_end - 1) == Some(&b'\n') {
-            line_end -= 1;
-        }
-        if line_end > start && self.mmap.get(line_end - 1) == Some(&b'\r') {
-            line_end -= 1;
-        }
-
-        std::str::from_utf8(&self.mmap[start..line_end]).ok()
+        line_text(&self.data[start..end])
     }
 
     fn line_count(&self) -> usize {
@@ -118,7 +126,7 @@ impl JsonlEngine {
                 };
                 (preview, true)
             }
-            Err(_) => (line.chars().take(60).collect::<String>(), false),
+            Err(_) => (line.chars().take(super::preview_len(60)).collect::<String>(), false),
         }
     }
 
@@ -174,45 +182,149 @@ impl JsonlEngine {
             serde_json::Value::Null => "null".to_string(),
             serde_json::Value::Bool(b) => b.to_string(),
             serde_json::Value::Number(n) => n.to_string(),
-            serde_json::Value::String(s) => {
-                if s.len() > 40 {
-                    format!("\"{}...\"", &s[..37])
-                } else {
-                    format!("\"{}\"", s)
-                }
-            }
+            serde_json::Value::String(s) => format!("\"{}\"", truncate(s, super::preview_len(40))),
             serde_json::Value::Array(arr) => format!("[{} items]", arr.len()),
             serde_json::Value::Object(map) => format!("{{{} keys}}", map.len()),
         }
     }
 
-    pub fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+    /// Keys seen across the first `SCHEMA_SAMPLE` records with their types and how
+    /// often they occur, e.g. `user.id: string (100%)`
+    fn schema_summary(&self) -> (String, String) {
+        let mut keys: HashMap<String, (BTreeSet<&'static str>, usize)> = HashMap::new();
+        let mut records = 0;
+        for idx in 0..self.line_count().min(SCHEMA_SAMPLE) {
+            let value = self.get_line(idx).and_then(|line| serde_json::from_str::<serde_json::Value>(line).ok());
+            if let Some(serde_json::Value::Object(map)) = value {
+                records += 1;
+                collect_schema(&map, "", &mut keys);
+            }
+        }
+
+        let mut names: Vec<_> = keys.keys().cloned().collect();
+        names.sort();
+        let text = if names.is_empty() {
+            "(no JSON objects in sample)".to_string()
+        } else {
+            names
+                .iter()
+                .map(|name| {
+                    let (types, count) = &keys[name];
+                    let types: Vec<_> = types.iter().copied().collect();
+                    format!("{}: {} ({}%)", name, types.join("|"), count * 100 / records)
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        (format!("Schema of {} records", records), text)
+    }
+
+    fn search_next(&mut self, query: &str, forward: bool) -> bool {
+        let lower = query.to_lowercase();
+        let mut found = false;
+        let total = self.display_count().max(1);
+        let start = if forward {
+            (self.selection + 1) % total
+        } else {
+            (self.selection + total - 1) % total
+        };
+
+        for offset in 0..total {
+            let idx = if forward {
+                (start + offset) % total
+            } else {
+                (start + total - offset % total) % total
+            };
+            let line = self.display_to_actual(idx).and_then(|actual| self.get_line(actual));
+            if line.is_some_and(|line| search_matches(line, &lower)) {
+                self.selection = idx;
+                found = true;
+                break;
+            }
+        }
+        self.last_match = Some(query.to_string());
+        found
+    }
+
+    fn is_displayed(&self, actual_idx: usize) -> bool {
+        match &self.filtered_indices {
+            Some(indices) => indices.binary_search(&actual_idx).is_ok(),
+            None => actual_idx < self.line_count(),
+        }
+    }
+
+    /// Rows taken by the expanded tree below an actual line (0 if collapsed)
+    fn expanded_height(&self, actual_idx: usize) -> usize {
+        self.expanded.get(&actual_idx).copied().unwrap_or(0)
+    }
+
+    /// Expand an actual line, or collapse it when already expanded
+    fn toggle_expanded(&mut self, actual_idx: usize) {
+        if self.expanded.remove(&actual_idx).is_none() {
+            let rows = self
+                .get_line(actual_idx)
+                .and_then(|line| serde_json::from_str::<serde_json::Value>(line).ok())
+                .map_or(0, |value| count_json_nodes(&value));
+            self.expanded.insert(actual_idx, rows);
+        }
+    }
+}
+
+impl Engine for JsonlEngine {
+    fn name(&self) -> &'static str {
+        "JsonlEngine"
+    }
+
+    fn set_visual_range(&mut self, range: Option<(usize, usize)>) {
+        self.visual_range = range;
+    }
+
+    fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
         let height = area.height as usize;
         self.last_view_height = height;
 
-        if self.selection < self.scroll {
-            self.scroll = self.selection;
-        } else if self.selection >= self.scroll + height {
-            self.scroll = self.selection.saturating_sub(height - 1);
+        if self.display_count() == 0 {
+            let message = if self.filtered_indices.is_some() { "(no matches)" } else { "(empty file)" };
+            super::render_placeholder(frame, area, message);
+            return;
+        }
+
+        let display_total = self.display_count();
+        if self.selection >= display_total {
+            self.selection = display_total - 1;
         }
 
+        self.scroll = super::follow_selection(self.scroll, self.selection, height, display_total);
+        // Expanded records above the selection take extra rows; scroll further
+        // until the selected record itself is on screen, walking up from it
+        let mut top = self.selection;
+        let mut rows_before = 0;
+        while top > self.scroll {
+            let rows = self.display_to_actual(top - 1).map_or(1, |idx| 1 + self.expanded_height(idx));
+            if rows_before + rows >= height {
+                break;
+            }
+            rows_before += rows;
+            top -= 1;
+        }
+        self.scroll = top;
+
         let total_lines = self.line_count();
         let line_no_width = total_lines.max(1).to_string().len().max(2);
 
         let mut visible_lines: Vec<Line> = Vec::new();
-        let mut line_idx = 0;
+        let mut display_idx = self.scroll;
 
-        // Build visible content, accounting for expanded lines
-        while visible_lines.len() < height && line_idx < total_lines {
-            if line_idx < self.scroll {
-                line_idx += 1;
-                continue;
-            }
+        // Build visible content from display rows, accounting for expanded lines
+        while visible_lines.len() < height && display_idx < display_total {
+            let Some(line_idx) = self.display_to_actual(display_idx) else {
+                break;
+            };
 
             if let Some(content) = self.get_line(line_idx) {
                 let (preview, is_valid) = self.parse_line_preview(content);
-                let selected = line_idx == self.selection;
-                let is_expanded = self.expanded.contains(&line_idx);
+                let selected = display_idx == self.selection;
+                let is_expanded = self.expanded.contains_key(&line_idx);
 
                 // Main line
                 let mut spans = Vec::new();
@@ -222,8 +334,10 @@ impl JsonlEngine {
                 } else {
                     Style::default().fg(Color::DarkGray)
                 };
-                spans.push(Span::styled(line_no, line_no_style));
-                spans.push(Span::styled("│ ", Style::default().fg(Color::DarkGray)));
+                if super::line_numbers() {
+                    spans.push(Span::styled(line_no, line_no_style));
+                    spans.push(Span::styled("│ ", Style::default().fg(Color::DarkGray)));
+                }
 
                 // Expand/collapse marker
                 if is_valid {
@@ -256,24 +370,35 @@ impl JsonlEngine {
                             break;
                         }
                         let mut spans = Vec::new();
-                        spans.push(Span::styled(
-                            " ".repeat(line_no_width + 1),
-                            Style::default(),
-                        ));
-                        spans.push(Span::styled("│ ", Style::default().fg(Color::DarkGray)));
+                        if super::line_numbers() {
+                            spans.push(Span::styled(" ".repeat(line_no_width + 1), Style::default()));
+                            spans.push(Span::styled("│ ", Style::default().fg(Color::DarkGray)));
+                        }
                         spans.push(Span::styled(text, style));
                         visible_lines.push(Line::from(spans));
                     }
                 }
             }
-            line_idx += 1;
+            display_idx += 1;
         }
 
         let block = Block::default().borders(Borders::NONE);
         frame.render_widget(Paragraph::new(visible_lines).block(block), area);
+
+        if let Some((title, text)) = &self.schema {
+            super::render_popup(frame, area, title, text);
+        }
     }
 
-    pub fn handle_key(&mut self, key: KeyEvent) {
+    #[allow(clippy::collapsible_match)]
+    fn handle_key(&mut self, key: KeyEvent) {
+        // The schema popup is modal: it only closes
+        if self.schema.is_some() {
+            if matches!(key.code, KeyCode::Char('s') | KeyCode::Enter | KeyCode::Esc) {
+                self.schema = None;
+            }
+            return;
+        }
         match key.code {
             KeyCode::Char('g') => {
                 if self.pending_g {
@@ -289,7 +414,7 @@ impl JsonlEngine {
             }
         }
 
-        let total = self.line_count();
+        let total = self.display_count();
         match key.code {
             KeyCode::Char('j') | KeyCode::Down => {
                 if self.selection + 1 < total {
@@ -300,12 +425,10 @@ impl JsonlEngine {
                 self.selection = self.selection.saturating_sub(1);
             }
             KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let jump = page_jump(self.last_view_height).min(self.selection);
-                self.selection = self.selection.saturating_sub(jump);
+                self.selection = super::half_page_up(self.selection, self.last_view_height);
             }
             KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let jump = page_jump(self.last_view_height).min(total.saturating_sub(1));
-                self.selection = (self.selection + jump).min(total.saturating_sub(1));
+                self.selection = super::half_page_down(self.selection, self.last_view_height, total);
             }
             KeyCode::Char('G') => {
                 if total > 0 {
@@ -313,13 +436,15 @@ impl JsonlEngine {
                 }
             }
             KeyCode::Enter => {
-                // Toggle expand/collapse
-                if self.expanded.contains(&self.selection) {
-                    self.expanded.remove(&self.selection);
-                } else {
-                    self.expanded.insert(self.selection);
+                // Toggle expand/collapse; expansion is keyed by actual line so it
+                // survives filtering
+                if let Some(actual_idx) = self.display_to_actual(self.selection) {
+                    self.toggle_expanded(actual_idx);
                 }
             }
+            KeyCode::Char('s') => {
+                self.schema = Some(self.schema_summary());
+            }
             KeyCode::Char('n') => {
                 if let Some(query) = self.last_match.clone() {
                     self.search_next(&query, true);
@@ -334,46 +459,22 @@ impl JsonlEngine {
         }
     }
 
-    pub fn apply_search(&mut self, query: &str) {
+    fn apply_search(&mut self, query: &str, forward: bool) -> bool {
         let trimmed = query.trim();
         if trimmed.is_empty() {
-            return;
+            return false;
         }
         self.last_query = Some(trimmed.to_string());
-        self.search_next(trimmed, true);
+        let found = self.search_next(trimmed, forward);
         self.last_match = Some(trimmed.to_string());
+        found
     }
 
-    fn search_next(&mut self, query: &str, forward: bool) {
-        let lower = query.to_lowercase();
-        let total = self.line_count().max(1);
-        let start = if forward {
-            (self.selection + 1) % total
-        } else {
-            self.selection.saturating_sub(1)
-        };
-
-        for offset in 0..total {
-            let idx = if forward {
-                (start + offset) % total
-            } else {
-                (start + total - offset % total) % total
-            };
-            if let Some(line) = self.get_line(idx) {
-                if line.to_lowercase().contains(&lower) {
-                    self.selection = idx;
-                    break;
-                }
-            }
-        }
-        self.last_match = Some(query.to_string());
-    }
-
-    pub fn breadcrumbs(&self) -> String {
-        format!("{} line {}/{}", self.file_name, self.selection + 1, self.line_count())
+    fn breadcrumbs(&self) -> String {
+        format!("{} line {}/{}", self.file_name, self.selection + 1, self.display_count())
     }
 
-    pub fn status_line(&self) -> String {
+    fn status_line(&self) -> String {
         let query = self
             .last_query
             .as_ref()
@@ -385,49 +486,58 @@ impl JsonlEngine {
             ""
         };
         format!(
-            "j/k move | gg/G jump | Ctrl+u/d half-page | Enter expand/collapse | n/N next/prev | / search | f filter{}{}",
+            "j/k move | gg/G jump | Ctrl+u/d half-page | Enter expand/collapse | s schema | n/N next/prev | / search | f filter{}{}",
             filter, query
         )
     }
 
-    pub fn apply_filter(&mut self, query: &str) {
+    fn apply_filter(&mut self, query: &str) -> bool {
         let trimmed = query.trim();
         if trimmed.is_empty() {
-            return;
+            return false;
         }
         let lower = trimmed.to_lowercase();
-        let mut matches = Vec::new();
-        for idx in 0..self.line_count() {
-            if let Some(line) = self.get_line(idx) {
-                if line.to_lowercase().contains(&lower) {
-                    matches.push(idx);
-                }
-            }
-        }
+        // Scan in parallel; indexed collect keeps matches in line order
+        let matches: Vec<usize> = (0..self.line_count())
+            .into_par_iter()
+            .filter(|&idx| {
+                self.get_line(idx)
+                    .is_some_and(|line| search_matches(line, &lower))
+            })
+            .collect();
+        let found = !matches.is_empty();
         self.filtered_indices = Some(matches);
         self.selection = 0;
         self.scroll = 0;
+        found
     }
 
-    pub fn clear_filter(&mut self) {
+    fn clear_filter(&mut self) {
         self.filtered_indices = None;
         self.selection = 0;
         self.scroll = 0;
     }
 
-    #[allow(dead_code)]
-    pub fn selected_path(&self) -> Option<String> {
+    /// Keys specific to this view, listed in the help overlay
+    fn help_lines(&self) -> Vec<Line<'static>> {
+        vec![
+            Line::from("  Enter        Expand/collapse record"),
+            Line::from("  s            Key types and frequency"),
+        ]
+    }
+
+    fn selected_path(&self) -> Option<String> {
         None
     }
 
     /// Get the content of the currently selected line
-    pub fn get_selected_line(&self) -> Option<String> {
+    fn get_selected_line(&self) -> Option<String> {
         let actual_idx = self.display_to_actual(self.selection)?;
         self.get_line(actual_idx).map(|s| s.to_string())
     }
 
     /// Get lines in a range (inclusive), joined by newlines
-    pub fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
+    fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
         let (start, end) = if start <= end { (start, end) } else { (end, start) };
         let total = self.display_count();
         if start >= total { return None; }
@@ -442,24 +552,41 @@ impl JsonlEngine {
     }
 
     /// Get current selection index (for visual mode)
-    pub fn selection(&self) -> usize {
+    fn selection(&self) -> usize {
         self.selection
     }
 
-    pub fn content_height(&self) -> usize {
-        // Base line count + expanded content
-        let mut height = self.line_count();
-        for &idx in &self.expanded {
-            if let Some(line) = self.get_line(idx) {
-                if let Ok(value) = serde_json::from_str::<serde_json::Value>(line) {
-                    height += count_json_nodes(&value);
-                }
-            }
-        }
-        height
+    fn select(&mut self, row: usize) {
+        self.selection = row.min(self.display_count().saturating_sub(1));
+    }
+
+    /// Scroll so the selection sits at the top, middle or bottom of the view (`zt`/`zz`/`zb`)
+    fn align_scroll(&mut self, align: super::ScrollAlign) {
+        self.scroll = super::aligned_scroll(self.selection, self.last_view_height, align);
+    }
+
+    fn content_height(&mut self) -> usize {
+        // Displayed line count + expanded content of displayed lines
+        let expanded_rows: usize = self
+            .expanded
+            .iter()
+            .filter(|(idx, _)| self.is_displayed(**idx))
+            .map(|(_, rows)| rows)
+            .sum();
+        self.display_count() + expanded_rows
+    }
+
+    /// Parsed structure for `--dump`
+    fn to_json(&mut self) -> serde_json::Value {
+        // Lines that don't parse are kept as strings so record numbers still line up
+        let records: Vec<_> = (0..self.line_count())
+            .filter_map(|idx| self.get_line(idx))
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap_or_else(|_| json!(line)))
+            .collect();
+        json!({ "records": records })
     }
 
-    pub fn render_plain_lines(&self, _width: u16) -> Vec<Line<'static>> {
+    fn render_plain_lines(&mut self, _width: u16) -> Vec<Line<'static>> {
         let total = self.line_count();
         let line_no_width = total.max(1).to_string().len().max(2);
 
@@ -469,8 +596,10 @@ impl JsonlEngine {
                 let (preview, _) = self.parse_line_preview(content);
                 let mut spans = Vec::new();
                 let line_no = format!("{:>width$} ", idx + 1, width = line_no_width);
-                spans.push(Span::styled(line_no, Style::default().fg(Color::DarkGray)));
-                spans.push(Span::styled("│ ", Style::default().fg(Color::DarkGray)));
+                if super::line_numbers() {
+                    spans.push(Span::styled(line_no, Style::default().fg(Color::DarkGray)));
+                    spans.push(Span::styled("│ ", Style::default().fg(Color::DarkGray)));
+                }
                 spans.push(Span::styled(preview, Style::default().fg(Color::LightGreen)));
                 Some(Line::from(spans))
             })
@@ -494,6 +623,31 @@ fn build_line_offsets(data: &[u8]) -> Vec<usize> {
     offsets
 }
 
+/// Record each key of `map` (nested objects as `parent.child`) with its JSON type
+fn collect_schema(
+    map: &serde_json::Map<String, serde_json::Value>,
+    prefix: &str,
+    keys: &mut HashMap<String, (BTreeSet<&'static str>, usize)>,
+) {
+    for (key, value) in map {
+        let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+        let kind = match value {
+            serde_json::Value::Null => "null",
+            serde_json::Value::Bool(_) => "bool",
+            serde_json::Value::Number(_) => "number",
+            serde_json::Value::String(_) => "string",
+            serde_json::Value::Array(_) => "array",
+            serde_json::Value::Object(_) => "object",
+        };
+        let entry = keys.entry(path.clone()).or_default();
+        entry.0.insert(kind);
+        entry.1 += 1;
+        if let serde_json::Value::Object(child) = value {
+            collect_schema(child, &path, keys);
+        }
+    }
+}
+
 fn count_json_nodes(value: &serde_json::Value) -> usize {
     match value {
         serde_json::Value::Object(map) => {
@@ -506,7 +660,61 @@ fn count_json_nodes(value: &serde_json::Value) -> usize {
     }
 }
 
-fn page_jump(view_height: usize) -> usize {
-    let half = view_height / 2;
-    if half == 0 { 1 } else { half }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rows(engine: &mut JsonlEngine, height: u16) -> Vec<String> {
+        let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(40, height)).unwrap();
+        terminal.draw(|frame| engine.render(frame, frame.size())).unwrap();
+        let buffer = terminal.backend().buffer();
+        (0..height).map(|y| (0..40).map(|x| buffer.get(x, y).symbol()).collect()).collect()
+    }
+
+    const RECORDS: &[u8] = b"{\"a\":1,\"x\":{\"y\":2}}\n{\"b\":2}\n{\"a\":3}\n";
+
+    #[test]
+    fn render_shows_only_filtered_records() {
+        let mut engine = JsonlEngine::from_bytes(Path::new("events.jsonl"), RECORDS).unwrap();
+        assert!(engine.apply_filter("\"a\""));
+        let drawn = rows(&mut engine, 4);
+        assert!(drawn[0].starts_with(" 1 │") && drawn[1].starts_with(" 3 │"), "{:?}", drawn);
+        assert!(drawn[2].trim().is_empty());
+    }
+
+    #[test]
+    fn expanding_under_a_filter_counts_and_scrolls_in_display_rows() {
+        let mut engine = JsonlEngine::from_bytes(Path::new("events.jsonl"), RECORDS).unwrap();
+        assert!(engine.apply_filter("\"a\""));
+        // Expand both shown records: line 1 has three nodes, line 3 one
+        engine.handle_key(KeyEvent::from(KeyCode::Enter));
+        engine.handle_key(KeyEvent::from(KeyCode::Char('j')));
+        engine.handle_key(KeyEvent::from(KeyCode::Enter));
+        assert_eq!(engine.content_height(), 2 + 3 + 1);
+
+        // Line 1 and its tree fill a three-row view, so selecting line 3 scrolls past it
+        let drawn = rows(&mut engine, 3);
+        assert_eq!(engine.scroll, 1);
+        assert!(drawn[0].starts_with(" 3 │"), "{:?}", drawn);
+
+        // Expansion is kept per record, so line 2 joins without a tree
+        engine.clear_filter();
+        assert_eq!(engine.content_height(), 3 + 3 + 1);
+    }
+
+    #[test]
+    fn schema_summary_counts_keys_and_types() {
+        let data = b"{\"user\":{\"id\":\"a\"},\"age\":30}\n{\"user\":{\"id\":\"b\"},\"age\":null}\n{\"user\":{\"id\":\"c\"}}\nnot json\n";
+        let mut engine = JsonlEngine::from_bytes(Path::new("people.jsonl"), data).unwrap();
+        engine.handle_key(KeyEvent::new(KeyCode::Char('s'), KeyModifiers::NONE));
+        let (title, text) = engine.schema.clone().unwrap();
+        assert_eq!(title, "Schema of 3 records");
+        assert_eq!(
+            text.lines().collect::<Vec<_>>(),
+            vec!["age: null|number (66%)", "user: object (100%)", "user.id: string (100%)"]
+        );
+
+        engine.handle_key(KeyEvent::new(KeyCode::Esc, KeyModifiers::NONE));
+        assert!(engine.schema.is_none());
+    }
 }
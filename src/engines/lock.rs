@@ -1,11 +1,24 @@
+use std::collections::BTreeMap;
 use std::path::Path;
 
 use anyhow::{anyhow, Result};
-use crossterm::event::{KeyCode, KeyEvent};
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::layout::{Constraint, Rect};
 use ratatui::style::{Color, Style, Stylize};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Cell, Row, Table, TableState};
+use rayon::prelude::*;
+use serde::Deserialize;
+use serde_json::json;
+use serde_json::value::RawValue;
+
+use super::util::{search_matches, truncate};
+use super::Engine;
+
+/// Column titles for lockfiles
+const LOCK_HEADERS: [&str; 5] = ["Name", "Version", "Source", "Checksum", "Dependencies"];
+/// Column titles for Python dependency manifests, which list constraints rather than resolved versions
+const PYTHON_HEADERS: [&str; 5] = ["Name", "Constraint", "Pin", "Extras", "Markers"];
 
 #[derive(Clone)]
 struct LockEntry {
@@ -18,11 +31,13 @@ struct LockEntry {
 
 pub struct LockEngine {
     entries: Vec<LockEntry>,
+    headers: [&'static str; 5],
     selection: usize,
     scroll: usize,
     file_name: String,
     last_query: Option<String>,
     pending_g: bool,
+    last_view_height: usize,
     last_match: Option<String>,
     /// Visual selection range (start, end) for highlighting
     pub visual_range: Option<(usize, usize)>,
@@ -30,39 +45,72 @@ pub struct LockEngine {
 
 impl LockEngine {
     pub fn from_path(path: &Path) -> Result<Self> {
+        Self::from_bytes(path, &std::fs::read(path)?)
+    }
+
+    /// Create from in-memory bytes; `path` only supplies the lockfile name
+    pub fn from_bytes(path: &Path, bytes: &[u8]) -> Result<Self> {
+        let content = std::str::from_utf8(bytes)?;
         let file_name = path
             .file_name()
             .and_then(|s| s.to_str())
             .unwrap_or("")
             .to_string();
+        let mut headers = LOCK_HEADERS;
         let entries = if file_name == "Cargo.lock" {
-            parse_cargo_lock(path)?
+            parse_cargo_lock(content)?
         } else if file_name == "package-lock.json" {
-            parse_package_lock(path)?
+            parse_package_lock(content)?
         } else if file_name == "pnpm-lock.yaml" || file_name == "pnpm-lock.yml" {
-            parse_pnpm_lock(path)?
+            parse_pnpm_lock(content)?
+        } else if file_name == "Pipfile.lock" {
+            headers = PYTHON_HEADERS;
+            parse_pipfile_lock(content)?
+        } else if file_name == "Pipfile" {
+            headers = PYTHON_HEADERS;
+            parse_pipfile(content)?
+        } else if file_name == "pyproject.toml" {
+            headers = PYTHON_HEADERS;
+            parse_pyproject(content)?
+        } else if is_requirements_file(&file_name) {
+            headers = PYTHON_HEADERS;
+            parse_requirements(content)
         } else {
             return Err(anyhow!("Unsupported lockfile: {}", file_name));
         };
         Ok(Self {
             entries,
+            headers,
             selection: 0,
             scroll: 0,
             file_name,
             last_query: None,
             pending_g: false,
+            last_view_height: 0,
             last_match: None,
             visual_range: None,
         })
     }
+}
+
+impl Engine for LockEngine {
+    fn name(&self) -> &'static str {
+        "LockEngine"
+    }
+
+    fn set_visual_range(&mut self, range: Option<(usize, usize)>) {
+        self.visual_range = range;
+    }
 
-    pub fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+    fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+        self.last_view_height = area.height as usize;
         let height = area.height.saturating_sub(1) as usize;
-        if self.selection < self.scroll {
-            self.scroll = self.selection;
-        } else if self.selection >= self.scroll + height {
-            self.scroll = self.selection.saturating_sub(height - 1);
+
+        if self.entries.is_empty() {
+            super::render_placeholder(frame, area, "(no packages)");
+            return;
         }
+        self.scroll = super::follow_selection(self.scroll, self.selection, height, self.entries.len());
 
         let slice = if self.entries.is_empty() {
             &[][..]
@@ -78,26 +126,32 @@ impl LockEngine {
             .bold();
         headers.push(Cell::from("#").style(header_style));
         headers.push(Cell::from("│").style(Style::default().fg(Color::LightBlue)));
-        headers.push(Cell::from("Name").style(header_style));
-        headers.push(Cell::from("Version").style(header_style));
-        headers.push(Cell::from("Source").style(header_style));
-        headers.push(Cell::from("Checksum").style(header_style));
-        headers.push(Cell::from("Dependencies").style(header_style));
+        headers.extend(self.headers.iter().map(|title| Cell::from(*title).style(header_style)));
         let header = Row::new(headers);
 
         let mut rows = Vec::new();
         for (idx, entry) in slice.iter().enumerate() {
-            let mut cells = Vec::new();
-            cells.push(
+            let row = self.scroll + idx;
+            let in_visual = self.visual_range.is_some_and(|(start, end)| {
+                let (lo, hi) = if start <= end { (start, end) } else { (end, start) };
+                row >= lo && row <= hi
+            });
+            let cells = vec![
                 Cell::from((self.scroll + idx + 1).to_string())
                     .style(Style::default().fg(Color::DarkGray)),
-            );
-            cells.push(Cell::from("│").style(Style::default().fg(Color::DarkGray)));
-            cells.push(Cell::from(truncate(&entry.name, 22)).style(Style::default().fg(Color::Cyan).bold()));
-            cells.push(Cell::from(truncate(&entry.version, 12)).style(Style::default().fg(Color::Magenta)));
-            cells.push(Cell::from(truncate(&entry.source, 28)).style(Style::default().fg(Color::Green)));
-            cells.push(Cell::from(truncate(&entry.checksum, 16)).style(Style::default().fg(Color::DarkGray)));
-            cells.push(Cell::from(truncate(&entry.dependencies.join(", "), 40)).style(Style::default().fg(Color::Yellow)));
+                Cell::from("│").style(Style::default().fg(Color::DarkGray)),
+                Cell::from(truncate(&entry.name, 22)).style(Style::default().fg(Color::Cyan).bold()),
+                Cell::from(truncate(&entry.version, 12)).style(Style::default().fg(Color::Magenta)),
+                Cell::from(truncate(&entry.source, 28)).style(Style::default().fg(source_color(&entry.source))),
+                Cell::from(truncate(&entry.checksum, 16)).style(Style::default().fg(Color::DarkGray)),
+                Cell::from(truncate(&entry.dependencies.join(", "), super::preview_len(40))).style(Style::default().fg(Color::Yellow)),
+            ];
+            let cells: Vec<Cell> = if in_visual {
+                let visual_style = Style::default().fg(Color::Black).bg(Color::LightYellow);
+                cells.into_iter().map(|cell| cell.style(visual_style)).collect()
+            } else {
+                cells
+            };
             rows.push(Row::new(cells));
         }
 
@@ -123,7 +177,8 @@ impl LockEngine {
         frame.render_stateful_widget(table, area, &mut state);
     }
 
-    pub fn handle_key(&mut self, key: KeyEvent) {
+    #[allow(clippy::collapsible_match)]
+    fn handle_key(&mut self, key: KeyEvent) {
         match key.code {
             KeyCode::Char('g') => {
                 if self.pending_g {
@@ -147,6 +202,12 @@ impl LockEngine {
             KeyCode::Char('k') | KeyCode::Up => {
                 self.selection = self.selection.saturating_sub(1);
             }
+            KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.selection = super::half_page_up(self.selection, self.last_view_height);
+            }
+            KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                self.selection = super::half_page_down(self.selection, self.last_view_height, self.entries.len());
+            }
             KeyCode::Char('G') => {
                 if !self.entries.is_empty() {
                     self.selection = self.entries.len() - 1;
@@ -166,46 +227,51 @@ impl LockEngine {
         }
     }
 
-    pub fn apply_search(&mut self, query: &str) {
+    fn apply_search(&mut self, query: &str, forward: bool) -> bool {
         let trimmed = query.trim();
         if trimmed.is_empty() {
-            return;
+            return false;
         }
         self.last_query = Some(trimmed.to_string());
-        self.search_next(trimmed, true);
+        let found = self.search_next(trimmed, forward);
         self.last_match = Some(trimmed.to_string());
+        found
     }
 
-    pub fn breadcrumbs(&self) -> String {
+    fn breadcrumbs(&self) -> String {
         format!("{} row {}", self.file_name, self.selection + 1)
     }
 
-    pub fn status_line(&self) -> String {
+    fn status_line(&self) -> String {
         let query = self
             .last_query
             .as_ref()
             .map(|q| format!(" | search: {}", q))
             .unwrap_or_default();
-        format!("j/k move | gg/G jump | n/N next/prev | / search | f filter{}", query)
+        format!("j/k move | gg/G jump | Ctrl+u/d half-page | n/N next/prev | / search | f filter{}", query)
     }
 
-    pub fn apply_filter(&mut self, query: &str) {
-        self.apply_search(query);
+    fn apply_filter(&mut self, query: &str) -> bool {
+        self.apply_search(query, true)
     }
 
-    pub fn clear_filter(&mut self) {
+    fn clear_filter(&mut self) {
         self.last_query = None;
     }
 
-    #[allow(dead_code)]
-    pub fn selected_path(&self) -> Option<String> {
+    /// Keys specific to this view, listed in the help overlay
+    fn help_lines(&self) -> Vec<Line<'static>> {
+        Vec::new()
+    }
+
+    fn selected_path(&self) -> Option<String> {
         None
     }
 
     /// Get the content of the currently selected line
-    pub fn get_selected_line(&self) -> Option<String> {
+    fn get_selected_line(&self) -> Option<String> {
         if self.selection == 0 {
-            Some("Name\tVersion\tSource\tChecksum\tDependencies".to_string())
+            Some(self.headers.join("\t"))
         } else {
             self.entries.get(self.selection.saturating_sub(1)).map(|e| {
                 format!("{}\t{}\t{}\t{}\t{}", e.name, e.version, e.source, e.checksum, e.dependencies.join(", "))
@@ -214,7 +280,7 @@ impl LockEngine {
     }
 
     /// Get lines in a range (inclusive), joined by newlines
-    pub fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
+    fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
         let (start, end) = if start <= end { (start, end) } else { (end, start) };
         let total = self.entries.len() + 1;
         if start >= total { return None; }
@@ -222,7 +288,7 @@ impl LockEngine {
         let lines: Vec<String> = (start..=end)
             .filter_map(|idx| {
                 if idx == 0 {
-                    Some("Name\tVersion\tSource\tChecksum\tDependencies".to_string())
+                    Some(self.headers.join("\t"))
                 } else {
                     self.entries.get(idx.saturating_sub(1)).map(|e| {
                         format!("{}\t{}\t{}\t{}\t{}", e.name, e.version, e.source, e.checksum, e.dependencies.join(", "))
@@ -234,25 +300,53 @@ impl LockEngine {
     }
 
     /// Get current selection index (for visual mode)
-    pub fn selection(&self) -> usize {
+    fn selection(&self) -> usize {
         self.selection
     }
 
-    pub fn content_height(&self) -> usize {
+    fn select(&mut self, row: usize) {
+        self.selection = row.min(self.entries.len().saturating_sub(1));
+    }
+
+    /// Scroll so the selection sits at the top, middle or bottom of the view (`zt`/`zz`/`zb`)
+    fn align_scroll(&mut self, align: super::ScrollAlign) {
+        self.scroll = super::aligned_scroll(self.selection, self.last_view_height, align);
+    }
+
+    fn content_height(&mut self) -> usize {
         self.entries.len() + 1
     }
 
-    pub fn render_plain_lines(&self, _width: u16) -> Vec<Line<'static>> {
+    /// Parsed structure for `--dump`
+    fn to_json(&mut self) -> serde_json::Value {
+        let keys = self.headers.map(str::to_lowercase);
+        let packages: Vec<_> = self
+            .entries
+            .iter()
+            .map(|e| {
+                let mut package = serde_json::Map::new();
+                package.insert(keys[0].clone(), json!(e.name));
+                package.insert(keys[1].clone(), json!(e.version));
+                package.insert(keys[2].clone(), json!(e.source));
+                package.insert(keys[3].clone(), json!(e.checksum));
+                package.insert(keys[4].clone(), json!(e.dependencies));
+                serde_json::Value::Object(package)
+            })
+            .collect();
+        json!({ "packages": packages })
+    }
+
+    fn render_plain_lines(&mut self, _width: u16) -> Vec<Line<'static>> {
         let mut lines = Vec::new();
-        let headers = vec![
+        let mut headers = vec![
             Span::styled("#", Style::default().fg(Color::Black).bg(Color::LightBlue)),
             Span::styled("│", Style::default().fg(Color::LightBlue)),
-            Span::styled("Name", Style::default().fg(Color::Black).bg(Color::LightBlue)),
-            Span::styled("Version", Style::default().fg(Color::Black).bg(Color::LightBlue)),
-            Span::styled("Source", Style::default().fg(Color::Black).bg(Color::LightBlue)),
-            Span::styled("Checksum", Style::default().fg(Color::Black).bg(Color::LightBlue)),
-            Span::styled("Dependencies", Style::default().fg(Color::Black).bg(Color::LightBlue)),
         ];
+        headers.extend(
+            self.headers
+                .iter()
+                .map(|title| Span::styled(*title, Style::default().fg(Color::Black).bg(Color::LightBlue))),
+        );
         lines.push(Line::from(join_with_sep(headers, "  ")));
         for (idx, entry) in self.entries.iter().enumerate() {
             let spans = vec![
@@ -260,7 +354,7 @@ impl LockEngine {
                 Span::styled("│", Style::default().fg(Color::DarkGray)),
                 Span::styled(entry.name.clone(), Style::default().fg(Color::Cyan).bold()),
                 Span::styled(entry.version.clone(), Style::default().fg(Color::Magenta)),
-                Span::styled(entry.source.clone(), Style::default().fg(Color::Green)),
+                Span::styled(entry.source.clone(), Style::default().fg(source_color(&entry.source))),
                 Span::styled(entry.checksum.clone(), Style::default().fg(Color::DarkGray)),
                 Span::styled(entry.dependencies.join(", "), Style::default().fg(Color::Yellow)),
             ];
@@ -270,110 +364,144 @@ impl LockEngine {
     }
 }
 
-fn parse_cargo_lock(path: &Path) -> Result<Vec<LockEntry>> {
-    let content = std::fs::read_to_string(path)?;
-    let value: toml::Value = toml::from_str(&content)?;
-    let packages = value
-        .get("package")
+/// Cargo.lock split at each `[[package]]` table. Each package is parsed on its own,
+/// in parallel, since the TOML parse is most of the load time for big workspaces.
+fn parse_cargo_lock(content: &str) -> Result<Vec<LockEntry>> {
+    let starts: Vec<usize> = content
+        .match_indices("[[package]]")
+        .map(|(start, _)| start)
+        .filter(|&start| start == 0 || content.as_bytes()[start - 1] == b'\n')
+        .collect();
+    if starts.is_empty() {
+        return Err(anyhow!("Cargo.lock missing package list"));
+    }
+    let ends = starts.iter().skip(1).copied().chain([content.len()]);
+    let chunks: Vec<&str> = starts.iter().zip(ends).map(|(&start, end)| &content[start..end]).collect();
+    chunks
+        .into_par_iter()
+        .map(|chunk| {
+            let value: toml::Value = toml::from_str(chunk)?;
+            let pkg = value
+                .get("package")
+                .and_then(|v| v.as_array())
+                .and_then(|packages| packages.first())
+                .ok_or_else(|| anyhow!("Cargo.lock missing package list"))?;
+            Ok(cargo_entry(pkg))
+        })
+        .collect()
+}
+
+fn cargo_entry(pkg: &toml::Value) -> LockEntry {
+    let name = pkg
+        .get("name")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+    let version = pkg
+        .get("version")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+    let source = pkg
+        .get("source")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+    let checksum = pkg
+        .get("checksum")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+    let dependencies = pkg
+        .get("dependencies")
         .and_then(|v| v.as_array())
-        .ok_or_else(|| anyhow!("Cargo.lock missing package list"))?;
-    let mut entries = Vec::new();
-    for pkg in packages {
-        let name = pkg
-            .get("name")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
-        let version = pkg
-            .get("version")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
-        let source = pkg
-            .get("source")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
-        let checksum = pkg
-            .get("checksum")
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
-        let dependencies = pkg
-            .get("dependencies")
-            .and_then(|v| v.as_array())
-            .map(|arr| {
-                arr.iter()
-                    .filter_map(|v| v.as_str())
-                    .map(dep_name_only)
-                    .collect::<Vec<_>>()
-            })
-            .unwrap_or_default();
-        entries.push(LockEntry {
-            name,
-            version,
-            source,
-            checksum,
-            dependencies,
-        });
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|v| v.as_str())
+                .map(dep_name_only)
+                .collect::<Vec<_>>()
+        })
+        .unwrap_or_default();
+    LockEntry {
+        name,
+        version,
+        source,
+        checksum,
+        dependencies,
     }
-    Ok(entries)
 }
 
-fn parse_package_lock(path: &Path) -> Result<Vec<LockEntry>> {
-    let content = std::fs::read_to_string(path)?;
-    let value: serde_json::Value = serde_json::from_str(&content)?;
-    if let Some(packages) = value.get("packages").and_then(|v| v.as_object()) {
-        let mut entries = Vec::new();
-        for (key, info) in packages {
-            if key.is_empty() {
-                continue;
-            }
-            let name = info
-                .get("name")
-                .and_then(|v| v.as_str())
-                .map(|s| s.to_string())
-                .unwrap_or_else(|| package_name_from_path(key));
-            let version = info
-                .get("version")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string();
-            let source = info
-                .get("resolved")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string();
-            let checksum = info
-                .get("integrity")
-                .and_then(|v| v.as_str())
-                .unwrap_or("")
-                .to_string();
-            let dependencies = info
-                .get("dependencies")
-                .and_then(|v| v.as_object())
-                .map(|deps| deps.keys().cloned().collect::<Vec<_>>())
-                .unwrap_or_default();
-            entries.push(LockEntry {
-                name,
-                version,
-                source,
-                checksum,
-                dependencies,
-            });
-        }
-        return Ok(entries);
+/// package-lock.json with each package left as raw JSON, so the entries are
+/// parsed in parallel rather than as one big document
+#[derive(Deserialize)]
+struct PackageLock<'a> {
+    /// lockfileVersion 2 and later: one entry per install path, "" for the project itself
+    #[serde(borrow, default)]
+    packages: Option<BTreeMap<String, &'a RawValue>>,
+    /// lockfileVersion 1: nested dependency tree
+    #[serde(borrow, default)]
+    dependencies: Option<&'a RawValue>,
+}
+
+fn parse_package_lock(content: &str) -> Result<Vec<LockEntry>> {
+    let lock: PackageLock = serde_json::from_str(content)?;
+    if let Some(packages) = lock.packages {
+        let packages: Vec<_> = packages.into_iter().filter(|(key, _)| !key.is_empty()).collect();
+        // The indexed collect keeps the map's order
+        return packages
+            .into_par_iter()
+            .map(|(key, raw)| {
+                let info: serde_json::Value = serde_json::from_str(raw.get())?;
+                Ok(package_lock_entry(&key, &info))
+            })
+            .collect();
     }
 
-    let deps = value
-        .get("dependencies")
-        .and_then(|v| v.as_object())
+    let deps: serde_json::Map<String, serde_json::Value> = lock
+        .dependencies
+        .map(|raw| serde_json::from_str(raw.get()))
+        .transpose()?
         .ok_or_else(|| anyhow!("package-lock.json missing dependencies"))?;
     let mut entries = Vec::new();
-    flatten_package_lock_deps(deps, &mut entries);
+    flatten_package_lock_deps(&deps, &mut entries);
     Ok(entries)
 }
 
+fn package_lock_entry(key: &str, info: &serde_json::Value) -> LockEntry {
+    let name = info
+        .get("name")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+        .unwrap_or_else(|| package_name_from_path(key));
+    let version = info
+        .get("version")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+    let source = info
+        .get("resolved")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+    let checksum = info
+        .get("integrity")
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+    let dependencies = info
+        .get("dependencies")
+        .and_then(|v| v.as_object())
+        .map(|deps| deps.keys().cloned().collect::<Vec<_>>())
+        .unwrap_or_default();
+    LockEntry {
+        name,
+        version,
+        source,
+        checksum,
+        dependencies,
+    }
+}
+
 fn flatten_package_lock_deps(deps: &serde_json::Map<String, serde_json::Value>, entries: &mut Vec<LockEntry>) {
     for (name, info) in deps {
         let version = info
@@ -409,42 +537,356 @@ fn flatten_package_lock_deps(deps: &serde_json::Map<String, serde_json::Value>,
     }
 }
 
-fn parse_pnpm_lock(path: &Path) -> Result<Vec<LockEntry>> {
-    let content = std::fs::read_to_string(path)?;
-    let value: serde_yaml::Value = serde_yaml::from_str(&content)?;
-    let json = serde_json::to_value(value)?;
-    let packages = json
-        .get("packages")
-        .and_then(|v| v.as_object())
-        .ok_or_else(|| anyhow!("pnpm lock missing packages"))?;
+/// pnpm-lock.yaml, whose `packages:` entries are cut apart and parsed in parallel.
+/// A lockfile that doesn't lay them out as an indented block is parsed whole.
+fn parse_pnpm_lock(content: &str) -> Result<Vec<LockEntry>> {
+    let Some(chunks) = pnpm_package_chunks(content) else {
+        let value: serde_yaml::Value = serde_yaml::from_str(content)?;
+        let json = serde_json::to_value(value)?;
+        let packages = json
+            .get("packages")
+            .and_then(|v| v.as_object())
+            .ok_or_else(|| anyhow!("pnpm lock missing packages"))?;
+        return Ok(packages.iter().map(|(key, info)| pnpm_entry(key, info)).collect());
+    };
+    let entries: Vec<Vec<LockEntry>> = chunks
+        .into_par_iter()
+        .map(|chunk| {
+            let packages: serde_yaml::Mapping = serde_yaml::from_str(&chunk)?;
+            packages
+                .into_iter()
+                .map(|(key, info)| {
+                    let key = key.as_str().unwrap_or_default().to_string();
+                    Ok(pnpm_entry(&key, &serde_json::to_value(info)?))
+                })
+                .collect::<Result<Vec<_>>>()
+        })
+        .collect::<Result<_>>()?;
+    Ok(entries.into_iter().flatten().collect())
+}
+
+/// Each entry of the top-level `packages:` block as its own YAML document, dedented
+/// to column 0. None when there is no such block (e.g. a flow-style `packages: {}`).
+fn pnpm_package_chunks(content: &str) -> Option<Vec<String>> {
+    let mut lines = content.lines().skip_while(|line| line.trim_end() != "packages:");
+    lines.next()?;
+    let mut chunks: Vec<String> = Vec::new();
+    let mut indent = None;
+    for line in lines {
+        let body = line.trim_start();
+        if body.is_empty() || body.starts_with('#') {
+            continue;
+        }
+        let depth = line.len() - body.len();
+        if depth == 0 {
+            // The next top-level key ends the block
+            break;
+        }
+        let indent = *indent.get_or_insert(depth);
+        if depth == indent {
+            chunks.push(String::new());
+        }
+        let chunk = chunks.last_mut()?;
+        chunk.push_str(line.get(indent..).unwrap_or(body));
+        chunk.push('\n');
+    }
+    Some(chunks)
+}
+
+fn pnpm_entry(key: &str, info: &serde_json::Value) -> LockEntry {
+    let (name, version) = parse_pnpm_key(key);
+    let source = info
+        .get("resolution")
+        .and_then(|v| v.get("tarball"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+    let checksum = info
+        .get("resolution")
+        .and_then(|v| v.get("integrity"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("")
+        .to_string();
+    let mut dependencies = Vec::new();
+    for field in ["dependencies", "optionalDependencies", "devDependencies"] {
+        if let Some(dep_map) = info.get(field).and_then(|v| v.as_object()) {
+            dependencies.extend(dep_map.keys().cloned());
+        }
+    }
+    LockEntry {
+        name,
+        version,
+        source,
+        checksum,
+        dependencies,
+    }
+}
+
+/// `requirements.txt`, `requirements-dev.txt` and the like
+pub fn is_requirements_file(file_name: &str) -> bool {
+    file_name.starts_with("requirements") && file_name.ends_with(".txt")
+}
+
+/// pip requirements: one PEP 508 requirement per line, plus `-e` editable installs
+fn parse_requirements(content: &str) -> Vec<LockEntry> {
     let mut entries = Vec::new();
-    for (key, info) in packages {
-        let (name, version) = parse_pnpm_key(key);
-        let source = info
-            .get("resolution")
-            .and_then(|v| v.get("tarball"))
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
-        let checksum = info
-            .get("resolution")
-            .and_then(|v| v.get("integrity"))
-            .and_then(|v| v.as_str())
-            .unwrap_or("")
-            .to_string();
-        let mut dependencies = Vec::new();
-        for field in ["dependencies", "optionalDependencies", "devDependencies"] {
-            if let Some(dep_map) = info.get(field).and_then(|v| v.as_object()) {
-                dependencies.extend(dep_map.keys().cloned());
+    let mut pending = String::new();
+    for line in content.lines() {
+        // pip joins lines ending in a backslash
+        if let Some(head) = line.strip_suffix('\\') {
+            pending.push_str(head);
+            pending.push(' ');
+            continue;
+        }
+        pending.push_str(line);
+        let logical = std::mem::take(&mut pending);
+        let text = strip_requirement_comment(&logical).trim();
+        if text.is_empty() {
+            continue;
+        }
+        if let Some(target) = text.strip_prefix("-e ").or_else(|| text.strip_prefix("--editable ")) {
+            entries.push(location_entry(target.trim(), "editable"));
+            continue;
+        }
+        // -r/-c includes, index URLs and other pip options
+        if text.starts_with('-') {
+            continue;
+        }
+        // Per-requirement options such as --hash follow the requirement itself
+        let spec = text.find(" --").map_or(text, |idx| &text[..idx]).trim();
+        if !spec.contains(" @ ") && (spec.contains("://") || spec.starts_with(['.', '/'])) {
+            entries.push(location_entry(spec, "url"));
+        } else if let Some(entry) = parse_requirement(spec) {
+            entries.push(entry);
+        }
+    }
+    entries
+}
+
+/// `#` starts a comment at the start of a line or after whitespace
+fn strip_requirement_comment(line: &str) -> &str {
+    let mut prev = ' ';
+    for (idx, c) in line.char_indices() {
+        if c == '#' && prev.is_whitespace() {
+            return &line[..idx];
+        }
+        prev = c;
+    }
+    line
+}
+
+/// A requirement given as a path or URL; the name comes from `#egg=` or the last segment
+fn location_entry(target: &str, pin: &str) -> LockEntry {
+    let name = match target.split_once("#egg=") {
+        Some((_, egg)) => egg.to_string(),
+        None => {
+            let path = target.split(['#', '?']).next().unwrap_or(target).trim_end_matches('/');
+            path.rsplit('/').next().unwrap_or(path).to_string()
+        }
+    };
+    LockEntry {
+        name,
+        version: target.to_string(),
+        source: pin.to_string(),
+        checksum: String::new(),
+        dependencies: Vec::new(),
+    }
+}
+
+/// One PEP 508 requirement, e.g. `requests[socks]>=2.28; python_version < "3.12"`
+fn parse_requirement(spec: &str) -> Option<LockEntry> {
+    let (spec, marker) = match spec.split_once(';') {
+        Some((spec, marker)) => (spec.trim(), marker.trim()),
+        None => (spec.trim(), ""),
+    };
+    let name_end = spec
+        .find(|c: char| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')))
+        .unwrap_or(spec.len());
+    if name_end == 0 {
+        return None;
+    }
+    let mut rest = spec[name_end..].trim_start();
+    let mut extras = String::new();
+    if let Some(inner) = rest.strip_prefix('[') {
+        let (list, after) = inner.split_once(']')?;
+        extras = list.split(',').map(str::trim).filter(|e| !e.is_empty()).collect::<Vec<_>>().join(", ");
+        rest = after.trim_start();
+    }
+    let (version, pin) = match rest.strip_prefix('@') {
+        Some(url) => (url.trim().to_string(), "url"),
+        None => {
+            let constraint: String = rest
+                .trim_start_matches('(')
+                .trim_end_matches(')')
+                .chars()
+                .filter(|c| !c.is_whitespace())
+                .collect();
+            let pin = pin_kind(&constraint);
+            (constraint, pin)
+        }
+    };
+    Some(LockEntry {
+        name: spec[..name_end].to_string(),
+        version,
+        source: pin.to_string(),
+        checksum: extras,
+        dependencies: if marker.is_empty() { Vec::new() } else { vec![marker.to_string()] },
+    })
+}
+
+/// `pinned` for an exact version, `range` for anything looser, `any` when unconstrained
+fn pin_kind(constraint: &str) -> &'static str {
+    let constraint = constraint.trim();
+    if constraint.is_empty() || constraint == "*" {
+        return "any";
+    }
+    let single = !constraint.contains([',', '*', '|']);
+    // Poetry reads a bare version as an exact pin
+    let exact = constraint.starts_with("===")
+        || (single && (constraint.starts_with("==") || constraint.starts_with(|c: char| c.is_ascii_digit())));
+    if exact {
+        "pinned"
+    } else {
+        "range"
+    }
+}
+
+fn source_color(source: &str) -> Color {
+    match source {
+        "range" => Color::Yellow,
+        "any" => Color::LightRed,
+        _ => Color::Green,
+    }
+}
+
+/// Tag an entry with the dependency group (`dev`, an optional extra, ...) it came from
+fn in_group(mut entry: LockEntry, group: Option<&str>) -> LockEntry {
+    if let Some(group) = group {
+        entry.dependencies.insert(0, format!("group {}", group));
+    }
+    entry
+}
+
+/// `[project]` dependencies and optional extras, PEP 735 `[dependency-groups]`,
+/// and Poetry's dependency tables
+fn parse_pyproject(content: &str) -> Result<Vec<LockEntry>> {
+    let value: toml::Value = toml::from_str(content)?;
+    let mut entries = Vec::new();
+    let mut push_requirements = |deps: Option<&toml::Value>, group: Option<&str>| {
+        let specs = deps.and_then(|v| v.as_array()).into_iter().flatten();
+        // Group includes like `{ include-group = "test" }` aren't packages
+        for entry in specs.filter_map(|v| v.as_str()).filter_map(parse_requirement) {
+            entries.push(in_group(entry, group));
+        }
+    };
+
+    let project = value.get("project");
+    push_requirements(project.and_then(|p| p.get("dependencies")), None);
+    if let Some(extras) = project.and_then(|p| p.get("optional-dependencies")).and_then(|v| v.as_table()) {
+        for (group, deps) in extras {
+            push_requirements(Some(deps), Some(group));
+        }
+    }
+    if let Some(groups) = value.get("dependency-groups").and_then(|v| v.as_table()) {
+        for (group, deps) in groups {
+            push_requirements(Some(deps), Some(group));
+        }
+    }
+
+    if let Some(poetry) = value.get("tool").and_then(|t| t.get("poetry")) {
+        let mut tables = vec![(None, poetry.get("dependencies")), (Some("dev"), poetry.get("dev-dependencies"))];
+        if let Some(groups) = poetry.get("group").and_then(|v| v.as_table()) {
+            tables.extend(groups.iter().map(|(group, t)| (Some(group.as_str()), t.get("dependencies"))));
+        }
+        for (group, table) in tables {
+            let deps = table.and_then(|v| v.as_table()).into_iter().flatten();
+            // `python` is the interpreter constraint, not a package
+            for (name, spec) in deps.filter(|(name, _)| name.as_str() != "python") {
+                entries.push(in_group(table_dependency(name, spec), group));
             }
         }
-        entries.push(LockEntry {
-            name,
-            version,
-            source,
-            checksum,
-            dependencies,
-        });
+    }
+    Ok(entries)
+}
+
+/// Pipenv's `[packages]` and `[dev-packages]`
+fn parse_pipfile(content: &str) -> Result<Vec<LockEntry>> {
+    let value: toml::Value = toml::from_str(content)?;
+    let mut entries = Vec::new();
+    for (section, group) in [("packages", None), ("dev-packages", Some("dev"))] {
+        let deps = value.get(section).and_then(|v| v.as_table()).into_iter().flatten();
+        for (name, spec) in deps {
+            entries.push(in_group(table_dependency(name, spec), group));
+        }
+    }
+    Ok(entries)
+}
+
+/// A Poetry or Pipfile dependency: a version string, or a table such as
+/// `{ version = "^1.2", extras = ["socks"], markers = "sys_platform == 'linux'" }`
+fn table_dependency(name: &str, spec: &toml::Value) -> LockEntry {
+    let str_of = |key: &str| spec.get(key).and_then(|v| v.as_str());
+    let (version, pin) = match spec {
+        toml::Value::String(version) => (version.clone(), pin_kind(version)),
+        // Poetry's multiple-constraint form: one table per Python version or platform
+        toml::Value::Array(alternatives) => {
+            let versions: Vec<&str> = alternatives
+                .iter()
+                .filter_map(|alt| alt.get("version").and_then(|v| v.as_str()))
+                .collect();
+            (versions.join(" | "), "range")
+        }
+        _ => match str_of("version") {
+            Some(version) => (version.to_string(), pin_kind(version)),
+            None => match ["git", "url", "path", "file"].into_iter().find_map(str_of) {
+                Some(location) if spec.get("editable").and_then(|v| v.as_bool()) == Some(true) => {
+                    (location.to_string(), "editable")
+                }
+                Some(location) => (location.to_string(), "url"),
+                None => (String::new(), "any"),
+            },
+        },
+    };
+    let extras = spec
+        .get("extras")
+        .and_then(|v| v.as_array())
+        .map(|extras| extras.iter().filter_map(|v| v.as_str()).collect::<Vec<_>>().join(", "))
+        .unwrap_or_default();
+    let mut markers: Vec<String> = str_of("markers").map(str::to_string).into_iter().collect();
+    markers.extend(str_of("python").map(|python| format!("python {}", python)));
+    LockEntry {
+        name: name.to_string(),
+        version,
+        source: pin.to_string(),
+        checksum: extras,
+        dependencies: markers,
+    }
+}
+
+/// Pipenv's resolved `default` and `develop` packages
+fn parse_pipfile_lock(content: &str) -> Result<Vec<LockEntry>> {
+    let value: serde_json::Value = serde_json::from_str(content)?;
+    let mut entries = Vec::new();
+    for (section, group) in [("default", None), ("develop", Some("dev"))] {
+        let deps = value.get(section).and_then(|v| v.as_object()).into_iter().flatten();
+        for (name, info) in deps {
+            let str_of = |key: &str| info.get(key).and_then(|v| v.as_str()).unwrap_or("");
+            let version = str_of("version");
+            let extras = info
+                .get("extras")
+                .and_then(|v| v.as_array())
+                .map(|extras| extras.iter().filter_map(|v| v.as_str()).collect::<Vec<_>>().join(", "))
+                .unwrap_or_default();
+            let markers = str_of("markers");
+            let entry = LockEntry {
+                name: name.clone(),
+                version: version.to_string(),
+                source: pin_kind(version).to_string(),
+                checksum: extras,
+                dependencies: if markers.is_empty() { Vec::new() } else { vec![markers.to_string()] },
+            };
+            entries.push(in_group(entry, group));
+        }
     }
     Ok(entries)
 }
@@ -477,15 +919,6 @@ fn dep_name_only(dep: &str) -> String {
     dep.split_whitespace().next().unwrap_or(dep).to_string()
 }
 
-fn truncate(value: &str, max: usize) -> String {
-    if value.len() <= max {
-        return value.to_string();
-    }
-    let mut out = value.chars().take(max.saturating_sub(3)).collect::<String>();
-    out.push_str("...");
-    out
-}
-
 fn join_with_sep(mut spans: Vec<Span<'static>>, sep: &str) -> Vec<Span<'static>> {
     if spans.is_empty() {
         return spans;
@@ -501,17 +934,19 @@ fn join_with_sep(mut spans: Vec<Span<'static>>, sep: &str) -> Vec<Span<'static>>
 }
 
 impl LockEngine {
-    fn search_next(&mut self, query: &str, forward: bool) {
+    fn search_next(&mut self, query: &str, forward: bool) -> bool {
         let trimmed = query.trim();
         if trimmed.is_empty() {
-            return;
+            return false;
         }
         let lower = trimmed.to_lowercase();
+        let mut found = false;
         let total = self.entries.len().max(1);
+        // Backward from the first entry wraps to the last one
         let start = if forward {
             (self.selection + 1) % total
         } else {
-            self.selection.saturating_sub(1)
+            (self.selection + total - 1) % total
         };
         for offset in 0..self.entries.len() {
             let idx = if forward {
@@ -520,19 +955,136 @@ impl LockEngine {
                 (start + total - offset % total) % total
             };
             let entry = &self.entries[idx];
-            if entry.name.to_lowercase().contains(&lower)
-                || entry.version.to_lowercase().contains(&lower)
-                || entry.source.to_lowercase().contains(&lower)
-                || entry.checksum.to_lowercase().contains(&lower)
+            if search_matches(&entry.name, &lower)
+                || search_matches(&entry.version, &lower)
+                || search_matches(&entry.source, &lower)
+                || search_matches(&entry.checksum, &lower)
                 || entry
                     .dependencies
                     .iter()
-                    .any(|dep| dep.to_lowercase().contains(&lower))
+                    .any(|dep| search_matches(dep, &lower))
             {
                 self.selection = idx;
+                found = true;
                 break;
             }
         }
         self.last_match = Some(trimmed.to_string());
+        found
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn previous_match_searches_backward_and_wraps() {
+        let content = "alpha==1\nbeta==1\nalpine==1\ngamma==1\n";
+        let mut engine = LockEngine::from_bytes(Path::new("requirements.txt"), content.as_bytes()).unwrap();
+        assert!(engine.apply_search("al", true));
+        assert_eq!(engine.entries[engine.selection].name, "alpine");
+        engine.handle_key(KeyEvent::new(KeyCode::Char('N'), KeyModifiers::NONE));
+        assert_eq!(engine.entries[engine.selection].name, "alpha");
+        engine.handle_key(KeyEvent::new(KeyCode::Char('N'), KeyModifiers::NONE));
+        assert_eq!(engine.entries[engine.selection].name, "alpine");
+    }
+
+    #[test]
+    fn lockfile_entries_parse_separately_in_file_order() {
+        let cargo = "\
+version = 3
+
+[[package]]
+name = \"anyhow\"
+version = \"1.0.0\"
+
+[[package]]
+name = \"vat\"
+version = \"0.1.0\"
+dependencies = [\"anyhow 1.0.0\"]
+";
+        let names = |entries: Vec<LockEntry>| entries.into_iter().map(|e| (e.name, e.version)).collect::<Vec<_>>();
+        let entries = parse_cargo_lock(cargo).unwrap();
+        assert_eq!(entries[1].dependencies, vec!["anyhow".to_string()]);
+        assert_eq!(names(entries), [("anyhow".into(), "1.0.0".into()), ("vat".into(), "0.1.0".into())]);
+        assert!(parse_cargo_lock("version = 3\n").is_err());
+
+        let npm = r#"{"packages": {"": {"name": "app"}, "node_modules/b": {"version": "2.0.0"},
+            "node_modules/a": {"version": "1.0.0", "dependencies": {"b": "^2"}}},
+            "dependencies": {"ignored": {"version": "9"}}}"#;
+        let entries = parse_package_lock(npm).unwrap();
+        assert_eq!(entries[0].dependencies, vec!["b".to_string()]);
+        assert_eq!(names(entries), [("a".into(), "1.0.0".into()), ("b".into(), "2.0.0".into())]);
+        let v1 = r#"{"dependencies": {"a": {"version": "1.0.0"}}}"#;
+        assert_eq!(names(parse_package_lock(v1).unwrap()), [("a".into(), "1.0.0".into())]);
+
+        let pnpm = "\
+lockfileVersion: '6.0'
+packages:
+
+  /b/2.0.0:
+    resolution: {integrity: sha512-b}
+    # comment
+    dependencies:
+      a: 1.0.0
+
+  /a/1.0.0:
+    resolution: {integrity: sha512-a}
+settings:
+  autoInstallPeers: true
+";
+        let entries = parse_pnpm_lock(pnpm).unwrap();
+        assert_eq!((entries[0].checksum.as_str(), &entries[0].dependencies[..]), ("sha512-b", &["a".to_string()][..]));
+        assert_eq!(names(entries), [("b".into(), "2.0.0".into()), ("a".into(), "1.0.0".into())]);
+        let flow = "packages: {/a/1.0.0: {resolution: {integrity: x}}}\n";
+        assert_eq!(names(parse_pnpm_lock(flow).unwrap()), [("a".into(), "1.0.0".into())]);
+    }
+
+    #[test]
+    fn requirements_rows_mark_pinned_and_ranged() {
+        let content = "\
+# core
+requests[socks,security]==2.31.0 --hash=sha256:abc
+urllib3>=1.26,<3 ; python_version >= \"3.8\"
+rich
+-r dev.txt
+-e git+https://github.com/org/tool.git#egg=tool
+";
+        let entries = parse_requirements(content);
+        let rows: Vec<(&str, &str, &str)> = entries
+            .iter()
+            .map(|e| (e.name.as_str(), e.version.as_str(), e.source.as_str()))
+            .collect();
+        assert_eq!(
+            rows,
+            vec![
+                ("requests", "==2.31.0", "pinned"),
+                ("urllib3", ">=1.26,<3", "range"),
+                ("rich", "", "any"),
+                ("tool", "git+https://github.com/org/tool.git#egg=tool", "editable"),
+            ]
+        );
+        assert_eq!(entries[0].checksum, "socks, security");
+        assert_eq!(entries[1].dependencies, vec!["python_version >= \"3.8\"".to_string()]);
+    }
+
+    #[test]
+    fn pyproject_lists_project_and_poetry_dependencies() {
+        let content = r#"
+[project]
+dependencies = ["httpx>=0.27"]
+
+[project.optional-dependencies]
+test = ["pytest==8.0.0"]
+
+[tool.poetry.dependencies]
+python = "^3.11"
+click = "8.1.7"
+"#;
+        let entries = parse_pyproject(content).unwrap();
+        let rows: Vec<(&str, &str)> = entries.iter().map(|e| (e.name.as_str(), e.source.as_str())).collect();
+        assert_eq!(rows, vec![("httpx", "range"), ("pytest", "pinned"), ("click", "pinned")]);
+        assert_eq!(entries[1].dependencies, vec!["group test".to_string()]);
     }
 }
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::path::Path;
 
 use anyhow::Result;
@@ -7,6 +8,10 @@ use ratatui::style::{Color, Style, Stylize};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph};
 use regex::Regex;
+use serde_json::json;
+
+use super::util::{ansi_spans, search_matches, strip_controls, truncate, Source};
+use super::Engine;
 
 #[derive(Clone)]
 struct LogEntry {
@@ -14,7 +19,6 @@ struct LogEntry {
     level: Option<LogLevel>,
     source: Option<String>,
     message: String,
-    raw: String,
 }
 
 #[derive(Clone, Copy, PartialEq)]
@@ -26,8 +30,24 @@ enum LogLevel {
     Fatal,
 }
 
+/// LogEngine memory-maps the file and indexes non-empty lines up front;
+/// timestamp/level parsing happens only for lines that are displayed,
+/// searched, or needed by the level filter.
 pub struct LogEngine {
-    entries: Vec<(usize, LogEntry)>,
+    /// File content (memory-mapped when read from disk)
+    data: Source,
+    /// (line_no, start, end) byte ranges of non-empty lines
+    line_index: Vec<(usize, usize, usize)>,
+    /// (byte offset, line number) of the last line, where indexing resumes when
+    /// streamed input appends to it
+    index_resume: (usize, usize),
+    patterns: LogPatterns,
+    /// Level of every indexed line, computed the first time it's needed
+    levels: Option<Vec<Option<LogLevel>>>,
+    /// Entries per level (debug, info, warn, error and fatal), kept with `levels`
+    level_counts: [usize; 4],
+    /// Indexed lines passing the level filter (None = show all)
+    filtered_indices: Option<Vec<usize>>,
     selection: usize,
     scroll: usize,
     file_name: String,
@@ -42,17 +62,31 @@ pub struct LogEngine {
 
 impl LogEngine {
     pub fn from_path(path: &Path) -> Result<Self> {
-        let content = std::fs::read_to_string(path)?;
+        Ok(Self::from_source(path, Source::map(path)?))
+    }
+
+    /// Create from in-memory bytes; `path` only supplies the displayed name
+    pub fn from_bytes(path: &Path, bytes: &[u8]) -> Result<Self> {
+        Ok(Self::from_source(path, Source::Owned(bytes.to_vec())))
+    }
+
+    fn from_source(path: &Path, data: Source) -> Self {
+        let mut line_index = Vec::new();
+        let index_resume = build_line_index(&data, (0, 1), &mut line_index);
         let file_name = path
             .file_name()
             .and_then(|s| s.to_str())
             .unwrap_or("")
             .to_string();
 
-        let entries = parse_log(&content);
-
-        Ok(Self {
-            entries,
+        Self {
+            data,
+            line_index,
+            index_resume,
+            patterns: LogPatterns::new(),
+            levels: None,
+            level_counts: [0; 4],
+            filtered_indices: None,
             selection: 0,
             scroll: 0,
             file_name,
@@ -62,48 +96,178 @@ impl LogEngine {
             last_match: None,
             filter_level: None,
             visual_range: None,
+        }
+    }
+
+    /// Trimmed text of an indexed line, zero-copy from the mmap unless it
+    /// isn't UTF-8, in which case invalid bytes show as U+FFFD so every
+    /// indexed line still has text
+    fn get_line(&self, idx: usize) -> Option<Cow<'_, str>> {
+        let &(_, start, end) = self.line_index.get(idx)?;
+        Some(match String::from_utf8_lossy(&self.data[start..end]) {
+            Cow::Borrowed(line) => Cow::Borrowed(line.trim()),
+            Cow::Owned(line) => Cow::Owned(line.trim().to_string()),
         })
     }
 
-    fn visible_entries(&self) -> Vec<usize> {
-        match self.filter_level {
-            Some(level) => self.entries
-                .iter()
-                .enumerate()
-                .filter(|(_, (_, e))| e.level.map_or(true, |l| level_priority(l) >= level_priority(level)))
-                .map(|(i, _)| i)
-                .collect(),
-            None => (0..self.entries.len()).collect(),
+    /// The raw line when `--ansi` is on and it carries its own colors, which
+    /// are then shown as-is instead of the parsed columns
+    fn colored_line(&self, idx: usize) -> Option<Cow<'_, str>> {
+        self.get_line(idx).filter(|line| super::ansi() && line.contains('\x1b'))
+    }
+
+    /// Parse an indexed line into its source line number and entry
+    fn entry(&self, idx: usize) -> Option<(usize, LogEntry)> {
+        let line_no = self.line_index.get(idx)?.0;
+        Some((line_no, self.patterns.parse(&self.get_line(idx)?)))
+    }
+
+    /// Number of non-empty lines in the file
+    fn line_count(&self) -> usize {
+        self.line_index.len()
+    }
+
+    /// Number of entries to display (filtered or all)
+    fn display_count(&self) -> usize {
+        self.filtered_indices.as_ref().map_or(self.line_count(), |f| f.len())
+    }
+
+    /// Get the indexed line for a display position
+    fn display_to_actual(&self, display_idx: usize) -> Option<usize> {
+        match &self.filtered_indices {
+            Some(indices) => indices.get(display_idx).copied(),
+            None => Some(display_idx),
+        }
+    }
+
+    fn ensure_levels(&mut self) {
+        if self.levels.is_some() {
+            return;
+        }
+        let levels: Vec<_> = (0..self.line_count())
+            .map(|idx| self.get_line(idx).and_then(|line| self.patterns.line_level(&line)))
+            .collect();
+        count_levels(&levels, &mut self.level_counts, true);
+        self.levels = Some(levels);
+    }
+
+    /// `1:DBG 2:INF 3:WRN 4:ERR 0:all` with the active filter in brackets, each
+    /// level followed by its count once levels have been read
+    fn level_legend(&self) -> String {
+        let labels = [
+            (LogLevel::Debug, "1:DBG"),
+            (LogLevel::Info, "2:INF"),
+            (LogLevel::Warn, "3:WRN"),
+            (LogLevel::Error, "4:ERR"),
+        ];
+        let mut parts: Vec<String> = labels
+            .iter()
+            .zip(self.level_counts)
+            .map(|(&(level, label), count)| {
+                let text = if self.levels.is_some() { format!("{} {}", label, count) } else { label.to_string() };
+                if self.filter_level == Some(level) {
+                    format!("[{}]", text)
+                } else {
+                    text
+                }
+            })
+            .collect();
+        parts.push(if self.filter_level.is_none() { "[0:all]" } else { "0:all" }.to_string());
+        let shown = match self.filter_level {
+            Some(_) => format!("{}/{} entries", self.display_count(), self.line_count()),
+            None => format!("{} entries", self.line_count()),
+        };
+        format!("{} | {}", parts.join(" "), shown)
+    }
+
+    /// Whether a display row is an error/fatal entry (levels must be loaded)
+    fn is_error(&self, display_idx: usize) -> bool {
+        let level = self
+            .display_to_actual(display_idx)
+            .and_then(|idx| self.levels.as_ref()?.get(idx).copied().flatten());
+        matches!(level, Some(LogLevel::Error | LogLevel::Fatal))
+    }
+
+    fn set_filter_level(&mut self, level: Option<LogLevel>) {
+        self.filter_level = level;
+        self.filtered_indices = match level {
+            Some(level) => {
+                self.ensure_levels();
+                self.levels.as_ref().map(|levels| {
+                    levels
+                        .iter()
+                        .enumerate()
+                        .filter(|(_, l)| l.is_none_or(|l| level_priority(l) >= level_priority(level)))
+                        .map(|(i, _)| i)
+                        .collect()
+                })
+            }
+            None => None,
+        };
+    }
+
+    fn search_next(&mut self, query: &str, forward: bool) -> bool {
+        let lower = query.to_lowercase();
+        let mut found = false;
+        let total = self.display_count().max(1);
+        let start = if forward {
+            (self.selection + 1) % total
+        } else {
+            (self.selection + total - 1) % total
+        };
+
+        for offset in 0..total {
+            let idx = if forward {
+                (start + offset) % total
+            } else {
+                (start + total - offset % total) % total
+            };
+            let line = self.display_to_actual(idx).and_then(|actual| self.get_line(actual));
+            if line.is_some_and(|line| search_matches(&line, &lower)) {
+                self.selection = idx;
+                found = true;
+                break;
+            }
         }
+        self.last_match = Some(query.to_string());
+        found
+    }
+}
+
+impl Engine for LogEngine {
+    fn name(&self) -> &'static str {
+        "LogEngine"
+    }
+
+    fn set_visual_range(&mut self, range: Option<(usize, usize)>) {
+        self.visual_range = range;
     }
 
-    pub fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+    fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
         let height = area.height as usize;
         self.last_view_height = height;
 
-        let visible = self.visible_entries();
-        let total = visible.len();
+        let total = self.display_count();
+        if total == 0 {
+            let message = if self.filter_level.is_some() { "(no entries at this level)" } else { "(empty file)" };
+            super::render_placeholder(frame, area, message);
+            return;
+        }
 
         if self.selection >= total && total > 0 {
             self.selection = total - 1;
         }
 
-        if self.selection < self.scroll {
-            self.scroll = self.selection;
-        } else if self.selection >= self.scroll + height {
-            self.scroll = self.selection.saturating_sub(height - 1);
-        }
+        self.scroll = super::follow_selection(self.scroll, self.selection, height, total);
 
-        let line_no_width = self.entries.len().max(1).to_string().len().max(2);
+        let last_line_no = self.line_index.last().map_or(0, |&(line_no, _, _)| line_no);
+        let line_no_width = last_line_no.max(1).to_string().len().max(2);
 
-        let display: Vec<Line> = visible
-            .iter()
-            .skip(self.scroll)
-            .take(height)
-            .enumerate()
-            .map(|(display_idx, &entry_idx)| {
-                let (line_no, entry) = &self.entries[entry_idx];
-                let row = self.scroll + display_idx;
+        // Only parse entries in the visible window
+        let display: Vec<Line> = (self.scroll..(self.scroll + height).min(total))
+            .filter_map(|row| {
+                let actual = self.display_to_actual(row)?;
+                let (line_no, entry) = self.entry(actual)?;
                 let selected = row == self.selection;
 
                 let mut spans = Vec::new();
@@ -113,8 +277,20 @@ impl LogEngine {
                 } else {
                     Style::default().fg(Color::DarkGray)
                 };
-                spans.push(Span::styled(line_no_str, line_no_style));
-                spans.push(Span::styled("│ ", Style::default().fg(Color::DarkGray)));
+                if super::line_numbers() {
+                    spans.push(Span::styled(line_no_str, line_no_style));
+                    spans.push(Span::styled("│ ", Style::default().fg(Color::DarkGray)));
+                }
+
+                let msg_style = if selected {
+                    Style::default().fg(Color::Black).bg(Color::LightBlue)
+                } else {
+                    Style::default().fg(Color::White)
+                };
+                if let Some(line) = self.colored_line(actual) {
+                    spans.extend(ansi_spans(&line, msg_style, true));
+                    return Some(Line::from(spans));
+                }
 
                 // Timestamp
                 if let Some(ts) = &entry.timestamp {
@@ -154,14 +330,9 @@ impl LogEngine {
                 }
 
                 // Message
-                let msg_style = if selected {
-                    Style::default().fg(Color::Black).bg(Color::LightBlue)
-                } else {
-                    Style::default().fg(Color::White)
-                };
-                spans.push(Span::styled(truncate(&entry.message, 80), msg_style));
+                spans.push(Span::styled(truncate(&entry.message, super::preview_len(80)), msg_style));
 
-                Line::from(spans)
+                Some(Line::from(spans))
             })
             .collect();
 
@@ -169,7 +340,8 @@ impl LogEngine {
         frame.render_widget(Paragraph::new(display).block(block), area);
     }
 
-    pub fn handle_key(&mut self, key: KeyEvent) {
+    #[allow(clippy::collapsible_match)]
+    fn handle_key(&mut self, key: KeyEvent) {
         match key.code {
             KeyCode::Char('g') => {
                 if self.pending_g {
@@ -185,8 +357,7 @@ impl LogEngine {
             }
         }
 
-        let visible = self.visible_entries();
-        let total = visible.len();
+        let total = self.display_count();
 
         match key.code {
             KeyCode::Char('j') | KeyCode::Down => {
@@ -198,34 +369,33 @@ impl LogEngine {
                 self.selection = self.selection.saturating_sub(1);
             }
             KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let jump = page_jump(self.last_view_height).min(self.selection);
-                self.selection = self.selection.saturating_sub(jump);
+                self.selection = super::half_page_up(self.selection, self.last_view_height);
             }
             KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let jump = page_jump(self.last_view_height).min(total.saturating_sub(1));
-                self.selection = (self.selection + jump).min(total.saturating_sub(1));
+                self.selection = super::half_page_down(self.selection, self.last_view_height, total);
             }
             KeyCode::Char('G') => {
                 if total > 0 {
                     self.selection = total - 1;
                 }
             }
-            KeyCode::Char('1') => self.filter_level = Some(LogLevel::Debug),
-            KeyCode::Char('2') => self.filter_level = Some(LogLevel::Info),
-            KeyCode::Char('3') => self.filter_level = Some(LogLevel::Warn),
-            KeyCode::Char('4') => self.filter_level = Some(LogLevel::Error),
-            KeyCode::Char('0') => self.filter_level = None,
+            KeyCode::Char('1') => self.set_filter_level(Some(LogLevel::Debug)),
+            KeyCode::Char('2') => self.set_filter_level(Some(LogLevel::Info)),
+            KeyCode::Char('3') => self.set_filter_level(Some(LogLevel::Warn)),
+            KeyCode::Char('4') => self.set_filter_level(Some(LogLevel::Error)),
+            KeyCode::Char('0') => self.set_filter_level(None),
             KeyCode::Char('e') => {
                 // Jump to next error
-                for i in (self.selection + 1)..total {
-                    if let Some(&entry_idx) = visible.get(i) {
-                        if self.entries[entry_idx].1.level == Some(LogLevel::Error)
-                            || self.entries[entry_idx].1.level == Some(LogLevel::Fatal)
-                        {
-                            self.selection = i;
-                            break;
-                        }
-                    }
+                self.ensure_levels();
+                if let Some(i) = ((self.selection + 1)..total).find(|&i| self.is_error(i)) {
+                    self.selection = i;
+                }
+            }
+            KeyCode::Char('E') => {
+                // Jump to previous error
+                self.ensure_levels();
+                if let Some(i) = (0..self.selection).rev().find(|&i| self.is_error(i)) {
+                    self.selection = i;
                 }
             }
             KeyCode::Char('n') => {
@@ -242,26 +412,27 @@ impl LogEngine {
         }
     }
 
-    pub fn apply_search(&mut self, query: &str) {
+    fn apply_search(&mut self, query: &str, forward: bool) -> bool {
         let trimmed = query.trim();
         if trimmed.is_empty() {
-            return;
+            return false;
         }
         self.last_query = Some(trimmed.to_string());
-        self.search_next(trimmed, true);
+        let found = self.search_next(trimmed, forward);
         self.last_match = Some(trimmed.to_string());
+        found
     }
 
-    pub fn apply_filter(&mut self, query: &str) {
-        self.apply_search(query);
+    fn apply_filter(&mut self, query: &str) -> bool {
+        self.apply_search(query, true)
     }
 
-    pub fn clear_filter(&mut self) {
+    fn clear_filter(&mut self) {
         self.last_query = None;
-        self.filter_level = None;
+        self.set_filter_level(None);
     }
 
-    pub fn breadcrumbs(&self) -> String {
+    fn breadcrumbs(&self) -> String {
         let filter = match self.filter_level {
             Some(LogLevel::Debug) => " [>=DEBUG]",
             Some(LogLevel::Info) => " [>=INFO]",
@@ -273,58 +444,144 @@ impl LogEngine {
         format!("{} line {}{}", self.file_name, self.selection + 1, filter)
     }
 
-    pub fn status_line(&self) -> String {
+    fn status_line(&self) -> String {
         let query = self
             .last_query
             .as_ref()
             .map(|q| format!(" | search: {}", q))
             .unwrap_or_default();
         format!(
-            "j/k move | gg/G jump | e next error | 1-4 filter level | 0 clear | n/N next/prev | / search{}",
+            "{} | j/k move | gg/G jump | e/E next/prev error | n/N next/prev | / search{}",
+            self.level_legend(),
             query
         )
     }
 
-    #[allow(dead_code)]
-    pub fn selected_path(&self) -> Option<String> {
+    /// Keys specific to this view, listed in the help overlay
+    fn help_lines(&self) -> Vec<Line<'static>> {
+        vec![
+            Line::from("  e/E          Next/previous error"),
+            Line::from("  1-4          Min level: debug/info/warn/error"),
+            Line::from("  0            Clear level filter"),
+        ]
+    }
+
+    fn selected_path(&self) -> Option<String> {
         None
     }
 
     /// Get the content of the currently selected line
-    pub fn get_selected_line(&self) -> Option<String> {
-        self.entries.get(self.selection).map(|(_, entry)| entry.raw.clone())
+    fn get_selected_line(&self) -> Option<String> {
+        let actual_idx = self.display_to_actual(self.selection)?;
+        self.get_line(actual_idx).map(Cow::into_owned)
     }
 
     /// Get lines in a range (inclusive), joined by newlines
-    pub fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
+    fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
         let (start, end) = if start <= end { (start, end) } else { (end, start) };
-        let total = self.entries.len();
+        let total = self.display_count();
         if start >= total { return None; }
         let end = end.min(total.saturating_sub(1));
-        let lines: Vec<String> = self.entries[start..=end].iter().map(|(_, entry)| entry.raw.clone()).collect();
+        let lines: Vec<String> = (start..=end)
+            .filter_map(|display_idx| {
+                let actual_idx = self.display_to_actual(display_idx)?;
+                self.get_line(actual_idx).map(Cow::into_owned)
+            })
+            .collect();
         if lines.is_empty() { None } else { Some(lines.join("\n")) }
     }
 
+    fn source_line(&self, row: usize) -> Option<usize> {
+        let actual_idx = self.display_to_actual(row)?;
+        self.line_index.get(actual_idx).map(|&(line_no, _, _)| line_no)
+    }
+
     /// Get current selection index (for visual mode)
-    pub fn selection(&self) -> usize {
+    fn selection(&self) -> usize {
         self.selection
     }
 
-    pub fn content_height(&self) -> usize {
-        self.visible_entries().len()
+    fn select(&mut self, row: usize) {
+        self.selection = row.min(self.display_count().saturating_sub(1));
     }
 
-    pub fn render_plain_lines(&self, _width: u16) -> Vec<Line<'static>> {
-        let line_no_width = self.entries.len().max(1).to_string().len().max(2);
-        self.entries
-            .iter()
+    fn append(&mut self, bytes: &[u8]) {
+        // Keep following the end of the stream when the last entry is selected
+        let at_end = self.selection + 1 >= self.display_count();
+        self.data.extend(bytes);
+        // Re-index the unterminated last line along with whatever follows it
+        if self.line_index.last().is_some_and(|&(_, start, _)| start == self.index_resume.0) {
+            self.line_index.pop();
+        }
+        let kept = self.line_index.len();
+        self.index_resume = build_line_index(&self.data, self.index_resume, &mut self.line_index);
+        if self.levels.is_some() {
+            let added: Vec<_> = (kept..self.line_count())
+                .map(|idx| self.get_line(idx).and_then(|line| self.patterns.line_level(&line)))
+                .collect();
+            if let Some(levels) = &mut self.levels {
+                count_levels(&levels[kept.min(levels.len())..], &mut self.level_counts, false);
+                levels.truncate(kept);
+                count_levels(&added, &mut self.level_counts, true);
+                levels.extend(added);
+            }
+        }
+        if self.filter_level.is_some() {
+            self.set_filter_level(self.filter_level);
+        }
+        if at_end {
+            self.selection = self.display_count().saturating_sub(1);
+        }
+    }
+
+    /// Scroll so the selection sits at the top, middle or bottom of the view (`zt`/`zz`/`zb`)
+    fn align_scroll(&mut self, align: super::ScrollAlign) {
+        self.scroll = super::aligned_scroll(self.selection, self.last_view_height, align);
+    }
+
+    fn set_scroll(&mut self, row: usize) {
+        self.scroll = row;
+    }
+
+    fn content_height(&mut self) -> usize {
+        self.display_count()
+    }
+
+    /// Parsed structure for `--dump`
+    fn to_json(&mut self) -> serde_json::Value {
+        let entries: Vec<_> = (0..self.line_count())
+            .filter_map(|idx| self.entry(idx))
             .map(|(line_no, entry)| {
+                json!({
+                    "line": line_no,
+                    "timestamp": entry.timestamp,
+                    "level": entry.level.map(level_name),
+                    "source": entry.source,
+                    "message": entry.message,
+                })
+            })
+            .collect();
+        json!({ "entries": entries })
+    }
+
+    fn render_plain_lines(&mut self, _width: u16) -> Vec<Line<'static>> {
+        let last_line_no = self.line_index.last().map_or(0, |&(line_no, _, _)| line_no);
+        let line_no_width = last_line_no.max(1).to_string().len().max(2);
+        (0..self.line_count())
+            .filter_map(|idx| Some((idx, self.entry(idx)?)))
+            .map(|(idx, (line_no, entry))| {
                 let mut spans = Vec::new();
-                spans.push(Span::styled(
-                    format!("{:>width$} ", line_no, width = line_no_width),
-                    Style::default().fg(Color::DarkGray),
-                ));
-                spans.push(Span::styled("│ ", Style::default().fg(Color::DarkGray)));
+                if super::line_numbers() {
+                    spans.push(Span::styled(
+                        format!("{:>width$} ", line_no, width = line_no_width),
+                        Style::default().fg(Color::DarkGray),
+                    ));
+                    spans.push(Span::styled("│ ", Style::default().fg(Color::DarkGray)));
+                }
+                if let Some(line) = self.colored_line(idx) {
+                    spans.extend(ansi_spans(&line, Style::default().fg(Color::White), true));
+                    return Line::from(spans);
+                }
 
                 if let Some(level) = entry.level {
                     let (text, color) = match level {
@@ -343,85 +600,101 @@ impl LogEngine {
             })
             .collect()
     }
+}
 
-    fn search_next(&mut self, query: &str, forward: bool) {
-        let lower = query.to_lowercase();
-        let visible = self.visible_entries();
-        let total = visible.len().max(1);
-        let start = if forward {
-            (self.selection + 1) % total
-        } else {
-            self.selection.saturating_sub(1)
-        };
-
-        for offset in 0..total {
-            let idx = if forward {
-                (start + offset) % total
-            } else {
-                (start + total - offset % total) % total
-            };
-            if let Some(&entry_idx) = visible.get(idx) {
-                if self.entries[entry_idx].1.raw.to_lowercase().contains(&lower) {
-                    self.selection = idx;
-                    break;
-                }
-            }
+/// Index the (line_no, start, end) byte range of every non-blank line.
+/// Only offsets are stored; entries are parsed on demand.
+fn build_line_index(data: &[u8], from: (usize, usize), index: &mut Vec<(usize, usize, usize)>) -> (usize, usize) {
+    let (mut start, mut line_no) = from;
+    while start < data.len() {
+        let end = data[start..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(data.len(), |p| start + p);
+        if data[start..end].iter().any(|b| !b.is_ascii_whitespace()) {
+            index.push((line_no, start, end));
         }
-        self.last_match = Some(query.to_string());
+        if end == data.len() {
+            // No newline yet: more input may extend this line
+            break;
+        }
+        start = end + 1;
+        line_no += 1;
     }
+    (start, line_no)
 }
 
-fn parse_log(content: &str) -> Vec<(usize, LogEntry)> {
-    let mut entries = Vec::new();
-
-    // Common patterns
-    let timestamp_re = Regex::new(r"^\[?(\d{4}[-/]\d{2}[-/]\d{2}[T ]\d{2}:\d{2}:\d{2}(?:\.\d+)?(?:Z|[+-]\d{2}:?\d{2})?)\]?").ok();
-    let level_re = Regex::new(r"(?i)\b(DEBUG|DBG|INFO|INF|WARN(?:ING)?|WRN|ERROR|ERR|FATAL|FTL|CRITICAL|CRIT)\b").ok();
+/// Timestamp and level patterns, compiled once per engine
+struct LogPatterns {
+    timestamp: Option<Regex>,
+    level: Option<Regex>,
+}
 
-    for (idx, line) in content.lines().enumerate() {
-        let line_no = idx + 1;
-        let trimmed = line.trim();
+impl LogPatterns {
+    fn new() -> Self {
+        Self {
+            timestamp: Regex::new(r"^\[?(\d{4}[-/]\d{2}[-/]\d{2}[T ]\d{2}:\d{2}:\d{2}(?:\.\d+)?(?:Z|[+-]\d{2}:?\d{2})?)\]?").ok(),
+            level: Regex::new(r"(?i)\b(DEBUG|DBG|INFO|INF|WARN(?:ING)?|WRN|ERROR|ERR|FATAL|FTL|CRITICAL|CRIT)\b").ok(),
+        }
+    }
 
-        if trimmed.is_empty() {
-            continue;
+    /// Parse a raw line; escape sequences are dropped first so colored levels
+    /// still match and nothing can reach the terminal
+    fn parse(&self, line: &str) -> LogEntry {
+        let line = strip_controls(line);
+        let (timestamp, remaining) = self.split_timestamp(&line);
+        LogEntry {
+            timestamp: timestamp.map(str::to_string),
+            level: self.level(remaining),
+            source: None,
+            message: remaining.to_string(),
         }
+    }
 
-        let mut timestamp = None;
-        let mut level = None;
-        let mut remaining = trimmed.to_string();
+    /// Level of a raw line, read after the timestamp exactly as `parse` does
+    /// so the level filter always agrees with the displayed entries
+    fn line_level(&self, line: &str) -> Option<LogLevel> {
+        self.level(self.split_timestamp(&strip_controls(line)).1)
+    }
 
-        // Extract timestamp
-        if let Some(ref re) = timestamp_re {
-            if let Some(caps) = re.captures(&remaining) {
-                timestamp = Some(caps[1].to_string());
-                remaining = remaining[caps[0].len()..].trim().to_string();
-            }
+    /// Split a leading timestamp off an already stripped line
+    fn split_timestamp<'a>(&self, line: &'a str) -> (Option<&'a str>, &'a str) {
+        match self.timestamp.as_ref().and_then(|re| re.captures(line)) {
+            Some(caps) => (caps.get(1).map(|m| m.as_str()), line[caps[0].len()..].trim()),
+            None => (None, line),
         }
+    }
 
-        // Extract level
-        if let Some(ref re) = level_re {
-            if let Some(caps) = re.captures(&remaining) {
-                level = Some(match caps[1].to_uppercase().as_str() {
-                    "DEBUG" | "DBG" => LogLevel::Debug,
-                    "INFO" | "INF" => LogLevel::Info,
-                    "WARN" | "WARNING" | "WRN" => LogLevel::Warn,
-                    "ERROR" | "ERR" => LogLevel::Error,
-                    "FATAL" | "FTL" | "CRITICAL" | "CRIT" => LogLevel::Fatal,
-                    _ => LogLevel::Info,
-                });
-            }
-        }
+    fn level(&self, text: &str) -> Option<LogLevel> {
+        let caps = self.level.as_ref()?.captures(text)?;
+        Some(match caps[1].to_uppercase().as_str() {
+            "DEBUG" | "DBG" => LogLevel::Debug,
+            "INFO" | "INF" => LogLevel::Info,
+            "WARN" | "WARNING" | "WRN" => LogLevel::Warn,
+            "ERROR" | "ERR" => LogLevel::Error,
+            "FATAL" | "FTL" | "CRITICAL" | "CRIT" => LogLevel::Fatal,
+            _ => LogLevel::Info,
+        })
+    }
+}
 
-        entries.push((line_no, LogEntry {
-            timestamp,
-            level,
-            source: None,
-            message: remaining,
-            raw: trimmed.to_string(),
-        }));
+fn level_name(level: LogLevel) -> &'static str {
+    match level {
+        LogLevel::Debug => "debug",
+        LogLevel::Info => "info",
+        LogLevel::Warn => "warn",
+        LogLevel::Error => "error",
+        LogLevel::Fatal => "fatal",
     }
+}
 
-    entries
+/// Add (or with `add` false, remove) `levels` to the per-level counts;
+/// fatal entries count as errors
+fn count_levels(levels: &[Option<LogLevel>], counts: &mut [usize; 4], add: bool) {
+    for level in levels.iter().flatten() {
+        let slot = &mut counts[usize::from(level_priority(*level).min(3))];
+        *slot = if add { *slot + 1 } else { slot.saturating_sub(1) };
+    }
 }
 
 fn level_priority(level: LogLevel) -> u8 {
@@ -434,16 +707,72 @@ fn level_priority(level: LogLevel) -> u8 {
     }
 }
 
-fn truncate(value: &str, max: usize) -> String {
-    if value.len() <= max {
-        return value.to_string();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn line_index_skips_blank_lines_and_keeps_line_numbers() {
+        let data = b"first\n\n   \nsecond\nthird";
+        let mut index = Vec::new();
+        let resume = build_line_index(data, (0, 1), &mut index);
+        let lines: Vec<_> = index.iter().map(|&(no, s, e)| (no, &data[s..e])).collect();
+        assert_eq!(lines, vec![(1, &b"first"[..]), (4, &b"second"[..]), (5, &b"third"[..])]);
+        // Indexing resumes at the unterminated last line
+        assert_eq!(resume, (data.len() - b"third".len(), 5));
     }
-    let mut out = value.chars().take(max.saturating_sub(3)).collect::<String>();
-    out.push_str("...");
-    out
-}
 
-fn page_jump(view_height: usize) -> usize {
-    let half = view_height / 2;
-    if half == 0 { 1 } else { half }
+    #[test]
+    fn appending_to_an_unterminated_line_reindexes_it() {
+        let mut engine = LogEngine::from_bytes(Path::new("app.log"), b"one\ntw").unwrap();
+        engine.append(b"o\n\nthree\n");
+        let lines: Vec<_> = (0..engine.line_count())
+            .map(|idx| (engine.line_index[idx].0, engine.get_line(idx).unwrap().into_owned()))
+            .collect();
+        assert_eq!(lines, vec![(1, "one".into()), (2, "two".into()), (4, "three".into())]);
+    }
+
+    #[test]
+    fn mapped_file_shows_every_indexed_line() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(b"2024-01-01 10:00:00 INFO ok\n2024-01-01 10:00:01 ERROR bad \xff byte\n")
+            .unwrap();
+        let mut engine = LogEngine::from_path(file.path()).unwrap();
+        assert_eq!(engine.display_count(), 2);
+        assert_eq!(engine.render_plain_lines(80).len(), engine.display_count());
+        let (_, entry) = engine.entry(1).unwrap();
+        assert_eq!(entry.message, "ERROR bad \u{fffd} byte");
+        assert_eq!(engine.get_lines_range(0, 1).unwrap().lines().count(), 2);
+    }
+
+    #[test]
+    fn level_filter_reads_levels_like_parse() {
+        let content = b"2024-01-01 10:00:00 DEBUG start\n\
+            [2024-01-01T10:00:01Z] WARN slow\n\
+            2024-01-01 10:00:02 ERROR failed\n\
+            plain line\n";
+        let mut engine = LogEngine::from_bytes(Path::new("app.log"), content).unwrap();
+        engine.set_filter_level(Some(LogLevel::Warn));
+        for display in 0..engine.display_count() {
+            let actual = engine.display_to_actual(display).unwrap();
+            let parsed = engine.entry(actual).unwrap().1.level;
+            assert!(parsed.is_none_or(|l| level_priority(l) >= level_priority(LogLevel::Warn)));
+            assert!(engine.levels.as_ref().unwrap()[actual] == parsed);
+        }
+        assert_eq!(engine.display_count(), 3);
+    }
+
+    #[test]
+    fn dump_parses_every_entry() {
+        let content = b"2024-01-01 10:00:00 ERROR boom\n\nplain\n";
+        let mut engine = LogEngine::from_bytes(Path::new("app.log"), content).unwrap();
+        assert_eq!(
+            engine.to_json(),
+            json!({ "entries": [
+                { "line": 1, "timestamp": "2024-01-01 10:00:00", "level": "error", "source": null, "message": "ERROR boom" },
+                { "line": 3, "timestamp": null, "level": null, "source": null, "message": "plain" },
+            ]})
+        );
+    }
 }
@@ -10,6 +10,10 @@ use ratatui::widgets::{Block, Borders, Paragraph};
 use nom::bytes::complete::{take_while1, take_while_m_n};
 use nom::character::complete::space1;
 use nom::sequence::tuple;
+use serde_json::json;
+
+use super::util::search_matches;
+use super::Engine;
 
 pub struct LogicEngine {
     lines: Vec<String>,
@@ -26,20 +30,25 @@ pub struct LogicEngine {
 
 impl LogicEngine {
     pub fn from_path(path: &Path) -> Result<Self> {
-        let raw = std::fs::read_to_string(path)?;
+        Self::from_bytes(path, &std::fs::read(path)?)
+    }
+
+    /// Create from in-memory bytes; `path` only supplies the name and extension
+    pub fn from_bytes(path: &Path, bytes: &[u8]) -> Result<Self> {
+        let raw = std::str::from_utf8(bytes)?;
         let file_name = path
             .file_name()
             .and_then(|s| s.to_str())
             .unwrap_or("")
             .to_string();
         let lines = if file_name == ".tmux.conf" {
-            parse_tmux(&raw)
+            parse_tmux(raw)
         } else if file_name == ".bashrc" {
-            parse_bashrc(&raw)
+            parse_bashrc(raw)
         } else if file_name == "crontab" {
-            parse_crontab(&raw)
+            parse_crontab(raw)
         } else {
-            parse_ssh_config(path, &raw)
+            parse_ssh_config(path, raw)
         };
         Ok(Self {
             lines,
@@ -53,15 +62,26 @@ impl LogicEngine {
             visual_range: None,
         })
     }
+}
+
+impl Engine for LogicEngine {
+    fn name(&self) -> &'static str {
+        "LogicEngine"
+    }
 
-    pub fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+    fn set_visual_range(&mut self, range: Option<(usize, usize)>) {
+        self.visual_range = range;
+    }
+
+    fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
         let height = area.height as usize;
         self.last_view_height = height;
-        if self.selection < self.scroll {
-            self.scroll = self.selection;
-        } else if self.selection >= self.scroll + height {
-            self.scroll = self.selection.saturating_sub(height - 1);
+
+        if self.lines.is_empty() {
+            super::render_placeholder(frame, area, "(empty file)");
+            return;
         }
+        self.scroll = super::follow_selection(self.scroll, self.selection, height, self.lines.len());
         let line_no_width = self.lines.len().max(1).to_string().len().max(2);
         let visible: Vec<Line> = self
             .lines
@@ -79,8 +99,10 @@ impl LogicEngine {
                 } else {
                     Style::default().fg(Color::LightYellow)
                 };
-                spans.push(Span::styled(line_no, line_no_style));
-                spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
+                if super::line_numbers() {
+                    spans.push(Span::styled(line_no, line_no_style));
+                    spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
+                }
                 let content_style = if line.trim_end().ends_with(':') {
                     Style::default().fg(Color::LightCyan).bold()
                 } else {
@@ -99,11 +121,16 @@ impl LogicEngine {
         frame.render_widget(Paragraph::new(visible).block(block), area);
     }
 
-    pub fn content_height(&self) -> usize {
+    fn content_height(&mut self) -> usize {
         self.lines.len()
     }
 
-    pub fn render_plain_lines(&self) -> Vec<Line<'static>> {
+    /// Parsed structure for `--dump`
+    fn to_json(&mut self) -> serde_json::Value {
+        json!({ "lines": self.lines })
+    }
+
+    fn render_plain_lines(&mut self, _width: u16) -> Vec<Line<'static>> {
         let line_no_width = self.lines.len().max(1).to_string().len().max(2);
         self.lines
             .iter()
@@ -111,11 +138,13 @@ impl LogicEngine {
             .map(|(idx, line)| {
                 let mut spans = Vec::new();
                 let line_no = format!("{:>width$} ", idx + 1, width = line_no_width);
-                spans.push(Span::styled(
-                    line_no,
-                    Style::default().fg(Color::LightYellow),
-                ));
-                spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
+                if super::line_numbers() {
+                    spans.push(Span::styled(
+                        line_no,
+                        Style::default().fg(Color::LightYellow),
+                    ));
+                    spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
+                }
                 let content_style = if line.trim_end().ends_with(':') {
                     Style::default().fg(Color::LightCyan).bold()
                 } else {
@@ -127,7 +156,8 @@ impl LogicEngine {
             .collect()
     }
 
-    pub fn handle_key(&mut self, key: KeyEvent) {
+    #[allow(clippy::collapsible_match)]
+    fn handle_key(&mut self, key: KeyEvent) {
         match key.code {
             KeyCode::Char('g') => {
                 if self.pending_g {
@@ -154,14 +184,12 @@ impl LogicEngine {
             KeyCode::Char('u')
                 if key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) =>
             {
-                let jump = page_jump(self.last_view_height).min(self.selection);
-                self.selection = self.selection.saturating_sub(jump);
+                self.selection = super::half_page_up(self.selection, self.last_view_height);
             }
             KeyCode::Char('d')
                 if key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) =>
             {
-                let jump = page_jump(self.last_view_height).min(self.lines.len().saturating_sub(1));
-                self.selection = (self.selection + jump).min(self.lines.len().saturating_sub(1));
+                self.selection = super::half_page_down(self.selection, self.last_view_height, self.lines.len());
             }
             KeyCode::Char('G') => {
                 if !self.lines.is_empty() {
@@ -182,21 +210,22 @@ impl LogicEngine {
         }
     }
 
-    pub fn apply_search(&mut self, query: &str) {
+    fn apply_search(&mut self, query: &str, forward: bool) -> bool {
         let trimmed = query.trim();
         if trimmed.is_empty() {
-            return;
+            return false;
         }
         self.last_query = Some(trimmed.to_string());
-        self.search_next(trimmed, true);
+        let found = self.search_next(trimmed, forward);
         self.last_match = Some(trimmed.to_string());
+        found
     }
 
-    pub fn breadcrumbs(&self) -> String {
+    fn breadcrumbs(&self) -> String {
         self.file_name.clone()
     }
 
-    pub fn status_line(&self) -> String {
+    fn status_line(&self) -> String {
         let query = self
             .last_query
             .as_ref()
@@ -208,26 +237,30 @@ impl LogicEngine {
         )
     }
 
-    pub fn apply_filter(&mut self, query: &str) {
-        self.apply_search(query);
+    fn apply_filter(&mut self, query: &str) -> bool {
+        self.apply_search(query, true)
     }
 
-    pub fn clear_filter(&mut self) {
+    fn clear_filter(&mut self) {
         self.last_query = None;
     }
 
-    #[allow(dead_code)]
-    pub fn selected_path(&self) -> Option<String> {
+    /// Keys specific to this view, listed in the help overlay
+    fn help_lines(&self) -> Vec<Line<'static>> {
+        Vec::new()
+    }
+
+    fn selected_path(&self) -> Option<String> {
         None
     }
 
     /// Get the content of the currently selected line
-    pub fn get_selected_line(&self) -> Option<String> {
+    fn get_selected_line(&self) -> Option<String> {
         self.lines.get(self.selection).cloned()
     }
 
     /// Get lines in a range (inclusive), joined by newlines
-    pub fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
+    fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
         let (start, end) = if start <= end { (start, end) } else { (end, start) };
         let total = self.lines.len();
         if start >= total {
@@ -239,9 +272,18 @@ impl LogicEngine {
     }
 
     /// Get current selection index (for visual mode)
-    pub fn selection(&self) -> usize {
+    fn selection(&self) -> usize {
         self.selection
     }
+
+    fn select(&mut self, row: usize) {
+        self.selection = row.min(self.lines.len().saturating_sub(1));
+    }
+
+    /// Scroll so the selection sits at the top, middle or bottom of the view (`zt`/`zz`/`zb`)
+    fn align_scroll(&mut self, align: super::ScrollAlign) {
+        self.scroll = super::aligned_scroll(self.selection, self.last_view_height, align);
+    }
 }
 
 fn parse_ssh_config(path: &Path, raw: &str) -> Vec<String> {
@@ -326,23 +368,19 @@ fn parse_ssh_config(path: &Path, raw: &str) -> Vec<String> {
     lines
 }
 
-fn page_jump(view_height: usize) -> usize {
-    let half = view_height / 2;
-    if half == 0 { 1 } else { half }
-}
-
 impl LogicEngine {
-    fn search_next(&mut self, query: &str, forward: bool) {
+    fn search_next(&mut self, query: &str, forward: bool) -> bool {
         let trimmed = query.trim();
         if trimmed.is_empty() {
-            return;
+            return false;
         }
         let lower = trimmed.to_lowercase();
+        let mut found = false;
         let total = self.lines.len().max(1);
         let start = if forward {
             (self.selection + 1) % total
         } else {
-            self.selection.saturating_sub(1)
+            (self.selection + total - 1) % total
         };
         for offset in 0..self.lines.len() {
             let idx = if forward {
@@ -350,12 +388,14 @@ impl LogicEngine {
             } else {
                 (start + total - offset % total) % total
             };
-            if self.lines[idx].to_lowercase().contains(&lower) {
+            if search_matches(&self.lines[idx], &lower) {
                 self.selection = idx;
+                found = true;
                 break;
             }
         }
         self.last_match = Some(trimmed.to_string());
+        found
     }
 }
 
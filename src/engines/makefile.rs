@@ -6,10 +6,15 @@ use ratatui::layout::Rect;
 use ratatui::style::{Color, Style, Stylize};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph};
+use serde_json::json;
+
+use super::util::{search_matches, truncate};
+use super::Engine;
 
 #[derive(Clone)]
 enum MakeLine {
-    Target { name: String, deps: Vec<String>, is_phony: bool },
+    /// `double_colon` marks `target:: deps` rules, whose recipes run independently
+    Target { name: String, deps: Vec<String>, is_phony: bool, double_colon: bool },
     Recipe(String),
     Variable { name: String, op: String, value: String },
     Include(String),
@@ -35,14 +40,19 @@ pub struct MakefileEngine {
 
 impl MakefileEngine {
     pub fn from_path(path: &Path) -> Result<Self> {
-        let content = std::fs::read_to_string(path)?;
+        Self::from_bytes(path, &std::fs::read(path)?)
+    }
+
+    /// Create from in-memory bytes; `path` only supplies the name and extension
+    pub fn from_bytes(path: &Path, bytes: &[u8]) -> Result<Self> {
+        let content = std::str::from_utf8(bytes)?;
         let file_name = path
             .file_name()
             .and_then(|s| s.to_str())
             .unwrap_or("")
             .to_string();
 
-        let (lines, phony_targets) = parse_makefile(&content);
+        let (lines, phony_targets) = parse_makefile(content);
 
         Ok(Self {
             lines,
@@ -58,16 +68,65 @@ impl MakefileEngine {
         })
     }
 
-    pub fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+    fn search_next(&mut self, query: &str, forward: bool) -> bool {
+        let lower = query.to_lowercase();
+        let mut found = false;
+        let total = self.lines.len().max(1);
+        let start = if forward {
+            (self.selection + 1) % total
+        } else {
+            (self.selection + total - 1) % total
+        };
+
+        for offset in 0..total {
+            let idx = if forward {
+                (start + offset) % total
+            } else {
+                (start + total - offset % total) % total
+            };
+            let text = match &self.lines[idx].2 {
+                MakeLine::Target { name, deps, double_colon, .. } => {
+                    let sep = if *double_colon { "::" } else { ":" };
+                    format!("{}{} {}", name, sep, deps.join(" "))
+                }
+                MakeLine::Recipe(cmd) => cmd.clone(),
+                MakeLine::Variable { name, op, value } => format!("{} {} {}", name, op, value),
+                MakeLine::Comment(text) => text.clone(),
+                MakeLine::Include(path) => format!("include {}", path),
+                MakeLine::Conditional(text) => text.clone(),
+                MakeLine::Empty => String::new(),
+            };
+            if search_matches(&text, &lower) {
+                self.selection = idx;
+                found = true;
+                break;
+            }
+        }
+        self.last_match = Some(query.to_string());
+        found
+    }
+}
+
+impl Engine for MakefileEngine {
+    fn name(&self) -> &'static str {
+        "MakefileEngine"
+    }
+
+    fn set_visual_range(&mut self, range: Option<(usize, usize)>) {
+        self.visual_range = range;
+    }
+
+    fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
         let height = area.height as usize;
         self.last_view_height = height;
 
-        if self.selection < self.scroll {
-            self.scroll = self.selection;
-        } else if self.selection >= self.scroll + height {
-            self.scroll = self.selection.saturating_sub(height - 1);
+        if self.lines.is_empty() {
+            super::render_placeholder(frame, area, "(empty file)");
+            return;
         }
 
+        self.scroll = super::follow_selection(self.scroll, self.selection, height, self.lines.len());
+
         let line_no_width = self.lines.len().max(1).to_string().len().max(2);
 
         let visible: Vec<Line> = self.lines
@@ -86,11 +145,13 @@ impl MakefileEngine {
                 } else {
                     Style::default().fg(Color::DarkGray)
                 };
-                spans.push(Span::styled(line_no_str, line_no_style));
-                spans.push(Span::styled("│ ", Style::default().fg(Color::DarkGray)));
+                if super::line_numbers() {
+                    spans.push(Span::styled(line_no_str, line_no_style));
+                    spans.push(Span::styled("│ ", Style::default().fg(Color::DarkGray)));
+                }
 
                 match parsed {
-                    MakeLine::Target { name, deps, is_phony } => {
+                    MakeLine::Target { name, deps, is_phony, double_colon } => {
                         if *is_phony {
                             spans.push(Span::styled("◆ ", Style::default().fg(Color::Magenta)));
                         }
@@ -100,7 +161,12 @@ impl MakefileEngine {
                             Style::default().fg(Color::Cyan).bold()
                         };
                         spans.push(Span::styled(name.clone(), name_style));
-                        spans.push(Span::styled(":", Style::default().fg(Color::DarkGray)));
+                        // `::` rules get a highlighted separator so they stand out
+                        if *double_colon {
+                            spans.push(Span::styled("::", Style::default().fg(Color::LightMagenta).bold()));
+                        } else {
+                            spans.push(Span::styled(":", Style::default().fg(Color::DarkGray)));
+                        }
                         if !deps.is_empty() {
                             let dep_style = if selected {
                                 Style::default().fg(Color::Black).bg(Color::LightBlue)
@@ -137,7 +203,7 @@ impl MakefileEngine {
                         };
                         spans.push(Span::styled(name.clone(), name_style));
                         spans.push(Span::styled(format!(" {} ", op), op_style));
-                        spans.push(Span::styled(truncate(value, 50), val_style));
+                        spans.push(Span::styled(truncate(value, super::preview_len(50)), val_style));
                     }
                     MakeLine::Include(path) => {
                         let kw_style = if selected {
@@ -175,7 +241,8 @@ impl MakefileEngine {
         frame.render_widget(Paragraph::new(visible).block(block), area);
     }
 
-    pub fn handle_key(&mut self, key: KeyEvent) {
+    #[allow(clippy::collapsible_match)]
+    fn handle_key(&mut self, key: KeyEvent) {
         match key.code {
             KeyCode::Char('g') => {
                 if self.pending_g {
@@ -202,12 +269,10 @@ impl MakefileEngine {
                 self.selection = self.selection.saturating_sub(1);
             }
             KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let jump = page_jump(self.last_view_height).min(self.selection);
-                self.selection = self.selection.saturating_sub(jump);
+                self.selection = super::half_page_up(self.selection, self.last_view_height);
             }
             KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let jump = page_jump(self.last_view_height).min(total.saturating_sub(1));
-                self.selection = (self.selection + jump).min(total.saturating_sub(1));
+                self.selection = super::half_page_down(self.selection, self.last_view_height, total);
             }
             KeyCode::Char('G') => {
                 if total > 0 {
@@ -223,6 +288,15 @@ impl MakefileEngine {
                     }
                 }
             }
+            KeyCode::Char('E') => {
+                // Jump to previous target
+                for i in (0..self.selection).rev() {
+                    if matches!(self.lines[i].2, MakeLine::Target { .. }) {
+                        self.selection = i;
+                        break;
+                    }
+                }
+            }
             KeyCode::Char('n') => {
                 if let Some(query) = self.last_match.clone() {
                     self.search_next(&query, true);
@@ -237,25 +311,26 @@ impl MakefileEngine {
         }
     }
 
-    pub fn apply_search(&mut self, query: &str) {
+    fn apply_search(&mut self, query: &str, forward: bool) -> bool {
         let trimmed = query.trim();
         if trimmed.is_empty() {
-            return;
+            return false;
         }
         self.last_query = Some(trimmed.to_string());
-        self.search_next(trimmed, true);
+        let found = self.search_next(trimmed, forward);
         self.last_match = Some(trimmed.to_string());
+        found
     }
 
-    pub fn apply_filter(&mut self, query: &str) {
-        self.apply_search(query);
+    fn apply_filter(&mut self, query: &str) -> bool {
+        self.apply_search(query, true)
     }
 
-    pub fn clear_filter(&mut self) {
+    fn clear_filter(&mut self) {
         self.last_query = None;
     }
 
-    pub fn breadcrumbs(&self) -> String {
+    fn breadcrumbs(&self) -> String {
         // Find current target
         let mut target = "".to_string();
         for i in (0..=self.selection).rev() {
@@ -271,30 +346,36 @@ impl MakefileEngine {
         }
     }
 
-    pub fn status_line(&self) -> String {
+    fn status_line(&self) -> String {
         let query = self
             .last_query
             .as_ref()
             .map(|q| format!(" | search: {}", q))
             .unwrap_or_default();
         format!(
-            "j/k move | gg/G jump | Ctrl+u/d half-page | e next target | n/N next/prev | / search{}",
+            "j/k move | gg/G jump | Ctrl+u/d half-page | e/E next/prev target | n/N next/prev | / search{}",
             query
         )
     }
 
-    #[allow(dead_code)]
-    pub fn selected_path(&self) -> Option<String> {
+    /// Keys specific to this view, listed in the help overlay
+    fn help_lines(&self) -> Vec<Line<'static>> {
+        vec![
+            Line::from("  e/E          Next/previous target"),
+        ]
+    }
+
+    fn selected_path(&self) -> Option<String> {
         None
     }
 
     /// Get the content of the currently selected line
-    pub fn get_selected_line(&self) -> Option<String> {
+    fn get_selected_line(&self) -> Option<String> {
         self.lines.get(self.selection).map(|(_, raw, _)| raw.clone())
     }
 
     /// Get lines in a range (inclusive), joined by newlines
-    pub fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
+    fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
         let (start, end) = if start <= end { (start, end) } else { (end, start) };
         let total = self.lines.len();
         if start >= total { return None; }
@@ -304,33 +385,75 @@ impl MakefileEngine {
     }
 
     /// Get current selection index (for visual mode)
-    pub fn selection(&self) -> usize {
+    fn selection(&self) -> usize {
         self.selection
     }
 
-    pub fn content_height(&self) -> usize {
+    fn select(&mut self, row: usize) {
+        self.selection = row.min(self.lines.len().saturating_sub(1));
+    }
+
+    /// Scroll so the selection sits at the top, middle or bottom of the view (`zt`/`zz`/`zb`)
+    fn align_scroll(&mut self, align: super::ScrollAlign) {
+        self.scroll = super::aligned_scroll(self.selection, self.last_view_height, align);
+    }
+
+    fn content_height(&mut self) -> usize {
         self.lines.len()
     }
 
-    pub fn render_plain_lines(&self, _width: u16) -> Vec<Line<'static>> {
+    /// Parsed structure for `--dump`
+    fn to_json(&mut self) -> serde_json::Value {
+        let mut targets: Vec<serde_json::Value> = Vec::new();
+        let mut variables = Vec::new();
+        let mut includes = Vec::new();
+        for (line_no, _, parsed) in &self.lines {
+            match parsed {
+                MakeLine::Target { name, deps, is_phony, double_colon } => targets.push(json!({
+                    "name": name,
+                    "deps": deps,
+                    "phony": is_phony,
+                    "double_colon": double_colon,
+                    "line": line_no,
+                    "recipe": [],
+                })),
+                MakeLine::Recipe(command) => {
+                    if let Some(recipe) = targets.last_mut().and_then(|t| t["recipe"].as_array_mut()) {
+                        recipe.push(json!(command));
+                    }
+                }
+                MakeLine::Variable { name, op, value } => {
+                    variables.push(json!({ "name": name, "op": op, "value": value, "line": line_no }));
+                }
+                MakeLine::Include(path) => includes.push(json!(path)),
+                MakeLine::Conditional(_) | MakeLine::Comment(_) | MakeLine::Empty => {}
+            }
+        }
+        json!({ "targets": targets, "variables": variables, "includes": includes })
+    }
+
+    fn render_plain_lines(&mut self, _width: u16) -> Vec<Line<'static>> {
         let line_no_width = self.lines.len().max(1).to_string().len().max(2);
         self.lines
             .iter()
             .map(|(line_no, _raw, parsed)| {
                 let mut spans = Vec::new();
-                spans.push(Span::styled(
-                    format!("{:>width$} ", line_no, width = line_no_width),
-                    Style::default().fg(Color::LightYellow),
-                ));
-                spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
+                if super::line_numbers() {
+                    spans.push(Span::styled(
+                        format!("{:>width$} ", line_no, width = line_no_width),
+                        Style::default().fg(Color::LightYellow),
+                    ));
+                    spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
+                }
 
                 match parsed {
-                    MakeLine::Target { name, deps, is_phony } => {
+                    MakeLine::Target { name, deps, is_phony, double_colon } => {
                         if *is_phony {
                             spans.push(Span::styled("[P] ", Style::default().fg(Color::Magenta)));
                         }
                         spans.push(Span::styled(name.clone(), Style::default().fg(Color::LightGreen).bold()));
-                        spans.push(Span::styled(":", Style::default().fg(Color::White)));
+                        let sep = if *double_colon { "::" } else { ":" };
+                        spans.push(Span::styled(sep, Style::default().fg(Color::White)));
                         if !deps.is_empty() {
                             spans.push(Span::styled(format!(" {}", deps.join(" ")), Style::default().fg(Color::LightCyan)));
                         }
@@ -354,38 +477,6 @@ impl MakefileEngine {
             })
             .collect()
     }
-
-    fn search_next(&mut self, query: &str, forward: bool) {
-        let lower = query.to_lowercase();
-        let total = self.lines.len().max(1);
-        let start = if forward {
-            (self.selection + 1) % total
-        } else {
-            self.selection.saturating_sub(1)
-        };
-
-        for offset in 0..total {
-            let idx = if forward {
-                (start + offset) % total
-            } else {
-                (start + total - offset % total) % total
-            };
-            let text = match &self.lines[idx].2 {
-                MakeLine::Target { name, deps, .. } => format!("{}: {}", name, deps.join(" ")),
-                MakeLine::Recipe(cmd) => cmd.clone(),
-                MakeLine::Variable { name, op, value } => format!("{} {} {}", name, op, value),
-                MakeLine::Comment(text) => text.clone(),
-                MakeLine::Include(path) => format!("include {}", path),
-                MakeLine::Conditional(text) => text.clone(),
-                MakeLine::Empty => String::new(),
-            };
-            if text.to_lowercase().contains(&lower) {
-                self.selection = idx;
-                break;
-            }
-        }
-        self.last_match = Some(query.to_string());
-    }
 }
 
 fn parse_makefile(content: &str) -> (Vec<(usize, String, MakeLine)>, Vec<String>) {
@@ -408,14 +499,14 @@ fn parse_makefile(content: &str) -> (Vec<(usize, String, MakeLine)>, Vec<String>
         }
 
         // Recipe line (starts with tab)
-        if line.starts_with('\t') {
-            lines.push((line_no, raw, MakeLine::Recipe(line[1..].to_string())));
+        if let Some(cmd) = line.strip_prefix('\t') {
+            lines.push((line_no, raw, MakeLine::Recipe(cmd.to_string())));
             continue;
         }
 
         // .PHONY
-        if trimmed.starts_with(".PHONY:") {
-            let targets: Vec<String> = trimmed[7..]
+        if let Some(rest) = trimmed.strip_prefix(".PHONY:") {
+            let targets: Vec<String> = rest
                 .split_whitespace()
                 .map(|s| s.to_string())
                 .collect();
@@ -443,38 +534,28 @@ fn parse_makefile(content: &str) -> (Vec<(usize, String, MakeLine)>, Vec<String>
             continue;
         }
 
-        // Variable assignment
-        let mut matched_var = false;
-        for op in &[":=", "?=", "+=", "="] {
-            if let Some(pos) = trimmed.find(op) {
-                let name = trimmed[..pos].trim().to_string();
-                let value = trimmed[pos + op.len()..].trim().to_string();
-                lines.push((line_no, raw.clone(), MakeLine::Variable {
-                    name,
-                    op: op.to_string(),
-                    value,
-                }));
-                matched_var = true;
-                break;
-            }
-        }
-        if matched_var {
+        // Variable assignment (parsed once; the value may contain colons)
+        if let Some((name, op, value)) = parse_assignment(trimmed) {
+            lines.push((line_no, raw, MakeLine::Variable {
+                name: name.to_string(),
+                op: op.to_string(),
+                value: value.to_string(),
+            }));
             continue;
         }
 
         // Target
         if let Some(colon_pos) = trimmed.find(':') {
-            // Check it's not a variable with ::
-            if !trimmed[colon_pos..].starts_with("::") || trimmed[colon_pos..].starts_with("::") {
-                let name = trimmed[..colon_pos].trim().to_string();
-                let deps: Vec<String> = trimmed[colon_pos + 1..]
-                    .split_whitespace()
-                    .map(|s| s.to_string())
-                    .collect();
-                let is_phony = phony_targets.contains(&name);
-                lines.push((line_no, raw, MakeLine::Target { name, deps, is_phony }));
-                continue;
-            }
+            let double_colon = trimmed[colon_pos..].starts_with("::");
+            let deps_start = colon_pos + if double_colon { 2 } else { 1 };
+            let name = trimmed[..colon_pos].trim().to_string();
+            let deps: Vec<String> = trimmed[deps_start..]
+                .split_whitespace()
+                .map(|s| s.to_string())
+                .collect();
+            let is_phony = phony_targets.contains(&name);
+            lines.push((line_no, raw, MakeLine::Target { name, deps, is_phony, double_colon }));
+            continue;
         }
 
         // Fallback
@@ -491,16 +572,48 @@ fn parse_makefile(content: &str) -> (Vec<(usize, String, MakeLine)>, Vec<String>
     (lines, phony_targets)
 }
 
-fn truncate(value: &str, max: usize) -> String {
-    if value.len() <= max {
-        return value.to_string();
+/// Split `NAME op VALUE` on the earliest assignment operator. Lines where a
+/// plain `:` comes first are rules (possibly with target-specific variables).
+fn parse_assignment(line: &str) -> Option<(&str, &str, &str)> {
+    let (pos, op) = ["::=", ":=", "?=", "+=", "="]
+        .iter()
+        .filter_map(|op| line.find(op).map(|pos| (pos, *op)))
+        .min_by_key(|(pos, _)| *pos)?;
+    let name = line[..pos].trim();
+    if name.is_empty() || name.contains(':') {
+        return None;
     }
-    let mut out = value.chars().take(max.saturating_sub(3)).collect::<String>();
-    out.push_str("...");
-    out
+    Some((name, op, line[pos + op.len()..].trim()))
 }
 
-fn page_jump(view_height: usize) -> usize {
-    let half = view_height / 2;
-    if half == 0 { 1 } else { half }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colon_in_variable_value_is_not_a_target() {
+        let (lines, _) = parse_makefile("CFLAGS := -I/a:/b\n");
+        assert_eq!(lines.len(), 1);
+        match &lines[0].2 {
+            MakeLine::Variable { name, op, value } => {
+                assert_eq!(name, "CFLAGS");
+                assert_eq!(op, ":=");
+                assert_eq!(value, "-I/a:/b");
+            }
+            _ => panic!("expected a variable"),
+        }
+    }
+
+    #[test]
+    fn double_colon_rules_are_flagged() {
+        let (lines, _) = parse_makefile("clean:: tmp\nbuild: src\n");
+        assert!(matches!(
+            &lines[0].2,
+            MakeLine::Target { name, deps, double_colon: true, .. } if name == "clean" && deps == &["tmp"]
+        ));
+        assert!(matches!(
+            &lines[1].2,
+            MakeLine::Target { name, double_colon: false, .. } if name == "build"
+        ));
+    }
 }
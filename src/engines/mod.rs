@@ -1,8 +1,15 @@
-use crossterm::event::KeyEvent;
-use ratatui::layout::Rect;
-use ratatui::text::Line;
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::layout::{Alignment, Rect};
+use ratatui::style::{Color, Style};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Wrap};
+use std::any::Any;
+use std::ops::{Deref, DerefMut};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use unicode_width::UnicodeWidthChar;
 
 mod archive;
+mod diff;
 mod dockerfile;
 mod env;
 mod gitignore;
@@ -15,14 +22,17 @@ mod lock;
 mod log;
 mod logic;
 mod makefile;
+mod picker;
 mod sqlite;
 mod syntax;
 mod table;
 mod text;
 mod tree;
+mod util;
 mod xml;
 
-pub use archive::ArchiveEngine;
+pub use archive::{compression_for_ext, decompressor, tar_compression, ArchiveEngine};
+pub use diff::{unified_diff, DiffEngine};
 pub use dockerfile::DockerfileEngine;
 pub use env::EnvEngine;
 pub use gitignore::GitIgnoreEngine;
@@ -31,415 +41,615 @@ pub use html::HtmlEngine;
 pub use image::ImageEngine;
 pub use ini::IniEngine;
 pub use jsonl::JsonlEngine;
-pub use lock::LockEngine;
+pub use lock::{is_requirements_file, LockEngine};
 pub use log::LogEngine;
 pub use logic::LogicEngine;
 pub use makefile::MakefileEngine;
+pub(crate) use picker::{ListPicker, PickerItem, PickerKey};
 pub use sqlite::SqliteEngine;
-pub use syntax::SyntaxEngine;
+pub use syntax::{modeline_language, SyntaxEngine};
 pub use table::TableEngine;
 pub use text::TextEngine;
 pub use tree::TreeEngine;
 pub use xml::XmlEngine;
 
-pub enum EngineState {
-    Tree(TreeEngine),
-    Table(TableEngine),
-    Logic(LogicEngine),
-    Syntax(SyntaxEngine),
-    Html(HtmlEngine),
-    Lock(LockEngine),
-    Jsonl(JsonlEngine),
-    Text(TextEngine),
-    Env(EnvEngine),
-    Ini(IniEngine),
-    Xml(XmlEngine),
-    Dockerfile(DockerfileEngine),
-    Makefile(MakefileEngine),
-    Log(LogEngine),
-    GitIgnore(GitIgnoreEngine),
-    Sqlite(SqliteEngine),
-    Archive(ArchiveEngine),
-    Image(ImageEngine),
-    Hex(HexEngine),
+/// Where `zt`/`zz`/`zb` put the selected row
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScrollAlign {
+    Top,
+    Center,
+    Bottom,
 }
 
-impl EngineState {
-    pub fn name(&self) -> &'static str {
-        match self {
-            EngineState::Tree(_) => "TreeEngine",
-            EngineState::Table(_) => "TableEngine",
-            EngineState::Logic(_) => "LogicEngine",
-            EngineState::Syntax(_) => "SyntaxEngine",
-            EngineState::Html(_) => "HtmlEngine",
-            EngineState::Lock(_) => "LockEngine",
-            EngineState::Jsonl(_) => "JsonlEngine",
-            EngineState::Text(_) => "TextEngine",
-            EngineState::Env(_) => "EnvEngine",
-            EngineState::Ini(_) => "IniEngine",
-            EngineState::Xml(_) => "XmlEngine",
-            EngineState::Dockerfile(_) => "DockerfileEngine",
-            EngineState::Makefile(_) => "MakefileEngine",
-            EngineState::Log(_) => "LogEngine",
-            EngineState::GitIgnore(_) => "GitIgnoreEngine",
-            EngineState::Sqlite(_) => "SqliteEngine",
-            EngineState::Archive(_) => "ArchiveEngine",
-            EngineState::Image(_) => "ImageEngine",
-            EngineState::Hex(_) => "HexEngine",
-        }
+/// A file view. Each engine implements this once and the analyzer picks which
+/// one to build; the app only talks to it through `EngineState`.
+pub trait Engine: AsAny {
+    /// Shown in the help overlay title
+    fn name(&self) -> &'static str;
+
+    fn render(&mut self, frame: &mut ratatui::Frame, area: Rect);
+
+    /// Implementations keep one arm per key with its bounds check inside the
+    /// arm rather than in a guard, so a blocked key never falls through to
+    /// another arm; they allow `clippy::collapsible_match` for that.
+    fn handle_key(&mut self, key: KeyEvent);
+
+    fn breadcrumbs(&self) -> String;
+
+    fn status_line(&self) -> String;
+
+    /// Engine-specific key help shown below the common keys in the `?` overlay
+    fn help_lines(&self) -> Vec<Line<'static>> {
+        Vec::new()
     }
 
-    pub fn breadcrumbs(&self) -> String {
-        match self {
-            EngineState::Tree(engine) => engine.breadcrumbs(),
-            EngineState::Table(engine) => engine.breadcrumbs(),
-            EngineState::Logic(engine) => engine.breadcrumbs(),
-            EngineState::Syntax(engine) => engine.breadcrumbs(),
-            EngineState::Html(engine) => engine.breadcrumbs(),
-            EngineState::Lock(engine) => engine.breadcrumbs(),
-            EngineState::Jsonl(engine) => engine.breadcrumbs(),
-            EngineState::Text(engine) => engine.breadcrumbs(),
-            EngineState::Env(engine) => engine.breadcrumbs(),
-            EngineState::Ini(engine) => engine.breadcrumbs(),
-            EngineState::Xml(engine) => engine.breadcrumbs(),
-            EngineState::Dockerfile(engine) => engine.breadcrumbs(),
-            EngineState::Makefile(engine) => engine.breadcrumbs(),
-            EngineState::Log(engine) => engine.breadcrumbs(),
-            EngineState::GitIgnore(engine) => engine.breadcrumbs(),
-            EngineState::Sqlite(engine) => engine.breadcrumbs(),
-            EngineState::Archive(engine) => engine.breadcrumbs(),
-            EngineState::Image(engine) => engine.breadcrumbs(),
-            EngineState::Hex(engine) => engine.breadcrumbs(),
-        }
+    /// Rows highlighted while visual mode is active
+    fn set_visual_range(&mut self, _range: Option<(usize, usize)>) {}
+
+    /// Wrap and tab settings, for views that lay out lines of text
+    fn set_layout(&mut self, _layout: TextLayout) {}
+
+    fn supports_search(&self) -> bool {
+        true
     }
 
-    pub fn status_line(&self) -> String {
-        match self {
-            EngineState::Tree(engine) => engine.status_line(),
-            EngineState::Table(engine) => engine.status_line(),
-            EngineState::Logic(engine) => engine.status_line(),
-            EngineState::Syntax(engine) => engine.status_line(),
-            EngineState::Html(engine) => engine.status_line(),
-            EngineState::Lock(engine) => engine.status_line(),
-            EngineState::Jsonl(engine) => engine.status_line(),
-            EngineState::Text(engine) => engine.status_line(),
-            EngineState::Env(engine) => engine.status_line(),
-            EngineState::Ini(engine) => engine.status_line(),
-            EngineState::Xml(engine) => engine.status_line(),
-            EngineState::Dockerfile(engine) => engine.status_line(),
-            EngineState::Makefile(engine) => engine.status_line(),
-            EngineState::Log(engine) => engine.status_line(),
-            EngineState::GitIgnore(engine) => engine.status_line(),
-            EngineState::Sqlite(engine) => engine.status_line(),
-            EngineState::Archive(engine) => engine.status_line(),
-            EngineState::Image(engine) => engine.status_line(),
-            EngineState::Hex(engine) => engine.status_line(),
-        }
+    /// Jump to the next match after the selection, or the previous one before it
+    /// when `forward` is false; false when nothing matches. `\<word\>` matches
+    /// whole words only
+    fn apply_search(&mut self, _query: &str, _forward: bool) -> bool {
+        false
     }
 
-    /// Set visual selection range for highlighting
-    pub fn set_visual_range(&mut self, range: Option<(usize, usize)>) {
-        match self {
-            EngineState::Tree(engine) => engine.visual_range = range,
-            EngineState::Table(engine) => engine.visual_range = range,
-            EngineState::Logic(engine) => engine.visual_range = range,
-            EngineState::Syntax(engine) => engine.visual_range = range,
-            EngineState::Html(engine) => engine.visual_range = range,
-            EngineState::Lock(engine) => engine.visual_range = range,
-            EngineState::Jsonl(engine) => engine.visual_range = range,
-            EngineState::Text(engine) => engine.visual_range = range,
-            EngineState::Env(engine) => engine.visual_range = range,
-            EngineState::Ini(engine) => engine.visual_range = range,
-            EngineState::Xml(engine) => engine.visual_range = range,
-            EngineState::Dockerfile(engine) => engine.visual_range = range,
-            EngineState::Makefile(engine) => engine.visual_range = range,
-            EngineState::Log(engine) => engine.visual_range = range,
-            EngineState::GitIgnore(engine) => engine.visual_range = range,
-            EngineState::Sqlite(engine) => engine.visual_range = range,
-            EngineState::Archive(engine) => engine.visual_range = range,
-            EngineState::Image(engine) => engine.visual_range = range,
-            EngineState::Hex(engine) => engine.visual_range = range,
-        }
+    /// Views without a filter of their own treat it as a search
+    fn apply_filter(&mut self, query: &str) -> bool {
+        self.apply_search(query, true)
+    }
+
+    fn clear_filter(&mut self) {}
+
+    /// Whether the view has its own prompt open (e.g. the symbol picker) that should
+    /// receive every key, including the ones the app would otherwise handle
+    fn captures_input(&self) -> bool {
+        false
+    }
+
+    /// Copyable location of the selection (a key path, XPath, archive entry...), copied by `y`
+    fn selected_path(&self) -> Option<String> {
+        None
+    }
+
+    /// Column of the cell cursor (a table column, a byte within a hex row), for views
+    /// that support visual block selection
+    fn block_column(&self) -> Option<usize> {
+        None
+    }
+
+    /// Columns highlighted within the visual rows; None for line-wise selection
+    fn set_visual_columns(&mut self, _columns: Option<(usize, usize)>) {}
+
+    /// Cells in `rows` × `columns` (both inclusive), one row per line; copied by
+    /// `y` in visual block mode
+    fn get_block(&self, _rows: (usize, usize), _columns: (usize, usize)) -> Option<String> {
+        None
+    }
+
+    /// Rows `start..=end` as a markdown table, for views that show one; copied by `Y`
+    fn markdown_table(&self, _start: usize, _end: usize) -> Option<String> {
+        None
+    }
+
+    /// Get the content of the currently selected line/row
+    fn get_selected_line(&self) -> Option<String>;
+
+    /// Get lines in a range (inclusive), joined by newlines
+    fn get_lines_range(&self, start: usize, end: usize) -> Option<String>;
+
+    /// 1-based line in the file shown at display `row`, for views that show
+    /// the file line by line; None where rows don't map back to source lines
+    fn source_line(&self, _row: usize) -> Option<usize> {
+        None
+    }
+
+    /// Get current selection index (for visual mode)
+    fn selection(&self) -> usize;
+
+    /// Select row `row`, or the last one past the end; the next render scrolls to it
+    fn select(&mut self, row: usize);
+
+    /// Char index of the column cursor on the selected line, for views that have one
+    fn cursor_col(&self) -> Option<usize> {
+        None
+    }
+
+    /// Scroll so the selection sits at the top, middle or bottom of the view (`zt`/`zz`/`zb`)
+    fn align_scroll(&mut self, align: ScrollAlign);
+
+    /// Put row `row` at the top of the view; views that only follow the selection ignore it
+    fn set_scroll(&mut self, _row: usize) {}
+
+    /// Columns scrolled off the left while wrapping is off (`zh`/`zl`); 0 in views
+    /// that don't scroll sideways
+    fn h_scroll(&self) -> usize {
+        0
+    }
+
+    fn set_h_scroll(&mut self, _cols: usize) {}
+
+    fn content_height(&mut self) -> usize;
+
+    /// Whether the whole content fits in `rows` lines
+    fn fits_height(&mut self, rows: usize) -> bool {
+        self.content_height() <= rows
+    }
+
+    /// The whole view as lines for plain (non-interactive) output
+    fn render_plain_lines(&mut self, width: u16) -> Vec<Line<'static>>;
+
+    /// Columns taken by the widest line, used to stop horizontal scrolling there.
+    /// Asked on every `zh`/`zl`, so views that scroll sideways keep it cached.
+    fn content_width(&mut self) -> usize {
+        0
+    }
+
+    /// Folded nodes worth remembering between runs; None for views without folding
+    fn folds(&self) -> Option<Vec<String>> {
+        None
+    }
+
+    /// Re-apply folds saved from an earlier run
+    fn restore_folds(&mut self, _folds: &[String]) {}
+
+    /// Take more input that arrived after the engine was built (streamed stdin);
+    /// views that parse the whole file up front ignore it
+    fn append(&mut self, _bytes: &[u8]) {}
+
+    /// What the engine parsed, as JSON for `--dump`
+    fn to_json(&mut self) -> serde_json::Value;
+}
+
+/// Hands back the concrete engine for the few calls only one view supports
+/// (comparing `.env` files, the tree's starting depth)
+pub trait AsAny: Any {
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+}
+
+impl<T: Any> AsAny for T {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+}
+
+/// The engine viewing the current file. Every call goes straight to its
+/// `Engine` impl, so a new view needs no changes here
+pub struct EngineState(Box<dyn Engine>);
+
+impl EngineState {
+    pub fn new(engine: impl Engine + 'static) -> Self {
+        Self(Box::new(engine))
+    }
+
+    /// The engine as `T`, if that's the view being shown
+    pub fn downcast_mut<T: Engine>(&mut self) -> Option<&mut T> {
+        // Through the trait object: the box itself is `Any` too
+        self.0.as_mut().as_any_mut().downcast_mut()
     }
 
     pub fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
-        match self {
-            EngineState::Tree(engine) => engine.render(frame, area),
-            EngineState::Table(engine) => engine.render(frame, area),
-            EngineState::Logic(engine) => engine.render(frame, area),
-            EngineState::Syntax(engine) => engine.render(frame, area),
-            EngineState::Html(engine) => engine.render(frame, area),
-            EngineState::Lock(engine) => engine.render(frame, area),
-            EngineState::Jsonl(engine) => engine.render(frame, area),
-            EngineState::Text(engine) => engine.render(frame, area),
-            EngineState::Env(engine) => engine.render(frame, area),
-            EngineState::Ini(engine) => engine.render(frame, area),
-            EngineState::Xml(engine) => engine.render(frame, area),
-            EngineState::Dockerfile(engine) => engine.render(frame, area),
-            EngineState::Makefile(engine) => engine.render(frame, area),
-            EngineState::Log(engine) => engine.render(frame, area),
-            EngineState::GitIgnore(engine) => engine.render(frame, area),
-            EngineState::Sqlite(engine) => engine.render(frame, area),
-            EngineState::Archive(engine) => engine.render(frame, area),
-            EngineState::Image(engine) => engine.render(frame, area),
-            EngineState::Hex(engine) => engine.render(frame, area),
+        // Tiny terminals (or a resize in progress) can hand us an empty area
+        if area.width == 0 || area.height == 0 {
+            return;
         }
+        self.0.render(frame, area);
     }
 
-    pub fn handle_key(&mut self, key: KeyEvent) {
-        match self {
-            EngineState::Tree(engine) => engine.handle_key(key),
-            EngineState::Table(engine) => engine.handle_key(key),
-            EngineState::Logic(engine) => engine.handle_key(key),
-            EngineState::Syntax(engine) => engine.handle_key(key),
-            EngineState::Html(engine) => engine.handle_key(key),
-            EngineState::Lock(engine) => engine.handle_key(key),
-            EngineState::Jsonl(engine) => engine.handle_key(key),
-            EngineState::Text(engine) => engine.handle_key(key),
-            EngineState::Env(engine) => engine.handle_key(key),
-            EngineState::Ini(engine) => engine.handle_key(key),
-            EngineState::Xml(engine) => engine.handle_key(key),
-            EngineState::Dockerfile(engine) => engine.handle_key(key),
-            EngineState::Makefile(engine) => engine.handle_key(key),
-            EngineState::Log(engine) => engine.handle_key(key),
-            EngineState::GitIgnore(engine) => engine.handle_key(key),
-            EngineState::Sqlite(engine) => engine.handle_key(key),
-            EngineState::Archive(engine) => engine.handle_key(key),
-            EngineState::Image(engine) => engine.handle_key(key),
-            EngineState::Hex(engine) => engine.handle_key(key),
-        }
+    /// Select `line` (0-based) with it at the top of the view (`+N`, `:N`)
+    pub fn jump_to_line(&mut self, line: usize) {
+        self.select(line);
+        self.align_scroll(ScrollAlign::Top);
     }
 
-    pub fn supports_search(&self) -> bool {
-        matches!(
-            self,
-            EngineState::Tree(_)
-                | EngineState::Syntax(_)
-                | EngineState::Logic(_)
-                | EngineState::Table(_)
-                | EngineState::Html(_)
-                | EngineState::Lock(_)
-                | EngineState::Jsonl(_)
-                | EngineState::Text(_)
-                | EngineState::Env(_)
-                | EngineState::Ini(_)
-                | EngineState::Xml(_)
-                | EngineState::Dockerfile(_)
-                | EngineState::Makefile(_)
-                | EngineState::Log(_)
-                | EngineState::GitIgnore(_)
-                | EngineState::Sqlite(_)
-                | EngineState::Archive(_)
-                | EngineState::Image(_)
-                | EngineState::Hex(_)
-        )
-    }
-
-    pub fn apply_search(&mut self, query: &str) {
-        match self {
-            EngineState::Tree(engine) => engine.apply_search(query),
-            EngineState::Syntax(engine) => engine.apply_search(query),
-            EngineState::Logic(engine) => engine.apply_search(query),
-            EngineState::Table(engine) => engine.apply_search(query),
-            EngineState::Html(engine) => engine.apply_search(query),
-            EngineState::Lock(engine) => engine.apply_search(query),
-            EngineState::Jsonl(engine) => engine.apply_search(query),
-            EngineState::Text(engine) => engine.apply_search(query),
-            EngineState::Env(engine) => engine.apply_search(query),
-            EngineState::Ini(engine) => engine.apply_search(query),
-            EngineState::Xml(engine) => engine.apply_search(query),
-            EngineState::Dockerfile(engine) => engine.apply_search(query),
-            EngineState::Makefile(engine) => engine.apply_search(query),
-            EngineState::Log(engine) => engine.apply_search(query),
-            EngineState::GitIgnore(engine) => engine.apply_search(query),
-            EngineState::Sqlite(engine) => engine.apply_search(query),
-            EngineState::Archive(engine) => engine.apply_search(query),
-            EngineState::Image(engine) => engine.apply_search(query),
-            EngineState::Hex(engine) => engine.apply_search(query),
-        }
+    /// Select the last line with the last `lines` lines in view (`--tail`)
+    pub fn tail(&mut self, lines: usize) {
+        self.handle_key(KeyEvent::new(KeyCode::Char('G'), KeyModifiers::NONE));
+        let total = self.content_height();
+        self.set_scroll(total.saturating_sub(lines));
     }
+}
 
-    pub fn apply_filter(&mut self, query: &str) {
-        match self {
-            EngineState::Tree(engine) => engine.apply_filter(query),
-            EngineState::Syntax(engine) => engine.apply_filter(query),
-            EngineState::Logic(engine) => engine.apply_filter(query),
-            EngineState::Table(engine) => engine.apply_filter(query),
-            EngineState::Html(engine) => engine.apply_filter(query),
-            EngineState::Lock(engine) => engine.apply_filter(query),
-            EngineState::Jsonl(engine) => engine.apply_filter(query),
-            EngineState::Text(engine) => engine.apply_filter(query),
-            EngineState::Env(engine) => engine.apply_filter(query),
-            EngineState::Ini(engine) => engine.apply_filter(query),
-            EngineState::Xml(engine) => engine.apply_filter(query),
-            EngineState::Dockerfile(engine) => engine.apply_filter(query),
-            EngineState::Makefile(engine) => engine.apply_filter(query),
-            EngineState::Log(engine) => engine.apply_filter(query),
-            EngineState::GitIgnore(engine) => engine.apply_filter(query),
-            EngineState::Sqlite(engine) => engine.apply_filter(query),
-            EngineState::Archive(engine) => engine.apply_filter(query),
-            EngineState::Image(engine) => engine.apply_filter(query),
-            EngineState::Hex(engine) => engine.apply_filter(query),
-        }
+impl Deref for EngineState {
+    type Target = dyn Engine;
+
+    fn deref(&self) -> &Self::Target {
+        self.0.as_ref()
     }
+}
 
-    pub fn clear_filter(&mut self) {
-        match self {
-            EngineState::Tree(engine) => engine.clear_filter(),
-            EngineState::Syntax(engine) => engine.clear_filter(),
-            EngineState::Logic(engine) => engine.clear_filter(),
-            EngineState::Table(engine) => engine.clear_filter(),
-            EngineState::Html(engine) => engine.clear_filter(),
-            EngineState::Lock(engine) => engine.clear_filter(),
-            EngineState::Jsonl(engine) => engine.clear_filter(),
-            EngineState::Text(engine) => engine.clear_filter(),
-            EngineState::Env(engine) => engine.clear_filter(),
-            EngineState::Ini(engine) => engine.clear_filter(),
-            EngineState::Xml(engine) => engine.clear_filter(),
-            EngineState::Dockerfile(engine) => engine.clear_filter(),
-            EngineState::Makefile(engine) => engine.clear_filter(),
-            EngineState::Log(engine) => engine.clear_filter(),
-            EngineState::GitIgnore(engine) => engine.clear_filter(),
-            EngineState::Sqlite(engine) => engine.clear_filter(),
-            EngineState::Archive(engine) => engine.clear_filter(),
-            EngineState::Image(engine) => engine.clear_filter(),
-            EngineState::Hex(engine) => engine.clear_filter(),
-        }
+impl DerefMut for EngineState {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.0.as_mut()
     }
+}
 
-    #[allow(dead_code)]
-    pub fn selected_path(&self) -> Option<String> {
-        match self {
-            EngineState::Tree(engine) => engine.selected_path(),
-            EngineState::Archive(engine) => engine.selected_path(),
-            EngineState::Sqlite(engine) => engine.selected_path(),
-            EngineState::GitIgnore(engine) => engine.selected_path(),
-            EngineState::Image(engine) => engine.selected_path(),
-            EngineState::Hex(engine) => engine.selected_path(),
-            _ => None,
-        }
+/// Percent of the view `Ctrl+u`/`Ctrl+d` move (`half_page` in the config)
+static PAGE_PERCENT: AtomicUsize = AtomicUsize::new(50);
+
+thread_local! {
+    /// Set while `Ctrl+f`/`Ctrl+b` are replayed to the engine as `Ctrl+d`/`Ctrl+u`;
+    /// scoped to the key being handled rather than shared across the process
+    static FULL_PAGE: std::cell::Cell<bool> = const { std::cell::Cell::new(false) };
+}
+
+/// Share of the view a half-page jump covers, from a tenth up to all of it
+pub fn set_page_fraction(fraction: f64) {
+    let percent = (fraction.clamp(0.1, 1.0) * 100.0).round() as usize;
+    PAGE_PERCENT.store(percent, Ordering::Relaxed);
+}
+
+/// Run `f` with page jumps covering the whole view, so `Ctrl+f`/`Ctrl+b` reuse
+/// every engine's `Ctrl+d`/`Ctrl+u` handling
+pub fn full_page<T>(f: impl FnOnce() -> T) -> T {
+    let outer = FULL_PAGE.replace(true);
+    let result = f();
+    FULL_PAGE.set(outer);
+    result
+}
+
+/// Rows moved by `Ctrl+u`/`Ctrl+d` (half the view by default) or by `Ctrl+b`/`Ctrl+f`
+/// (all of it), at least one
+pub fn page_jump(view_height: usize) -> usize {
+    let percent = if FULL_PAGE.get() { 100 } else { PAGE_PERCENT.load(Ordering::Relaxed) };
+    (view_height * percent / 100).max(1)
+}
+
+/// Selection after `Ctrl+u`, stopping at the first row
+pub fn half_page_up(selection: usize, view_height: usize) -> usize {
+    selection.saturating_sub(page_jump(view_height))
+}
+
+/// Selection after `Ctrl+d`, stopping at the last of `total` rows
+pub fn half_page_down(selection: usize, view_height: usize, total: usize) -> usize {
+    (selection + page_jump(view_height)).min(total.saturating_sub(1))
+}
+
+/// Rows of context kept above and below the selection (`scroll_off` in the config)
+static SCROLL_OFF: AtomicUsize = AtomicUsize::new(0);
+
+pub fn set_scroll_off(rows: usize) {
+    SCROLL_OFF.store(rows, Ordering::Relaxed);
+}
+
+/// Characters a value preview keeps before it's cut with "..." (`preview_len` in the
+/// config); 0 leaves each view its own default
+static PREVIEW_LEN: AtomicUsize = AtomicUsize::new(0);
+
+pub fn set_preview_len(chars: usize) {
+    PREVIEW_LEN.store(chars, Ordering::Relaxed);
+}
+
+/// The configured preview length, or the view's `default` when none is set
+pub(crate) fn preview_len(default: usize) -> usize {
+    match PREVIEW_LEN.load(Ordering::Relaxed) {
+        0 => default,
+        chars => chars,
     }
+}
 
-    /// Get the content of the currently selected line/row
-    pub fn get_selected_line(&self) -> Option<String> {
-        match self {
-            EngineState::Text(engine) => engine.get_selected_line(),
-            EngineState::Syntax(engine) => engine.get_selected_line(),
-            EngineState::Tree(engine) => engine.get_selected_line(),
-            EngineState::Table(engine) => engine.get_selected_line(),
-            EngineState::Logic(engine) => engine.get_selected_line(),
-            EngineState::Html(engine) => engine.get_selected_line(),
-            EngineState::Lock(engine) => engine.get_selected_line(),
-            EngineState::Jsonl(engine) => engine.get_selected_line(),
-            EngineState::Env(engine) => engine.get_selected_line(),
-            EngineState::Ini(engine) => engine.get_selected_line(),
-            EngineState::Xml(engine) => engine.get_selected_line(),
-            EngineState::Dockerfile(engine) => engine.get_selected_line(),
-            EngineState::Makefile(engine) => engine.get_selected_line(),
-            EngineState::Log(engine) => engine.get_selected_line(),
-            EngineState::GitIgnore(engine) => engine.get_selected_line(),
-            EngineState::Sqlite(engine) => engine.get_selected_line(),
-            EngineState::Archive(engine) => engine.get_selected_line(),
-            EngineState::Image(engine) => engine.get_selected_line(),
-            EngineState::Hex(engine) => engine.get_selected_line(),
-        }
+/// Whether line-based views draw the `N │ ` gutter (`line_numbers` in the config, `Ctrl+g`)
+static LINE_NUMBERS: AtomicBool = AtomicBool::new(true);
+
+pub fn set_line_numbers(show: bool) {
+    LINE_NUMBERS.store(show, Ordering::Relaxed);
+}
+
+pub fn line_numbers() -> bool {
+    LINE_NUMBERS.load(Ordering::Relaxed)
+}
+
+/// Whether the gutter counts lines from the selection instead of from the top
+/// (`--line-numbers relative`), like vim's `relativenumber`
+static RELATIVE_NUMBERS: AtomicBool = AtomicBool::new(false);
+
+pub fn set_relative_numbers(relative: bool) {
+    RELATIVE_NUMBERS.store(relative, Ordering::Relaxed);
+}
+
+/// Number drawn in the gutter for display row `row`, whose 1-based line number is
+/// `line_no`: the distance to `selection` in relative mode, where the selected
+/// line keeps its own number
+pub(crate) fn gutter_number(line_no: usize, row: usize, selection: usize) -> usize {
+    if RELATIVE_NUMBERS.load(Ordering::Relaxed) && row != selection {
+        row.abs_diff(selection)
+    } else {
+        line_no
     }
+}
 
-    /// Get lines in a range (inclusive), joined by newlines
-    pub fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
-        match self {
-            EngineState::Text(engine) => engine.get_lines_range(start, end),
-            EngineState::Syntax(engine) => engine.get_lines_range(start, end),
-            EngineState::Tree(engine) => engine.get_lines_range(start, end),
-            EngineState::Table(engine) => engine.get_lines_range(start, end),
-            EngineState::Logic(engine) => engine.get_lines_range(start, end),
-            EngineState::Html(engine) => engine.get_lines_range(start, end),
-            EngineState::Lock(engine) => engine.get_lines_range(start, end),
-            EngineState::Jsonl(engine) => engine.get_lines_range(start, end),
-            EngineState::Env(engine) => engine.get_lines_range(start, end),
-            EngineState::Ini(engine) => engine.get_lines_range(start, end),
-            EngineState::Xml(engine) => engine.get_lines_range(start, end),
-            EngineState::Dockerfile(engine) => engine.get_lines_range(start, end),
-            EngineState::Makefile(engine) => engine.get_lines_range(start, end),
-            EngineState::Log(engine) => engine.get_lines_range(start, end),
-            EngineState::GitIgnore(engine) => engine.get_lines_range(start, end),
-            EngineState::Sqlite(engine) => engine.get_lines_range(start, end),
-            EngineState::Archive(engine) => engine.get_lines_range(start, end),
-            EngineState::Image(engine) => engine.get_lines_range(start, end),
-            EngineState::Hex(engine) => engine.get_lines_range(start, end),
+/// Whether text and log views color lines by their embedded ANSI codes (`--ansi`);
+/// otherwise escape sequences are dropped so they can't garble the screen
+static ANSI: AtomicBool = AtomicBool::new(false);
+
+pub fn set_ansi(ansi: bool) {
+    ANSI.store(ansi, Ordering::Relaxed);
+}
+
+pub fn ansi() -> bool {
+    ANSI.load(Ordering::Relaxed)
+}
+
+/// How text and code views lay out their lines, held by `App` and handed to the engine
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextLayout {
+    /// Wrap long lines instead of cutting them off (`--wrap`, `w`)
+    pub wrap: bool,
+    /// Columns between tab stops (`--tabs`)
+    pub tab_width: usize,
+}
+
+impl Default for TextLayout {
+    fn default() -> Self {
+        Self { wrap: false, tab_width: 4 }
+    }
+}
+
+/// Replace tabs in `spans` with spaces up to the next `tab_width` stop, counting
+/// columns from the start of the first span
+pub(crate) fn expand_tabs(spans: Vec<Span<'static>>, tab_width: usize) -> Vec<Span<'static>> {
+    let tab_width = tab_width.max(1);
+    let mut col = 0;
+    spans
+        .into_iter()
+        .map(|span| {
+            if !span.content.contains('\t') {
+                col += span.width();
+                return span;
+            }
+            let mut expanded = String::with_capacity(span.content.len());
+            for c in span.content.chars() {
+                if c == '\t' {
+                    let pad = tab_width - col % tab_width;
+                    expanded.push_str(&" ".repeat(pad));
+                    col += pad;
+                } else {
+                    expanded.push(c);
+                    col += c.width().unwrap_or(0);
+                }
+            }
+            Span::styled(expanded, span.style)
+        })
+        .collect()
+}
+
+/// Split `line` into rows at most `width` columns wide. Continuation rows start with
+/// `indent` blank columns so wrapped text stays clear of the line-number gutter.
+pub(crate) fn wrap_line(line: Line<'static>, width: usize, indent: usize) -> Vec<Line<'static>> {
+    let width = width.max(indent + 1);
+    let mut rows = Vec::new();
+    let mut current = Vec::new();
+    let mut col = 0;
+    for span in line.spans {
+        let mut chunk = String::new();
+        for c in span.content.chars() {
+            let char_width = c.width().unwrap_or(0);
+            if col + char_width > width && col > indent {
+                if !chunk.is_empty() {
+                    current.push(Span::styled(std::mem::take(&mut chunk), span.style));
+                }
+                rows.push(Line::from(std::mem::take(&mut current)).style(line.style));
+                current.push(Span::raw(" ".repeat(indent)));
+                col = indent;
+            }
+            chunk.push(c);
+            col += char_width;
+        }
+        if !chunk.is_empty() {
+            current.push(Span::styled(chunk, span.style));
         }
     }
+    rows.push(Line::from(current).style(line.style));
+    rows
+}
 
-    /// Get current selection index (for visual mode)
-    pub fn selection(&self) -> usize {
-        match self {
-            EngineState::Text(engine) => engine.selection(),
-            EngineState::Syntax(engine) => engine.selection(),
-            EngineState::Tree(engine) => engine.selection(),
-            EngineState::Table(engine) => engine.selection(),
-            EngineState::Logic(engine) => engine.selection(),
-            EngineState::Html(engine) => engine.selection(),
-            EngineState::Lock(engine) => engine.selection(),
-            EngineState::Jsonl(engine) => engine.selection(),
-            EngineState::Env(engine) => engine.selection(),
-            EngineState::Ini(engine) => engine.selection(),
-            EngineState::Xml(engine) => engine.selection(),
-            EngineState::Dockerfile(engine) => engine.selection(),
-            EngineState::Makefile(engine) => engine.selection(),
-            EngineState::Log(engine) => engine.selection(),
-            EngineState::GitIgnore(engine) => engine.selection(),
-            EngineState::Sqlite(engine) => engine.selection(),
-            EngineState::Archive(engine) => engine.selection(),
-            EngineState::Image(engine) => engine.selection(),
-            EngineState::Hex(engine) => engine.selection(),
+/// Drop `cols` columns from `line` after its first `keep` (the line-number gutter),
+/// for horizontally scrolled views
+pub(crate) fn shift_line(line: Line<'static>, cols: usize, keep: usize) -> Line<'static> {
+    if cols == 0 {
+        return line;
+    }
+    let mut spans = Vec::new();
+    let mut col = 0;
+    for span in line.spans {
+        let mut kept = String::new();
+        for c in span.content.chars() {
+            let char_width = c.width().unwrap_or(0);
+            if col < keep || col >= keep + cols {
+                kept.push(c);
+            } else if col + char_width > keep + cols {
+                // A wide glyph cut in half: pad its visible part so columns stay aligned
+                kept.push_str(&" ".repeat(col + char_width - keep - cols));
+            }
+            col += char_width;
+        }
+        if !kept.is_empty() {
+            spans.push(Span::styled(kept, span.style));
         }
     }
+    Line::from(spans).style(line.style)
+}
 
-    pub fn content_height(&mut self) -> usize {
-        match self {
-            EngineState::Tree(engine) => engine.content_height(),
-            EngineState::Table(engine) => engine.content_height(),
-            EngineState::Logic(engine) => engine.content_height(),
-            EngineState::Syntax(engine) => engine.content_height(),
-            EngineState::Html(engine) => engine.content_height(),
-            EngineState::Lock(engine) => engine.content_height(),
-            EngineState::Jsonl(engine) => engine.content_height(),
-            EngineState::Text(engine) => engine.content_height(),
-            EngineState::Env(engine) => engine.content_height(),
-            EngineState::Ini(engine) => engine.content_height(),
-            EngineState::Xml(engine) => engine.content_height(),
-            EngineState::Dockerfile(engine) => engine.content_height(),
-            EngineState::Makefile(engine) => engine.content_height(),
-            EngineState::Log(engine) => engine.content_height(),
-            EngineState::GitIgnore(engine) => engine.content_height(),
-            EngineState::Sqlite(engine) => engine.content_height(),
-            EngineState::Archive(engine) => engine.content_height(),
-            EngineState::Image(engine) => engine.content_height(),
-            EngineState::Hex(engine) => engine.content_height(),
+/// Give the character at `col` (counted across `spans`) its own `style`
+pub(crate) fn restyle_char(spans: Vec<Span<'static>>, col: usize, style: Style) -> Vec<Span<'static>> {
+    let mut out = Vec::new();
+    let mut start = 0;
+    for span in spans {
+        let len = span.content.chars().count();
+        if (start..start + len).contains(&col) {
+            let chars: Vec<char> = span.content.chars().collect();
+            let split = col - start;
+            let before: String = chars[..split].iter().collect();
+            let after: String = chars[split + 1..].iter().collect();
+            if !before.is_empty() {
+                out.push(Span::styled(before, span.style));
+            }
+            out.push(Span::styled(chars[split].to_string(), span.style.patch(style)));
+            if !after.is_empty() {
+                out.push(Span::styled(after, span.style));
+            }
+        } else {
+            out.push(span);
         }
+        start += len;
     }
+    out
+}
 
-    pub fn render_plain_lines(&mut self, width: u16) -> Vec<Line<'static>> {
-        match self {
-            EngineState::Tree(engine) => engine.render_plain_lines(),
-            EngineState::Table(engine) => engine.render_plain_lines(width),
-            EngineState::Logic(engine) => engine.render_plain_lines(),
-            EngineState::Syntax(engine) => engine.render_plain_lines(),
-            EngineState::Html(engine) => engine.render_plain_lines(width),
-            EngineState::Lock(engine) => engine.render_plain_lines(width),
-            EngineState::Jsonl(engine) => engine.render_plain_lines(width),
-            EngineState::Text(engine) => engine.render_plain_lines(width),
-            EngineState::Env(engine) => engine.render_plain_lines(width),
-            EngineState::Ini(engine) => engine.render_plain_lines(width),
-            EngineState::Xml(engine) => engine.render_plain_lines(width),
-            EngineState::Dockerfile(engine) => engine.render_plain_lines(width),
-            EngineState::Makefile(engine) => engine.render_plain_lines(width),
-            EngineState::Log(engine) => engine.render_plain_lines(width),
-            EngineState::GitIgnore(engine) => engine.render_plain_lines(width),
-            EngineState::Sqlite(engine) => engine.render_plain_lines(width),
-            EngineState::Archive(engine) => engine.render_plain_lines(width),
-            EngineState::Image(engine) => engine.render_plain_lines(width),
-            EngineState::Hex(engine) => engine.render_plain_lines(width),
+/// Where a column-cursor key moves the cursor on `line`: `h`/`l` by a character,
+/// `0`/`$` to either end, `w`/`b` to the next/previous word. None for other keys.
+pub(crate) fn move_cursor(line: &str, col: usize, key: KeyCode) -> Option<usize> {
+    let chars: Vec<char> = line.chars().collect();
+    let last = chars.len().saturating_sub(1);
+    let col = col.min(last);
+    // Words are runs of identifier characters or of punctuation, split by whitespace
+    let class = |c: char| {
+        if c.is_whitespace() {
+            0
+        } else if c.is_alphanumeric() || c == '_' {
+            1
+        } else {
+            2
+        }
+    };
+    Some(match key {
+        KeyCode::Char('h') | KeyCode::Left => col.saturating_sub(1),
+        KeyCode::Char('l') | KeyCode::Right => (col + 1).min(last),
+        KeyCode::Char('0') | KeyCode::Home => 0,
+        KeyCode::Char('$') | KeyCode::End => last,
+        KeyCode::Char('w') => {
+            let mut next = col;
+            if let Some(&c) = chars.get(col) {
+                while next < chars.len() && class(chars[next]) == class(c) && class(c) != 0 {
+                    next += 1;
+                }
+            }
+            while next < chars.len() && class(chars[next]) == 0 {
+                next += 1;
+            }
+            next.min(last)
         }
+        KeyCode::Char('b') => {
+            let mut prev = col;
+            while prev > 0 && class(chars[prev - 1]) == 0 {
+                prev -= 1;
+            }
+            if prev > 0 {
+                let word = class(chars[prev - 1]);
+                while prev > 0 && class(chars[prev - 1]) == word {
+                    prev -= 1;
+                }
+            }
+            prev
+        }
+        _ => return None,
+    })
+}
+
+/// Columns taken by the first `col` chars of `line` once tabs are expanded
+pub(crate) fn display_col(line: &str, col: usize, tab_width: usize) -> usize {
+    let prefix: String = line.chars().take(col).collect();
+    expand_tabs(vec![Span::raw(prefix)], tab_width).iter().map(Span::width).sum()
+}
+
+/// Horizontal scroll, from `scroll`, that keeps the cursor at display column `col`
+/// inside a view `width` columns wide
+pub(crate) fn follow_cursor(scroll: usize, col: usize, width: usize) -> usize {
+    if col < scroll {
+        col
+    } else if width > 0 && col >= scroll + width {
+        col + 1 - width
+    } else {
+        scroll
     }
 }
+
+/// Scroll offset that keeps `selection` visible in a `height`-row view of `total` rows,
+/// with `scroll_off` rows of context on either side where the view is tall enough
+pub(crate) fn follow_selection(scroll: usize, selection: usize, height: usize, total: usize) -> usize {
+    let margin = SCROLL_OFF.load(Ordering::Relaxed).min(height.saturating_sub(1) / 2);
+    let scroll = if selection < scroll + margin {
+        selection.saturating_sub(margin)
+    } else if selection + margin >= scroll + height {
+        (selection + margin).saturating_sub(height.saturating_sub(1))
+    } else {
+        scroll
+    };
+    // Don't scroll past the last row just to show context below it
+    scroll.min(total.saturating_sub(height).max(selection.saturating_sub(height.saturating_sub(1))))
+}
+
+/// Scroll offset placing `selection` per `align` in a `height`-row view; the next
+/// render still clamps it to the content
+pub(crate) fn aligned_scroll(selection: usize, height: usize, align: ScrollAlign) -> usize {
+    match align {
+        ScrollAlign::Top => selection,
+        ScrollAlign::Center => selection.saturating_sub(height / 2),
+        ScrollAlign::Bottom => selection.saturating_sub(height.saturating_sub(1)),
+    }
+}
+
+/// Render a dim, centered message for engines with nothing to display
+pub(crate) fn render_placeholder(frame: &mut ratatui::Frame, area: Rect, message: &str) {
+    let line_area = Rect::new(area.x, area.y + area.height / 2, area.width, 1.min(area.height));
+    let text = Paragraph::new(message.to_string())
+        .alignment(Alignment::Center)
+        .style(Style::default().fg(Color::DarkGray));
+    frame.render_widget(text, line_area);
+}
+
+/// Most matches listed in a fuzzy picker (symbols, recent files)
+pub(crate) const PICKER_ROWS: usize = 12;
+
+/// How badly `name` matches `query` typed as a subsequence (case-insensitive):
+/// the characters skipped before and between the matched ones. None when it doesn't match
+pub(crate) fn fuzzy_cost(query: &str, name: &str) -> Option<usize> {
+    let name: Vec<char> = name.to_lowercase().chars().collect();
+    let mut cost = 0;
+    let mut next = 0;
+    for wanted in query.to_lowercase().chars() {
+        let found = next + name[next..].iter().position(|&c| c == wanted)?;
+        cost += found - next;
+        next = found + 1;
+    }
+    Some(cost)
+}
+
+/// Draw `text` wrapped in a centered, bordered popup over `area`
+pub(crate) fn render_popup(frame: &mut ratatui::Frame, area: Rect, title: &str, text: &str) {
+    let width = (area.width / 10 * 7).max(20);
+    let width = width.min(area.width);
+    let inner_width = width.saturating_sub(2).max(1) as usize;
+    // Rough wrapped height; the paragraph wraps on words so this may undercount slightly
+    let wrapped: usize = text
+        .lines()
+        .map(|line| line.chars().count().div_ceil(inner_width).max(1))
+        .sum();
+    let height = (wrapped.min(area.height as usize) as u16 + 2).max(3);
+    let height = height.min(area.height);
+    let popup = Rect::new(
+        area.x + (area.width - width) / 2,
+        area.y + (area.height - height) / 2,
+        width,
+        height,
+    );
+
+    let block = Block::default()
+        .title(format!(" {} ", title))
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(Color::LightCyan))
+        .style(Style::default().bg(Color::Black));
+    frame.render_widget(Clear, popup);
+    frame.render_widget(
+        Paragraph::new(text.to_string())
+            .wrap(Wrap { trim: false })
+            .block(block),
+        popup,
+    );
+}
@@ -0,0 +1,116 @@
+use std::borrow::Cow;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::layout::Rect;
+
+use super::{fuzzy_cost, render_popup, PICKER_ROWS};
+
+/// An entry in a `ListPicker`
+pub(crate) trait PickerItem {
+    /// Text the typed query is fuzzily matched against
+    fn label(&self) -> Cow<'_, str>;
+
+    /// Row shown in the popup
+    fn row(&self) -> String {
+        self.label().into_owned()
+    }
+
+    /// Orders equally good matches, lowest first; equal ones keep their list order
+    fn tie_break(&self) -> usize {
+        0
+    }
+}
+
+/// What a key did to an open `ListPicker`
+pub(crate) enum PickerKey<'a, T> {
+    /// Still open
+    Open,
+    /// Esc or Ctrl+c
+    Cancel,
+    /// Enter on a match
+    Pick(&'a T),
+}
+
+/// A popup list narrowed by fuzzy-matching typed text, as the `@` symbol and
+/// `Ctrl+o` recent-file pickers use
+pub(crate) struct ListPicker<T> {
+    items: Vec<T>,
+    query: String,
+    /// Highlighted row among the matches
+    selected: usize,
+}
+
+impl<T: PickerItem> ListPicker<T> {
+    pub(crate) fn new(items: Vec<T>) -> Self {
+        Self {
+            items,
+            query: String::new(),
+            selected: 0,
+        }
+    }
+
+    /// Items whose label fuzzily matches the query, best first
+    pub(crate) fn matches(&self) -> Vec<&T> {
+        let mut scored: Vec<(usize, usize, &T)> = self
+            .items
+            .iter()
+            .filter_map(|item| Some((fuzzy_cost(&self.query, &item.label())?, item.tie_break(), item)))
+            .collect();
+        scored.sort_by_key(|(cost, tie, _)| (*cost, *tie));
+        scored.into_iter().map(|(_, _, item)| item).collect()
+    }
+
+    /// Typing narrows the list, Up/Down (Tab, Ctrl+n/Ctrl+p) move, Enter picks
+    pub(crate) fn handle_key(&mut self, key: KeyEvent) -> PickerKey<'_, T> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        match key.code {
+            KeyCode::Esc => return PickerKey::Cancel,
+            KeyCode::Char('c') if ctrl => return PickerKey::Cancel,
+            KeyCode::Enter => {
+                if let Some(item) = self.matches().get(self.selected).copied() {
+                    return PickerKey::Pick(item);
+                }
+            }
+            KeyCode::Char('n') if ctrl => return self.handle_key(KeyEvent::from(KeyCode::Down)),
+            KeyCode::Char('p') if ctrl => return self.handle_key(KeyEvent::from(KeyCode::Up)),
+            KeyCode::Down | KeyCode::Tab => {
+                let count = self.matches().len().min(PICKER_ROWS);
+                if self.selected + 1 < count {
+                    self.selected += 1;
+                }
+            }
+            KeyCode::Up | KeyCode::BackTab => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Backspace => {
+                self.query.pop();
+                self.selected = 0;
+            }
+            KeyCode::Char(c) if !ctrl => {
+                self.query.push(c);
+                self.selected = 0;
+            }
+            _ => {}
+        }
+        PickerKey::Open
+    }
+
+    /// Draw the best matches in a popup titled `prefix` and the query, or `empty`
+    /// when nothing matches
+    pub(crate) fn render(&self, frame: &mut ratatui::Frame, area: Rect, prefix: &str, empty: &str) {
+        let matches = self.matches();
+        let text = if matches.is_empty() {
+            empty.to_string()
+        } else {
+            matches
+                .iter()
+                .take(PICKER_ROWS)
+                .enumerate()
+                .map(|(row, item)| {
+                    let marker = if row == self.selected { "▸" } else { " " };
+                    format!("{} {}", marker, item.row())
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        render_popup(frame, area, &format!("{}{}", prefix, self.query), &text);
+    }
+}
@@ -1,12 +1,17 @@
+use std::io::Write;
 use std::path::Path;
 
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
 use ratatui::layout::{Constraint, Rect};
 use ratatui::style::{Color, Style, Stylize};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Cell, Row, Table, TableState};
-use rusqlite::Connection;
+use rusqlite::{Connection, ErrorCode, OpenFlags};
+use serde_json::json;
+
+use super::util::{format_size, search_matches, truncate};
+use super::{Engine, TreeEngine};
 
 #[derive(Clone)]
 struct TableInfo {
@@ -27,15 +32,27 @@ pub struct SqliteEngine {
     tables: Vec<TableInfo>,
     current_table: usize,
     preview_rows: Vec<Vec<String>>,
+    /// Table index `preview_rows` was loaded for (None = not loaded yet)
+    preview_table: Option<usize>,
     selection: usize,
+    /// Column of the cell cursor in the preview
+    column: usize,
+    /// Full value of the selected cell, shown as a popup: (column name, value)
+    detail: Option<(String, String)>,
+    /// JSON cell opened as a tree; keys go to it until Esc
+    nested: Option<(String, Box<TreeEngine>)>,
     scroll: usize,
     file_name: String,
     last_query: Option<String>,
     pending_g: bool,
     last_view_height: usize,
     last_match: Option<String>,
+    /// Index into `search_slots` of the last name match, where `n`/`N` continue from
+    search_slot: Option<usize>,
     view_mode: ViewMode,
     db_path: std::path::PathBuf,
+    /// Backing file for databases built from bytes; SQLite needs a real file
+    _temp_file: Option<tempfile::NamedTempFile>,
     /// Visual selection range (start, end) for highlighting
     pub visual_range: Option<(usize, usize)>,
 }
@@ -47,6 +64,23 @@ enum ViewMode {
 }
 
 impl SqliteEngine {
+    /// Create from in-memory bytes; `path` only supplies the displayed name.
+    /// The bytes are written to a temporary file that lives as long as the engine.
+    pub fn from_bytes(path: &Path, bytes: &[u8]) -> Result<Self> {
+        let mut temp = tempfile::Builder::new().suffix(".db").tempfile()?;
+        temp.write_all(bytes)?;
+        temp.flush()?;
+
+        let mut engine = Self::from_path(temp.path())?;
+        engine.file_name = path
+            .file_name()
+            .and_then(|s| s.to_str())
+            .unwrap_or("")
+            .to_string();
+        engine._temp_file = Some(temp);
+        Ok(engine)
+    }
+
     pub fn from_path(path: &Path) -> Result<Self> {
         let file_name = path
             .file_name()
@@ -54,33 +88,41 @@ impl SqliteEngine {
             .unwrap_or("")
             .to_string();
 
-        let conn = Connection::open(path)?;
-        let tables = get_table_info(&conn)?;
-        let preview_rows = if !tables.is_empty() {
-            get_preview_rows(&conn, &tables[0].name, &tables[0].columns)?
-        } else {
-            Vec::new()
-        };
+        let (_, tables) = open_read_only(path)?;
 
         Ok(Self {
             tables,
             current_table: 0,
-            preview_rows,
+            preview_rows: Vec::new(),
+            preview_table: None,
             selection: 0,
+            column: 0,
+            detail: None,
+            nested: None,
             scroll: 0,
             file_name,
             last_query: None,
             pending_g: false,
             last_view_height: 0,
             last_match: None,
+            search_slot: None,
             view_mode: ViewMode::Schema,
             db_path: path.to_path_buf(),
+            _temp_file: None,
             visual_range: None,
         })
     }
 
-    fn refresh_preview(&mut self) {
-        if let Ok(conn) = Connection::open(&self.db_path) {
+    /// Load preview rows for the current table if they aren't loaded yet.
+    /// Deferred until the preview is actually shown so opening a database
+    /// (or tabbing through tables in schema view) doesn't query row data.
+    fn ensure_preview(&mut self) {
+        if self.view_mode != ViewMode::Preview || self.preview_table == Some(self.current_table) {
+            return;
+        }
+        self.preview_rows.clear();
+        self.preview_table = Some(self.current_table);
+        if let Ok((conn, _)) = open_read_only(&self.db_path) {
             if let Some(table) = self.tables.get(self.current_table) {
                 if let Ok(rows) = get_preview_rows(&conn, &table.name, &table.columns) {
                     self.preview_rows = rows;
@@ -89,16 +131,6 @@ impl SqliteEngine {
         }
     }
 
-    pub fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
-        let height = area.height as usize;
-        self.last_view_height = height;
-
-        match self.view_mode {
-            ViewMode::Schema => self.render_schema(frame, area),
-            ViewMode::Preview => self.render_preview(frame, area),
-        }
-    }
-
     fn render_schema(&mut self, frame: &mut ratatui::Frame, area: Rect) {
         let height = area.height.saturating_sub(1) as usize;
 
@@ -178,11 +210,7 @@ impl SqliteEngine {
             self.selection = total - 1;
         }
 
-        if self.selection < self.scroll {
-            self.scroll = self.selection;
-        } else if self.selection >= self.scroll + height {
-            self.scroll = self.selection.saturating_sub(height - 1);
-        }
+        self.scroll = super::follow_selection(self.scroll, self.selection, height, total);
 
         let visible: Vec<Line> = display_lines
             .into_iter()
@@ -203,11 +231,7 @@ impl SqliteEngine {
         let table = &self.tables[self.current_table];
         let height = area.height.saturating_sub(2) as usize;
 
-        if self.selection < self.scroll {
-            self.scroll = self.selection;
-        } else if self.selection >= self.scroll + height {
-            self.scroll = self.selection.saturating_sub(height - 1);
-        }
+        self.scroll = super::follow_selection(self.scroll, self.selection, height, self.preview_rows.len());
 
         let header_style = Style::default().fg(Color::Black).bg(Color::LightBlue).bold();
         let headers: Vec<Cell> = table.columns
@@ -221,15 +245,23 @@ impl SqliteEngine {
             .skip(self.scroll)
             .take(height)
             .enumerate()
-            .map(|(_, row)| {
-                let cells: Vec<Cell> = row
+            .map(|(idx, values)| {
+                let row = self.scroll + idx;
+                let in_visual = self.visual_range.is_some_and(|(start, end)| {
+                    let (lo, hi) = if start <= end { (start, end) } else { (end, start) };
+                    row >= lo && row <= hi
+                });
+                let cells: Vec<Cell> = values
                     .iter()
-                    .map(|v| {
+                    .enumerate()
+                    .map(|(col_idx, v)| {
                         // Color by value type
                         let style = if v == "NULL" {
                             Style::default().fg(Color::DarkGray)
-                        } else if v == "[BLOB]" {
+                        } else if v.starts_with("[BLOB") {
                             Style::default().fg(Color::DarkGray).italic()
+                        } else if parse_json_cell(v).is_some() {
+                            Style::default().fg(Color::LightGreen)
                         } else if v.parse::<f64>().is_ok() {
                             Style::default().fg(Color::Magenta)
                         } else if v == "true" || v == "false" || v == "1" || v == "0" {
@@ -237,7 +269,18 @@ impl SqliteEngine {
                         } else {
                             Style::default().fg(Color::Yellow)
                         };
-                        Cell::from(truncate(v, 30)).style(style)
+                        let style = if in_visual {
+                            Style::default().fg(Color::Black).bg(Color::LightYellow)
+                        } else {
+                            style
+                        };
+                        // Reversed survives the row highlight, so the cursor cell stands out
+                        let style = if row == self.selection && col_idx == self.column {
+                            style.reversed().bold()
+                        } else {
+                            style
+                        };
+                        Cell::from(truncate(v, super::preview_len(30))).style(style)
                     })
                     .collect();
                 Row::new(cells)
@@ -260,9 +303,164 @@ impl SqliteEngine {
             state.select(Some(relative));
         }
         frame.render_stateful_widget(table_widget, area, &mut state);
+        if self.preview_rows.is_empty() {
+            super::render_placeholder(frame, area, "(no rows)");
+        }
+    }
+
+    /// Open the cell under the cursor: JSON objects and arrays as a tree,
+    /// anything else as a popup with the untruncated value
+    fn open_cell(&mut self) {
+        let Some(column) = self
+            .tables
+            .get(self.current_table)
+            .and_then(|t| t.columns.get(self.column))
+        else {
+            return;
+        };
+        let Some(value) = self.preview_rows.get(self.selection).and_then(|row| row.get(self.column)) else {
+            return;
+        };
+        if parse_json_cell(value).is_some() {
+            if let Ok(tree) = TreeEngine::from_bytes(Path::new("cell.json"), value.as_bytes()) {
+                self.nested = Some((column.name.clone(), Box::new(tree)));
+                return;
+            }
+        }
+        self.detail = Some((column.name.clone(), value.clone()));
     }
 
-    pub fn handle_key(&mut self, key: KeyEvent) {
+    /// Table names and their columns in schema order: `(table, None)` for a
+    /// table, `(table, Some(column))` for each of its columns
+    fn search_slots(&self) -> Vec<(usize, Option<usize>)> {
+        self.tables
+            .iter()
+            .enumerate()
+            .flat_map(|(table_idx, table)| {
+                std::iter::once((table_idx, None)).chain((0..table.columns.len()).map(move |c| (table_idx, Some(c))))
+            })
+            .collect()
+    }
+
+    /// The slot searches continue from: the schema row under the cursor, or in the
+    /// preview the last match if it is in the current table, else the table itself
+    fn current_slot(&self, slots: &[(usize, Option<usize>)]) -> usize {
+        let table_slot = slots.iter().position(|&(t, c)| t == self.current_table && c.is_none());
+        match self.view_mode {
+            // Each table's rows are its slots plus a blank line after them
+            ViewMode::Schema => slots
+                .iter()
+                .enumerate()
+                .take_while(|(slot, (table_idx, _))| slot + table_idx <= self.selection)
+                .last()
+                .map_or(0, |(slot, _)| slot),
+            ViewMode::Preview => self
+                .search_slot
+                .filter(|&slot| slots.get(slot).is_some_and(|&(t, _)| t == self.current_table))
+                .or(table_slot)
+                .unwrap_or(0),
+        }
+    }
+
+    fn search_next(&mut self, query: &str, forward: bool) -> bool {
+        let lower = query.to_lowercase();
+        self.last_match = Some(query.to_string());
+        let slots = self.search_slots();
+        if slots.is_empty() {
+            return false;
+        }
+        let total = slots.len();
+        let current = self.current_slot(&slots);
+        // Ends back on the current slot, so a single match is still found
+        for offset in 1..=total {
+            let slot = if forward {
+                (current + offset) % total
+            } else {
+                (current + total - offset % total) % total
+            };
+            let (table_idx, column) = slots[slot];
+            let table = &self.tables[table_idx];
+            let name = column.map_or(&table.name, |c| &table.columns[c].name);
+            if !search_matches(name, &lower) {
+                continue;
+            }
+            if table_idx != self.current_table {
+                self.current_table = table_idx;
+                if self.view_mode == ViewMode::Preview {
+                    self.selection = 0;
+                    self.scroll = 0;
+                }
+            }
+            self.column = column.unwrap_or(0);
+            if self.view_mode == ViewMode::Schema {
+                self.selection = slot + table_idx;
+            }
+            self.search_slot = Some(slot);
+            self.ensure_preview();
+            return true;
+        }
+        false
+    }
+
+    /// Rows in the current view, counting the blank line after each table in the schema
+    fn row_count(&self) -> usize {
+        match self.view_mode {
+            ViewMode::Schema => self.tables.iter().map(|t| t.columns.len() + 2).sum(),
+            ViewMode::Preview => self.preview_rows.len() + 1,
+        }
+    }
+}
+
+impl Engine for SqliteEngine {
+    fn name(&self) -> &'static str {
+        "SqliteEngine"
+    }
+
+    fn set_visual_range(&mut self, range: Option<(usize, usize)>) {
+        self.visual_range = range;
+    }
+
+    fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+        let height = area.height as usize;
+        self.last_view_height = height;
+        self.ensure_preview();
+
+        if self.tables.is_empty() {
+            super::render_placeholder(frame, area, "(no tables)");
+            return;
+        }
+        if let Some((_, tree)) = self.nested.as_mut() {
+            tree.render(frame, area);
+            return;
+        }
+
+        match self.view_mode {
+            ViewMode::Schema => self.render_schema(frame, area),
+            ViewMode::Preview => self.render_preview(frame, area),
+        }
+        if let Some((name, value)) = &self.detail {
+            let title = format!("{} (row {})", name, self.selection + 1);
+            super::render_popup(frame, area, &title, value);
+        }
+    }
+
+    #[allow(clippy::collapsible_match)]
+    fn handle_key(&mut self, key: KeyEvent) {
+        if let Some((_, tree)) = self.nested.as_mut() {
+            if key.code == KeyCode::Esc {
+                self.nested = None;
+            } else {
+                tree.handle_key(key);
+            }
+            return;
+        }
+        // The detail popup is modal: it only closes
+        if self.detail.is_some() {
+            if matches!(key.code, KeyCode::Enter | KeyCode::Esc) {
+                self.detail = None;
+            }
+            return;
+        }
         match key.code {
             KeyCode::Char('g') => {
                 if self.pending_g {
@@ -278,6 +476,7 @@ impl SqliteEngine {
             }
         }
 
+        self.ensure_preview();
         let total = match self.view_mode {
             ViewMode::Schema => {
                 self.tables.iter().map(|t| t.columns.len() + 2).sum::<usize>()
@@ -294,13 +493,23 @@ impl SqliteEngine {
             KeyCode::Char('k') | KeyCode::Up => {
                 self.selection = self.selection.saturating_sub(1);
             }
+            KeyCode::Char('h') | KeyCode::Left if self.view_mode == ViewMode::Preview => {
+                self.column = self.column.saturating_sub(1);
+            }
+            KeyCode::Char('l') | KeyCode::Right if self.view_mode == ViewMode::Preview => {
+                let width = self.tables.get(self.current_table).map_or(0, |t| t.columns.len());
+                if self.column + 1 < width {
+                    self.column += 1;
+                }
+            }
+            KeyCode::Enter if self.view_mode == ViewMode::Preview => {
+                self.open_cell();
+            }
             KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let jump = page_jump(self.last_view_height).min(self.selection);
-                self.selection = self.selection.saturating_sub(jump);
+                self.selection = super::half_page_up(self.selection, self.last_view_height);
             }
             KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let jump = page_jump(self.last_view_height).min(total.saturating_sub(1));
-                self.selection = (self.selection + jump).min(total.saturating_sub(1));
+                self.selection = super::half_page_down(self.selection, self.last_view_height, total);
             }
             KeyCode::Char('G') => {
                 if total > 0 {
@@ -314,12 +523,14 @@ impl SqliteEngine {
                 };
                 self.selection = 0;
                 self.scroll = 0;
+                self.ensure_preview();
             }
             KeyCode::Tab => {
                 if !self.tables.is_empty() {
                     self.current_table = (self.current_table + 1) % self.tables.len();
-                    self.refresh_preview();
+                    self.ensure_preview();
                     self.selection = 0;
+                    self.column = 0;
                     self.scroll = 0;
                 }
             }
@@ -330,8 +541,9 @@ impl SqliteEngine {
                     } else {
                         self.current_table - 1
                     };
-                    self.refresh_preview();
+                    self.ensure_preview();
                     self.selection = 0;
+                    self.column = 0;
                     self.scroll = 0;
                 }
             }
@@ -349,26 +561,40 @@ impl SqliteEngine {
         }
     }
 
-    pub fn apply_search(&mut self, query: &str) {
+    fn apply_search(&mut self, query: &str, forward: bool) -> bool {
+        if let Some((_, tree)) = self.nested.as_mut() {
+            return tree.apply_search(query, forward);
+        }
         let trimmed = query.trim();
         if trimmed.is_empty() {
-            return;
+            return false;
         }
         self.last_query = Some(trimmed.to_string());
-        self.search_next(trimmed, true);
+        let found = self.search_next(trimmed, forward);
         self.last_match = Some(trimmed.to_string());
+        found
     }
 
-    pub fn apply_filter(&mut self, query: &str) {
-        self.apply_search(query);
+    fn apply_filter(&mut self, query: &str) -> bool {
+        self.apply_search(query, true)
     }
 
-    pub fn clear_filter(&mut self) {
+    fn clear_filter(&mut self) {
         self.last_query = None;
     }
 
-    pub fn breadcrumbs(&self) -> String {
+    fn breadcrumbs(&self) -> String {
         let table_name = self.tables.get(self.current_table).map(|t| t.name.as_str()).unwrap_or("");
+        if let Some((column, tree)) = &self.nested {
+            return format!(
+                "{} [{}] row {} · {} > {}",
+                self.file_name,
+                table_name,
+                self.selection + 1,
+                column,
+                tree.breadcrumbs()
+            );
+        }
         let mode = match self.view_mode {
             ViewMode::Schema => "schema",
             ViewMode::Preview => "data",
@@ -376,7 +602,10 @@ impl SqliteEngine {
         format!("{} [{}] {} line {}", self.file_name, table_name, mode, self.selection + 1)
     }
 
-    pub fn status_line(&self) -> String {
+    fn status_line(&self) -> String {
+        if let Some((_, tree)) = &self.nested {
+            return format!("Esc back to table | {}", tree.status_line());
+        }
         let query = self
             .last_query
             .as_ref()
@@ -387,18 +616,43 @@ impl SqliteEngine {
             ViewMode::Preview => "preview",
         };
         format!(
-            "j/k move | gg/G jump | Tab/Shift+Tab tables | s toggle view ({}) | / search{}",
+            "j/k move | h/l column | Enter cell | gg/G jump | Tab/Shift+Tab tables | s toggle view ({}) | / search{}",
             mode, query
         )
     }
 
-    #[allow(dead_code)]
-    pub fn selected_path(&self) -> Option<String> {
+    /// Keys specific to this view, listed in the help overlay
+    fn help_lines(&self) -> Vec<Line<'static>> {
+        vec![
+            Line::from("  Tab/S-Tab    Next/previous table"),
+            Line::from("  s            Toggle schema/preview"),
+            Line::from("  h/l          Previous / next column"),
+            Line::from("  Enter        Open cell (JSON as a tree)"),
+            Line::from("  Esc          Close cell"),
+        ]
+    }
+
+    fn selected_path(&self) -> Option<String> {
         None
     }
 
+    /// Preview rows `start..=end` under the current table's column names
+    fn markdown_table(&self, start: usize, end: usize) -> Option<String> {
+        if self.view_mode != ViewMode::Preview || self.nested.is_some() {
+            return None;
+        }
+        let (start, end) = if start <= end { (start, end) } else { (end, start) };
+        let table = self.tables.get(self.current_table)?;
+        if start >= self.preview_rows.len() {
+            return None;
+        }
+        let rows = &self.preview_rows[start..=end.min(self.preview_rows.len() - 1)];
+        let headers: Vec<&str> = table.columns.iter().map(|c| c.name.as_str()).collect();
+        Some(super::util::markdown_table(&headers, rows))
+    }
+
     /// Get the content of the currently selected line
-    pub fn get_selected_line(&self) -> Option<String> {
+    fn get_selected_line(&self) -> Option<String> {
         match self.view_mode {
             ViewMode::Schema => {
                 let mut idx = 0;
@@ -430,9 +684,9 @@ impl SqliteEngine {
     }
 
     /// Get lines in a range (inclusive), joined by newlines
-    pub fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
+    fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
         let (start, end) = if start <= end { (start, end) } else { (end, start) };
-        let total = self.content_height();
+        let total = self.row_count();
         if start >= total { return None; }
         let end = end.min(total.saturating_sub(1));
         let lines: Vec<String> = (start..=end)
@@ -466,18 +720,61 @@ impl SqliteEngine {
     }
 
     /// Get current selection index (for visual mode)
-    pub fn selection(&self) -> usize {
+    fn selection(&self) -> usize {
         self.selection
     }
 
-    pub fn content_height(&self) -> usize {
-        match self.view_mode {
-            ViewMode::Schema => self.tables.iter().map(|t| t.columns.len() + 2).sum(),
-            ViewMode::Preview => self.preview_rows.len() + 1,
+    fn select(&mut self, row: usize) {
+        if let Some((_, tree)) = self.nested.as_mut() {
+            tree.select(row);
+            return;
+        }
+        self.ensure_preview();
+        let total = match self.view_mode {
+            ViewMode::Schema => self.row_count(),
+            ViewMode::Preview => self.preview_rows.len(),
+        };
+        self.selection = row.min(total.saturating_sub(1));
+    }
+
+    /// Scroll so the selection sits at the top, middle or bottom of the view (`zt`/`zz`/`zb`)
+    fn align_scroll(&mut self, align: super::ScrollAlign) {
+        if let Some((_, tree)) = self.nested.as_mut() {
+            tree.align_scroll(align);
+            return;
         }
+        self.scroll = super::aligned_scroll(self.selection, self.last_view_height, align);
+    }
+
+    fn content_height(&mut self) -> usize {
+        self.row_count()
+    }
+
+    /// Parsed structure for `--dump`
+    fn to_json(&mut self) -> serde_json::Value {
+        let tables: Vec<_> = self
+            .tables
+            .iter()
+            .map(|table| {
+                let columns: Vec<_> = table
+                    .columns
+                    .iter()
+                    .map(|c| {
+                        json!({
+                            "name": c.name,
+                            "type": c.col_type,
+                            "primary_key": c.is_pk,
+                            "nullable": c.nullable,
+                        })
+                    })
+                    .collect();
+                json!({ "name": table.name, "rows": table.row_count, "columns": columns })
+            })
+            .collect();
+        json!({ "tables": tables })
     }
 
-    pub fn render_plain_lines(&self, _width: u16) -> Vec<Line<'static>> {
+    fn render_plain_lines(&mut self, _width: u16) -> Vec<Line<'static>> {
         let mut lines = Vec::new();
 
         for table in &self.tables {
@@ -503,29 +800,48 @@ impl SqliteEngine {
 
         lines
     }
+}
 
-    fn search_next(&mut self, query: &str, _forward: bool) {
-        let lower = query.to_lowercase();
-        // Search in table names and column names
-        for (idx, table) in self.tables.iter().enumerate() {
-            if table.name.to_lowercase().contains(&lower) {
-                self.current_table = idx;
-                self.refresh_preview();
-                return;
-            }
-            for col in &table.columns {
-                if col.name.to_lowercase().contains(&lower) {
-                    self.current_table = idx;
-                    self.refresh_preview();
-                    return;
-                }
-            }
+/// Open a database without taking write locks, so a live app's database can be
+/// viewed while it is being written. If the WAL side files can't be created
+/// (e.g. a read-only directory), fall back to opening it as immutable.
+fn open_read_only(path: &Path) -> Result<(Connection, Vec<TableInfo>)> {
+    let flags = OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX;
+    let attempt = Connection::open_with_flags(path, flags).and_then(|conn| {
+        conn.busy_timeout(std::time::Duration::from_millis(500))?;
+        let tables = get_table_info(&conn)?;
+        Ok((conn, tables))
+    });
+    let error = match attempt {
+        Ok(opened) => return Ok(opened),
+        Err(e) => e,
+    };
+    if error.sqlite_error_code() == Some(ErrorCode::NotADatabase) {
+        return Err(anyhow!("{}: not a SQLite database", path.display()));
+    }
+
+    let uri = format!("file:{}?immutable=1", uri_escape(&path.to_string_lossy()));
+    Connection::open_with_flags(uri, flags | OpenFlags::SQLITE_OPEN_URI)
+        .and_then(|conn| {
+            let tables = get_table_info(&conn)?;
+            Ok((conn, tables))
+        })
+        .map_err(|_| anyhow!("{}: cannot open database: {}", path.display(), error))
+}
+
+/// Percent-escape the characters that would end the path part of a `file:` URI
+fn uri_escape(path: &str) -> String {
+    let mut escaped = String::with_capacity(path.len());
+    for c in path.chars() {
+        match c {
+            '%' | '?' | '#' => escaped.push_str(&format!("%{:02X}", c as u32)),
+            _ => escaped.push(c),
         }
-        self.last_match = Some(query.to_string());
     }
+    escaped
 }
 
-fn get_table_info(conn: &Connection) -> Result<Vec<TableInfo>> {
+fn get_table_info(conn: &Connection) -> rusqlite::Result<Vec<TableInfo>> {
     let mut tables = Vec::new();
 
     let mut stmt = conn.prepare(
@@ -562,10 +878,21 @@ fn get_table_info(conn: &Connection) -> Result<Vec<TableInfo>> {
 }
 
 fn get_preview_rows(conn: &Connection, table_name: &str, columns: &[ColumnInfo]) -> Result<Vec<Vec<String>>> {
-    let col_names: Vec<&str> = columns.iter().map(|c| c.name.as_str()).collect();
+    // Each column is selected twice: its storage type, and either its value or,
+    // for BLOBs, just its byte length so blob contents are never materialized
     let query = format!(
         "SELECT {} FROM \"{}\" LIMIT 100",
-        col_names.iter().map(|c| format!("\"{}\"", c)).collect::<Vec<_>>().join(", "),
+        columns
+            .iter()
+            .map(|c| {
+                let name = format!("\"{}\"", c.name);
+                format!(
+                    "typeof({0}), CASE WHEN typeof({0}) = 'blob' THEN length({0}) ELSE {0} END",
+                    name
+                )
+            })
+            .collect::<Vec<_>>()
+            .join(", "),
         table_name
     );
 
@@ -574,8 +901,12 @@ fn get_preview_rows(conn: &Connection, table_name: &str, columns: &[ColumnInfo])
         .query_map([], |row| {
             let mut values = Vec::new();
             for i in 0..columns.len() {
-                let value: String = row.get::<_, rusqlite::types::Value>(i)
+                let kind: String = row.get(i * 2).unwrap_or_default();
+                let value: String = row.get::<_, rusqlite::types::Value>(i * 2 + 1)
                     .map(|v| match v {
+                        rusqlite::types::Value::Integer(len) if kind == "blob" => {
+                            format!("[BLOB {}]", format_size(len.max(0) as u64))
+                        }
                         rusqlite::types::Value::Null => "NULL".to_string(),
                         rusqlite::types::Value::Integer(i) => i.to_string(),
                         rusqlite::types::Value::Real(f) => f.to_string(),
@@ -593,16 +924,102 @@ fn get_preview_rows(conn: &Connection, table_name: &str, columns: &[ColumnInfo])
     Ok(rows)
 }
 
-fn truncate(value: &str, max: usize) -> String {
-    if value.len() <= max {
-        return value.to_string();
+/// Parse a cell holding a JSON object or array; scalars like `1` or `"x"`
+/// are left as plain values
+fn parse_json_cell(value: &str) -> Option<serde_json::Value> {
+    let trimmed = value.trim_start();
+    if !trimmed.starts_with('{') && !trimmed.starts_with('[') {
+        return None;
     }
-    let mut out = value.chars().take(max.saturating_sub(3)).collect::<String>();
-    out.push_str("...");
-    out
+    serde_json::from_str(value).ok()
 }
 
-fn page_jump(view_height: usize) -> usize {
-    let half = view_height / 2;
-    if half == 0 { 1 } else { half }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_cells_open_as_tree() {
+        let temp = tempfile::Builder::new().suffix(".db").tempfile().unwrap();
+        let conn = Connection::open(temp.path()).unwrap();
+        conn.execute_batch(
+            r#"CREATE TABLE events (id INTEGER, payload TEXT);
+               INSERT INTO events VALUES (1, '{"a":1}'), (2, 'plain text');"#,
+        )
+        .unwrap();
+        drop(conn);
+
+        let mut engine = SqliteEngine::from_path(temp.path()).unwrap();
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        engine.handle_key(key(KeyCode::Char('s')));
+        engine.handle_key(key(KeyCode::Char('l')));
+        engine.handle_key(key(KeyCode::Enter));
+        assert!(engine.nested.is_some());
+        assert!(engine.breadcrumbs().contains("payload"));
+
+        engine.handle_key(key(KeyCode::Esc));
+        engine.handle_key(key(KeyCode::Char('j')));
+        engine.handle_key(key(KeyCode::Enter));
+        assert!(engine.nested.is_none());
+        assert_eq!(engine.detail, Some(("payload".to_string(), "plain text".to_string())));
+    }
+
+    #[test]
+    fn dump_lists_tables_and_columns() {
+        let temp = tempfile::Builder::new().suffix(".db").tempfile().unwrap();
+        let conn = Connection::open(temp.path()).unwrap();
+        conn.execute_batch("CREATE TABLE users (id INTEGER PRIMARY KEY, name TEXT NOT NULL);").unwrap();
+        drop(conn);
+
+        let mut engine = SqliteEngine::from_path(temp.path()).unwrap();
+        let dump = engine.to_json();
+        assert_eq!(dump["tables"][0]["name"], "users");
+        assert_eq!(dump["tables"][0]["columns"][0]["primary_key"], true);
+        assert_eq!(dump["tables"][0]["columns"][1]["nullable"], false);
+    }
+
+    #[test]
+    fn search_cycles_through_table_and_column_names() {
+        let temp = tempfile::Builder::new().suffix(".db").tempfile().unwrap();
+        let conn = Connection::open(temp.path()).unwrap();
+        conn.execute_batch(
+            "CREATE TABLE users (id INTEGER, user_name TEXT);
+             CREATE TABLE posts (id INTEGER, user_id INTEGER);",
+        )
+        .unwrap();
+        drop(conn);
+
+        let mut engine = SqliteEngine::from_path(temp.path()).unwrap();
+        let names: Vec<&str> = engine.tables.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["posts", "users"]);
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+        // Schema rows: posts, id, user_id, blank, users, id, user_name
+        assert!(engine.apply_search("user", true));
+        assert_eq!(engine.selection, 2);
+        engine.handle_key(key(KeyCode::Char('n')));
+        assert_eq!((engine.current_table, engine.selection), (1, 4));
+        engine.handle_key(key(KeyCode::Char('n')));
+        assert_eq!((engine.selection, engine.column), (6, 1));
+        engine.handle_key(key(KeyCode::Char('n')));
+        assert_eq!((engine.current_table, engine.selection), (0, 2));
+        engine.handle_key(key(KeyCode::Char('N')));
+        assert_eq!((engine.current_table, engine.selection), (1, 6));
+    }
+
+    #[test]
+    fn non_database_file_errors_cleanly() {
+        let mut temp = tempfile::Builder::new().suffix(".db").tempfile().unwrap();
+        temp.write_all(b"definitely not a database, just some text padding it out")
+            .unwrap();
+        let error = SqliteEngine::from_path(temp.path()).err().unwrap();
+        assert!(error.to_string().ends_with("not a SQLite database"));
+    }
+
+    #[test]
+    fn only_objects_and_arrays_count_as_json() {
+        assert!(parse_json_cell(r#"{"a": [1, 2]}"#).is_some());
+        assert!(parse_json_cell("[1, 2]").is_some());
+        assert!(parse_json_cell("42").is_none());
+        assert!(parse_json_cell("{not json").is_none());
+    }
 }
@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::collections::HashSet;
 use std::path::Path;
 
@@ -13,14 +14,49 @@ use tree_sitter_css as ts_css;
 use tree_sitter_javascript as ts_js;
 use tree_sitter_typescript as ts_ts;
 use syntect::easy::HighlightLines;
-use syntect::highlighting::{Style as SynStyle, ThemeSet};
-use syntect::parsing::SyntaxSet;
+use syntect::highlighting::{
+    HighlightIterator, HighlightState, Highlighter, Style as SynStyle, ThemeSet,
+};
+use syntect::parsing::{ParseState, ScopeStack, SyntaxSet};
+use serde_json::json;
+
+use super::util::search_matches;
+use super::{Engine, ListPicker, PickerItem, PickerKey, TextLayout};
+
+/// Number of lines between saved highlighter states. Rendering a window only
+/// re-highlights from the nearest checkpoint instead of from the top of the file.
+const HIGHLIGHT_CHECKPOINT_INTERVAL: usize = 256;
+
+/// Columns taken by the component sidebar
+const SIDEBAR_WIDTH: u16 = 28;
 
 struct ComponentInfo {
     name: String,
     props: Option<String>,
 }
 
+/// A definition found by `extract_symbols`
+struct Symbol {
+    line: usize,
+    kind: String,
+    name: String,
+}
+
+/// Symbols are listed in line order, so shorter names win ties and then earlier lines
+impl PickerItem for Symbol {
+    fn label(&self) -> Cow<'_, str> {
+        Cow::Borrowed(&self.name)
+    }
+
+    fn row(&self) -> String {
+        format!("{}  {} :{}", self.name, self.kind, self.line + 1)
+    }
+
+    fn tie_break(&self) -> usize {
+        self.name.len()
+    }
+}
+
 pub struct SyntaxEngine {
     lines: Vec<String>,
     selection: usize,
@@ -35,17 +71,50 @@ pub struct SyntaxEngine {
     is_css: bool,
     is_markdown: bool,
     md_rendered: Vec<MdLine>,
+    /// Markdown prose wraps to the view width (`s` toggles it in markdown)
+    md_wrap: bool,
     syntax_error_lines: HashSet<usize>,
+    /// Parser/highlighter state at the start of every
+    /// `HIGHLIGHT_CHECKPOINT_INTERVAL`-th line, filled lazily as the view scrolls
+    highlight_checkpoints: Vec<(ParseState, HighlightState)>,
     pending_g: bool,
     last_view_height: usize,
     last_match: Option<String>,
     /// Visual selection range (start, end) for highlighting
     pub visual_range: Option<(usize, usize)>,
+    /// Wrap and tab settings, set by `App`
+    layout: TextLayout,
+    /// Widest code or markdown line, gutter aside; measured on the first `zh`/`zl`
+    widest: Option<usize>,
+    /// Char index of the column cursor within the selected line (`h`/`l`/`0`/`$`/`w`/`b`)
+    cursor_col: usize,
+    /// Columns scrolled off the left while wrapping is off (`zh`/`zl`)
+    h_scroll: usize,
+    /// A key moved the cursor since the last render, which scrolls it back into view;
+    /// left alone otherwise so `zh`/`zl` can scroll away from it
+    cursor_moved: bool,
+    /// (line, char index) of the bracket closing the one left open on the selected line
+    bracket_match: Option<(usize, usize)>,
+    /// Why tree-sitter features are missing, when the grammar failed to load
+    grammar_note: Option<String>,
+    /// Open `@` symbol picker
+    picker: Option<ListPicker<Symbol>>,
 }
 
 impl SyntaxEngine {
+    /// Open a file; a `linguist-language` in `.gitattributes` picks its grammar
     pub fn from_path(path: &Path) -> Result<Self> {
-        let content = std::fs::read_to_string(path)?;
+        Self::with_language(path, &std::fs::read(path)?, gitattributes_language(path))
+    }
+
+    /// Create from in-memory bytes; `path` only supplies the name and extension
+    pub fn from_bytes(path: &Path, bytes: &[u8]) -> Result<Self> {
+        Self::with_language(path, bytes, None)
+    }
+
+    /// `language` names the grammar outright, ahead of modelines and the extension
+    fn with_language(path: &Path, bytes: &[u8], language: Option<String>) -> Result<Self> {
+        let content = std::str::from_utf8(bytes)?;
         let lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
         let syntax_set = SyntaxSet::load_defaults_newlines();
         let theme_set = ThemeSet::load_defaults();
@@ -62,25 +131,44 @@ impl SyntaxEngine {
             .unwrap_or("")
             .to_string();
         let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("");
-        let syntax = syntax_set
-            .find_syntax_for_file(path)
-            .ok()
-            .flatten()
+        // An explicit language or modeline wins; then the same lookup order as
+        // `find_syntax_for_file` (name, extension, shebang), without reading the file again
+        let by_language = |language: &str| match language.to_lowercase().as_str() {
+            "shell" | "bash" | "zsh" => syntax_set.find_syntax_by_extension("sh"),
+            language => syntax_set.find_syntax_by_token(language),
+        };
+        let syntax = language
+            .and_then(|language| by_language(&language))
+            .or_else(|| modeline_language(content).and_then(|language| by_language(&language)))
+            .or_else(|| syntax_set.find_syntax_by_extension(&file_name))
+            .or_else(|| syntax_set.find_syntax_by_extension(ext))
+            .or_else(|| shebang_language(content).and_then(by_language))
+            .or_else(|| content.lines().next().and_then(|l| syntax_set.find_syntax_by_first_line(l)))
             .map(|s| s.name.clone());
         let is_css = matches!(ext, "css" | "tcss");
         let is_markdown = ext == "md";
-        let components = if matches!(ext, "jsx" | "tsx" | "js" | "ts") {
-            extract_components(&content, ext)
+        let has_outline = matches!(ext, "jsx" | "tsx" | "js" | "ts");
+        let components = if has_outline {
+            extract_components(content, ext)
         } else {
             Vec::new()
         };
         let show_sidebar = !components.is_empty();
+        // A grammar that fails to load (e.g. a tree-sitter ABI mismatch) leaves the
+        // sidebar and error marks empty; say so instead of showing nothing
+        let grammar_note = matches!(grammar_parser(ext), Some(Err(_))).then(|| {
+            if has_outline && components.is_empty() {
+                "outline unavailable".to_string()
+            } else {
+                "syntax checks unavailable".to_string()
+            }
+        });
         let md_rendered = if is_markdown {
-            render_markdown(&content)
+            render_markdown(content)
         } else {
             Vec::new()
         };
-        let syntax_error_lines = parse_syntax_errors(&content, ext);
+        let syntax_error_lines = parse_syntax_errors(content, ext);
 
         Ok(Self {
             lines,
@@ -96,20 +184,285 @@ impl SyntaxEngine {
             is_css,
             is_markdown,
             md_rendered,
+            md_wrap: true,
             syntax_error_lines,
+            highlight_checkpoints: Vec::new(),
             pending_g: false,
             last_view_height: 0,
             last_match: None,
             visual_range: None,
+            layout: TextLayout::default(),
+            widest: None,
+            cursor_col: 0,
+            h_scroll: 0,
+            cursor_moved: false,
+            bracket_match: None,
+            grammar_note,
+            picker: None,
         })
     }
 
-    pub fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+    fn render_sidebar(&self, frame: &mut ratatui::Frame, area: Rect) {
+        let mut lines = Vec::new();
+        lines.push(Line::from("Components"));
+        for comp in &self.components {
+            let props = comp
+                .props
+                .as_ref()
+                .map(|p| format!(" ({})", p))
+                .unwrap_or_default();
+            lines.push(Line::from(format!("- {}{}", comp.name, props)));
+        }
+        let block = Block::default().borders(Borders::RIGHT);
+        frame.render_widget(Paragraph::new(lines).block(block), area);
+    }
+
+    /// Keys while the symbol picker is open: typing narrows it, Enter jumps
+    fn handle_picker_key(&mut self, key: KeyEvent) {
+        let Some(picker) = self.picker.as_mut() else {
+            return;
+        };
+        match picker.handle_key(key) {
+            PickerKey::Open => {}
+            PickerKey::Cancel => self.picker = None,
+            PickerKey::Pick(symbol) => {
+                let line = symbol.line;
+                self.picker = None;
+                self.selection = line;
+                self.cursor_col = 0;
+                self.scroll = super::aligned_scroll(line, self.last_view_height, super::ScrollAlign::Center);
+            }
+        }
+    }
+
+    fn render_picker(&self, frame: &mut ratatui::Frame, area: Rect) {
+        if let Some(picker) = &self.picker {
+            picker.render(frame, area, "@", "(no matching symbols)");
+        }
+    }
+
+    fn render_code(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+        if self.is_markdown {
+            self.render_markdown(frame, area);
+            return;
+        }
+
+        if self.lines.is_empty() {
+            super::render_placeholder(frame, area, "(empty file)");
+            return;
+        }
+        let height = area.height as usize;
+        self.scroll = super::follow_selection(self.scroll, self.selection, height, self.lines.len());
+
+        let line_no_width = self.lines.len().max(1).to_string().len().max(2);
+        let width = area.width as usize;
+        let gutter = if super::line_numbers() { line_no_width + 3 } else { 0 };
+        if self.layout.wrap {
+            // Wrapped lines take several rows; scroll on until the selected one fits
+            while self.scroll < self.selection {
+                let rows: usize = (self.scroll..=self.selection)
+                    .map(|idx| super::wrap_line(self.code_line(idx, None, line_no_width), width, gutter).len())
+                    .sum();
+                if rows <= height {
+                    break;
+                }
+                self.scroll += 1;
+            }
+        }
+
+        let cursor = self.clamped_cursor();
+        if !self.layout.wrap && std::mem::take(&mut self.cursor_moved) {
+            let line = &self.lines[self.selection.min(self.lines.len() - 1)];
+            let col = super::display_col(line, cursor, self.layout.tab_width);
+            self.h_scroll = super::follow_cursor(self.h_scroll, col, width.saturating_sub(gutter));
+        }
+        self.bracket_match = matching_bracket(&self.lines, self.selection, cursor);
+        let end = (self.scroll + height).min(self.lines.len());
+        let mut highlighted = self.highlight_range(self.scroll, end).map(Vec::into_iter);
+
+        let mut output = Vec::new();
+        for idx in self.scroll..end {
+            let regions = highlighted.as_mut().and_then(|lines| lines.next());
+            let line = self.code_line(idx, regions, line_no_width);
+            if self.layout.wrap {
+                output.extend(super::wrap_line(line, width, gutter));
+                if output.len() >= height {
+                    break;
+                }
+            } else {
+                output.push(super::shift_line(line, self.h_scroll, gutter));
+            }
+        }
+        output.truncate(height);
+
+        let block = Block::default().borders(Borders::NONE);
+        frame.render_widget(Paragraph::new(output).block(block), area);
+    }
+
+    /// Gutter and code for line `idx`; `regions` are its highlighted spans, if any
+    fn code_line(&self, idx: usize, regions: Option<Vec<Span<'static>>>, line_no_width: usize) -> Line<'static> {
+        let line = &self.lines[idx];
+        let mut spans = Vec::new();
+        let line_no = super::gutter_number(idx + 1, idx, self.selection);
+        let line_no = format!("{:>width$} ", line_no, width = line_no_width);
+        let in_visual = self.visual_range.is_some_and(|(start, end)| {
+            let (lo, hi) = if start <= end { (start, end) } else { (end, start) };
+            idx >= lo && idx <= hi
+        });
+        let line_no_style = if idx == self.selection {
+            Style::default().fg(Color::Black).bg(Color::LightBlue).bold()
+        } else if in_visual {
+            Style::default().fg(Color::Black).bg(Color::LightYellow).bold()
+        } else {
+            Style::default().fg(Color::LightYellow)
+        };
+        if super::line_numbers() {
+            spans.push(Span::styled(line_no, line_no_style));
+            spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
+        }
+        if self.is_css {
+            if let Some(swatch) = css_swatch(line) {
+                spans.push(swatch);
+                spans.push(Span::raw(" "));
+            } else {
+                spans.push(Span::raw("   "));
+            }
+        }
+
+        let mut content = regions.unwrap_or_else(|| vec![Span::raw(line.clone())]);
+        if let Some((_, col)) = self.bracket_match.filter(|&(row, _)| row == idx) {
+            let style = Style::default().fg(Color::Black).bg(Color::LightMagenta).bold();
+            content = super::restyle_char(content, col, style);
+        }
+        if idx == self.selection {
+            content = super::restyle_char(content, self.clamped_cursor(), Style::default().reversed());
+        }
+        spans.extend(super::expand_tabs(content, self.layout.tab_width));
+
+        let mut style = Style::default();
+        if self.syntax_error_lines.contains(&idx) {
+            style = style.fg(Color::Red).bold();
+        }
+        if line.contains("TODO") {
+            style = style.fg(Color::Red).bold();
+        }
+        if idx == self.selection {
+            style = style.bg(Color::LightBlue).fg(Color::Black);
+        } else if in_visual {
+            style = style.bg(Color::LightYellow).fg(Color::Black);
+        }
+        Line::from(spans).style(style)
+    }
+
+    /// The cursor column, kept within the selected line when it is shorter
+    fn clamped_cursor(&self) -> usize {
+        let len = self.lines.get(self.selection).map_or(0, |line| line.chars().count());
+        self.cursor_col.min(len.saturating_sub(1))
+    }
+
+    /// Highlight lines `start..end`, resuming from the closest saved checkpoint.
+    /// Returns `None` when the file has no known syntax.
+    fn highlight_range(&mut self, start: usize, end: usize) -> Option<Vec<Vec<Span<'static>>>> {
+        let syntax = self
+            .syntax
+            .as_ref()
+            .and_then(|name| self.syntax_set.find_syntax_by_name(name))?;
+        let highlighter = Highlighter::new(&self.theme);
+        let checkpoints = &mut self.highlight_checkpoints;
+        if checkpoints.is_empty() {
+            checkpoints.push((
+                ParseState::new(syntax),
+                HighlightState::new(&highlighter, ScopeStack::new()),
+            ));
+        }
+
+        // Extend checkpoints up to the one covering `start`
+        let target = start / HIGHLIGHT_CHECKPOINT_INTERVAL;
+        while checkpoints.len() <= target {
+            let from = (checkpoints.len() - 1) * HIGHLIGHT_CHECKPOINT_INTERVAL;
+            let (mut parse, mut highlight) = checkpoints[checkpoints.len() - 1].clone();
+            for line in self.lines.iter().skip(from).take(HIGHLIGHT_CHECKPOINT_INTERVAL) {
+                highlight_line(&mut parse, &mut highlight, &highlighter, line, &self.syntax_set);
+            }
+            checkpoints.push((parse, highlight));
+        }
+
+        let (mut parse, mut highlight) = checkpoints[target].clone();
+        let mut output = Vec::new();
+        for idx in target * HIGHLIGHT_CHECKPOINT_INTERVAL..end {
+            let spans = highlight_line(
+                &mut parse,
+                &mut highlight,
+                &highlighter,
+                &self.lines[idx],
+                &self.syntax_set,
+            );
+            if idx >= start {
+                output.push(spans);
+            }
+        }
+        Some(output)
+    }
+
+    fn render_markdown(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+        if self.selection >= self.md_rendered.len() {
+            self.selection = self.md_rendered.len().saturating_sub(1);
+        }
+        let height = area.height as usize;
+        let width = area.width as usize;
+        self.scroll = super::follow_selection(self.scroll, self.selection, height, self.md_rendered.len());
+
+        let lines = render_markdown_with_gutter(&self.md_rendered, Some((self.selection, 0)), width);
+        let gutter = if super::line_numbers() { md_line_no_width(&self.md_rendered) + 3 } else { 0 };
+        let output: Vec<Line> = if self.md_wrap {
+            // Wrapped paragraphs take several rows; scroll on until the selected line fits,
+            // so `G` and half-page jumps land on a line that is actually on screen
+            let rows = |idx: usize| super::wrap_line(lines[idx].clone(), width, gutter).len();
+            while self.scroll < self.selection && (self.scroll..=self.selection).map(rows).sum::<usize>() > height {
+                self.scroll += 1;
+            }
+            lines
+                .into_iter()
+                .skip(self.scroll)
+                .flat_map(|line| super::wrap_line(line, width, gutter))
+                .take(height)
+                .collect()
+        } else {
+            lines
+                .into_iter()
+                .skip(self.scroll)
+                .take(height)
+                .map(|line| super::shift_line(line, self.h_scroll, gutter))
+                .collect()
+        };
+
+        let block = Block::default().borders(Borders::NONE);
+        frame.render_widget(Paragraph::new(output).block(block), area);
+    }
+}
+
+impl Engine for SyntaxEngine {
+    fn name(&self) -> &'static str {
+        "SyntaxEngine"
+    }
+
+    fn set_visual_range(&mut self, range: Option<(usize, usize)>) {
+        self.visual_range = range;
+    }
+
+    fn set_layout(&mut self, layout: TextLayout) {
+        if layout.tab_width != self.layout.tab_width {
+            self.widest = None;
+        }
+        self.layout = layout;
+    }
+
+    fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
         self.last_view_height = area.height as usize;
         let chunks = if self.show_sidebar {
             Layout::default()
                 .direction(Direction::Horizontal)
-                .constraints([Constraint::Length(28), Constraint::Min(1)])
+                .constraints([Constraint::Length(SIDEBAR_WIDTH), Constraint::Min(1)])
                 .split(area)
         } else {
             Layout::default()
@@ -124,9 +477,10 @@ impl SyntaxEngine {
         } else {
             self.render_code(frame, chunks[0]);
         }
+        self.render_picker(frame, area);
     }
 
-    pub fn content_height(&mut self) -> usize {
+    fn content_height(&mut self) -> usize {
         if self.is_markdown {
             self.md_rendered.len()
         } else {
@@ -134,9 +488,56 @@ impl SyntaxEngine {
         }
     }
 
-    pub fn render_plain_lines(&mut self) -> Vec<Line<'static>> {
+    /// Parsed structure for `--dump`
+    fn to_json(&mut self) -> serde_json::Value {
+        let components: Vec<_> = self
+            .components
+            .iter()
+            .map(|c| json!({ "name": c.name, "props": c.props }))
+            .collect();
+        let mut errors: Vec<usize> = self.syntax_error_lines.iter().map(|idx| idx + 1).collect();
+        errors.sort_unstable();
+        json!({
+            "syntax": self.syntax,
+            "lines": self.lines.len(),
+            "components": components,
+            "syntax_error_lines": errors,
+        })
+    }
+
+    /// Markdown wraps to the view, so only code can run past its right edge
+    fn content_width(&mut self) -> usize {
+        // Wrapped prose never needs horizontal scrolling
+        if self.is_markdown && self.md_wrap {
+            return 0;
+        }
+        let tab_width = self.layout.tab_width;
+        let widest = *self.widest.get_or_insert_with(|| {
+            let widest = if self.is_markdown {
+                self.md_rendered.iter().map(|line| line.line.width()).max()
+            } else {
+                self.lines.iter().map(|line| super::display_col(line, usize::MAX, tab_width)).max()
+            };
+            widest.unwrap_or(0)
+        });
+        let line_no_width = if self.is_markdown {
+            md_line_no_width(&self.md_rendered)
+        } else {
+            self.lines.len().max(1).to_string().len().max(2)
+        };
+        let gutter = if super::line_numbers() { line_no_width + 3 } else { 0 };
+        if self.is_markdown {
+            return gutter + widest;
+        }
+        // CSS lines start with a color swatch or the blank space it would take
+        let swatch = if self.is_css { 3 } else { 0 };
+        let sidebar = if self.show_sidebar { SIDEBAR_WIDTH as usize } else { 0 };
+        sidebar + gutter + swatch + widest
+    }
+
+    fn render_plain_lines(&mut self, width: u16) -> Vec<Line<'static>> {
         if self.is_markdown {
-            return render_markdown_with_gutter(&self.md_rendered, None);
+            return render_markdown_with_gutter(&self.md_rendered, None, width as usize);
         }
 
         let mut output = Vec::new();
@@ -150,11 +551,13 @@ impl SyntaxEngine {
         for (idx, line) in self.lines.iter().enumerate() {
             let mut spans = Vec::new();
             let line_no = format!("{:>width$} ", idx + 1, width = line_no_width);
-            spans.push(Span::styled(
-                line_no,
-                Style::default().fg(Color::LightYellow),
-            ));
-            spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
+            if super::line_numbers() {
+                spans.push(Span::styled(
+                    line_no,
+                    Style::default().fg(Color::LightYellow),
+                ));
+                spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
+            }
             if self.is_css {
                 if let Some(swatch) = css_swatch(line) {
                     spans.push(swatch);
@@ -163,19 +566,30 @@ impl SyntaxEngine {
                     spans.push(Span::raw("   "));
                 }
             }
-            if let Some(ref mut hl) = highlighter {
+            let content = if let Some(ref mut hl) = highlighter {
                 let line_with_newline = format!("{}\n", line);
                 let regions = hl.highlight_line(&line_with_newline, &self.syntax_set).unwrap_or_default();
-                spans.extend(regions.into_iter().map(|(style, part)| syntect_span(style, part)));
+                regions.into_iter().map(|(style, part)| syntect_span(style, part)).collect()
             } else {
-                spans.push(Span::styled(line.clone(), Style::default().fg(Color::White)));
-            }
+                vec![Span::styled(line.clone(), Style::default().fg(Color::White))]
+            };
+            spans.extend(super::expand_tabs(content, self.layout.tab_width));
             output.push(Line::from(spans));
         }
         output
     }
 
-    pub fn handle_key(&mut self, key: KeyEvent) {
+    #[allow(clippy::collapsible_match)]
+    fn handle_key(&mut self, key: KeyEvent) {
+        self.cursor_moved = true;
+        if self.picker.is_some() {
+            self.handle_picker_key(key);
+            return;
+        }
+        if key.code == KeyCode::Char('@') && !self.is_markdown {
+            self.picker = Some(ListPicker::new(extract_symbols(&self.lines)));
+            return;
+        }
         match key.code {
             KeyCode::Char('g') => {
                 if self.pending_g {
@@ -190,6 +604,13 @@ impl SyntaxEngine {
                 self.pending_g = false;
             }
         }
+        if !self.is_markdown && !key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) {
+            let line = self.lines.get(self.selection);
+            if let Some(col) = line.and_then(|line| super::move_cursor(line, self.clamped_cursor(), key.code)) {
+                self.cursor_col = col;
+                return;
+            }
+        }
         let max_lines = if self.is_markdown {
             self.md_rendered.len()
         } else {
@@ -208,14 +629,12 @@ impl SyntaxEngine {
             KeyCode::Char('u')
                 if key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) =>
             {
-                let jump = page_jump(self.last_view_height).min(self.selection);
-                self.selection = self.selection.saturating_sub(jump);
+                self.selection = super::half_page_up(self.selection, self.last_view_height);
             }
             KeyCode::Char('d')
                 if key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) =>
             {
-                let jump = page_jump(self.last_view_height).min(max_lines.saturating_sub(1));
-                self.selection = (self.selection + jump).min(max_lines.saturating_sub(1));
+                self.selection = super::half_page_down(self.selection, self.last_view_height, max_lines);
             }
             KeyCode::Char('n') => {
                 if let Some(query) = self.last_match.clone() {
@@ -234,6 +653,16 @@ impl SyntaxEngine {
                     }
                 }
             }
+            KeyCode::Char('E') => {
+                if self.is_markdown {
+                    if let Some(prev) = prev_markdown_heading(&self.md_rendered, self.selection) {
+                        self.selection = prev;
+                    }
+                }
+            }
+            KeyCode::Char('s') if self.is_markdown => {
+                self.md_wrap = !self.md_wrap;
+            }
             KeyCode::Char('s') => {
                 self.show_sidebar = !self.show_sidebar;
             }
@@ -246,21 +675,25 @@ impl SyntaxEngine {
         }
     }
 
-    pub fn apply_search(&mut self, query: &str) {
+    fn apply_search(&mut self, query: &str, forward: bool) -> bool {
         let trimmed = query.trim();
         if trimmed.is_empty() {
-            return;
+            return false;
         }
         self.last_query = Some(trimmed.to_string());
-        self.search_next(trimmed, true);
+        let found = self.search_next(trimmed, forward);
         self.last_match = Some(trimmed.to_string());
+        found
     }
 
-    pub fn breadcrumbs(&self) -> String {
+    fn breadcrumbs(&self) -> String {
         format!("{} line {}", self.file_name, self.selection + 1)
     }
 
-    pub fn status_line(&self) -> String {
+    fn status_line(&self) -> String {
+        if self.picker.is_some() {
+            return "type to filter symbols | Up/Down choose | Enter jump | Esc close".to_string();
+        }
         let query = self
             .last_query
             .as_ref()
@@ -271,37 +704,63 @@ impl SyntaxEngine {
         } else {
             format!(" | syntax errors: {}", self.syntax_error_lines.len())
         };
+        let grammar = self
+            .grammar_note
+            .as_ref()
+            .map(|note| format!(" | {}", note))
+            .unwrap_or_default();
+        if self.is_markdown {
+            return format!(
+                "j/k move | gg/G jump | Ctrl+u/d half-page | n/N next/prev | e/E next/prev heading | s wrap {} | / search | f filter{}",
+                if self.md_wrap { "off" } else { "on" },
+                query
+            );
+        }
         format!(
-            "j/k move | gg/G jump | Ctrl+u/d half-page | n/N next/prev | e next heading | s toggle sidebar | / search | f filter{}{}",
-            query, errors
+            "j/k move | gg/G jump | Ctrl+u/d half-page | n/N next/prev | @ go to symbol | s toggle sidebar | / search | f filter{}{}{}",
+            query, errors, grammar
         )
     }
 
-    pub fn apply_filter(&mut self, query: &str) {
+    fn apply_filter(&mut self, query: &str) -> bool {
         // For syntax, filter acts like search - jump to matching lines
-        self.apply_search(query);
+        self.apply_search(query, true)
     }
 
-    pub fn clear_filter(&mut self) {
+    fn clear_filter(&mut self) {
         self.last_query = None;
     }
 
-    #[allow(dead_code)]
-    pub fn selected_path(&self) -> Option<String> {
+    /// Keys specific to this view, listed in the help overlay
+    fn help_lines(&self) -> Vec<Line<'static>> {
+        vec![
+            Line::from("  e/E          Next/prev heading (markdown)"),
+            Line::from("  s            Toggle sidebar (code) / wrapping (markdown)"),
+            Line::from("  h/l, 0/$     Cursor left/right, line start/end"),
+            Line::from("  w/b          Cursor to next/previous word"),
+            Line::from("  @            Go to symbol (type to filter, Enter jumps)"),
+        ]
+    }
+
+    fn captures_input(&self) -> bool {
+        self.picker.is_some()
+    }
+
+    fn selected_path(&self) -> Option<String> {
         None
     }
 
     /// Get the content of the currently selected line
-    pub fn get_selected_line(&self) -> Option<String> {
+    fn get_selected_line(&self) -> Option<String> {
         if self.is_markdown {
-            self.md_rendered.get(self.selection).map(|md| md_line_text(md))
+            self.md_rendered.get(self.selection).map(md_line_text)
         } else {
             self.lines.get(self.selection).cloned()
         }
     }
 
     /// Get lines in a range (inclusive), joined by newlines
-    pub fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
+    fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
         let (start, end) = if start <= end { (start, end) } else { (end, start) };
         if self.is_markdown {
             let total = self.md_rendered.len();
@@ -310,7 +769,7 @@ impl SyntaxEngine {
             }
             let end = end.min(total.saturating_sub(1));
             let lines: Vec<String> = (start..=end)
-                .filter_map(|idx| self.md_rendered.get(idx).map(|md| md_line_text(md)))
+                .filter_map(|idx| self.md_rendered.get(idx).map(md_line_text))
                 .collect();
             if lines.is_empty() { None } else { Some(lines.join("\n")) }
         } else {
@@ -324,127 +783,37 @@ impl SyntaxEngine {
         }
     }
 
+    /// Code rows are the file's lines; rendered markdown rows aren't
+    fn source_line(&self, row: usize) -> Option<usize> {
+        (!self.is_markdown && row < self.lines.len()).then_some(row + 1)
+    }
+
     /// Get current selection index (for visual mode)
-    pub fn selection(&self) -> usize {
+    fn selection(&self) -> usize {
         self.selection
     }
 
-    fn render_sidebar(&self, frame: &mut ratatui::Frame, area: Rect) {
-        let mut lines = Vec::new();
-        lines.push(Line::from("Components"));
-        for comp in &self.components {
-            let props = comp
-                .props
-                .as_ref()
-                .map(|p| format!(" ({})", p))
-                .unwrap_or_default();
-            lines.push(Line::from(format!("- {}{}", comp.name, props)));
-        }
-        let block = Block::default().borders(Borders::RIGHT);
-        frame.render_widget(Paragraph::new(lines).block(block), area);
+    fn select(&mut self, row: usize) {
+        let total = if self.is_markdown { self.md_rendered.len() } else { self.lines.len() };
+        self.selection = row.min(total.saturating_sub(1));
+        self.cursor_moved = true;
     }
 
-    fn render_code(&mut self, frame: &mut ratatui::Frame, area: Rect) {
-        if self.selection < self.scroll {
-            self.scroll = self.selection;
-        } else if self.selection >= self.scroll + area.height as usize {
-            self.scroll = self.selection.saturating_sub(area.height as usize - 1);
-        }
-
-        if self.is_markdown {
-            self.render_markdown(frame, area);
-            return;
-        }
-
-        let syntax = self
-            .syntax
-            .as_ref()
-            .and_then(|name| self.syntax_set.find_syntax_by_name(name));
-        let mut highlighter = syntax
-            .map(|syn| HighlightLines::new(syn, &self.theme));
-
-        let mut output = Vec::new();
-        let line_no_width = self.lines.len().max(1).to_string().len().max(2);
-        for (idx, line) in self.lines.iter().enumerate() {
-            let line_with_newline = format!("{}\n", line);
-            if idx < self.scroll {
-                if let Some(ref mut hl) = highlighter {
-                    let _ = hl.highlight_line(&line_with_newline, &self.syntax_set);
-                }
-                continue;
-            }
-            if idx >= self.scroll + area.height as usize {
-                break;
-            }
-            let mut spans = Vec::new();
-            let line_no = format!("{:>width$} ", idx + 1, width = line_no_width);
-            let in_visual = self.visual_range.map_or(false, |(start, end)| {
-                let (lo, hi) = if start <= end { (start, end) } else { (end, start) };
-                idx >= lo && idx <= hi
-            });
-            let line_no_style = if idx == self.selection {
-                Style::default().fg(Color::Black).bg(Color::LightBlue).bold()
-            } else if in_visual {
-                Style::default().fg(Color::Black).bg(Color::LightYellow).bold()
-            } else {
-                Style::default().fg(Color::LightYellow)
-            };
-            spans.push(Span::styled(line_no, line_no_style));
-            spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
-            if self.is_css {
-                if let Some(swatch) = css_swatch(line) {
-                    spans.push(swatch);
-                    spans.push(Span::raw(" "));
-                } else {
-                    spans.push(Span::raw("   "));
-                }
-            }
-
-            if let Some(ref mut hl) = highlighter {
-                let regions = hl.highlight_line(&line_with_newline, &self.syntax_set).unwrap_or_default();
-                spans.extend(regions.into_iter().map(|(style, part)| syntect_span(style, part)));
-            } else {
-                spans.push(Span::raw(line.clone()));
-            }
-
-            let mut line_widget = Line::from(spans);
-            let mut style = Style::default();
-            if self.syntax_error_lines.contains(&idx) {
-                style = style.fg(Color::Red).bold();
-            }
-            if line.contains("TODO") {
-                style = style.fg(Color::Red).bold();
-            }
-            if idx == self.selection {
-                style = style.bg(Color::LightBlue).fg(Color::Black);
-            } else if in_visual {
-                style = style.bg(Color::LightYellow).fg(Color::Black);
-            }
-            line_widget = line_widget.style(style);
-            output.push(line_widget);
-        }
-
-        let block = Block::default().borders(Borders::NONE);
-        frame.render_widget(Paragraph::new(output).block(block), area);
+    fn cursor_col(&self) -> Option<usize> {
+        (!self.is_markdown).then(|| self.clamped_cursor())
     }
 
-    fn render_markdown(&mut self, frame: &mut ratatui::Frame, area: Rect) {
-        if self.selection >= self.md_rendered.len() {
-            self.selection = self.md_rendered.len().saturating_sub(1);
-        }
-        let height = area.height as usize;
-        if self.selection < self.scroll {
-            self.scroll = self.selection;
-        } else if self.selection >= self.scroll + height {
-            self.scroll = self.selection.saturating_sub(height - 1);
-        }
+    /// Scroll so the selection sits at the top, middle or bottom of the view (`zt`/`zz`/`zb`)
+    fn align_scroll(&mut self, align: super::ScrollAlign) {
+        self.scroll = super::aligned_scroll(self.selection, self.last_view_height, align);
+    }
 
-        let mut output = render_markdown_with_gutter(&self.md_rendered, Some((self.selection, self.scroll)));
-        output.truncate(height);
+    fn h_scroll(&self) -> usize {
+        self.h_scroll
+    }
 
-        let block = Block::default().borders(Borders::NONE);
-        let paragraph = Paragraph::new(output).block(block).wrap(ratatui::widgets::Wrap { trim: false });
-        frame.render_widget(paragraph, area);
+    fn set_h_scroll(&mut self, cols: usize) {
+        self.h_scroll = cols;
     }
 }
 
@@ -456,6 +825,83 @@ fn syntect_span(style: SynStyle, text: &str) -> Span<'static> {
     )
 }
 
+fn highlight_line(
+    parse: &mut ParseState,
+    highlight: &mut HighlightState,
+    highlighter: &Highlighter,
+    line: &str,
+    syntax_set: &SyntaxSet,
+) -> Vec<Span<'static>> {
+    let line_with_newline = format!("{}\n", line);
+    let ops = parse.parse_line(&line_with_newline, syntax_set).unwrap_or_default();
+    HighlightIterator::new(highlight, &ops, &line_with_newline, highlighter)
+        .map(|(style, part)| syntect_span(style, part))
+        .collect()
+}
+
+/// Where a bracket on line `row` is closed, as (line, char index): the one under the
+/// cursor at `cursor`, else the innermost one left open at the end of the line.
+/// Skips string literals and `//` and `/* */` comments; a rough scan, not a parser.
+fn matching_bracket(lines: &[String], row: usize, cursor: usize) -> Option<(usize, usize)> {
+    let mut depth = 0usize;
+    // Depth of the bracket to match, known once the selected line is scanned
+    let mut target = None;
+    let mut in_block_comment = false;
+    for (idx, line) in lines.iter().enumerate().skip(row) {
+        let chars: Vec<char> = line.chars().collect();
+        let mut quote: Option<char> = None;
+        let mut col = 0;
+        while col < chars.len() {
+            let c = chars[col];
+            let next = chars.get(col + 1).copied();
+            if in_block_comment {
+                if c == '*' && next == Some('/') {
+                    in_block_comment = false;
+                    col += 1;
+                }
+            } else if let Some(q) = quote {
+                if c == '\\' {
+                    col += 1;
+                } else if c == q {
+                    quote = None;
+                }
+            } else {
+                match c {
+                    '/' if next == Some('/') => break,
+                    '/' if next == Some('*') => {
+                        in_block_comment = true;
+                        col += 1;
+                    }
+                    // A Rust lifetime like `'a` isn't a character literal
+                    '\'' if next.is_some_and(char::is_alphabetic) && chars.get(col + 2) != Some(&'\'') => {}
+                    '"' | '\'' | '`' => quote = Some(c),
+                    '(' | '[' | '{' => {
+                        depth += 1;
+                        if idx == row && col == cursor {
+                            target = Some(depth);
+                        }
+                    }
+                    ')' | ']' | '}' => {
+                        if target == Some(depth) {
+                            return Some((idx, col));
+                        }
+                        depth = depth.saturating_sub(1);
+                    }
+                    _ => {}
+                }
+            }
+            col += 1;
+        }
+        if idx == row && target.is_none() {
+            if depth == 0 {
+                return None;
+            }
+            target = Some(depth);
+        }
+    }
+    None
+}
+
 fn css_swatch(line: &str) -> Option<Span<'static>> {
     let hex_re = Regex::new(r"#(?P<hex>[0-9a-fA-F]{6})").ok()?;
     let rgb_re = Regex::new(r"rgb\((?P<r>\d{1,3}),\s*(?P<g>\d{1,3}),\s*(?P<b>\d{1,3})\)").ok()?;
@@ -493,17 +939,24 @@ fn extract_components(content: &str, ext: &str) -> Vec<ComponentInfo> {
     comps
 }
 
-fn extract_components_tree_sitter(content: &str, ext: &str) -> Vec<ComponentInfo> {
-    let mut parser = Parser::new();
+/// A tree-sitter parser for `ext`; None when there is no grammar for it, and an
+/// error when the grammar doesn't load
+fn grammar_parser(ext: &str) -> Option<Result<Parser, tree_sitter::LanguageError>> {
     let language = match ext {
         "ts" => ts_ts::language_typescript(),
         "tsx" => ts_ts::language_tsx(),
         "js" | "jsx" => ts_js::language(),
-        _ => return Vec::new(),
+        "css" | "tcss" => ts_css::language(),
+        _ => return None,
     };
-    if parser.set_language(&language).is_err() {
+    let mut parser = Parser::new();
+    Some(parser.set_language(&language).map(|()| parser))
+}
+
+fn extract_components_tree_sitter(content: &str, ext: &str) -> Vec<ComponentInfo> {
+    let Some(Ok(mut parser)) = grammar_parser(ext) else {
         return Vec::new();
-    }
+    };
     let tree = match parser.parse(content, None) {
         Some(tree) => tree,
         None => return Vec::new(),
@@ -608,6 +1061,115 @@ fn extract_components_regex(content: &str) -> Vec<ComponentInfo> {
     comps
 }
 
+/// Language run by a `#!` interpreter line, e.g. `#!/usr/bin/env -S python3 -u`;
+/// versioned interpreters (`python3.12`, `ruby2.7`) name their language
+fn shebang_language(content: &str) -> Option<&'static str> {
+    let line = content.lines().next()?.strip_prefix("#!")?;
+    let mut words = line.split_whitespace();
+    let mut program = words.next()?.rsplit('/').next()?;
+    if program == "env" {
+        // Skip env's own flags and `NAME=value` assignments
+        program = words.find(|word| !word.starts_with('-') && !word.contains('='))?;
+    }
+    let name = program.trim_end_matches(|c: char| c.is_ascii_digit() || c == '.' || c == '-');
+    Some(match name {
+        "sh" | "bash" | "zsh" | "ksh" | "dash" | "ash" => "bash",
+        "python" | "pypy" => "python",
+        "node" | "nodejs" | "deno" | "bun" => "js",
+        "ruby" => "ruby",
+        "perl" => "perl",
+        "php" => "php",
+        "lua" | "luajit" => "lua",
+        "Rscript" => "r",
+        "tclsh" | "wish" => "tcl",
+        "groovy" => "groovy",
+        "scala" => "scala",
+        _ => return None,
+    })
+}
+
+/// Language named by a vim or emacs modeline in the first or last five lines,
+/// e.g. `# vim: ft=rust` or `# -*- mode: python -*-`
+pub fn modeline_language(content: &str) -> Option<String> {
+    let vim = Regex::new(r"(?:^|\s)(?:vim?|ex):.*?\b(?:ft|filetype|syntax|syn)=([\w+#-]+)").ok()?;
+    let emacs_mode = Regex::new(r"(?:^|;)\s*mode:\s*([\w+-]+)").ok()?;
+    let lines: Vec<&str> = content.lines().collect();
+    let tail_start = lines.len().saturating_sub(5).max(5);
+    lines.iter().take(5).chain(lines.iter().skip(tail_start)).find_map(|line| {
+        if let Some(caps) = vim.captures(line) {
+            return Some(caps[1].to_string());
+        }
+        let (_, rest) = line.split_once("-*-")?;
+        let (inner, _) = rest.split_once("-*-")?;
+        match emacs_mode.captures(inner) {
+            Some(caps) => Some(caps[1].to_string()),
+            // `-*- python -*-` names the mode alone
+            None => {
+                let mode = inner.trim();
+                (!mode.is_empty() && !mode.contains([':', ' '])).then(|| mode.to_string())
+            }
+        }
+    })
+}
+
+/// `linguist-language` given to `path` by the nearest `.gitattributes`, looking up
+/// to the repository root; the last matching line in a file wins, as in git
+fn gitattributes_language(path: &Path) -> Option<String> {
+    let path = path.canonicalize().ok()?;
+    for dir in path.ancestors().skip(1) {
+        if let Ok(text) = std::fs::read_to_string(dir.join(".gitattributes")) {
+            let relative = path.strip_prefix(dir).ok()?.to_str()?.replace('\\', "/");
+            let language = text.lines().rev().find_map(|line| {
+                let mut fields = line.split_whitespace();
+                let pattern = fields.next().filter(|p| !p.starts_with('#'))?;
+                let language = fields.rev().find_map(|attr| attr.strip_prefix("linguist-language="))?;
+                attribute_pattern_matches(pattern, &relative).then(|| language.to_string())
+            });
+            if language.is_some() {
+                return language;
+            }
+        }
+        if dir.join(".git").exists() {
+            break;
+        }
+    }
+    None
+}
+
+/// A `.gitattributes` pattern: without a `/` it matches the file name at any depth,
+/// with one it matches the path from the attributes file's directory
+fn attribute_pattern_matches(pattern: &str, relative: &str) -> bool {
+    use super::gitignore::glob_match;
+
+    if !pattern.contains('/') {
+        return glob_match(pattern, relative.rsplit('/').next().unwrap_or(relative));
+    }
+    let parts: Vec<&str> = pattern.trim_start_matches('/').split('/').collect();
+    let names: Vec<&str> = relative.split('/').collect();
+    parts.len() == names.len() && parts.iter().zip(&names).all(|(part, name)| glob_match(part, name))
+}
+
+/// Definitions worth jumping to: functions, types, classes and modules in Rust,
+/// Python, JavaScript/TypeScript, Go and similar languages, matched per line
+fn extract_symbols(lines: &[String]) -> Vec<Symbol> {
+    let definition = Regex::new(
+        r"^\s*(?:(?:pub(?:\([^)]*\))?|export|default|async|unsafe|const|extern|abstract|public|private|protected|static)\s+)*(fn|struct|enum|trait|mod|type|union|macro_rules!|def|class|function\*?|func|interface)\s+([A-Za-z_$][\w$]*)",
+    )
+    .unwrap();
+    lines
+        .iter()
+        .enumerate()
+        .filter_map(|(line, text)| {
+            let caps = definition.captures(text)?;
+            Some(Symbol {
+                line,
+                kind: caps[1].trim_end_matches(['!', '*']).to_string(),
+                name: caps[2].to_string(),
+            })
+        })
+        .collect()
+}
+
 fn extract_props(args: &str) -> Option<String> {
     let trimmed = args
         .trim()
@@ -622,17 +1184,9 @@ fn extract_props(args: &str) -> Option<String> {
 
 fn parse_syntax_errors(content: &str, ext: &str) -> HashSet<usize> {
     let mut errors = HashSet::new();
-    let language = match ext {
-        "ts" => ts_ts::language_typescript(),
-        "tsx" => ts_ts::language_tsx(),
-        "js" | "jsx" => ts_js::language(),
-        "css" | "tcss" => ts_css::language(),
-        _ => return errors,
-    };
-    let mut parser = Parser::new();
-    if parser.set_language(&language).is_err() {
+    let Some(Ok(mut parser)) = grammar_parser(ext) else {
         return errors;
-    }
+    };
     let tree = match parser.parse(content, None) {
         Some(tree) => tree,
         None => return errors,
@@ -643,7 +1197,7 @@ fn parse_syntax_errors(content: &str, ext: &str) -> HashSet<usize> {
 
 fn collect_error_lines(node: tree_sitter::Node, errors: &mut HashSet<usize>) {
     if node.is_error() {
-        errors.insert(node.start_position().row as usize);
+        errors.insert(node.start_position().row);
     }
     let mut cursor = node.walk();
     for child in node.children(&mut cursor) {
@@ -665,6 +1219,9 @@ fn render_markdown(content: &str) -> Vec<MdLine> {
     renderer.lines
 }
 
+/// Length of a markdown rule before it's stretched to the view
+const MD_RULE_MIN: usize = 3;
+
 struct MdRenderer {
     lines: Vec<MdLine>,
     current: Vec<Span<'static>>,
@@ -689,6 +1246,7 @@ impl MdRenderer {
             self.lines.push(MdLine {
                 line: Line::from(self.current.drain(..).collect::<Vec<_>>()),
                 source_line: self.current_source,
+                rule: false,
             });
             self.current_source = None;
         }
@@ -699,6 +1257,7 @@ impl MdRenderer {
         self.lines.push(MdLine {
             line: Line::from(""),
             source_line: None,
+            rule: false,
         });
     }
 
@@ -709,7 +1268,7 @@ impl MdRenderer {
         in_quote: bool,
     ) {
         use comrak::nodes::NodeValue;
-        let source = node.data.borrow().sourcepos.start.line.saturating_sub(1) as usize;
+        let source = node.data.borrow().sourcepos.start.line.saturating_sub(1);
         match &node.data.borrow().value {
             NodeValue::Heading(heading) => {
                 self.blank_line();
@@ -722,6 +1281,7 @@ impl MdRenderer {
                 self.lines.push(MdLine {
                     line: Line::from(spans),
                     source_line: Some(source),
+                    rule: false,
                 });
                 self.blank_line();
             }
@@ -738,6 +1298,7 @@ impl MdRenderer {
                 self.lines.push(MdLine {
                     line: Line::from(spans),
                     source_line: Some(source),
+                    rule: false,
                 });
                 self.blank_line();
             }
@@ -758,12 +1319,14 @@ impl MdRenderer {
                     self.lines.push(MdLine {
                         line: Line::from(spans),
                         source_line: Some(source + offset),
+                        rule: false,
                     });
                 }
                 self.blank_line();
             }
             NodeValue::List(list) => {
-                let mut idx = 1;
+                // Numbering restarts for every list, nested ones included
+                let mut idx = list.start;
                 for child in node.children() {
                     let bullet = if list.list_type == comrak::nodes::ListType::Ordered {
                         let marker = format!("{}. ", idx);
@@ -774,6 +1337,47 @@ impl MdRenderer {
                     };
                     self.render_list_item(child, indent, in_quote, bullet);
                 }
+                let nested = node.parent().is_some_and(|parent| {
+                    matches!(parent.data.borrow().value, NodeValue::Item(..) | NodeValue::TaskItem(..))
+                });
+                if !nested {
+                    self.blank_line();
+                }
+            }
+            NodeValue::ThematicBreak => {
+                self.flush_line();
+                let mut spans = Vec::new();
+                if in_quote {
+                    spans.push(Span::styled("> ", Style::default().fg(Color::LightCyan)));
+                }
+                if indent > 0 {
+                    spans.push(Span::raw(" ".repeat(indent)));
+                }
+                spans.push(Span::styled("─".repeat(MD_RULE_MIN), Style::default().fg(Color::DarkGray)));
+                self.lines.push(MdLine {
+                    line: Line::from(spans),
+                    source_line: Some(source),
+                    rule: true,
+                });
+                self.blank_line();
+            }
+            NodeValue::HtmlBlock(html) => {
+                // Shown as-is, dimmed, since there's no HTML layout to apply
+                for (offset, line) in html.literal.lines().enumerate() {
+                    let mut spans = Vec::new();
+                    if in_quote {
+                        spans.push(Span::styled("> ", Style::default().fg(Color::LightCyan)));
+                    }
+                    if indent > 0 {
+                        spans.push(Span::raw(" ".repeat(indent)));
+                    }
+                    spans.push(Span::styled(line.to_string(), Style::default().fg(Color::DarkGray)));
+                    self.lines.push(MdLine {
+                        line: Line::from(spans),
+                        source_line: Some(source + offset),
+                        rule: false,
+                    });
+                }
                 self.blank_line();
             }
             NodeValue::BlockQuote => {
@@ -798,7 +1402,10 @@ impl MdRenderer {
         in_quote: bool,
         bullet: String,
     ) {
-        let source = node.data.borrow().sourcepos.start.line.saturating_sub(1) as usize;
+        use comrak::nodes::NodeValue;
+        let source = node.data.borrow().sourcepos.start.line.saturating_sub(1);
+        // Nested blocks line up with the item's text, past the bullet
+        let child_indent = indent + bullet.chars().count();
         let mut spans = Vec::new();
         if in_quote {
             spans.push(Span::styled("> ", Style::default().fg(Color::LightCyan)));
@@ -806,13 +1413,20 @@ impl MdRenderer {
         if indent > 0 {
             spans.push(Span::raw(" ".repeat(indent)));
         }
-                spans.push(Span::styled(bullet, Style::default().fg(Color::LightYellow)));
-                spans.extend(self.render_inlines(node, Style::default().fg(Color::White)));
-                self.lines.push(MdLine {
-                    line: Line::from(spans),
-                    source_line: Some(source),
-                });
-            }
+        spans.push(Span::styled(bullet, Style::default().fg(Color::LightYellow)));
+        let mut children = node.children().peekable();
+        if let Some(first) = children.next_if(|child| matches!(child.data.borrow().value, NodeValue::Paragraph)) {
+            spans.extend(self.render_inlines(first, Style::default().fg(Color::White)));
+        }
+        self.lines.push(MdLine {
+            line: Line::from(spans),
+            source_line: Some(source),
+            rule: false,
+        });
+        for child in children {
+            self.render_block(child, child_indent, in_quote);
+        }
+    }
 
     fn render_inlines<'a>(
         &self,
@@ -848,6 +1462,17 @@ impl MdRenderer {
                     ));
                     spans.extend(link_spans);
                 }
+                NodeValue::Image(link) => {
+                    let alt: String = self
+                        .render_inlines(child, base_style)
+                        .iter()
+                        .map(|span| span.content.as_ref())
+                        .collect();
+                    spans.push(Span::styled(
+                        format!("[image: {}]({})", alt, link.url),
+                        base_style.fg(Color::LightMagenta),
+                    ));
+                }
                 NodeValue::SoftBreak | NodeValue::LineBreak => {
                     spans.push(Span::styled(" ".to_string(), base_style));
                 }
@@ -872,6 +1497,8 @@ fn heading_style(level: u8) -> Style {
 struct MdLine {
     line: Line<'static>,
     source_line: Option<usize>,
+    /// A thematic break, stretched to the view width when drawn
+    rule: bool,
 }
 
 fn md_line_text(line: &MdLine) -> String {
@@ -883,11 +1510,9 @@ fn md_line_text(line: &MdLine) -> String {
         .join("")
 }
 
-fn render_markdown_with_gutter(
-    lines: &[MdLine],
-    selection: Option<(usize, usize)>,
-) -> Vec<Line<'static>> {
-    let line_no_width = lines
+/// Digits in the largest source line number shown in the markdown gutter
+fn md_line_no_width(lines: &[MdLine]) -> usize {
+    lines
         .iter()
         .filter_map(|line| line.source_line)
         .max()
@@ -895,7 +1520,16 @@ fn render_markdown_with_gutter(
         .saturating_add(1)
         .to_string()
         .len()
-        .max(2);
+        .max(2)
+}
+
+/// Rules fill `width` columns; narrower widths leave them at their minimum length
+fn render_markdown_with_gutter(
+    lines: &[MdLine],
+    selection: Option<(usize, usize)>,
+    width: usize,
+) -> Vec<Line<'static>> {
+    let line_no_width = md_line_no_width(lines);
     let (sel, scroll) = selection.unwrap_or((usize::MAX, 0));
     lines
         .iter()
@@ -913,9 +1547,18 @@ fn render_markdown_with_gutter(
                 Style::default().fg(Color::LightYellow)
             };
             let mut spans = Vec::new();
-            spans.push(Span::styled(line_no, line_no_style));
-            spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
+            if super::line_numbers() {
+                spans.push(Span::styled(line_no, line_no_style));
+                spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
+            }
             spans.extend(line.line.spans.clone());
+            if line.rule {
+                let used: usize = spans.iter().map(|span| span.width()).sum();
+                if let Some(rule) = spans.last_mut() {
+                    let len = (width.saturating_sub(used) + rule.width()).max(MD_RULE_MIN);
+                    rule.content = "─".repeat(len).into();
+                }
+            }
             let mut line_widget = Line::from(spans);
             if row == sel {
                 line_widget =
@@ -927,40 +1570,38 @@ fn render_markdown_with_gutter(
 }
 
 fn next_markdown_heading(lines: &[MdLine], current: usize) -> Option<usize> {
-    for (idx, line) in lines.iter().enumerate().skip(current + 1) {
-        for span in &line.line.spans {
-            let style = span.style;
-            if style.add_modifier.contains(ratatui::style::Modifier::BOLD)
-                && matches!(
-                    style.fg,
-                    Some(Color::LightMagenta | Color::LightCyan | Color::LightBlue | Color::LightYellow)
-                )
-            {
-                return Some(idx);
-            }
-        }
-    }
-    None
+    (current + 1..lines.len()).find(|&idx| is_markdown_heading(&lines[idx]))
 }
 
-fn page_jump(view_height: usize) -> usize {
-    let half = view_height / 2;
-    if half == 0 { 1 } else { half }
+fn prev_markdown_heading(lines: &[MdLine], current: usize) -> Option<usize> {
+    (0..current.min(lines.len())).rev().find(|&idx| is_markdown_heading(&lines[idx]))
+}
+
+fn is_markdown_heading(line: &MdLine) -> bool {
+    line.line.spans.iter().any(|span| {
+        let style = span.style;
+        style.add_modifier.contains(ratatui::style::Modifier::BOLD)
+            && matches!(
+                style.fg,
+                Some(Color::LightMagenta | Color::LightCyan | Color::LightBlue | Color::LightYellow)
+            )
+    })
 }
 
 impl SyntaxEngine {
-    fn search_next(&mut self, query: &str, forward: bool) {
+    fn search_next(&mut self, query: &str, forward: bool) -> bool {
         let trimmed = query.trim();
         if trimmed.is_empty() {
-            return;
+            return false;
         }
         let lower = trimmed.to_lowercase();
+        let mut found = false;
         if self.is_markdown {
             let total = self.md_rendered.len().max(1);
             let start = if forward {
                 (self.selection + 1) % total
             } else {
-                self.selection.saturating_sub(1)
+                (self.selection + total - 1) % total
             };
             for offset in 0..self.md_rendered.len() {
                 let idx = if forward {
@@ -968,8 +1609,9 @@ impl SyntaxEngine {
                 } else {
                     (start + total - offset % total) % total
                 };
-                if md_line_text(&self.md_rendered[idx]).to_lowercase().contains(&lower) {
+                if search_matches(&md_line_text(&self.md_rendered[idx]), &lower) {
                     self.selection = idx;
+                    found = true;
                     break;
                 }
             }
@@ -978,7 +1620,7 @@ impl SyntaxEngine {
             let start = if forward {
                 (self.selection + 1) % total
             } else {
-                self.selection.saturating_sub(1)
+                (self.selection + total - 1) % total
             };
             for offset in 0..self.lines.len() {
                 let idx = if forward {
@@ -986,13 +1628,15 @@ impl SyntaxEngine {
                 } else {
                     (start + total - offset % total) % total
                 };
-                if self.lines[idx].to_lowercase().contains(&lower) {
+                if search_matches(&self.lines[idx], &lower) {
                     self.selection = idx;
+                    found = true;
                     break;
                 }
             }
         }
         self.last_match = Some(trimmed.to_string());
+        found
     }
 }
 
@@ -1000,6 +1644,85 @@ impl SyntaxEngine {
 mod tests {
     use super::*;
 
+    #[test]
+    fn symbol_picker_jumps_to_the_best_fuzzy_match() {
+        let source = b"use std::fs;\n\npub(crate) fn parse_dockerfile(text: &str) {}\n\nstruct Config;\nfn pairs() {}\n";
+        let mut engine = SyntaxEngine::from_bytes(Path::new("lib.rs"), source).unwrap();
+        let press = |engine: &mut SyntaxEngine, code| engine.handle_key(KeyEvent::from(code));
+
+        press(&mut engine, KeyCode::Char('@'));
+        assert!(engine.captures_input());
+        for c in "parse".chars() {
+            press(&mut engine, KeyCode::Char(c));
+        }
+        let names: Vec<&str> = engine.picker.as_ref().unwrap().matches().iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, ["parse_dockerfile"]);
+        press(&mut engine, KeyCode::Enter);
+        assert!(!engine.captures_input());
+        assert_eq!(engine.selection(), 2);
+
+        assert_eq!(crate::engines::fuzzy_cost("prs", "Parser"), Some(1));
+        assert_eq!(crate::engines::fuzzy_cost("prs", "pairs"), Some(2));
+        assert_eq!(crate::engines::fuzzy_cost("xyz", "pairs"), None);
+    }
+
+    #[test]
+    fn markdown_end_shows_last_line_when_paragraphs_wrap() {
+        let mut source = String::new();
+        for idx in 0..6 {
+            source.push_str(&format!("Paragraph {} {}\n\n", idx, "word ".repeat(20)));
+        }
+        source.push_str("The end\n");
+        let mut engine = SyntaxEngine::from_bytes(Path::new("notes.md"), source.as_bytes()).unwrap();
+        engine.handle_key(KeyEvent::from(KeyCode::Char('G')));
+
+        let backend = ratatui::backend::TestBackend::new(30, 6);
+        let mut terminal = ratatui::Terminal::new(backend).unwrap();
+        terminal.draw(|frame| engine.render(frame, frame.size())).unwrap();
+        let buffer = terminal.backend().buffer();
+        let rows: Vec<String> = (0..6).map(|y| (0..30).map(|x| buffer.get(x, y).symbol().to_string()).collect()).collect();
+        assert!(rows.iter().any(|row| row.contains("The end")), "{:?}", rows);
+
+        // Without wrapping each line is one row and scrolls sideways
+        engine.handle_key(KeyEvent::from(KeyCode::Char('s')));
+        assert!(engine.content_width() > 30);
+    }
+
+    #[test]
+    fn shebangs_modelines_and_gitattributes_pick_the_grammar() {
+        let syntax = |name: &str, source: &str| {
+            SyntaxEngine::from_bytes(Path::new(name), source.as_bytes()).unwrap().syntax.unwrap()
+        };
+        assert_eq!(syntax("deploy", "#!/bin/bash\necho hi\n"), "Bourne Again Shell (bash)");
+        assert_eq!(syntax("build", "fn main() {}\n// vim: set ft=rust :\n"), "Rust");
+        assert_eq!(syntax("tool", "# -*- mode: python -*-\nprint(1)\n"), "Python");
+        assert_eq!(modeline_language("# -*- ruby -*-\n"), Some("ruby".to_string()));
+        assert_eq!(syntax("serve", "#!/usr/bin/env -S python3.12 -u\nprint(1)\n"), "Python");
+        assert_eq!(syntax("run", "#!/usr/bin/env node\nconsole.log(1)\n"), "JavaScript");
+        assert_eq!(shebang_language("#!/usr/local/bin/zsh -f\n"), Some("bash"));
+        assert_eq!(shebang_language("#!/opt/tool/bin/frobnicate\n"), None);
+
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(dir.path().join(".gitattributes"), "*.tpl linguist-language=HTML\n").unwrap();
+        let page = dir.path().join("page.tpl");
+        std::fs::write(&page, "<p>hi</p>\n").unwrap();
+        assert_eq!(SyntaxEngine::from_path(&page).unwrap().syntax.as_deref(), Some("HTML"));
+    }
+
+    #[test]
+    fn sideways_scroll_survives_render_until_the_cursor_moves() {
+        let source = format!("let x = \"{}\";\n", "y".repeat(100));
+        let mut engine = SyntaxEngine::from_bytes(Path::new("wide.rs"), source.as_bytes()).unwrap();
+        let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(30, 4)).unwrap();
+        engine.set_h_scroll(3);
+        terminal.draw(|frame| engine.render(frame, frame.size())).unwrap();
+        assert_eq!(engine.h_scroll(), 3);
+
+        engine.handle_key(KeyEvent::from(KeyCode::Char('$')));
+        terminal.draw(|frame| engine.render(frame, frame.size())).unwrap();
+        assert!(engine.h_scroll() > 3);
+    }
+
     #[test]
     fn detects_syntax_errors() {
         let content = "function () {";
@@ -1015,6 +1738,52 @@ mod tests {
         assert!(!lines.is_empty());
     }
 
+    #[test]
+    fn nested_markdown_lists_indent_and_restart_numbering() {
+        let content = "- outer\n  - inner\n    - deepest\n- next\n\n3. three\n   1. one\n   2. two\n4. four\n";
+        let texts: Vec<String> = render_markdown(content).iter().map(md_line_text).collect();
+        let find = |text: &str| texts.iter().find(|line| line.trim_start().ends_with(text)).unwrap().clone();
+        assert_eq!(find("outer"), "- outer");
+        assert_eq!(find("inner"), "  - inner");
+        assert_eq!(find("deepest"), "    - deepest");
+        assert_eq!(find("next"), "- next");
+        assert_eq!(find("three"), "3. three");
+        assert_eq!(find("one"), "   1. one");
+        assert_eq!(find("two"), "   2. two");
+        assert_eq!(find("four"), "4. four");
+    }
+
+    #[test]
+    fn markdown_rules_fill_the_width_and_images_show_their_source() {
+        let content = "Intro ![logo](img/logo.png)\n\n---\n\nAfter\n";
+        let md = render_markdown(content);
+        let rule = md.iter().position(|line| line.rule).expect("rule line");
+        assert_eq!(md[rule].source_line, Some(2));
+        let lines = render_markdown_with_gutter(&md, None, 40);
+        assert_eq!(lines[rule].width(), 40);
+        assert!(md_line_text(&md[0]).contains("[image: logo](img/logo.png)"));
+    }
+
+    #[test]
+    fn checkpointed_highlighting_matches_full_pass() {
+        // A docstring that straddles the first checkpoint boundary
+        let mut content = String::new();
+        for idx in 0..HIGHLIGHT_CHECKPOINT_INTERVAL - 2 {
+            content.push_str(&format!("x{} = {}\n", idx, idx));
+        }
+        content.push_str("\"\"\"\ninside\nstill inside\n\"\"\"\ny = 2\n");
+        let path = std::env::temp_dir().join(format!("lens_test_{}.py", std::process::id()));
+        std::fs::write(&path, &content).unwrap();
+        let mut engine = SyntaxEngine::from_path(&path).unwrap();
+        std::fs::remove_file(&path).ok();
+
+        let total = engine.lines.len();
+        let full = engine.highlight_range(0, total).unwrap();
+        let start = HIGHLIGHT_CHECKPOINT_INTERVAL;
+        let window = engine.highlight_range(start, total).unwrap();
+        assert_eq!(window, full[start..].to_vec());
+    }
+
     #[test]
     fn python_multiline_string_highlighting_preserved() {
         // Test that highlighting state is preserved across lines for Python multiline strings
@@ -1055,4 +1824,36 @@ y = 2
         assert!(!string_line.is_empty());
         assert!(!code_line.is_empty());
     }
+
+    #[test]
+    fn matching_bracket_skips_strings_and_comments() {
+        let lines: Vec<String> = [
+            "fn main() {",
+            "    let s = \"}\"; // }",
+            "    if x { y(); }",
+            "}",
+        ]
+        .iter()
+        .map(|line| line.to_string())
+        .collect();
+        assert_eq!(matching_bracket(&lines, 0, 0), Some((3, 0)));
+        assert_eq!(matching_bracket(&lines, 2, 0), None);
+        // With the cursor on the `(` of `y()`, its own close on the same line
+        assert_eq!(matching_bracket(&lines, 2, 12), Some((2, 13)));
+
+        let spans = crate::engines::restyle_char(vec![Span::raw("ab"), Span::raw("cd")], 2, Style::default().bold());
+        let parts: Vec<&str> = spans.iter().map(|span| span.content.as_ref()).collect();
+        assert_eq!(parts, ["ab", "c", "d"]);
+    }
+
+    #[test]
+    fn bundled_grammars_load_without_a_note() {
+        assert!(matches!(grammar_parser("js"), Some(Ok(_))));
+        assert!(matches!(grammar_parser("css"), Some(Ok(_))));
+        assert!(grammar_parser("py").is_none());
+
+        let engine = SyntaxEngine::from_bytes(Path::new("app.js"), b"export function App() {}\n").unwrap();
+        assert!(engine.grammar_note.is_none());
+        assert!(!engine.status_line().contains("unavailable"));
+    }
 }
@@ -1,13 +1,19 @@
 use std::fs::File;
-use std::path::Path;
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
 
 use anyhow::{anyhow, Result};
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use polars::io::mmap::MmapBytesReader;
 use polars::prelude::*;
 use ratatui::layout::{Constraint, Rect};
 use ratatui::style::{Color, Style, Stylize};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Cell, Row, Table, TableState};
+use serde_json::json;
+
+use super::util::{search_matches, Source};
+use super::Engine;
 
 /// TableEngine for CSV/TSV/Parquet files.
 /// Uses Polars DataFrame for efficient columnar storage.
@@ -15,9 +21,24 @@ use ratatui::widgets::{Block, Borders, Cell, Row, Table, TableState};
 /// random access. For Parquet, Polars uses efficient columnar storage with lazy evaluation.
 pub struct TableEngine {
     df: DataFrame,
+    /// CSV/TSV file and separator, read again from disk when the header toggle changes.
+    /// None for other formats and for CSV that only exists in memory (decompressed)
+    csv_source: Option<(PathBuf, u8)>,
+    /// Whether the first CSV row is used as column names
+    has_header: bool,
+    /// All rows while a `column op value` filter narrows `df`, and that filter's text
+    unfiltered: Option<(DataFrame, String)>,
     selection: usize,
+    /// Column of the cell cursor within the selected row
+    column: usize,
+    /// Open cell detail popup: (column name, full value)
+    detail: Option<(String, String)>,
     scroll: usize,
     schema_view: bool,
+    /// Statistic shown in the footer row for numeric columns (`a` cycles it)
+    aggregate: Option<Aggregate>,
+    /// Footer cells for `aggregate`, computed when it or the rows change rather than every frame
+    totals: Vec<String>,
     file_name: String,
     last_query: Option<String>,
     pending_g: bool,
@@ -25,72 +46,424 @@ pub struct TableEngine {
     last_match: Option<String>,
     /// Visual selection range (start, end) for highlighting
     pub visual_range: Option<(usize, usize)>,
+    /// Columns of a visual block selection; None highlights whole rows
+    visual_columns: Option<(usize, usize)>,
 }
 
 impl TableEngine {
     pub fn from_path(path: &Path) -> Result<Self> {
+        Self::from_reader(path, File::open(path)?, true)
+    }
+
+    /// Create from in-memory bytes; `path` only supplies the name and extension
+    pub fn from_bytes(path: &Path, bytes: &[u8]) -> Result<Self> {
+        Self::from_reader(path, std::io::Cursor::new(bytes), false)
+    }
+
+    /// `on_disk` is set when `path` holds the content, so CSV can be re-read from it
+    fn from_reader<R: MmapBytesReader>(path: &Path, mut reader: R, on_disk: bool) -> Result<Self> {
         let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("");
+        // Columnar files are also recognised by their magic bytes when the name doesn't say
+        let mut magic = [0u8; 4];
+        let read = reader.read(&mut magic)?;
+        reader.seek(SeekFrom::Start(0))?;
+        let ext = match &magic[..read] {
+            _ if matches!(ext, "csv" | "tsv" | "parquet" | "avro" | "orc" | "har") => ext,
+            b"PAR1" => "parquet",
+            b"Obj\x01" => "avro",
+            [b'O', b'R', b'C', ..] => "orc",
+            _ => ext,
+        };
+        let mut csv_source = None;
+        let mut has_header = true;
         let df = match ext {
-            "csv" => {
-                CsvReader::from_path(path)
-                    .map_err(|e| anyhow!("CSV open failed: {}", e))?
-                    .has_header(true)
-                    .finish()
-                    .map_err(|e| anyhow!("CSV read failed: {}", e))?
-            }
-            "tsv" => {
-                CsvReader::from_path(path)
-                    .map_err(|e| anyhow!("TSV open failed: {}", e))?
-                    .has_header(true)
-                    .with_separator(b'\t')
-                    .finish()
-                    .map_err(|e| anyhow!("TSV read failed: {}", e))?
+            "csv" | "tsv" => {
+                let separator = if ext == "tsv" { b'\t' } else { b',' };
+                let mut bytes = Vec::new();
+                reader.read_to_end(&mut bytes)?;
+                has_header = detect_header(&bytes, separator);
+                let df = read_csv(&bytes, separator, has_header)?;
+                csv_source = on_disk.then(|| (path.to_path_buf(), separator));
+                df
             }
             "parquet" => {
-                let file = File::open(path)?;
-                ParquetReader::new(file)
+                ParquetReader::new(reader)
                     .finish()
                     .map_err(|e| anyhow!("Parquet read failed: {}", e))?
             }
+            "avro" => {
+                polars::io::avro::AvroReader::new(reader)
+                    .finish()
+                    .map_err(|e| anyhow!("Avro read failed: {}", e))?
+            }
+            "orc" => {
+                let mut bytes = Vec::new();
+                reader.read_to_end(&mut bytes)?;
+                orc_frame(bytes)?
+            }
+            "har" => {
+                let mut bytes = Vec::new();
+                reader.read_to_end(&mut bytes)?;
+                har_frame(&bytes)?
+            }
             _ => return Err(anyhow!("Unsupported tabular format: {}", ext)),
         };
 
+        let file_name = path.file_name().and_then(|s| s.to_str()).unwrap_or("");
         Ok(Self {
+            csv_source,
+            has_header,
+            ..Self::from_frame(file_name, df)
+        })
+    }
+
+    /// Create from JSON records (an array of objects); the columns are the union
+    /// of their keys, in the order they first appear
+    pub fn from_records(name: &str, records: &[serde_json::Value]) -> Result<Self> {
+        Ok(Self::from_frame(name, records_frame(records)?))
+    }
+
+    fn from_frame(file_name: &str, df: DataFrame) -> Self {
+        Self {
             df,
+            csv_source: None,
+            has_header: true,
+            unfiltered: None,
             selection: 0,
+            column: 0,
+            detail: None,
             scroll: 0,
             schema_view: false,
-            file_name: path
-                .file_name()
-                .and_then(|s| s.to_str())
-                .unwrap_or("")
-                .to_string(),
+            aggregate: None,
+            totals: Vec::new(),
+            file_name: file_name.to_string(),
             last_query: None,
             pending_g: false,
             last_view_height: 0,
             last_match: None,
             visual_range: None,
-        })
+            visual_columns: None,
+        }
     }
 
-    pub fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
-        self.last_view_height = area.height as usize;
-        if self.schema_view {
-            self.render_schema(frame, area);
+    /// Narrow `df` to the rows matching `predicate`, always starting from all rows
+    fn filter_rows(&mut self, predicate: &Predicate, text: &str) -> bool {
+        let all = match self.unfiltered.take() {
+            Some((all, _)) => all,
+            None => self.df.clone(),
+        };
+        let filtered = all.column(&predicate.column).ok().and_then(|series| {
+            let mask: BooleanChunked = (0..series.len())
+                .map(|idx| series.get(idx).is_ok_and(|cell| predicate.matches(&cell)))
+                .collect();
+            all.filter(&mask).ok()
+        });
+        let Some(filtered) = filtered else {
+            self.df = all;
+            self.refresh_totals();
+            return false;
+        };
+        self.df = filtered;
+        self.unfiltered = Some((all, text.to_string()));
+        self.selection = 0;
+        self.scroll = 0;
+        self.refresh_totals();
+        self.df.height() > 0
+    }
+
+    /// Recompute the footer cells after `aggregate` or the rows change
+    fn refresh_totals(&mut self) {
+        self.totals = match self.aggregate {
+            Some(aggregate) => self
+                .df
+                .get_columns()
+                .iter()
+                .map(|series| column_stat(series, aggregate).map(format_stat).unwrap_or_default())
+                .collect(),
+            None => Vec::new(),
+        };
+    }
+
+    /// Open the popup with the untruncated value under the cell cursor
+    fn open_detail(&mut self) {
+        let Some(series) = self.df.get_columns().get(self.column) else {
+            return;
+        };
+        if let Ok(value) = series.str_value(self.selection) {
+            self.detail = Some((series.name().to_string(), value.into_owned()));
+        }
+    }
+
+    fn render_detail(&self, frame: &mut ratatui::Frame, area: Rect) {
+        if let Some((name, value)) = &self.detail {
+            let title = format!("{} (row {})", name, self.selection + 1);
+            super::render_popup(frame, area, &title, value);
+        }
+    }
+
+    /// Re-read the CSV with the first row as column names or as data
+    fn toggle_header(&mut self) {
+        let Some((path, separator)) = &self.csv_source else {
+            return;
+        };
+        let reread = Source::map(path).and_then(|data| read_csv(&data, *separator, !self.has_header));
+        if let Ok(df) = reread {
+            // Column names change, so a row filter no longer applies
+            self.unfiltered = None;
+            self.df = df;
+            self.has_header = !self.has_header;
+            self.selection = self.selection.min(self.df.height().saturating_sub(1));
+            self.column = self.column.min(self.df.width().saturating_sub(1));
+            self.refresh_totals();
+        }
+    }
+
+    fn render_table(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+        if self.df.width() == 0 {
+            super::render_placeholder(frame, area, "(empty file)");
+            return;
+        }
+
+        // The totals footer takes the bottom row
+        let footer_rows = u16::from(self.aggregate.is_some());
+        let (area, footer_area) = if footer_rows > 0 && area.height > 2 {
+            let table_height = area.height - footer_rows;
+            (
+                Rect { height: table_height, ..area },
+                Some(Rect { y: area.y + table_height, height: footer_rows, ..area }),
+            )
         } else {
-            self.render_table(frame, area);
+            (area, None)
+        };
+        let height = area.height.saturating_sub(1) as usize;
+        self.scroll = super::follow_selection(self.scroll, self.selection, height, self.df.height());
+
+        // Only render the visible slice (data is already in memory, just slicing the view)
+        let slice = self
+            .df
+            .slice(self.scroll as i64, height.min(self.df.height()));
+
+        // Generic col1..colN names are dimmed so they don't read as real headers
+        let header_style = if self.has_header {
+            Style::default().fg(Color::Black).bg(Color::LightBlue).bold()
+        } else {
+            Style::default().fg(Color::DarkGray).bg(Color::LightBlue).italic()
+        };
+        let mut headers: Vec<Cell> = Vec::new();
+        headers.push(Cell::from("#").style(header_style));
+        headers.push(Cell::from("│").style(Style::default().fg(Color::LightBlue)));
+        headers.extend(
+            slice
+                .get_column_names()
+                .iter()
+                .map(|name| Cell::from(*name).style(header_style)),
+        );
+        let header = Row::new(headers).style(
+            Style::default()
+                .fg(Color::Black)
+                .bg(Color::LightBlue)
+                .bold(),
+        );
+
+        let mut rows = Vec::new();
+        for row_idx in 0..slice.height() {
+            let row = self.scroll + row_idx;
+            let in_visual = self.visual_range.is_some_and(|(start, end)| {
+                let (lo, hi) = if start <= end { (start, end) } else { (end, start) };
+                row >= lo && row <= hi
+            });
+            let mut cells = Vec::new();
+            cells.push(
+                Cell::from((self.scroll + row_idx + 1).to_string()).style(if in_visual {
+                    Style::default().fg(Color::Black).bg(Color::LightYellow).bold()
+                } else {
+                    Style::default().fg(Color::DarkGray)
+                }),
+            );
+            cells.push(Cell::from("│").style(Style::default().fg(Color::DarkGray)));
+            for (col_idx, series) in slice.get_columns().iter().enumerate() {
+                let in_block = in_visual
+                    && self.visual_columns.is_none_or(|(lo, hi)| col_idx >= lo && col_idx <= hi);
+                let value = series.get(row_idx).map(|v| v.to_string()).unwrap_or_default();
+                // Color based on data type
+                let style = match series.dtype() {
+                    polars::datatypes::DataType::Int8
+                    | polars::datatypes::DataType::Int16
+                    | polars::datatypes::DataType::Int32
+                    | polars::datatypes::DataType::Int64
+                    | polars::datatypes::DataType::UInt8
+                    | polars::datatypes::DataType::UInt16
+                    | polars::datatypes::DataType::UInt32
+                    | polars::datatypes::DataType::UInt64
+                    | polars::datatypes::DataType::Float32
+                    | polars::datatypes::DataType::Float64 => Style::default().fg(Color::Magenta),
+                    polars::datatypes::DataType::Boolean => Style::default().fg(Color::Cyan),
+                    polars::datatypes::DataType::String => Style::default().fg(Color::Yellow),
+                    polars::datatypes::DataType::Date
+                    | polars::datatypes::DataType::Datetime(_, _)
+                    | polars::datatypes::DataType::Time => Style::default().fg(Color::Green),
+                    polars::datatypes::DataType::Null => Style::default().fg(Color::DarkGray),
+                    _ => Style::default().fg(Color::White),
+                };
+                let style = if in_block {
+                    Style::default().fg(Color::Black).bg(Color::LightYellow)
+                } else {
+                    style
+                };
+                // Reversed survives the row highlight, so the cursor cell stands out
+                let style = if row == self.selection && col_idx == self.column {
+                    style.reversed().bold()
+                } else {
+                    style
+                };
+                cells.push(Cell::from(value).style(style));
+            }
+            rows.push(Row::new(cells));
         }
+
+        let row_count = rows.len();
+        let mut widths = vec![Constraint::Length(6), Constraint::Length(2)];
+        widths.extend(make_widths(slice.width()));
+        let table = Table::new(rows, widths)
+            .header(header)
+            .block(Block::default().borders(Borders::NONE))
+            .highlight_style(Style::default().bg(Color::LightBlue).fg(Color::Black));
+
+        let mut state = TableState::default();
+        if row_count != 0 {
+            let relative = self.selection.saturating_sub(self.scroll);
+            state.select(Some(relative));
+        }
+        frame.render_stateful_widget(table, area, &mut state);
+        if self.df.height() == 0 {
+            super::render_placeholder(frame, area, "(no rows)");
+        }
+        if let (Some(aggregate), Some(footer_area)) = (self.aggregate, footer_area) {
+            self.render_totals(frame, footer_area, aggregate);
+        }
+    }
+
+    /// One row under the table with `aggregate` over every row of each numeric column
+    fn render_totals(&self, frame: &mut ratatui::Frame, area: Rect, aggregate: Aggregate) {
+        let style = Style::default().fg(Color::Black).bg(Color::LightCyan).bold();
+        let mut cells = vec![
+            Cell::from(aggregate.label()).style(style),
+            Cell::from("│").style(Style::default().fg(Color::LightCyan)),
+        ];
+        cells.extend(self.totals.iter().map(|value| Cell::from(value.as_str()).style(style)));
+        let mut widths = vec![Constraint::Length(6), Constraint::Length(2)];
+        widths.extend(make_widths(self.df.width()));
+        frame.render_widget(Table::new(vec![Row::new(cells)], widths), area);
+    }
+
+    fn render_schema(&self, frame: &mut ratatui::Frame, area: Rect) {
+        let mut lines = Vec::new();
+        for field in self.df.schema().iter_fields() {
+            lines.push(Line::from(format!("{}: {}", field.name(), field.data_type())));
+        }
+        let block = Block::default().borders(Borders::NONE);
+        frame.render_widget(ratatui::widgets::Paragraph::new(lines).block(block), area);
+    }
+
+    fn search_next(&mut self, query: &str, forward: bool) -> bool {
+        let trimmed = query.trim();
+        if trimmed.is_empty() {
+            return false;
+        }
+        let lower = trimmed.to_lowercase();
+        let mut found = false;
+        let total = self.df.height().max(1);
+        let start = if forward {
+            (self.selection + 1) % total
+        } else {
+            (self.selection + total - 1) % total
+        };
+        for offset in 0..self.df.height() {
+            let idx = if forward {
+                (start + offset) % total
+            } else {
+                (start + total - offset % total) % total
+            };
+            let mut hit = false;
+            for series in self.df.get_columns() {
+                if let Ok(value) = series.get(idx) {
+                    if search_matches(&value.to_string(), &lower) {
+                        hit = true;
+                        break;
+                    }
+                }
+            }
+            if hit {
+                self.selection = idx;
+                found = true;
+                break;
+            }
+        }
+        self.last_match = Some(trimmed.to_string());
+        found
     }
 
-    pub fn content_height(&self) -> usize {
+    /// Rows in the current view: schema fields, or the header plus data rows
+    fn row_count(&self) -> usize {
         if self.schema_view {
             self.df.schema().len()
         } else {
             self.df.height() + 1
         }
     }
+}
+
+impl Engine for TableEngine {
+    fn name(&self) -> &'static str {
+        "TableEngine"
+    }
+
+    fn set_visual_range(&mut self, range: Option<(usize, usize)>) {
+        self.visual_range = range;
+    }
 
-    pub fn render_plain_lines(&self, _width: u16) -> Vec<Line<'static>> {
+    fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+        self.last_view_height = area.height as usize;
+        if self.schema_view {
+            self.render_schema(frame, area);
+        } else {
+            self.render_table(frame, area);
+            self.render_detail(frame, area);
+        }
+    }
+
+    fn content_height(&mut self) -> usize {
+        self.row_count()
+    }
+
+    /// Parsed structure for `--dump`
+    fn to_json(&mut self) -> serde_json::Value {
+        let columns: Vec<_> = self
+            .df
+            .schema()
+            .iter_fields()
+            .map(|field| {
+                json!({ "name": field.name().to_string(), "type": field.data_type().to_string() })
+            })
+            .collect();
+        let rows: Vec<_> = (0..self.df.height())
+            .map(|row_idx| {
+                let row: serde_json::Map<String, serde_json::Value> = self
+                    .df
+                    .get_columns()
+                    .iter()
+                    .map(|series| {
+                        let value = series.get(row_idx).map_or(serde_json::Value::Null, cell_json);
+                        (series.name().to_string(), value)
+                    })
+                    .collect();
+                serde_json::Value::Object(row)
+            })
+            .collect();
+        json!({ "columns": columns, "rows": rows })
+    }
+
+    fn render_plain_lines(&mut self, _width: u16) -> Vec<Line<'static>> {
         if self.schema_view {
             return self
                 .df
@@ -148,7 +521,15 @@ impl TableEngine {
         lines
     }
 
-    pub fn handle_key(&mut self, key: KeyEvent) {
+    #[allow(clippy::collapsible_match)]
+    fn handle_key(&mut self, key: KeyEvent) {
+        // The detail popup is modal: it only closes
+        if self.detail.is_some() {
+            if matches!(key.code, KeyCode::Enter | KeyCode::Esc) {
+                self.detail = None;
+            }
+            return;
+        }
         match key.code {
             KeyCode::Char('g') => {
                 if self.pending_g {
@@ -172,9 +553,19 @@ impl TableEngine {
             KeyCode::Char('k') | KeyCode::Up => {
                 self.selection = self.selection.saturating_sub(1);
             }
+            KeyCode::Char('h') | KeyCode::Left => {
+                self.column = self.column.saturating_sub(1);
+            }
+            KeyCode::Char('l') | KeyCode::Right => {
+                if self.column + 1 < self.df.width() {
+                    self.column += 1;
+                }
+            }
+            KeyCode::Enter if !self.schema_view => {
+                self.open_detail();
+            }
             KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let jump = page_jump(self.last_view_height).min(self.selection);
-                self.selection = self.selection.saturating_sub(jump);
+                self.selection = super::half_page_up(self.selection, self.last_view_height);
             }
             KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
                 let max_rows = if self.schema_view {
@@ -182,12 +573,21 @@ impl TableEngine {
                 } else {
                     self.df.height()
                 };
-                let jump = page_jump(self.last_view_height).min(max_rows.saturating_sub(1));
-                self.selection = (self.selection + jump).min(max_rows.saturating_sub(1));
+                self.selection = super::half_page_down(self.selection, self.last_view_height, max_rows);
             }
             KeyCode::Char('s') => {
                 self.schema_view = !self.schema_view;
             }
+            KeyCode::Char('H') => {
+                self.toggle_header();
+            }
+            KeyCode::Char('a') => {
+                self.aggregate = match self.aggregate {
+                    None => Some(Aggregate::Sum),
+                    Some(aggregate) => aggregate.next(),
+                };
+                self.refresh_totals();
+            }
             KeyCode::Char('n') => {
                 if let Some(query) = self.last_match.clone() {
                     self.search_next(&query, true);
@@ -207,49 +607,139 @@ impl TableEngine {
         }
     }
 
-    pub fn apply_search(&mut self, query: &str) {
+    fn apply_search(&mut self, query: &str, forward: bool) -> bool {
         let trimmed = query.trim();
         if trimmed.is_empty() {
-            return;
+            return false;
         }
         self.last_query = Some(trimmed.to_string());
-        self.search_next(trimmed, true);
+        let found = self.search_next(trimmed, forward);
         self.last_match = Some(trimmed.to_string());
+        found
     }
 
-    pub fn breadcrumbs(&self) -> String {
-        format!("{} row {}/{}", self.file_name, self.selection + 1, self.df.height())
+    fn breadcrumbs(&self) -> String {
+        let column = self
+            .df
+            .get_column_names()
+            .get(self.column)
+            .map(|name| format!(" · {}", name))
+            .unwrap_or_default();
+        format!("{} row {}/{}{}", self.file_name, self.selection + 1, self.df.height(), column)
     }
 
-    pub fn status_line(&self) -> String {
+    fn status_line(&self) -> String {
         let view = if self.schema_view { "schema" } else { "data" };
+        let header = if self.csv_source.is_none() {
+            ""
+        } else if self.has_header {
+            " | H header: on"
+        } else {
+            " | H header: off"
+        };
         let query = self
             .last_query
             .as_ref()
             .map(|q| format!(" | search: {}", q))
             .unwrap_or_default();
+        let filter = self
+            .unfiltered
+            .as_ref()
+            .map(|(all, text)| format!(" | filter: {} ({}/{} rows)", text, self.df.height(), all.height()))
+            .unwrap_or_default();
+        let totals = self.aggregate.map(|a| format!(" | a totals: {}", a.label())).unwrap_or_default();
         format!(
-            "j/k move | gg/G jump | Ctrl+u/d half-page | n/N next/prev | s toggle schema | / search | f filter{} | view: {}",
-            query, view
+            "j/k move | h/l column | Enter cell | gg/G jump | Ctrl+u/d half-page | n/N next/prev | s toggle schema{}{} | / search | f filter{}{} | view: {}",
+            header, totals, query, filter, view
         )
     }
 
-    pub fn apply_filter(&mut self, query: &str) {
-        // For table, filter acts like search - jump to matching rows
-        self.apply_search(query);
+    /// `column op value` (e.g. `price < 10`) hides the rows that don't match;
+    /// anything else jumps to matching rows like search
+    fn apply_filter(&mut self, query: &str) -> bool {
+        let all = self.unfiltered.as_ref().map_or(&self.df, |(all, _)| all);
+        let predicate = parse_predicate(query, &all.get_column_names());
+        match predicate {
+            Some(predicate) => self.filter_rows(&predicate, query.trim()),
+            None => self.apply_search(query, true),
+        }
     }
 
-    pub fn clear_filter(&mut self) {
+    fn clear_filter(&mut self) {
         self.last_query = None;
+        if let Some((all, _)) = self.unfiltered.take() {
+            self.df = all;
+            self.selection = self.selection.min(self.df.height().saturating_sub(1));
+            self.refresh_totals();
+        }
     }
 
-    #[allow(dead_code)]
-    pub fn selected_path(&self) -> Option<String> {
+    /// Keys specific to this view, listed in the help overlay
+    fn help_lines(&self) -> Vec<Line<'static>> {
+        let mut lines = vec![
+            Line::from("  h/l          Previous / next column"),
+            Line::from("  Enter        Show full cell value"),
+            Line::from("  s            Toggle schema view"),
+            Line::from("  f col < 10   Filter rows by column (=, !=, <, <=, >, >=)"),
+            Line::from("  a            Totals row: sum, avg, min, max, off"),
+        ];
+        if self.csv_source.is_some() {
+            lines.push(Line::from("  H            Toggle first row as header"));
+        }
+        lines
+    }
+
+    fn selected_path(&self) -> Option<String> {
         None
     }
 
+    fn block_column(&self) -> Option<usize> {
+        (!self.schema_view).then_some(self.column)
+    }
+
+    fn set_visual_columns(&mut self, columns: Option<(usize, usize)>) {
+        self.visual_columns = columns;
+    }
+
+    /// Cell values in the block, tab-separated like a spreadsheet paste
+    fn get_block(&self, rows: (usize, usize), columns: (usize, usize)) -> Option<String> {
+        if self.schema_view || rows.0 >= self.df.height() || columns.0 >= self.df.width() {
+            return None;
+        }
+        let series = &self.df.get_columns()[columns.0..=columns.1.min(self.df.width() - 1)];
+        let lines: Vec<String> = (rows.0..=rows.1.min(self.df.height() - 1))
+            .map(|row| {
+                series
+                    .iter()
+                    .map(|col| cell_text(col, row))
+                    .collect::<Vec<_>>()
+                    .join("\t")
+            })
+            .collect();
+        Some(lines.join("\n"))
+    }
+
+    /// Data rows `start..=end` with every column, as shown (filtered) on screen
+    fn markdown_table(&self, start: usize, end: usize) -> Option<String> {
+        let (start, end) = if start <= end { (start, end) } else { (end, start) };
+        if self.schema_view || start >= self.df.height() {
+            return None;
+        }
+        let end = end.min(self.df.height() - 1);
+        let rows: Vec<Vec<String>> = (start..=end)
+            .map(|row| {
+                self.df
+                    .get_columns()
+                    .iter()
+                    .map(|col| cell_text(col, row))
+                    .collect()
+            })
+            .collect();
+        Some(super::util::markdown_table(&self.df.get_column_names(), &rows))
+    }
+
     /// Get the content of the currently selected row
-    pub fn get_selected_line(&self) -> Option<String> {
+    fn get_selected_line(&self) -> Option<String> {
         if self.schema_view {
             self.df.schema().iter_fields().nth(self.selection).map(|f| {
                 format!("{}: {}", f.name(), f.data_type())
@@ -271,9 +761,9 @@ impl TableEngine {
     }
 
     /// Get rows in a range (inclusive), joined by newlines
-    pub fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
+    fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
         let (start, end) = if start <= end { (start, end) } else { (end, start) };
-        let total = self.content_height();
+        let total = self.row_count();
         if start >= total {
             return None;
         }
@@ -301,141 +791,180 @@ impl TableEngine {
     }
 
     /// Get current selection index (for visual mode)
-    pub fn selection(&self) -> usize {
+    fn selection(&self) -> usize {
         self.selection
     }
 
-    fn render_table(&mut self, frame: &mut ratatui::Frame, area: Rect) {
-        if self.df.width() == 0 {
-            frame.render_widget(Block::default().borders(Borders::ALL).title("Empty"), area);
-            return;
-        }
-
-        let height = area.height.saturating_sub(1) as usize;
-        if self.selection < self.scroll {
-            self.scroll = self.selection;
-        } else if self.selection >= self.scroll + height {
-            self.scroll = self.selection.saturating_sub(height - 1);
-        }
+    fn select(&mut self, row: usize) {
+        self.selection = row.min(self.df.height().saturating_sub(1));
+    }
 
-        // Only render the visible slice (data is already in memory, just slicing the view)
-        let slice = self
-            .df
-            .slice(self.scroll as i64, height.min(self.df.height()));
+    /// Scroll so the selection sits at the top, middle or bottom of the view (`zt`/`zz`/`zb`)
+    fn align_scroll(&mut self, align: super::ScrollAlign) {
+        self.scroll = super::aligned_scroll(self.selection, self.last_view_height, align);
+    }
+}
 
-        let header_style = Style::default()
-            .fg(Color::Black)
-            .bg(Color::LightBlue)
-            .bold();
-        let mut headers: Vec<Cell> = Vec::new();
-        headers.push(Cell::from("#").style(header_style));
-        headers.push(Cell::from("│").style(Style::default().fg(Color::LightBlue)));
-        headers.extend(
-            slice
-                .get_column_names()
-                .iter()
-                .map(|name| Cell::from(*name).style(header_style)),
-        );
-        let header = Row::new(headers).style(
-            Style::default()
-                .fg(Color::Black)
-                .bg(Color::LightBlue)
-                .bold(),
-        );
+/// Statistic in the totals footer
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Aggregate {
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
 
-        let mut rows = Vec::new();
-        for row_idx in 0..slice.height() {
-            let mut cells = Vec::new();
-            cells.push(
-                Cell::from((self.scroll + row_idx + 1).to_string())
-                    .style(Style::default().fg(Color::DarkGray)),
-            );
-            cells.push(Cell::from("│").style(Style::default().fg(Color::DarkGray)));
-            for series in slice.get_columns() {
-                let value = series.get(row_idx).map(|v| v.to_string()).unwrap_or_default();
-                // Color based on data type
-                let style = match series.dtype() {
-                    polars::datatypes::DataType::Int8
-                    | polars::datatypes::DataType::Int16
-                    | polars::datatypes::DataType::Int32
-                    | polars::datatypes::DataType::Int64
-                    | polars::datatypes::DataType::UInt8
-                    | polars::datatypes::DataType::UInt16
-                    | polars::datatypes::DataType::UInt32
-                    | polars::datatypes::DataType::UInt64
-                    | polars::datatypes::DataType::Float32
-                    | polars::datatypes::DataType::Float64 => Style::default().fg(Color::Magenta),
-                    polars::datatypes::DataType::Boolean => Style::default().fg(Color::Cyan),
-                    polars::datatypes::DataType::String => Style::default().fg(Color::Yellow),
-                    polars::datatypes::DataType::Date
-                    | polars::datatypes::DataType::Datetime(_, _)
-                    | polars::datatypes::DataType::Time => Style::default().fg(Color::Green),
-                    polars::datatypes::DataType::Null => Style::default().fg(Color::DarkGray),
-                    _ => Style::default().fg(Color::White),
-                };
-                cells.push(Cell::from(value).style(style));
-            }
-            rows.push(Row::new(cells));
+impl Aggregate {
+    /// The next statistic in the `a` cycle; None turns the footer off
+    fn next(self) -> Option<Self> {
+        match self {
+            Aggregate::Sum => Some(Aggregate::Avg),
+            Aggregate::Avg => Some(Aggregate::Min),
+            Aggregate::Min => Some(Aggregate::Max),
+            Aggregate::Max => None,
         }
+    }
 
-        let row_count = rows.len();
-        let mut widths = vec![Constraint::Length(6), Constraint::Length(2)];
-        widths.extend(make_widths(slice.width()));
-        let table = Table::new(rows, widths)
-            .header(header)
-            .block(Block::default().borders(Borders::NONE))
-            .highlight_style(Style::default().bg(Color::LightBlue).fg(Color::Black));
-
-        let mut state = TableState::default();
-        if row_count != 0 {
-            let relative = self.selection.saturating_sub(self.scroll);
-            state.select(Some(relative));
+    fn label(self) -> &'static str {
+        match self {
+            Aggregate::Sum => "sum",
+            Aggregate::Avg => "avg",
+            Aggregate::Min => "min",
+            Aggregate::Max => "max",
         }
-        frame.render_stateful_widget(table, area, &mut state);
     }
+}
 
-    fn render_schema(&self, frame: &mut ratatui::Frame, area: Rect) {
-        let mut lines = Vec::new();
-        for field in self.df.schema().iter_fields() {
-            lines.push(Line::from(format!("{}: {}", field.name(), field.data_type())));
+/// `aggregate` over the non-null values of a numeric column; None for other columns
+fn column_stat(series: &Series, aggregate: Aggregate) -> Option<f64> {
+    if !series.dtype().is_numeric() {
+        return None;
+    }
+    let values = series.cast(&DataType::Float64).ok()?;
+    let values = values.f64().ok()?.into_iter().flatten();
+    match aggregate {
+        Aggregate::Sum => Some(values.sum()),
+        Aggregate::Avg => {
+            let (sum, count) = values.fold((0.0, 0usize), |(sum, count), v| (sum + v, count + 1));
+            (count > 0).then(|| sum / count as f64)
         }
-        let block = Block::default().borders(Borders::NONE);
-        frame.render_widget(ratatui::widgets::Paragraph::new(lines).block(block), area);
+        Aggregate::Min => values.reduce(f64::min),
+        Aggregate::Max => values.reduce(f64::max),
     }
+}
 
-    fn search_next(&mut self, query: &str, forward: bool) {
-        let trimmed = query.trim();
-        if trimmed.is_empty() {
-            return;
+/// Whole numbers without a fraction, others to at most four decimals
+fn format_stat(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        return format!("{:.0}", value);
+    }
+    let text = format!("{:.4}", value);
+    text.trim_end_matches('0').trim_end_matches('.').to_string()
+}
+
+/// Comparison in a row filter
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+impl CmpOp {
+    fn accepts(self, ordering: std::cmp::Ordering) -> bool {
+        use std::cmp::Ordering::*;
+        match self {
+            CmpOp::Eq => ordering == Equal,
+            CmpOp::Ne => ordering != Equal,
+            CmpOp::Lt => ordering == Less,
+            CmpOp::Le => ordering != Greater,
+            CmpOp::Gt => ordering == Greater,
+            CmpOp::Ge => ordering != Less,
         }
-        let lower = trimmed.to_lowercase();
-        let total = self.df.height().max(1);
-        let start = if forward {
-            (self.selection + 1) % total
-        } else {
-            self.selection.saturating_sub(1)
+    }
+}
+
+/// A row filter such as `age > 30` or `status = active`
+#[derive(Debug, PartialEq)]
+struct Predicate {
+    column: String,
+    op: CmpOp,
+    value: String,
+}
+
+impl Predicate {
+    /// Numbers compare numerically when both sides parse as one, otherwise as text;
+    /// null cells never match
+    fn matches(&self, cell: &AnyValue) -> bool {
+        let ordering = match (cell_number(cell), self.value.parse::<f64>()) {
+            (Some(number), Ok(value)) => number.partial_cmp(&value),
+            _ => match cell {
+                AnyValue::Null => None,
+                AnyValue::String(text) => Some((*text).cmp(self.value.as_str())),
+                other => Some(other.to_string().as_str().cmp(self.value.as_str())),
+            },
         };
-        for offset in 0..self.df.height() {
-            let idx = if forward {
-                (start + offset) % total
-            } else {
-                (start + total - offset % total) % total
-            };
-            let mut hit = false;
-            for series in self.df.get_columns() {
-                if let Ok(value) = series.get(idx) {
-                    if value.to_string().to_lowercase().contains(&lower) {
-                        hit = true;
-                        break;
-                    }
-                }
-            }
-            if hit {
-                self.selection = idx;
-                break;
-            }
-        }
-        self.last_match = Some(trimmed.to_string());
+        ordering.is_some_and(|ordering| self.op.accepts(ordering))
+    }
+}
+
+/// Split `column op value`, where the column must name one of `columns`
+/// (exactly, or else ignoring case) and the value may be quoted
+fn parse_predicate(query: &str, columns: &[&str]) -> Option<Predicate> {
+    let start = query.find(['=', '!', '<', '>'])?;
+    let (op, len) = match &query[start..] {
+        rest if rest.starts_with("!=") => (CmpOp::Ne, 2),
+        rest if rest.starts_with("<=") => (CmpOp::Le, 2),
+        rest if rest.starts_with(">=") => (CmpOp::Ge, 2),
+        rest if rest.starts_with("==") => (CmpOp::Eq, 2),
+        rest if rest.starts_with('=') => (CmpOp::Eq, 1),
+        rest if rest.starts_with('<') => (CmpOp::Lt, 1),
+        rest if rest.starts_with('>') => (CmpOp::Gt, 1),
+        _ => return None,
+    };
+    let name = query[..start].trim();
+    let column = columns
+        .iter()
+        .find(|c| **c == name)
+        .or_else(|| columns.iter().find(|c| c.eq_ignore_ascii_case(name)))?;
+    let value = query[start + len..].trim();
+    let value = ['"', '\'']
+        .iter()
+        .find_map(|q| value.strip_prefix(*q).and_then(|v| v.strip_suffix(*q)))
+        .unwrap_or(value);
+    Some(Predicate { column: column.to_string(), op, value: value.to_string() })
+}
+
+/// A numeric cell, or a text cell holding a number
+fn cell_number(cell: &AnyValue) -> Option<f64> {
+    match cell {
+        AnyValue::String(text) => text.trim().parse().ok(),
+        value if value.dtype().is_numeric() => value.extract::<f64>(),
+        _ => None,
+    }
+}
+
+/// A cell as copied text: strings unquoted, nulls empty
+fn cell_text(series: &Series, row: usize) -> String {
+    match series.get(row) {
+        Ok(AnyValue::Null) | Err(_) => String::new(),
+        Ok(AnyValue::String(s)) => s.to_string(),
+        Ok(value) => value.to_string(),
+    }
+}
+
+/// A cell as a JSON value: numbers and booleans keep their type, everything else is text
+fn cell_json(value: AnyValue) -> serde_json::Value {
+    match value {
+        AnyValue::Null => serde_json::Value::Null,
+        AnyValue::Boolean(value) => json!(value),
+        AnyValue::String(value) => json!(value),
+        value if value.dtype().is_integer() => value.extract::<i64>().map_or(json!(null), |v| json!(v)),
+        value if value.dtype().is_float() => value.extract::<f64>().map_or(json!(null), |v| json!(v)),
+        value => json!(value.to_string()),
     }
 }
 
@@ -451,11 +980,6 @@ fn make_widths(cols: usize) -> Vec<Constraint> {
     widths
 }
 
-fn page_jump(view_height: usize) -> usize {
-    let half = view_height / 2;
-    if half == 0 { 1 } else { half }
-}
-
 fn join_with_sep(mut spans: Vec<Span<'static>>, sep: &str) -> Vec<Span<'static>> {
     if spans.is_empty() {
         return spans;
@@ -470,6 +994,187 @@ fn join_with_sep(mut spans: Vec<Span<'static>>, sep: &str) -> Vec<Span<'static>>
     joined
 }
 
+fn read_csv(bytes: &[u8], separator: u8, has_header: bool) -> Result<DataFrame> {
+    let label = if separator == b'\t' { "TSV" } else { "CSV" };
+    let mut df = CsvReader::new(std::io::Cursor::new(bytes))
+        .has_header(has_header)
+        .with_separator(separator)
+        .finish()
+        .map_err(|e| anyhow!("{} read failed: {}", label, e))?;
+    if !has_header {
+        let names: Vec<String> = (1..=df.width()).map(|i| format!("col{}", i)).collect();
+        df.set_column_names(&names)?;
+    }
+    Ok(df)
+}
+
+/// Guess whether the first row is a header: it is, unless it holds numbers
+/// like the rows after it do
+fn detect_header(bytes: &[u8], separator: u8) -> bool {
+    let sample = String::from_utf8_lossy(&bytes[..bytes.len().min(64 * 1024)]);
+    let is_number = |cell: &str| {
+        let cell = cell.trim().trim_matches('"');
+        !cell.is_empty() && cell.parse::<f64>().is_ok()
+    };
+    let mut rows = sample.lines().filter(|line| !line.trim().is_empty());
+    let Some(first) = rows.next() else {
+        return true;
+    };
+    let first_has_numbers = first.split(separator as char).any(is_number);
+    let later_has_numbers = rows.take(20).any(|row| row.split(separator as char).any(is_number));
+    !(first_has_numbers && later_has_numbers)
+}
+
+/// Flatten a HAR (HTTP Archive) log into one row per request
+fn har_frame(bytes: &[u8]) -> Result<DataFrame> {
+    use serde_json::Value;
+
+    let value: Value = serde_json::from_slice(bytes)?;
+    let entries = value
+        .pointer("/log/entries")
+        .and_then(Value::as_array)
+        .ok_or_else(|| anyhow!("Not a HAR file: missing log.entries"))?;
+
+    let text = |entry: &Value, pointer: &str| {
+        entry.pointer(pointer).and_then(Value::as_str).unwrap_or("").to_string()
+    };
+    let started: Vec<String> = entries.iter().map(|e| text(e, "/startedDateTime")).collect();
+    let method: Vec<String> = entries.iter().map(|e| text(e, "/request/method")).collect();
+    let url: Vec<String> = entries.iter().map(|e| text(e, "/request/url")).collect();
+    let status: Vec<i64> = entries
+        .iter()
+        .map(|e| e.pointer("/response/status").and_then(Value::as_i64).unwrap_or(0))
+        .collect();
+    let time_ms: Vec<f64> = entries
+        .iter()
+        .map(|e| e.get("time").and_then(Value::as_f64).unwrap_or(0.0))
+        .collect();
+    let size: Vec<i64> = entries
+        .iter()
+        .map(|e| e.pointer("/response/content/size").and_then(Value::as_i64).unwrap_or(-1))
+        .collect();
+    let mime: Vec<String> = entries.iter().map(|e| text(e, "/response/content/mimeType")).collect();
+
+    DataFrame::new(vec![
+        Series::new("started", started),
+        Series::new("method", method),
+        Series::new("url", url),
+        Series::new("status", status),
+        Series::new("time_ms", time_ms),
+        Series::new("size", size),
+        Series::new("type", mime),
+    ])
+    .map_err(|e| anyhow!("HAR read failed: {}", e))
+}
+
+/// orc-rust only reads from files, so in-memory ORC data (stdin, archive
+/// members) goes through this reader
+struct OrcBytes(bytes::Bytes);
+
+impl orc_rust::reader::ChunkReader for OrcBytes {
+    type T = bytes::buf::Reader<bytes::Bytes>;
+
+    fn len(&self) -> u64 {
+        self.0.len() as u64
+    }
+
+    fn get_read(&self, offset_from_start: u64) -> std::io::Result<Self::T> {
+        use bytes::Buf;
+        let start = (offset_from_start as usize).min(self.0.len());
+        Ok(self.0.slice(start..).reader())
+    }
+}
+
+/// Read every stripe of an ORC file. Integers, floats and booleans keep their
+/// type; strings, dates and nested values are shown as text
+fn orc_frame(bytes: Vec<u8>) -> Result<DataFrame> {
+    use arrow::array::{Array, ArrayRef, AsArray};
+    use arrow::datatypes::{DataType as ArrowType, Float64Type, Int64Type};
+    use arrow::record_batch::RecordBatchReader;
+    use arrow::util::display::{ArrayFormatter, FormatOptions};
+
+    let orc_err = |e: &dyn std::fmt::Display| anyhow!("ORC read failed: {}", e);
+    let reader = orc_rust::ArrowReaderBuilder::try_new(OrcBytes(bytes::Bytes::from(bytes)))
+        .map_err(|e| orc_err(&e))?
+        .build();
+    let schema = reader.schema();
+    let batches = reader.collect::<Result<Vec<_>, _>>().map_err(|e| orc_err(&e))?;
+
+    let column = |idx: usize, name: &str, data_type: &ArrowType| -> Result<Series> {
+        let arrays: Vec<&ArrayRef> = batches.iter().map(|batch| batch.column(idx)).collect();
+        let cast = |array: &ArrayRef, to: &ArrowType| arrow::compute::cast(array, to).map_err(|e| orc_err(&e));
+        Ok(if data_type.is_integer() {
+            let mut values: Vec<Option<i64>> = Vec::new();
+            for array in arrays {
+                values.extend(cast(array, &ArrowType::Int64)?.as_primitive::<Int64Type>().iter());
+            }
+            Series::new(name, values)
+        } else if data_type.is_floating() {
+            let mut values: Vec<Option<f64>> = Vec::new();
+            for array in arrays {
+                values.extend(cast(array, &ArrowType::Float64)?.as_primitive::<Float64Type>().iter());
+            }
+            Series::new(name, values)
+        } else if *data_type == ArrowType::Boolean {
+            let values: Vec<Option<bool>> = arrays.iter().flat_map(|array| array.as_boolean().iter()).collect();
+            Series::new(name, values)
+        } else {
+            let mut values: Vec<Option<String>> = Vec::new();
+            for array in arrays {
+                let formatter = ArrayFormatter::try_new(array.as_ref(), &FormatOptions::default()).map_err(|e| orc_err(&e))?;
+                values.extend((0..array.len()).map(|row| array.is_valid(row).then(|| formatter.value(row).to_string())));
+            }
+            Series::new(name, values)
+        })
+    };
+    let columns = schema
+        .fields()
+        .iter()
+        .enumerate()
+        .map(|(idx, field)| column(idx, field.name(), field.data_type()))
+        .collect::<Result<Vec<_>>>()?;
+    DataFrame::new(columns).map_err(|e| anyhow!("ORC read failed: {}", e))
+}
+
+/// One column per key across `records`, typed when every value agrees:
+/// integers, floats or booleans, else text (nested values as compact JSON)
+fn records_frame(records: &[serde_json::Value]) -> Result<DataFrame> {
+    use serde_json::Value;
+
+    let mut keys: Vec<&str> = Vec::new();
+    for record in records {
+        let object = record.as_object().ok_or_else(|| anyhow!("Not an array of objects"))?;
+        for key in object.keys() {
+            if !keys.contains(&key.as_str()) {
+                keys.push(key);
+            }
+        }
+    }
+
+    let columns = keys
+        .iter()
+        .map(|&key| {
+            let values: Vec<&Value> = records.iter().map(|r| r.get(key).unwrap_or(&Value::Null)).collect();
+            let present = || values.iter().filter(|v| !v.is_null());
+            if present().all(|v| v.is_i64()) {
+                Series::new(key, values.iter().map(|v| v.as_i64()).collect::<Vec<_>>())
+            } else if present().all(|v| v.is_number()) {
+                Series::new(key, values.iter().map(|v| v.as_f64()).collect::<Vec<_>>())
+            } else if present().all(|v| v.is_boolean()) {
+                Series::new(key, values.iter().map(|v| v.as_bool()).collect::<Vec<_>>())
+            } else {
+                let text = values.iter().map(|v| match v {
+                    Value::Null => None,
+                    Value::String(s) => Some(s.clone()),
+                    other => Some(other.to_string()),
+                });
+                Series::new(key, text.collect::<Vec<_>>())
+            }
+        })
+        .collect();
+    DataFrame::new(columns).map_err(|e| anyhow!("Table build failed: {}", e))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -485,4 +1190,186 @@ mod tests {
         }
         assert_eq!(total, 100);
     }
+
+    #[test]
+    fn predicates_parse_against_headers() {
+        let columns = ["Price", "status"];
+        assert_eq!(
+            parse_predicate("price <= 9.5", &columns),
+            Some(Predicate { column: "Price".to_string(), op: CmpOp::Le, value: "9.5".to_string() })
+        );
+        assert_eq!(parse_predicate("status != 'on hold'", &columns).unwrap().value, "on hold");
+        assert!(parse_predicate("size > 3", &columns).is_none());
+        assert!(parse_predicate("just text", &columns).is_none());
+    }
+
+    #[test]
+    fn predicate_filter_hides_other_rows() {
+        let csv = b"name,price,status\npen,2.5,active\nlamp,40,active\nmug,8,retired\n";
+        let mut engine = TableEngine::from_bytes(Path::new("items.csv"), csv).unwrap();
+        assert!(engine.apply_filter("price < 10"));
+        assert_eq!(engine.df.height(), 2);
+        assert!(engine.apply_filter("status = active"));
+        assert_eq!(engine.df.column("name").unwrap().str_value(1).unwrap(), "lamp");
+        assert!(!engine.apply_filter("price > 100"));
+        assert_eq!(engine.df.height(), 0);
+
+        engine.clear_filter();
+        assert_eq!(engine.df.height(), 3);
+    }
+
+    #[test]
+    fn dump_lists_columns_and_typed_rows() {
+        let csv = b"name,qty,price\npen,3,2.5\nmug,,8\n";
+        let mut engine = TableEngine::from_bytes(Path::new("items.csv"), csv).unwrap();
+        assert_eq!(
+            engine.to_json(),
+            json!({
+                "columns": [
+                    { "name": "name", "type": "str" },
+                    { "name": "qty", "type": "i64" },
+                    { "name": "price", "type": "f64" },
+                ],
+                "rows": [
+                    { "name": "pen", "qty": 3, "price": 2.5 },
+                    { "name": "mug", "qty": null, "price": 8.0 },
+                ],
+            })
+        );
+    }
+
+    #[test]
+    fn totals_cover_numeric_columns_only() {
+        let csv = b"name,qty,price\npen,3,2.5\nlamp,1,40\nmug,2,\n";
+        let engine = TableEngine::from_bytes(Path::new("items.csv"), csv).unwrap();
+        let column = |name: &str| engine.df.column(name).unwrap();
+        assert_eq!(column_stat(column("qty"), Aggregate::Sum), Some(6.0));
+        assert_eq!(column_stat(column("price"), Aggregate::Avg), Some(21.25));
+        assert_eq!(column_stat(column("price"), Aggregate::Min), Some(2.5));
+        assert_eq!(column_stat(column("name"), Aggregate::Max), None);
+        assert_eq!(format_stat(6.0), "6");
+        assert_eq!(format_stat(1.0 / 3.0), "0.3333");
+    }
+
+    #[test]
+    fn totals_footer_sits_on_the_bottom_row_and_follows_the_filter() {
+        fn rows(engine: &mut TableEngine) -> Vec<String> {
+            let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(30, 8)).unwrap();
+            terminal.draw(|frame| engine.render(frame, frame.size())).unwrap();
+            let buffer = terminal.backend().buffer();
+            (0..8).map(|y| (0..30).map(|x| buffer.get(x, y).symbol()).collect()).collect()
+        }
+        let csv = b"name,qty\npen,3\nlamp,1\nmug,2\n";
+        let mut engine = TableEngine::from_bytes(Path::new("items.csv"), csv).unwrap();
+        engine.handle_key(KeyEvent::from(KeyCode::Char('a')));
+        let drawn = rows(&mut engine);
+        assert!(drawn[7].starts_with("sum") && drawn[7].contains('6'), "{:?}", drawn);
+        assert!(!drawn[..7].iter().any(|row| row.contains("sum")));
+
+        assert!(engine.apply_filter("qty > 1"));
+        let drawn = rows(&mut engine);
+        assert!(drawn[7].starts_with("sum") && drawn[7].contains('5'), "{:?}", drawn);
+    }
+
+    #[test]
+    fn har_entries_become_rows() {
+        let har = br#"{"log": {"entries": [
+            {"startedDateTime": "t0", "time": 12.5,
+             "request": {"method": "GET", "url": "https://example.com/"},
+             "response": {"status": 200, "content": {"size": 512, "mimeType": "text/html"}}}
+        ]}}"#;
+        let df = har_frame(har).unwrap();
+        assert_eq!(df.shape(), (1, 7));
+        assert_eq!(df.column("method").unwrap().str_value(0).unwrap(), "GET");
+        assert_eq!(df.column("status").unwrap().str_value(0).unwrap(), "200");
+    }
+
+    #[test]
+    fn avro_files_are_read_by_magic() {
+        let mut df = df!("id" => [1i64, 2], "name" => ["a", "b"]).unwrap();
+        let mut bytes = Vec::new();
+        polars::io::avro::AvroWriter::new(&mut bytes).finish(&mut df).unwrap();
+
+        let engine = TableEngine::from_bytes(Path::new("events"), &bytes).unwrap();
+        assert_eq!(engine.df, df);
+    }
+
+    #[test]
+    fn orc_files_are_read_by_magic() {
+        // orc-rust 0.3 has no writer; the sample holds id [1, null] and name ["a", "b"]
+        let bytes = include_bytes!("../../samples/sample.orc");
+        let engine = TableEngine::from_bytes(Path::new("events"), bytes).unwrap();
+        let expected = df!("id" => [Some(1i64), None], "name" => ["a", "b"]).unwrap();
+        assert_eq!(engine.df, expected);
+    }
+
+    #[test]
+    fn rows_copy_as_markdown_table() {
+        let csv = b"name,price\nwidget,3\nbolt|nut,\n";
+        let engine = TableEngine::from_bytes(Path::new("parts.csv"), csv).unwrap();
+        assert_eq!(
+            engine.markdown_table(1, 0).unwrap(),
+            "| name | price |\n| --- | --- |\n| widget | 3 |\n| bolt\\|nut |  |"
+        );
+        assert_eq!(engine.markdown_table(5, 6), None);
+    }
+
+    #[test]
+    fn records_become_typed_columns() {
+        let records: Vec<serde_json::Value> = serde_json::from_str(
+            r#"[{"id": 1, "name": "a"}, {"id": 2, "tags": ["x"]}, {"name": "c", "id": null}]"#,
+        )
+        .unwrap();
+        let df = records_frame(&records).unwrap();
+        assert_eq!(df.get_column_names(), ["id", "name", "tags"]);
+        assert_eq!(df.column("id").unwrap().dtype(), &DataType::Int64);
+        assert_eq!(df.column("tags").unwrap().str_value(1).unwrap(), r#"["x"]"#);
+        assert_eq!(df.column("name").unwrap().null_count(), 1);
+    }
+
+    #[test]
+    fn headerless_numeric_csv_can_toggle_header() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("data.csv");
+        std::fs::write(&path, "1,2\n3,4\n").unwrap();
+        let mut engine = TableEngine::from_path(&path).unwrap();
+        assert!(!engine.has_header);
+        assert_eq!(engine.df.height(), 2);
+        assert_eq!(engine.df.get_column_names(), ["col1", "col2"]);
+
+        engine.toggle_header();
+        assert!(engine.has_header);
+        assert_eq!(engine.df.height(), 1);
+
+        let named = TableEngine::from_bytes(Path::new("data.csv"), b"a,b\n1,2\n").unwrap();
+        assert!(named.has_header);
+    }
+
+    #[test]
+    fn block_copies_one_column_of_rows() {
+        let csv = b"id,name,price\n1,bolt,3\n2,nut,1\n3,washer,2\n4,gear,9\n";
+        let mut engine = TableEngine::from_bytes(Path::new("parts.csv"), csv).unwrap();
+        engine.handle_key(KeyEvent::from(KeyCode::Char('l')));
+        assert_eq!(engine.block_column(), Some(1));
+        assert_eq!(engine.get_block((0, 2), (1, 1)).unwrap(), "bolt\nnut\nwasher");
+        assert_eq!(engine.get_block((2, 9), (1, 2)).unwrap(), "washer\t2\ngear\t9");
+    }
+
+    #[test]
+    fn enter_opens_full_cell_value() {
+        let long = "a description far longer than any column is wide";
+        let csv = format!("id,description\n1,{}\n", long);
+        let mut engine = TableEngine::from_bytes(Path::new("data.csv"), csv.as_bytes()).unwrap();
+        let key = |code| KeyEvent::new(code, KeyModifiers::NONE);
+
+        engine.handle_key(key(KeyCode::Char('l')));
+        engine.handle_key(key(KeyCode::Enter));
+        assert_eq!(engine.detail, Some(("description".to_string(), long.to_string())));
+
+        // The popup swallows navigation until it is closed
+        engine.handle_key(key(KeyCode::Char('h')));
+        assert_eq!(engine.column, 1);
+        engine.handle_key(key(KeyCode::Esc));
+        assert!(engine.detail.is_none());
+    }
 }
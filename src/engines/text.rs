@@ -1,23 +1,39 @@
-use std::fs::File;
+use std::borrow::Cow;
 use std::path::Path;
 
 use anyhow::Result;
 use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
-use memmap2::Mmap;
 use ratatui::layout::Rect;
 use ratatui::style::{Color, Style, Stylize};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph};
+use serde_json::json;
+
+use super::util::{ansi_spans, line_text_lossy, search_matches, Source};
+use super::{Engine, TextLayout};
+
+/// Bytes scanned per step when extending the line index
+const INDEX_CHUNK: usize = 1024 * 1024;
 
 /// TextEngine uses memory-mapped files for efficient handling of large files.
-/// Only the visible portion is read into memory during rendering.
+/// Only the visible portion is read into memory during rendering, and lines
+/// are indexed on demand so opening a multi-GB file doesn't scan all of it.
 pub struct TextEngine {
-    /// Memory-mapped file content
-    mmap: Mmap,
-    /// Byte offsets for the start of each line (built once on load)
+    /// File content (memory-mapped when read from disk)
+    data: Source,
+    /// Byte offsets for the start of each line, built up to `indexed_to`
     line_offsets: Vec<usize>,
+    /// Bytes of `data` already scanned into `line_offsets`
+    indexed_to: usize,
     selection: usize,
+    /// Char index of the column cursor within the selected line (`h`/`l`/`0`/`$`/`w`/`b`)
+    cursor_col: usize,
     scroll: usize,
+    /// Columns scrolled off the left while wrapping is off (`zh`/`zl`)
+    h_scroll: usize,
+    /// A key moved the cursor since the last render, which scrolls it back into view;
+    /// left alone otherwise so `zh`/`zl` can scroll away from it
+    cursor_moved: bool,
     file_name: String,
     last_query: Option<String>,
     pending_g: bool,
@@ -25,64 +41,103 @@ pub struct TextEngine {
     last_match: Option<String>,
     /// Filtered line indices (None = show all)
     filtered_indices: Option<Vec<usize>>,
+    /// Widest line content among the first `measured` lines, grown as lines are indexed
+    widest: usize,
+    measured: usize,
+    /// Widest line content among `filtered_indices`, measured once per filter
+    filtered_widest: Option<usize>,
     /// Visual selection range (start, end) for highlighting
     pub visual_range: Option<(usize, usize)>,
+    /// Wrap and tab settings, set by `App`
+    layout: TextLayout,
 }
 
 impl TextEngine {
     pub fn from_path(path: &Path) -> Result<Self> {
-        let file = File::open(path)?;
-        let mmap = unsafe { Mmap::map(&file)? };
+        Ok(Self::from_source(path, Source::map(path)?))
+    }
 
-        // Build line offset index - O(n) one-time cost, but only stores offsets (~8 bytes per line)
-        let line_offsets = build_line_offsets(&mmap);
+    /// Create from in-memory bytes; `path` only supplies the displayed name
+    pub fn from_bytes(path: &Path, bytes: &[u8]) -> Result<Self> {
+        Ok(Self::from_source(path, Source::Owned(bytes.to_vec())))
+    }
 
+    fn from_source(path: &Path, data: Source) -> Self {
         let file_name = path
             .file_name()
             .and_then(|s| s.to_str())
             .unwrap_or("")
             .to_string();
 
-        Ok(Self {
-            mmap,
-            line_offsets,
+        Self {
+            data,
+            // The first line always starts at 0; the rest are indexed as needed
+            line_offsets: vec![0],
+            indexed_to: 0,
             selection: 0,
+            cursor_col: 0,
             scroll: 0,
+            h_scroll: 0,
+            cursor_moved: false,
             file_name,
             last_query: None,
             pending_g: false,
             last_view_height: 0,
             last_match: None,
             filtered_indices: None,
+            widest: 0,
+            measured: 0,
+            filtered_widest: None,
             visual_range: None,
-        })
+            layout: TextLayout::default(),
+        }
+    }
+
+    /// Extend the line index until it holds `count` lines or the whole file.
+    /// O(n) in the bytes scanned, but only stores offsets (~8 bytes per line)
+    fn index_lines(&mut self, count: usize) {
+        let len = self.data.len();
+        while self.line_offsets.len() < count && self.indexed_to < len {
+            let start = self.indexed_to;
+            let end = (start + INDEX_CHUNK).min(len);
+            for (i, &byte) in self.data[start..end].iter().enumerate() {
+                if byte == b'\n' && start + i + 1 < len {
+                    self.line_offsets.push(start + i + 1);
+                }
+            }
+            self.indexed_to = end;
+        }
+    }
+
+    fn index_all(&mut self) {
+        self.index_lines(usize::MAX);
+    }
+
+    fn fully_indexed(&self) -> bool {
+        self.indexed_to >= self.data.len()
     }
 
-    /// Get line content at given index (zero-copy from mmap)
-    fn get_line(&self, idx: usize) -> Option<&str> {
+    /// Get line content at given index (zero-copy from mmap unless it isn't UTF-8)
+    fn get_line(&self, idx: usize) -> Option<Cow<'_, str>> {
         if idx >= self.line_offsets.len() {
             return None;
         }
         let start = self.line_offsets[idx];
         let end = if idx + 1 < self.line_offsets.len() {
             self.line_offsets[idx + 1]
+        } else if self.fully_indexed() {
+            self.data.len()
         } else {
-            self.mmap.len()
+            // Last indexed line: its end hasn't been scanned yet
+            self.data[start..]
+                .iter()
+                .position(|&b| b == b'\n')
+                .map_or(self.data.len(), |pos| start + pos + 1)
         };
-
-        // Find actual line end (strip \n or \r\n)
-        let mut line_end = end;
-        if line_end > start && self.mmap.get(line_end - 1) == Some(&b'\n') {
-            line_end -= 1;
-        }
-        if line_end > start && self.mmap.get(line_end - 1) == Some(&b'\r') {
-            line_end -= 1;
-        }
-
-        std::str::from_utf8(&self.mmap[start..line_end]).ok()
+        Some(line_text_lossy(&self.data[start..end]))
     }
 
-    /// Total number of lines in the file
+    /// Number of lines indexed so far (all lines once `fully_indexed`)
     fn line_count(&self) -> usize {
         self.line_offsets.len()
     }
@@ -100,11 +155,129 @@ impl TextEngine {
         }
     }
 
-    pub fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+    /// Gutter and content for the line at `display_row`, tabs expanded
+    fn display_line(&self, display_row: usize, line_no_width: usize) -> Option<Line<'static>> {
+        let actual_row = self.display_to_actual(display_row)?;
+        let line_content = self.get_line(actual_row)?;
+        let selected = display_row == self.selection;
+        let in_visual = self.visual_range.is_some_and(|(start, end)| {
+            let (lo, hi) = if start <= end { (start, end) } else { (end, start) };
+            display_row >= lo && display_row <= hi
+        });
+
+        let mut spans = Vec::new();
+        let line_no = super::gutter_number(actual_row + 1, display_row, self.selection);
+        let line_no = format!("{:>width$} ", line_no, width = line_no_width);
+        let line_no_style = if selected {
+            Style::default().fg(Color::Black).bg(Color::LightBlue).bold()
+        } else if in_visual {
+            Style::default().fg(Color::Black).bg(Color::LightYellow).bold()
+        } else {
+            Style::default().fg(Color::LightYellow)
+        };
+        if super::line_numbers() {
+            spans.push(Span::styled(line_no, line_no_style));
+            spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
+        }
+
+        let mut content_style = Style::default().fg(Color::White);
+        if line_content.contains("TODO") {
+            content_style = content_style.fg(Color::LightRed).bold();
+        }
+        if selected {
+            content_style = content_style.fg(Color::Black).bg(Color::LightBlue);
+        } else if in_visual {
+            content_style = content_style.fg(Color::Black).bg(Color::LightYellow);
+        }
+        let mut content = ansi_spans(&line_content, content_style, super::ansi());
+        if selected {
+            content = super::restyle_char(content, self.clamped_cursor(&line_content), Style::default().reversed());
+        }
+        spans.extend(super::expand_tabs(content, self.layout.tab_width));
+        Some(Line::from(spans))
+    }
+
+    /// Columns `line` takes once escapes are dropped and tabs expanded, gutter aside
+    fn content_cols(&self, line: &str) -> usize {
+        let content = ansi_spans(line, Style::default(), super::ansi());
+        super::expand_tabs(content, self.layout.tab_width).iter().map(Span::width).sum()
+    }
+
+    /// The cursor column, kept within `line` when the selection moved to a shorter one
+    fn clamped_cursor(&self, line: &str) -> usize {
+        self.cursor_col.min(line.chars().count().saturating_sub(1))
+    }
+
+    fn search_next(&mut self, query: &str, forward: bool) -> bool {
+        let trimmed = query.trim();
+        if trimmed.is_empty() {
+            return false;
+        }
+        let lower = trimmed.to_lowercase();
+        let mut found = false;
+        self.index_all();
+        let total = self.line_count().max(1);
+        let start = if forward {
+            (self.selection + 1) % total
+        } else {
+            (self.selection + total - 1) % total
+        };
+
+        for offset in 0..total {
+            let idx = if forward {
+                (start + offset) % total
+            } else {
+                (start + total - offset % total) % total
+            };
+            if let Some(line) = self.get_line(idx) {
+                if search_matches(&line, &lower) {
+                    self.selection = idx;
+                    found = true;
+                    break;
+                }
+            }
+        }
+        self.last_match = Some(trimmed.to_string());
+        found
+    }
+}
+
+impl Engine for TextEngine {
+    fn name(&self) -> &'static str {
+        "TextEngine"
+    }
+
+    fn set_visual_range(&mut self, range: Option<(usize, usize)>) {
+        self.visual_range = range;
+    }
+
+    fn set_layout(&mut self, layout: TextLayout) {
+        if layout.tab_width != self.layout.tab_width {
+            // Tabs take other widths now, so every line is measured again
+            self.widest = 0;
+            self.measured = 0;
+            self.filtered_widest = None;
+        }
+        self.layout = layout;
+    }
+
+    /// Whether the file has at most `rows` lines, indexing no further than needed
+    fn fits_height(&mut self, rows: usize) -> bool {
+        self.index_lines(rows + 1);
+        self.line_count() <= rows
+    }
+
+    fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
         let height = area.height as usize;
         self.last_view_height = height;
+        self.index_lines(self.selection.max(self.scroll) + height + 1);
 
         let display_total = self.display_count();
+        if self.data.is_empty() || display_total == 0 {
+            let message = if self.filtered_indices.is_some() { "(no matches)" } else { "(empty file)" };
+            super::render_placeholder(frame, area, message);
+            return;
+        }
 
         // Clamp selection to display range
         if self.selection >= display_total && display_total > 0 {
@@ -112,58 +285,57 @@ impl TextEngine {
         }
 
         // Adjust scroll to keep selection visible
-        if self.selection < self.scroll {
-            self.scroll = self.selection;
-        } else if self.selection >= self.scroll + height {
-            self.scroll = self.selection.saturating_sub(height - 1);
-        }
+        self.scroll = super::follow_selection(self.scroll, self.selection, height, display_total);
 
         let total_lines = self.line_count();
         let line_no_width = total_lines.max(1).to_string().len().max(2);
+        let width = area.width as usize;
+        let gutter = if super::line_numbers() { line_no_width + 3 } else { 0 };
+
+        if self.layout.wrap {
+            // Wrapped lines take several rows; scroll on until the selected one fits
+            while self.scroll < self.selection {
+                let rows: usize = (self.scroll..=self.selection)
+                    .filter_map(|row| self.display_line(row, line_no_width))
+                    .map(|line| super::wrap_line(line, width, gutter).len())
+                    .sum();
+                if rows <= height {
+                    break;
+                }
+                self.scroll += 1;
+            }
+        }
 
         // Only read lines in the visible window
-        let visible: Vec<Line> = (0..height)
-            .filter_map(|i| {
-                let display_row = self.scroll + i;
-                let actual_row = self.display_to_actual(display_row)?;
-                let line_content = self.get_line(actual_row)?;
-                let selected = display_row == self.selection;
-                let in_visual = self.visual_range.map_or(false, |(start, end)| {
-                    let (lo, hi) = if start <= end { (start, end) } else { (end, start) };
-                    display_row >= lo && display_row <= hi
-                });
+        if !self.layout.wrap && std::mem::take(&mut self.cursor_moved) {
+            if let Some(line) = self.display_to_actual(self.selection).and_then(|row| self.get_line(row)) {
+                let col = super::display_col(&line, self.clamped_cursor(&line), self.layout.tab_width);
+                self.h_scroll = super::follow_cursor(self.h_scroll, col, width.saturating_sub(gutter));
+            }
+        }
 
-                let mut spans = Vec::new();
-                let line_no = format!("{:>width$} ", actual_row + 1, width = line_no_width);
-                let line_no_style = if selected {
-                    Style::default().fg(Color::Black).bg(Color::LightBlue).bold()
-                } else if in_visual {
-                    Style::default().fg(Color::Black).bg(Color::LightYellow).bold()
-                } else {
-                    Style::default().fg(Color::LightYellow)
-                };
-                spans.push(Span::styled(line_no, line_no_style));
-                spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
-
-                let mut content_style = Style::default().fg(Color::White);
-                if line_content.contains("TODO") {
-                    content_style = content_style.fg(Color::LightRed).bold();
-                }
-                if selected {
-                    content_style = content_style.fg(Color::Black).bg(Color::LightBlue);
-                } else if in_visual {
-                    content_style = content_style.fg(Color::Black).bg(Color::LightYellow);
-                }
-                spans.push(Span::styled(line_content.to_string(), content_style));
-                Some(Line::from(spans))
-            })
-            .collect();
+        let mut visible = Vec::new();
+        let mut row = self.scroll;
+        while visible.len() < height {
+            let Some(line) = self.display_line(row, line_no_width) else {
+                break;
+            };
+            if self.layout.wrap {
+                visible.extend(super::wrap_line(line, width, gutter));
+            } else {
+                visible.push(super::shift_line(line, self.h_scroll, gutter));
+            }
+            row += 1;
+        }
+        visible.truncate(height);
 
         let block = Block::default().borders(Borders::NONE);
         frame.render_widget(Paragraph::new(visible).block(block), area);
     }
 
-    pub fn handle_key(&mut self, key: KeyEvent) {
+    #[allow(clippy::collapsible_match)]
+    fn handle_key(&mut self, key: KeyEvent) {
+        self.cursor_moved = true;
         match key.code {
             KeyCode::Char('g') => {
                 if self.pending_g {
@@ -179,6 +351,20 @@ impl TextEngine {
             }
         }
 
+        if !key.modifiers.contains(KeyModifiers::CONTROL) {
+            let line = self.display_to_actual(self.selection).and_then(|row| self.get_line(row));
+            if let Some(col) = line.and_then(|line| super::move_cursor(&line, self.clamped_cursor(&line), key.code)) {
+                self.cursor_col = col;
+                return;
+            }
+        }
+
+        // Index just past wherever this key can move the selection
+        let reach = match key.code {
+            KeyCode::Char('G') => usize::MAX,
+            _ => self.selection + super::page_jump(self.last_view_height) + 2,
+        };
+        self.index_lines(reach);
         let total = self.display_count();
         match key.code {
             KeyCode::Char('j') | KeyCode::Down => {
@@ -190,12 +376,10 @@ impl TextEngine {
                 self.selection = self.selection.saturating_sub(1);
             }
             KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let jump = page_jump(self.last_view_height).min(self.selection);
-                self.selection = self.selection.saturating_sub(jump);
+                self.selection = super::half_page_up(self.selection, self.last_view_height);
             }
             KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let jump = page_jump(self.last_view_height).min(total.saturating_sub(1));
-                self.selection = (self.selection + jump).min(total.saturating_sub(1));
+                self.selection = super::half_page_down(self.selection, self.last_view_height, total);
             }
             KeyCode::Char('G') => {
                 if total > 0 {
@@ -216,56 +400,64 @@ impl TextEngine {
         }
     }
 
-    pub fn apply_search(&mut self, query: &str) {
+    fn apply_search(&mut self, query: &str, forward: bool) -> bool {
         let trimmed = query.trim();
         if trimmed.is_empty() {
-            return;
+            return false;
         }
         self.last_query = Some(trimmed.to_string());
-        self.search_next(trimmed, true);
+        let found = self.search_next(trimmed, forward);
         self.last_match = Some(trimmed.to_string());
+        found
     }
 
-    pub fn apply_filter(&mut self, query: &str) {
+    fn apply_filter(&mut self, query: &str) -> bool {
         let trimmed = query.trim();
         if trimmed.is_empty() {
-            return;
+            return false;
         }
         let lower = trimmed.to_lowercase();
+        self.index_all();
         let mut matches = Vec::new();
         for idx in 0..self.line_count() {
             if let Some(line) = self.get_line(idx) {
-                if line.to_lowercase().contains(&lower) {
+                if search_matches(&line, &lower) {
                     matches.push(idx);
                 }
             }
         }
+        let found = !matches.is_empty();
         self.filtered_indices = Some(matches);
+        self.filtered_widest = None;
         self.selection = 0;
         self.scroll = 0;
+        found
     }
 
-    pub fn clear_filter(&mut self) {
+    fn clear_filter(&mut self) {
         self.filtered_indices = None;
         self.selection = 0;
         self.scroll = 0;
     }
 
-    pub fn breadcrumbs(&self) -> String {
+    fn breadcrumbs(&self) -> String {
         let filter_info = if self.filtered_indices.is_some() {
             format!(" [filtered: {}/{}]", self.display_count(), self.line_count())
         } else {
             String::new()
         };
-        format!("{} line {}/{}{}",
+        // Until the whole file is indexed the total is only a lower bound
+        let more = if self.fully_indexed() { "" } else { "+" };
+        format!("{} line {}/{}{}{}",
             self.file_name,
             self.selection + 1,
             self.display_count(),
+            more,
             filter_info
         )
     }
 
-    pub fn status_line(&self) -> String {
+    fn status_line(&self) -> String {
         let query = self
             .last_query
             .as_ref()
@@ -282,19 +474,26 @@ impl TextEngine {
         )
     }
 
-    #[allow(dead_code)]
-    pub fn selected_path(&self) -> Option<String> {
+    /// Keys specific to this view, listed in the help overlay
+    fn help_lines(&self) -> Vec<Line<'static>> {
+        vec![
+            Line::from("  h/l, 0/$     Cursor left/right, line start/end"),
+            Line::from("  w/b          Cursor to next/previous word"),
+        ]
+    }
+
+    fn selected_path(&self) -> Option<String> {
         None
     }
 
     /// Get the content of the currently selected line
-    pub fn get_selected_line(&self) -> Option<String> {
+    fn get_selected_line(&self) -> Option<String> {
         let actual_idx = self.display_to_actual(self.selection)?;
         self.get_line(actual_idx).map(|s| s.to_string())
     }
 
     /// Get lines in a range (inclusive), joined by newlines
-    pub fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
+    fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
         let (start, end) = if start <= end { (start, end) } else { (end, start) };
         let total = self.display_count();
         if start >= total {
@@ -314,16 +513,104 @@ impl TextEngine {
         }
     }
 
+    fn source_line(&self, row: usize) -> Option<usize> {
+        self.display_to_actual(row).filter(|&idx| idx < self.line_count()).map(|idx| idx + 1)
+    }
+
     /// Get current selection index (for visual mode)
-    pub fn selection(&self) -> usize {
+    fn selection(&self) -> usize {
         self.selection
     }
 
-    pub fn content_height(&self) -> usize {
+    fn select(&mut self, row: usize) {
+        self.index_lines(row.saturating_add(1));
+        self.selection = row.min(self.display_count().saturating_sub(1));
+        self.cursor_moved = true;
+    }
+
+    fn cursor_col(&self) -> Option<usize> {
+        let line = self.display_to_actual(self.selection).and_then(|row| self.get_line(row))?;
+        Some(self.clamped_cursor(&line))
+    }
+
+    fn append(&mut self, bytes: &[u8]) {
+        // Keep following the end of the stream when the last line is selected
+        let at_end = self.filtered_indices.is_none()
+            && self.fully_indexed()
+            && self.selection + 1 >= self.line_count();
+        let old_len = self.data.len();
+        self.data.extend(bytes);
+        // A newline at the very end was scanned without starting a line; it does now
+        if self.indexed_to == old_len && old_len > 0 && self.data[old_len - 1] == b'\n' {
+            self.line_offsets.push(old_len);
+        }
+        // An unfinished last line may have grown, so measure it again
+        self.measured = self.measured.min(self.line_count().saturating_sub(1));
+        if at_end {
+            self.index_all();
+            self.selection = self.line_count() - 1;
+        }
+    }
+
+    /// Scroll so the selection sits at the top, middle or bottom of the view (`zt`/`zz`/`zb`)
+    fn align_scroll(&mut self, align: super::ScrollAlign) {
+        self.scroll = super::aligned_scroll(self.selection, self.last_view_height, align);
+    }
+
+    fn set_scroll(&mut self, row: usize) {
+        self.scroll = row;
+    }
+
+    fn h_scroll(&self) -> usize {
+        self.h_scroll
+    }
+
+    fn set_h_scroll(&mut self, cols: usize) {
+        self.h_scroll = cols;
+    }
+
+    fn content_height(&mut self) -> usize {
+        self.index_all();
         self.line_count()
     }
 
-    pub fn render_plain_lines(&self, _width: u16) -> Vec<Line<'static>> {
+    /// Parsed structure for `--dump`
+    fn to_json(&mut self) -> serde_json::Value {
+        self.index_all();
+        let lines: Vec<_> = (0..self.line_count()).map(|idx| self.get_line(idx).unwrap_or_default()).collect();
+        json!({ "lines": lines })
+    }
+
+    /// Widest line indexed so far, so huge files aren't scanned just to measure them.
+    /// Only lines indexed since the last call are measured.
+    fn content_width(&mut self) -> usize {
+        for idx in self.measured..self.line_count() {
+            if let Some(line) = self.get_line(idx) {
+                self.widest = self.widest.max(self.content_cols(&line));
+            }
+        }
+        self.measured = self.line_count();
+        let line_no_width = self.line_count().max(1).to_string().len().max(2);
+        let gutter = if super::line_numbers() { line_no_width + 3 } else { 0 };
+        let widest = match (&self.filtered_indices, self.filtered_widest) {
+            (None, _) => self.widest,
+            (Some(_), Some(widest)) => widest,
+            (Some(indices), None) => {
+                let widest = indices
+                    .iter()
+                    .filter_map(|&idx| self.get_line(idx))
+                    .map(|line| self.content_cols(&line))
+                    .max()
+                    .unwrap_or(0);
+                self.filtered_widest = Some(widest);
+                widest
+            }
+        };
+        gutter + widest
+    }
+
+    fn render_plain_lines(&mut self, _width: u16) -> Vec<Line<'static>> {
+        self.index_all();
         let total = self.line_count();
         let line_no_width = total.max(1).to_string().len().max(2);
 
@@ -332,60 +619,212 @@ impl TextEngine {
                 let line_content = self.get_line(idx)?;
                 let mut spans = Vec::new();
                 let line_no = format!("{:>width$} ", idx + 1, width = line_no_width);
-                spans.push(Span::styled(line_no, Style::default().fg(Color::LightYellow)));
-                spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
-                spans.push(Span::styled(line_content.to_string(), Style::default().fg(Color::White)));
+                if super::line_numbers() {
+                    spans.push(Span::styled(line_no, Style::default().fg(Color::LightYellow)));
+                    spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
+                }
+                let content = ansi_spans(&line_content, Style::default().fg(Color::White), super::ansi());
+                spans.extend(super::expand_tabs(content, self.layout.tab_width));
                 Some(Line::from(spans))
             })
             .collect()
     }
+}
 
-    fn search_next(&mut self, query: &str, forward: bool) {
-        let trimmed = query.trim();
-        if trimmed.is_empty() {
-            return;
-        }
-        let lower = trimmed.to_lowercase();
-        let total = self.line_count().max(1);
-        let start = if forward {
-            (self.selection + 1) % total
-        } else {
-            self.selection.saturating_sub(1)
-        };
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::engines::{EngineState, ScrollAlign};
+
+    #[test]
+    fn filtered_rows_map_to_their_file_lines() {
+        let mut engine = TextEngine::from_bytes(Path::new("notes.txt"), b"alpha\nbeta\nalpine\n").unwrap();
+        // Rows past the lines indexed so far aren't on screen yet
+        assert_eq!(engine.source_line(1), None);
+        engine.index_all();
+        assert_eq!(engine.source_line(1), Some(2));
+        assert!(engine.apply_filter("alp"));
+        assert_eq!((engine.source_line(0), engine.source_line(1)), (Some(1), Some(3)));
+        assert_eq!(engine.source_line(2), None);
+    }
 
-        for offset in 0..total {
-            let idx = if forward {
-                (start + offset) % total
-            } else {
-                (start + total - offset % total) % total
-            };
-            if let Some(line) = self.get_line(idx) {
-                if line.to_lowercase().contains(&lower) {
-                    self.selection = idx;
-                    break;
-                }
-            }
-        }
-        self.last_match = Some(trimmed.to_string());
+    #[test]
+    fn lines_after_invalid_utf8_still_render() {
+        let mut engine = TextEngine::from_bytes(Path::new("mixed.txt"), b"one\ntw\xffo\nthree\n").unwrap();
+        let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(20, 4)).unwrap();
+        terminal.draw(|frame| engine.render(frame, frame.size())).unwrap();
+        let buffer = terminal.backend().buffer();
+        let rows: Vec<String> = (0..3).map(|y| (0..20).map(|x| buffer.get(x, y).symbol()).collect()).collect();
+        assert!(rows[1].contains("tw\u{fffd}o"), "{:?}", rows);
+        assert!(rows[2].contains("three"), "{:?}", rows);
     }
-}
 
-/// Build an index of byte offsets for each line start.
-/// This is O(n) but only stores ~8 bytes per line (just the offset).
-fn build_line_offsets(data: &[u8]) -> Vec<usize> {
-    let mut offsets = Vec::new();
-    offsets.push(0); // First line starts at 0
+    #[test]
+    fn append_extends_lines_and_follows_the_end() {
+        let mut engine = TextEngine::from_bytes(Path::new("<stdin>"), b"").unwrap();
+        engine.append(b"one\ntw");
+        engine.append(b"o\n");
+        engine.append(b"three\n");
+        engine.index_all();
+        assert_eq!(engine.line_count(), 3);
+        assert_eq!(engine.get_line(1).as_deref(), Some("two"));
+        assert_eq!(engine.selection(), 2);
+
+        // Scrolling back up stops the view from following new lines
+        engine.handle_key(KeyEvent::new(KeyCode::Char('g'), KeyModifiers::NONE));
+        engine.handle_key(KeyEvent::new(KeyCode::Char('g'), KeyModifiers::NONE));
+        engine.append(b"four\n");
+        assert_eq!(engine.selection(), 0);
+    }
 
-    for (i, &byte) in data.iter().enumerate() {
-        if byte == b'\n' && i + 1 < data.len() {
-            offsets.push(i + 1);
-        }
+    #[test]
+    fn lines_are_indexed_on_demand() {
+        let text: String = (1..=50).map(|n| format!("line {}\n", n)).collect();
+        let mut engine = TextEngine::from_bytes(Path::new("notes.txt"), text.as_bytes()).unwrap();
+        assert!(!engine.fully_indexed());
+        assert_eq!(engine.get_line(0).as_deref(), Some("line 1"));
+        assert!(!engine.fits_height(10));
+        assert!(engine.fits_height(50));
+
+        engine.handle_key(KeyEvent::new(KeyCode::Char('G'), KeyModifiers::NONE));
+        assert!(engine.fully_indexed());
+        assert_eq!(engine.selection(), 49);
+        assert_eq!(engine.get_selected_line().as_deref(), Some("line 50"));
     }
 
-    offsets
-}
+    #[test]
+    fn search_and_filter_report_missing_patterns() {
+        let mut engine = TextEngine::from_bytes(Path::new("notes.txt"), b"alpha\nbeta\n").unwrap();
+        assert!(engine.apply_search("beta", true));
+        assert_eq!(engine.selection(), 1);
+        assert!(!engine.apply_search("gamma", true));
+        assert_eq!(engine.selection(), 1);
+        assert!(!engine.apply_filter("gamma"));
+    }
+
+    #[test]
+    fn tail_selects_the_end_with_the_last_lines_in_view() {
+        let text: String = (1..=50).map(|n| format!("line {}\n", n)).collect();
+        let engine = TextEngine::from_bytes(Path::new("app.txt"), text.as_bytes()).unwrap();
+        let mut state = EngineState::new(engine);
+        state.tail(5);
+        let engine = state.downcast_mut::<TextEngine>().unwrap();
+        assert_eq!(engine.selection, 49);
+        assert_eq!(engine.scroll, 45);
+    }
+
+    #[test]
+    fn jump_selects_the_line_at_the_top_without_replaying_keys() {
+        let text: String = (1..=50).map(|n| format!("line {}\n", n)).collect();
+        let mut state = EngineState::new(TextEngine::from_bytes(Path::new("app.txt"), text.as_bytes()).unwrap());
+        state.jump_to_line(39);
+        assert_eq!((state.selection(), state.downcast_mut::<TextEngine>().unwrap().scroll), (39, 39));
+        state.jump_to_line(500);
+        assert_eq!(state.selection(), 49);
+    }
 
-fn page_jump(view_height: usize) -> usize {
-    let half = view_height / 2;
-    if half == 0 { 1 } else { half }
+    #[test]
+    fn sideways_scroll_survives_render_until_the_cursor_moves() {
+        let text = format!("{}\nshort\n", "x".repeat(100));
+        let mut engine = TextEngine::from_bytes(Path::new("wide.txt"), text.as_bytes()).unwrap();
+        let mut terminal = ratatui::Terminal::new(ratatui::backend::TestBackend::new(30, 4)).unwrap();
+        terminal.draw(|frame| engine.render(frame, frame.size())).unwrap();
+
+        // `zl` with the cursor at column 0
+        engine.set_h_scroll(1);
+        terminal.draw(|frame| engine.render(frame, frame.size())).unwrap();
+        assert_eq!(engine.h_scroll(), 1);
+
+        // Moving the cursor brings it back into view
+        engine.handle_key(KeyEvent::from(KeyCode::Char('0')));
+        terminal.draw(|frame| engine.render(frame, frame.size())).unwrap();
+        assert_eq!(engine.h_scroll(), 0);
+    }
+
+    #[test]
+    fn align_scroll_places_selection_in_view() {
+        let text: String = (1..=50).map(|n| format!("line {}\n", n)).collect();
+        let mut engine = TextEngine::from_bytes(Path::new("notes.txt"), text.as_bytes()).unwrap();
+        engine.last_view_height = 10;
+        engine.selection = 30;
+
+        engine.align_scroll(ScrollAlign::Center);
+        assert_eq!(engine.scroll, 25);
+        engine.align_scroll(ScrollAlign::Top);
+        assert_eq!(engine.scroll, 30);
+        engine.align_scroll(ScrollAlign::Bottom);
+        assert_eq!(engine.scroll, 21);
+    }
+
+    #[test]
+    fn wrapped_rows_keep_clear_of_the_gutter() {
+        let line = Line::from(vec![Span::raw(" 1 │ "), Span::raw("abcdefgh")]);
+        let rows: Vec<String> = crate::engines::wrap_line(line, 9, 5)
+            .iter()
+            .map(|row| row.spans.iter().map(|span| span.content.as_ref()).collect())
+            .collect();
+        assert_eq!(rows, vec![" 1 │ abcd", "     efgh"]);
+
+        let expanded = crate::engines::expand_tabs(vec![Span::raw("a\tb")], 4);
+        assert_eq!(expanded[0].content, "a   b");
+    }
+
+    #[test]
+    fn crlf_files_render_like_lf() {
+        let mut lf = TextEngine::from_bytes(Path::new("a.txt"), b"one\n\tTODO two\n\nlast").unwrap();
+        let mut crlf = TextEngine::from_bytes(Path::new("a.txt"), b"one\r\n\tTODO two\r\n\r\nlast").unwrap();
+        assert_eq!(crlf.render_plain_lines(80), lf.render_plain_lines(80));
+        assert_eq!(crlf.content_width(), lf.content_width());
+    }
+
+    #[test]
+    fn content_width_measures_indexed_lines() {
+        let mut engine = TextEngine::from_bytes(Path::new("notes.txt"), b"ab\n\tlonger line\nc\n").unwrap();
+        engine.index_all();
+        // Gutter (" 2 │ ") plus the tab-expanded second line
+        assert_eq!(engine.content_width(), 5 + 4 + 11);
+
+        let line = Line::from(vec![Span::raw(" 1 │ "), Span::raw("abcdefgh")]);
+        let shifted = crate::engines::shift_line(line, 3, 5);
+        let text: String = shifted.spans.iter().map(|span| span.content.as_ref()).collect();
+        assert_eq!(text, " 1 │ defgh");
+    }
+
+    #[test]
+    fn content_width_grows_with_the_index_and_follows_the_filter() {
+        let mut engine = TextEngine::from_bytes(Path::new("notes.txt"), b"ab\nabcdefgh\nabc\n").unwrap();
+        engine.index_lines(1);
+        assert_eq!(engine.content_width(), 5 + 2);
+        engine.index_all();
+        assert_eq!(engine.content_width(), 5 + 8);
+        engine.apply_filter("abc");
+        assert_eq!(engine.content_width(), 5 + 8);
+        engine.apply_filter("\\<abc\\>");
+        assert_eq!(engine.content_width(), 5 + 3);
+        engine.clear_filter();
+        assert_eq!(engine.content_width(), 5 + 8);
+    }
+
+    #[test]
+    fn cursor_moves_within_the_selected_line() {
+        let mut engine = TextEngine::from_bytes(Path::new("notes.txt"), b"let total = a + b;\nx\n").unwrap();
+        let key = |c| KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE);
+        engine.handle_key(key('l'));
+        assert_eq!(engine.cursor_col(), Some(1));
+        engine.handle_key(key('w'));
+        assert_eq!(engine.cursor_col(), Some(4));
+        engine.handle_key(key('w'));
+        assert_eq!(engine.cursor_col(), Some(10));
+        engine.handle_key(key('b'));
+        assert_eq!(engine.cursor_col(), Some(4));
+        engine.handle_key(key('$'));
+        assert_eq!(engine.cursor_col(), Some(17));
+
+        // A shorter line keeps the cursor on its last character
+        engine.handle_key(key('j'));
+        assert_eq!(engine.cursor_col(), Some(0));
+        engine.handle_key(key('0'));
+        engine.handle_key(key('k'));
+        assert_eq!(engine.cursor_col(), Some(0));
+    }
 }
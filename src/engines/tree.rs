@@ -8,7 +8,11 @@ use memmap2::Mmap;
 use ratatui::layout::Rect;
 use ratatui::style::{Color, Style, Stylize};
 use ratatui::text::{Line, Span};
-use ratatui::widgets::{Block, Borders, List, ListItem};
+use ratatui::widgets::{Block, Borders, List, ListItem, Paragraph};
+use serde_json::json;
+
+use super::util::{search_matches, truncate};
+use super::{Engine, TableEngine};
 
 /// Maximum file size for TreeEngine (50MB)
 /// For larger files, recommend using JSONL format instead
@@ -58,12 +62,21 @@ pub struct TreeEngine {
     scroll: usize,
     collapsed: HashSet<String>,
     flat: Vec<FlatNode>,
+    /// Set when `collapsed` changes so `flat` is rebuilt on next use
+    flat_dirty: bool,
     last_query: Option<String>,
     pending_g: bool,
     last_view_height: usize,
     last_match: Option<String>,
+    /// Digits typed after `:` for an array index jump
+    index_input: Option<String>,
     /// Visual selection range (start, end) for highlighting
     pub visual_range: Option<(usize, usize)>,
+    /// The root is a non-empty array of objects, which `s` shows one row per record
+    records: bool,
+    /// The records table, built on the first `s` and shown while `show_table` is set
+    table: Option<Box<TableEngine>>,
+    show_table: bool,
 }
 
 impl TreeEngine {
@@ -89,15 +102,20 @@ impl TreeEngine {
         Self::from_bytes_internal(ext, &mmap)
     }
 
-    /// Create TreeEngine from bytes (used by tests)
-    #[allow(dead_code)]
+    /// Create TreeEngine from in-memory bytes; `path` only supplies the extension
     pub fn from_bytes(path: &Path, bytes: &[u8]) -> Result<Self> {
         let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("");
         Self::from_bytes_internal(ext, bytes)
     }
 
     fn from_bytes_internal(ext: &str, bytes: &[u8]) -> Result<Self> {
-        let value = parse_value(ext, bytes)?;
+        let mut value = parse_value(ext, bytes)?;
+        if let Some(outline) = openapi_outline(&value) {
+            value = outline;
+        }
+        let records = value
+            .as_array()
+            .is_some_and(|records| !records.is_empty() && records.iter().all(serde_json::Value::is_object));
         let mut arena = Vec::new();
         let root = build_json_node(&value, "root".to_string(), &mut arena);
         let mut engine = Self {
@@ -107,31 +125,284 @@ impl TreeEngine {
             scroll: 0,
             collapsed: HashSet::new(),
             flat: Vec::new(),
+            flat_dirty: false,
             last_query: None,
             pending_g: false,
             last_view_height: 0,
             last_match: None,
+            index_input: None,
             visual_range: None,
+            records,
+            table: None,
+            show_table: false,
         };
         engine.rebuild_flat();
         Ok(engine)
     }
 
-    pub fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+    /// The records table while it is shown
+    fn table_view(&self) -> Option<&TableEngine> {
+        self.table.as_deref().filter(|_| self.show_table)
+    }
+
+    fn table_view_mut(&mut self) -> Option<&mut TableEngine> {
+        self.table.as_deref_mut().filter(|_| self.show_table)
+    }
+
+    /// Switch between the tree and the records table, building the table the first time
+    fn toggle_table(&mut self) {
+        if self.table.is_none() {
+            let serde_json::Value::Array(records) = self.node_json(self.root) else {
+                return;
+            };
+            let Ok(table) = TableEngine::from_records("root", &records) else {
+                return;
+            };
+            self.table = Some(Box::new(table));
+        }
+        self.show_table = !self.show_table;
+    }
+
+    /// Select `[index]` in the selected array, or in the nearest array above the selection
+    fn jump_to_array_index(&mut self, index: usize) {
+        let Some(array) = ancestors(&self.flat, self.selection)
+            .find(|&idx| matches!(self.flat[idx].value_kind, ValueKind::Array))
+        else {
+            return;
+        };
+        if self.collapsed.remove(&self.flat[array].copy_path) {
+            self.rebuild_flat();
+        }
+        let depth = self.flat[array].depth + 1;
+        let label = format!("[{}]", index);
+        if let Some(offset) = self.flat[array + 1..]
+            .iter()
+            .take_while(|f| f.depth >= depth)
+            .position(|f| f.depth == depth && f.label == label)
+        {
+            self.selection = array + 1 + offset;
+        }
+    }
+
+    /// Serialize a node to JSON string
+    fn node_to_json(&self, idx: usize) -> String {
+        let node = &self.arena[idx];
+        match &node.kind {
+            NodeKind::Null => "null".to_string(),
+            NodeKind::Bool(b) => b.to_string(),
+            NodeKind::Number(n) => n.clone(),
+            NodeKind::String(s) => format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"")),
+            NodeKind::Object => {
+                let pairs: Vec<String> = node.children.iter().map(|&child_idx| {
+                    let child = &self.arena[child_idx];
+                    let key = &child.label;
+                    let value = self.node_to_json(child_idx);
+                    format!("\"{}\": {}", key, value)
+                }).collect();
+                format!("{{{}}}", pairs.join(", "))
+            }
+            NodeKind::Array => {
+                let items: Vec<String> = node.children.iter().map(|&child_idx| {
+                    self.node_to_json(child_idx)
+                }).collect();
+                format!("[{}]", items.join(", "))
+            }
+        }
+    }
+
+    /// Fold every container `depth` or more levels below the root (`--depth`), so
+    /// `0` shows only the root and `1` shows the top-level keys folded
+    pub fn collapse_to_depth(&mut self, depth: usize) {
+        let folds = self.container_paths().into_iter().filter(|(level, _)| *level >= depth);
+        self.collapsed.extend(folds.map(|(_, path)| path));
         self.rebuild_flat();
+        self.selection = self.selection.min(self.flat.len().saturating_sub(1));
+    }
+
+    /// Every object and array with its level below the root
+    fn container_paths(&self) -> Vec<(usize, String)> {
+        let mut paths = Vec::new();
+        let mut segments = vec!["root".to_string()];
+        self.collect_containers(self.root, 0, &mut segments, &mut paths);
+        paths
+    }
+
+    fn collect_containers(
+        &self,
+        index: usize,
+        level: usize,
+        segments: &mut Vec<String>,
+        paths: &mut Vec<(usize, String)>,
+    ) {
+        if !matches!(self.arena[index].kind, NodeKind::Object | NodeKind::Array) {
+            return;
+        }
+        paths.push((level, path_from_segments(segments)));
+        for &child in &self.arena[index].children {
+            segments.push(self.arena[child].label.clone());
+            self.collect_containers(child, level + 1, segments, paths);
+            segments.pop();
+        }
+    }
+
+    /// The value under `index`, rebuilt from the arena
+    fn node_json(&self, index: usize) -> serde_json::Value {
+        let node = &self.arena[index];
+        match &node.kind {
+            NodeKind::Null => serde_json::Value::Null,
+            NodeKind::Bool(value) => json!(value),
+            NodeKind::Number(value) => serde_json::from_str(value).unwrap_or_else(|_| json!(value)),
+            NodeKind::String(value) => json!(value),
+            NodeKind::Array => node.children.iter().map(|&child| self.node_json(child)).collect(),
+            NodeKind::Object => serde_json::Value::Object(
+                node.children
+                    .iter()
+                    .map(|&child| (self.arena[child].label.clone(), self.node_json(child)))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Rebuild the flattened view only if folding changed since the last build
+    fn ensure_flat(&mut self) {
+        if self.flat_dirty {
+            self.rebuild_flat();
+        }
+    }
+
+    fn rebuild_flat(&mut self) {
+        self.flat.clear();
+        let mut segments = vec!["root".to_string()];
+        self.flatten(self.root, 0, &mut segments);
+        self.flat_dirty = false;
+    }
+
+    fn flatten(&mut self, index: usize, depth: usize, segments: &mut Vec<String>) {
+        let (label, kind, children) = {
+            let node = &self.arena[index];
+            (node.label.clone(), node.kind.clone(), node.children.clone())
+        };
+        let copy_path = path_from_segments(segments);
+        let breadcrumb = segments.join(" > ");
+        let (value_preview, value_kind, is_container) = match &kind {
+            NodeKind::Null => ("null".to_string(), ValueKind::Null, false),
+            NodeKind::Bool(value) => (value.to_string(), ValueKind::Bool, false),
+            NodeKind::Number(value) => (value.clone(), ValueKind::Number, false),
+            NodeKind::String(value) => {
+                (format!("\"{}\"", truncate(&escape_line_breaks(value), super::preview_len(50))), ValueKind::String, false)
+            }
+            NodeKind::Object => (container_preview('{', '}', children.len(), "key"), ValueKind::Object, true),
+            NodeKind::Array => (container_preview('[', ']', children.len(), "item"), ValueKind::Array, true),
+        };
+
+        self.flat.push(FlatNode {
+            arena_idx: index,
+            depth,
+            copy_path,
+            breadcrumb,
+            label,
+            value_preview,
+            value_kind,
+            is_container,
+        });
+
+        if is_container && self.collapsed.contains(&path_from_segments(segments)) {
+            return;
+        }
+
+        for child in children {
+            match &self.arena[child].kind {
+                NodeKind::Array | NodeKind::Object => {
+                    let label = self.arena[child].label.clone();
+                    segments.push(label);
+                }
+                _ => {
+                    let label = self.arena[child].label.clone();
+                    segments.push(label);
+                }
+            }
+            self.flatten(child, depth + 1, segments);
+            segments.pop();
+        }
+    }
+
+    /// Ancestor path of the selection, if its parent row is scrolled above the view
+    fn sticky_path(&self) -> Option<String> {
+        let selected = self.flat.get(self.selection)?;
+        let parent = self.flat[..self.selection]
+            .iter()
+            .rposition(|flat| flat.depth + 1 == selected.depth)?;
+        if parent >= self.scroll {
+            return None;
+        }
+        selected.breadcrumb.rsplit_once(" > ").map(|(ancestors, _)| ancestors.to_string())
+    }
+
+    fn list_state(&self) -> ratatui::widgets::ListState {
+        let mut state = ratatui::widgets::ListState::default();
+        if !self.flat.is_empty() {
+            let relative = self.selection.saturating_sub(self.scroll);
+            state.select(Some(relative));
+        }
+        state
+    }
+}
+
+impl Engine for TreeEngine {
+    fn name(&self) -> &'static str {
+        self.table_view().map_or("TreeEngine", |table| table.name())
+    }
+
+    fn set_visual_range(&mut self, range: Option<(usize, usize)>) {
+        if let Some(table) = self.table_view_mut() {
+            table.set_visual_range(range);
+        }
+        self.visual_range = range;
+    }
+
+    fn set_visual_columns(&mut self, columns: Option<(usize, usize)>) {
+        if let Some(table) = self.table_view_mut() {
+            table.set_visual_columns(columns);
+        }
+    }
+
+    fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+        if let Some(table) = self.table_view_mut() {
+            table.render(frame, area);
+            return;
+        }
+        self.ensure_flat();
         if self.selection >= self.flat.len() {
             self.selection = self.flat.len().saturating_sub(1);
         }
 
-        let height = area.height as usize;
+        let mut area = area;
+        let mut height = area.height as usize;
         self.last_view_height = height;
-        if self.selection < self.scroll {
-            self.scroll = self.selection;
-        } else if self.selection >= self.scroll + height {
-            self.scroll = self.selection.saturating_sub(height - 1);
-        }
+        self.scroll = super::follow_selection(self.scroll, self.selection, height, self.flat.len());
 
         let line_no_width = self.flat.len().max(1).to_string().len().max(2);
+
+        // Pin the ancestor path to the top once the parent row scrolls out of view
+        if let Some(path) = self.sticky_path().filter(|_| height > 1) {
+            let mut spans = Vec::new();
+            if super::line_numbers() {
+                spans.push(Span::styled(
+                    format!("{:>width$} ", "↑", width = line_no_width),
+                    Style::default().fg(Color::DarkGray),
+                ));
+                spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
+            }
+            spans.push(Span::styled(path, Style::default().fg(Color::LightGreen).italic()));
+            let sticky = Line::from(spans).style(Style::default().underlined());
+            frame.render_widget(Paragraph::new(sticky), Rect { height: 1, ..area });
+            area = Rect { y: area.y + 1, height: area.height - 1, ..area };
+            height -= 1;
+            if self.selection >= self.scroll + height {
+                self.scroll += 1;
+            }
+        }
+
         let items: Vec<ListItem> = self
             .flat
             .iter()
@@ -139,13 +410,14 @@ impl TreeEngine {
             .skip(self.scroll)
             .take(height)
             .map(|(idx, flat)| {
-                let in_visual = self.visual_range.map_or(false, |(start, end)| {
+                let in_visual = self.visual_range.is_some_and(|(start, end)| {
                     let (lo, hi) = if start <= end { (start, end) } else { (end, start) };
                     idx >= lo && idx <= hi
                 });
                 let selected = idx == self.selection;
                 let mut spans = Vec::new();
-                let line_no = format!("{:>width$} ", idx + 1, width = line_no_width);
+                let line_no = super::gutter_number(idx + 1, idx, self.selection);
+                let line_no = format!("{:>width$} ", line_no, width = line_no_width);
                 let line_no_style = if selected {
                     Style::default().fg(Color::Black).bg(Color::LightBlue).bold()
                 } else if in_visual {
@@ -153,8 +425,10 @@ impl TreeEngine {
                 } else {
                     Style::default().fg(Color::LightYellow)
                 };
-                spans.push(Span::styled(line_no, line_no_style));
-                spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
+                if super::line_numbers() {
+                    spans.push(Span::styled(line_no, line_no_style));
+                    spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
+                }
                 let indent = "  ".repeat(flat.depth);
                 spans.push(Span::raw(indent));
                 if flat.is_container {
@@ -179,7 +453,7 @@ impl TreeEngine {
                 } else {
                     Style::default().bold().fg(Color::White)
                 };
-                spans.push(Span::styled(format!("{}", flat.label), key_style));
+                spans.push(Span::styled(flat.label.to_string(), key_style));
 
                 if !flat.value_preview.is_empty() {
                     let colon_style = if selected {
@@ -217,13 +491,24 @@ impl TreeEngine {
         frame.render_stateful_widget(list, area, &mut self.list_state());
     }
 
-    pub fn content_height(&mut self) -> usize {
-        self.rebuild_flat();
+    fn content_height(&mut self) -> usize {
+        if let Some(table) = self.table_view_mut() {
+            return table.content_height();
+        }
+        self.ensure_flat();
         self.flat.len()
     }
 
-    pub fn render_plain_lines(&mut self) -> Vec<Line<'static>> {
-        self.rebuild_flat();
+    /// Parsed structure for `--dump`
+    fn to_json(&mut self) -> serde_json::Value {
+        self.node_json(self.root)
+    }
+
+    fn render_plain_lines(&mut self, width: u16) -> Vec<Line<'static>> {
+        if let Some(table) = self.table_view_mut() {
+            return table.render_plain_lines(width);
+        }
+        self.ensure_flat();
         let line_no_width = self.flat.len().max(1).to_string().len().max(2);
         self.flat
             .iter()
@@ -231,11 +516,13 @@ impl TreeEngine {
             .map(|(idx, flat)| {
                 let mut spans = Vec::new();
                 let line_no = format!("{:>width$} ", idx + 1, width = line_no_width);
-                spans.push(Span::styled(
-                    line_no,
-                    Style::default().fg(Color::LightYellow),
-                ));
-                spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
+                if super::line_numbers() {
+                    spans.push(Span::styled(
+                        line_no,
+                        Style::default().fg(Color::LightYellow),
+                    ));
+                    spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
+                }
                 let indent = "  ".repeat(flat.depth);
                 spans.push(Span::raw(indent));
                 if flat.is_container {
@@ -249,7 +536,7 @@ impl TreeEngine {
                     spans.push(Span::raw("  "));
                 }
                 spans.push(Span::styled(
-                    format!("{}", flat.label),
+                    flat.label.to_string(),
                     Style::default().bold().fg(Color::White),
                 ));
                 if !flat.value_preview.is_empty() {
@@ -268,7 +555,41 @@ impl TreeEngine {
             .collect()
     }
 
-    pub fn handle_key(&mut self, key: KeyEvent) {
+    #[allow(clippy::collapsible_match)]
+    fn handle_key(&mut self, key: KeyEvent) {
+        if key.code == KeyCode::Char('s') && self.records && self.index_input.is_none() {
+            self.toggle_table();
+            return;
+        }
+        if let Some(table) = self.table_view_mut() {
+            table.handle_key(key);
+            return;
+        }
+        self.ensure_flat();
+        if let Some(input) = self.index_input.as_mut() {
+            match key.code {
+                KeyCode::Char(c) if c.is_ascii_digit() => {
+                    input.push(c);
+                    return;
+                }
+                KeyCode::Backspace => {
+                    input.pop();
+                    return;
+                }
+                KeyCode::Enter => {
+                    if let Ok(index) = input.parse() {
+                        self.jump_to_array_index(index);
+                    }
+                    self.index_input = None;
+                    return;
+                }
+                KeyCode::Esc => {
+                    self.index_input = None;
+                    return;
+                }
+                _ => self.index_input = None,
+            }
+        }
         match key.code {
             KeyCode::Char('g') => {
                 if self.pending_g {
@@ -295,14 +616,12 @@ impl TreeEngine {
             KeyCode::Char('u')
                 if key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) =>
             {
-                let jump = page_jump(self.last_view_height).min(self.selection);
-                self.selection = self.selection.saturating_sub(jump);
+                self.selection = super::half_page_up(self.selection, self.last_view_height);
             }
             KeyCode::Char('d')
                 if key.modifiers.contains(crossterm::event::KeyModifiers::CONTROL) =>
             {
-                let jump = page_jump(self.last_view_height).min(self.flat.len().saturating_sub(1));
-                self.selection = (self.selection + jump).min(self.flat.len().saturating_sub(1));
+                self.selection = super::half_page_down(self.selection, self.last_view_height, self.flat.len());
             }
             KeyCode::Char('G') => {
                 if !self.flat.is_empty() {
@@ -327,6 +646,7 @@ impl TreeEngine {
                         } else {
                             self.collapsed.insert(node.copy_path.clone());
                         }
+                        self.flat_dirty = true;
                     }
                 }
             }
@@ -335,56 +655,124 @@ impl TreeEngine {
                     self.selection = next;
                 }
             }
+            KeyCode::Char('E') => {
+                if let Some(prev) = prev_top_level_index(&self.flat, self.selection) {
+                    self.selection = prev;
+                }
+            }
+            KeyCode::Char(']') => {
+                if let Some(next) = sibling_element_index(&self.flat, self.selection, true) {
+                    self.selection = next;
+                }
+            }
+            KeyCode::Char('[') => {
+                if let Some(prev) = sibling_element_index(&self.flat, self.selection, false) {
+                    self.selection = prev;
+                }
+            }
+            KeyCode::Char(':') => {
+                self.index_input = Some(String::new());
+            }
             _ => {}
         }
     }
 
-    pub fn apply_search(&mut self, query: &str) {
+    fn apply_search(&mut self, query: &str, forward: bool) -> bool {
+        if let Some(table) = self.table_view_mut() {
+            return table.apply_search(query, forward);
+        }
         let trimmed = query.trim();
         if trimmed.is_empty() {
-            return;
+            return false;
         }
         self.last_query = Some(trimmed.to_string());
         self.last_match = Some(trimmed.to_string());
-        self.rebuild_flat();
-        self.search_next(trimmed, true);
+        self.ensure_flat();
+        self.search_next(trimmed, forward)
     }
 
-    pub fn breadcrumbs(&self) -> String {
+    fn breadcrumbs(&self) -> String {
+        if let Some(table) = self.table_view() {
+            return table.breadcrumbs();
+        }
         self.flat
             .get(self.selection)
             .map(|f| f.breadcrumb.clone())
             .unwrap_or_else(|| "root".to_string())
     }
 
-    pub fn status_line(&self) -> String {
+    fn status_line(&self) -> String {
+        if let Some(table) = self.table_view() {
+            return format!("s tree | {}", table.status_line());
+        }
         let query = self
             .last_query
             .as_ref()
             .map(|q| format!(" | search: {}", q))
             .unwrap_or_default();
+        if let Some(input) = &self.index_input {
+            return format!("index: [{}] | Enter jump | Esc cancel", input);
+        }
         format!(
-            "j/k move | gg/G jump | Ctrl+u/d half-page | e next top | n/N next/prev | Enter fold | y copy path | / search | f filter{}",
+            "j/k move | gg/G jump | Ctrl+u/d half-page | e/E next/prev top | [/] prev/next element | :N index | n/N next/prev | Enter fold | y copy path | / search | f filter{}{}",
+            if self.records { " | s table" } else { "" },
             query
         )
     }
 
-    pub fn apply_filter(&mut self, query: &str) {
+    fn apply_filter(&mut self, query: &str) -> bool {
+        if let Some(table) = self.table_view_mut() {
+            return table.apply_filter(query);
+        }
         // For tree, filter acts like search - jump to matching nodes
-        self.apply_search(query);
+        self.apply_search(query, true)
     }
 
-    pub fn clear_filter(&mut self) {
+    fn clear_filter(&mut self) {
+        if let Some(table) = self.table_view_mut() {
+            table.clear_filter();
+        }
         self.last_query = None;
     }
 
-    #[allow(dead_code)]
-    pub fn selected_path(&self) -> Option<String> {
+    /// Keys specific to this view, listed in the help overlay
+    fn help_lines(&self) -> Vec<Line<'static>> {
+        let mut lines = vec![
+            Line::from("  Enter        Expand/collapse node"),
+            Line::from("  e/E          Next/previous top-level key"),
+            Line::from("  [/]          Previous/next array element"),
+            Line::from("  :N Enter     Jump to array index N"),
+        ];
+        if self.records {
+            lines.push(Line::from("  s            Toggle tree / one row per record"));
+        }
+        lines
+    }
+
+    fn selected_path(&self) -> Option<String> {
+        if let Some(table) = self.table_view() {
+            return table.selected_path();
+        }
         self.flat.get(self.selection).map(|f| f.copy_path.clone())
     }
 
+    fn markdown_table(&self, start: usize, end: usize) -> Option<String> {
+        self.table_view().and_then(|table| table.markdown_table(start, end))
+    }
+
+    fn block_column(&self) -> Option<usize> {
+        self.table_view().and_then(|table| table.block_column())
+    }
+
+    fn get_block(&self, rows: (usize, usize), columns: (usize, usize)) -> Option<String> {
+        self.table_view().and_then(|table| table.get_block(rows, columns))
+    }
+
     /// Get the content of the currently selected node as JSON
-    pub fn get_selected_line(&self) -> Option<String> {
+    fn get_selected_line(&self) -> Option<String> {
+        if let Some(table) = self.table_view() {
+            return table.get_selected_line();
+        }
         self.flat.get(self.selection).map(|f| {
             self.node_to_json(f.arena_idx)
         })
@@ -392,7 +780,10 @@ impl TreeEngine {
 
     /// Get lines in a range (inclusive) - for tree, we serialize top-level nodes to JSON
     /// If a container and its children are selected, only the container is included
-    pub fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
+    fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
+        if let Some(table) = self.table_view() {
+            return table.get_lines_range(start, end);
+        }
         let (start, end) = if start <= end { (start, end) } else { (end, start) };
         let total = self.flat.len();
         if start >= total {
@@ -426,110 +817,62 @@ impl TreeEngine {
         if results.is_empty() { None } else { Some(results.join("\n")) }
     }
 
-    /// Serialize a node to JSON string
-    fn node_to_json(&self, idx: usize) -> String {
-        let node = &self.arena[idx];
-        match &node.kind {
-            NodeKind::Null => "null".to_string(),
-            NodeKind::Bool(b) => b.to_string(),
-            NodeKind::Number(n) => n.clone(),
-            NodeKind::String(s) => format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\"")),
-            NodeKind::Object => {
-                let pairs: Vec<String> = node.children.iter().map(|&child_idx| {
-                    let child = &self.arena[child_idx];
-                    let key = &child.label;
-                    let value = self.node_to_json(child_idx);
-                    format!("\"{}\": {}", key, value)
-                }).collect();
-                format!("{{{}}}", pairs.join(", "))
-            }
-            NodeKind::Array => {
-                let items: Vec<String> = node.children.iter().map(|&child_idx| {
-                    self.node_to_json(child_idx)
-                }).collect();
-                format!("[{}]", items.join(", "))
-            }
-        }
-    }
-
     /// Get current selection index (for visual mode)
-    pub fn selection(&self) -> usize {
-        self.selection
+    fn selection(&self) -> usize {
+        self.table_view().map_or(self.selection, |table| table.selection())
     }
 
-    fn rebuild_flat(&mut self) {
-        self.flat.clear();
-        let mut segments = vec!["root".to_string()];
-        self.flatten(self.root, 0, &mut segments);
+    fn select(&mut self, row: usize) {
+        if let Some(table) = self.table_view_mut() {
+            table.select(row);
+            return;
+        }
+        self.ensure_flat();
+        self.selection = row.min(self.flat.len().saturating_sub(1));
     }
 
-    fn flatten(&mut self, index: usize, depth: usize, segments: &mut Vec<String>) {
-        let (label, kind, children) = {
-            let node = &self.arena[index];
-            (node.label.clone(), node.kind.clone(), node.children.clone())
-        };
-        let copy_path = path_from_segments(segments);
-        let breadcrumb = segments.join(" > ");
-        let (value_preview, value_kind, is_container) = match &kind {
-            NodeKind::Null => ("null".to_string(), ValueKind::Null, false),
-            NodeKind::Bool(value) => (value.to_string(), ValueKind::Bool, false),
-            NodeKind::Number(value) => (value.clone(), ValueKind::Number, false),
-            NodeKind::String(value) => {
-                let mut preview = value.clone();
-                if preview.len() > 50 {
-                    preview.truncate(47);
-                    preview.push_str("...");
-                }
-                (format!("\"{}\"", preview), ValueKind::String, false)
-            }
-            NodeKind::Object => ("{...}".to_string(), ValueKind::Object, true),
-            NodeKind::Array => ("[...]".to_string(), ValueKind::Array, true),
-        };
-
-        self.flat.push(FlatNode {
-            arena_idx: index,
-            depth,
-            copy_path,
-            breadcrumb,
-            label,
-            value_preview,
-            value_kind,
-            is_container,
-        });
-
-        if is_container && self.collapsed.contains(&path_from_segments(segments)) {
+    /// Scroll so the selection sits at the top, middle or bottom of the view (`zt`/`zz`/`zb`)
+    fn align_scroll(&mut self, align: super::ScrollAlign) {
+        if let Some(table) = self.table_view_mut() {
+            table.align_scroll(align);
             return;
         }
+        self.scroll = super::aligned_scroll(self.selection, self.last_view_height, align);
+    }
 
-        for child in children {
-            match &self.arena[child].kind {
-                NodeKind::Array | NodeKind::Object => {
-                    let label = self.arena[child].label.clone();
-                    segments.push(label);
-                }
-                _ => {
-                    let label = self.arena[child].label.clone();
-                    segments.push(label);
-                }
-            }
-            self.flatten(child, depth + 1, segments);
-            segments.pop();
-        }
+    /// Paths of the folded containers, for the state file
+    fn folds(&self) -> Option<Vec<String>> {
+        let mut folds: Vec<String> = self.collapsed.iter().cloned().collect();
+        folds.sort();
+        Some(folds)
     }
 
-    fn list_state(&self) -> ratatui::widgets::ListState {
-        let mut state = ratatui::widgets::ListState::default();
-        if !self.flat.is_empty() {
-            let relative = self.selection.saturating_sub(self.scroll);
-            state.select(Some(relative));
-        }
-        state
+    /// Fold the containers saved by `folds`; paths no longer in the document are dropped
+    fn restore_folds(&mut self, folds: &[String]) {
+        let saved: HashSet<&str> = folds.iter().map(String::as_str).collect();
+        self.collapsed = self
+            .container_paths()
+            .into_iter()
+            .map(|(_, path)| path)
+            .filter(|path| saved.contains(path.as_str()))
+            .collect();
+        self.rebuild_flat();
+        self.selection = self.selection.min(self.flat.len().saturating_sub(1));
     }
 }
 
 fn parse_value(ext: &str, bytes: &[u8]) -> Result<serde_json::Value> {
     match ext {
-        "json" => Ok(serde_json::from_slice(bytes)?),
+        // Plain .json files such as tsconfig.json often carry comments too,
+        // so fall back to the lenient reader and keep the strict error if that fails
+        "json" => serde_json::from_slice(bytes)
+            .or_else(|err| parse_jsonc(bytes).map_err(|_| err))
+            .map_err(Into::into),
+        "jsonc" => parse_jsonc(bytes),
+        "json5" => {
+            let raw = std::str::from_utf8(bytes)?;
+            Ok(json5::from_str(raw)?)
+        }
         "yaml" | "yml" => {
             let value: serde_yaml::Value = serde_yaml::from_slice(bytes)?;
             Ok(serde_json::to_value(value)?)
@@ -548,6 +891,178 @@ fn parse_value(ext: &str, bytes: &[u8]) -> Result<serde_json::Value> {
     }
 }
 
+const HTTP_METHODS: [&str; 8] = ["get", "put", "post", "delete", "options", "head", "patch", "trace"];
+
+/// Outline an OpenAPI/Swagger spec as paths → methods → operations.
+/// Returns None unless the document has a top-level `openapi`/`swagger` version and `paths`.
+fn openapi_outline(spec: &serde_json::Value) -> Option<serde_json::Value> {
+    use serde_json::{Map, Value};
+
+    let version = spec.get("openapi").or_else(|| spec.get("swagger"))?.as_str()?;
+    let paths = spec.get("paths")?.as_object()?;
+
+    let mut outline = Map::new();
+    for (path, item) in paths {
+        let Some(item) = item.as_object() else { continue };
+        let mut methods = Map::new();
+        for (method, op) in item {
+            if !HTTP_METHODS.contains(&method.as_str()) {
+                continue;
+            }
+            let label = match op.get("summary").and_then(Value::as_str) {
+                Some(summary) if !summary.is_empty() => format!("{} — {}", method.to_uppercase(), summary),
+                _ => method.to_uppercase(),
+            };
+            methods.insert(label, operation_outline(op));
+        }
+        outline.insert(path.clone(), Value::Object(methods));
+    }
+
+    let mut root = Map::new();
+    root.insert("openapi".to_string(), Value::String(version.to_string()));
+    for key in ["title", "version"] {
+        if let Some(value) = spec.pointer(&format!("/info/{}", key)) {
+            root.insert(key.to_string(), value.clone());
+        }
+    }
+    if let Some(servers) = spec.get("servers").and_then(Value::as_array) {
+        let urls = servers.iter().filter_map(|s| s.get("url").cloned()).collect();
+        root.insert("servers".to_string(), Value::Array(urls));
+    }
+    root.insert("paths".to_string(), Value::Object(outline));
+    let schemas = spec
+        .pointer("/components/schemas")
+        .or_else(|| spec.get("definitions"))
+        .and_then(Value::as_object);
+    if let Some(schemas) = schemas {
+        let names = schemas.keys().map(|k| Value::String(k.clone())).collect();
+        root.insert("schemas".to_string(), Value::Array(names));
+    }
+    Some(Value::Object(root))
+}
+
+/// The parts of an operation worth scanning: id, tags, parameters, body types and responses
+fn operation_outline(op: &serde_json::Value) -> serde_json::Value {
+    use serde_json::{Map, Value};
+
+    let mut out = Map::new();
+    for key in ["operationId", "tags", "deprecated"] {
+        if let Some(value) = op.get(key) {
+            out.insert(key.to_string(), value.clone());
+        }
+    }
+    if let Some(params) = op.get("parameters").and_then(Value::as_array) {
+        let params = params
+            .iter()
+            .map(|p| {
+                let name = p.get("name").and_then(Value::as_str).unwrap_or("?");
+                let location = p.get("in").and_then(Value::as_str).unwrap_or("?");
+                let required = p.get("required").and_then(Value::as_bool).unwrap_or(false);
+                Value::String(format!("{} ({}){}", name, location, if required { ", required" } else { "" }))
+            })
+            .collect();
+        out.insert("parameters".to_string(), Value::Array(params));
+    }
+    if let Some(content) = op.pointer("/requestBody/content").and_then(Value::as_object) {
+        let types = content.keys().map(|k| Value::String(k.clone())).collect();
+        out.insert("requestBody".to_string(), Value::Array(types));
+    }
+    if let Some(responses) = op.get("responses").and_then(Value::as_object) {
+        let responses = responses
+            .iter()
+            .map(|(code, resp)| {
+                let description = resp.get("description").cloned().unwrap_or(Value::Null);
+                (code.clone(), description)
+            })
+            .collect();
+        out.insert("responses".to_string(), Value::Object(responses));
+    }
+    Value::Object(out)
+}
+
+/// Parse JSON with comments (`//`, `/* */`) and trailing commas
+fn parse_jsonc(bytes: &[u8]) -> Result<serde_json::Value> {
+    let raw = std::str::from_utf8(bytes)?;
+    let cleaned = strip_trailing_commas(&strip_json_comments(raw));
+    Ok(serde_json::from_str(&cleaned)?)
+}
+
+fn strip_json_comments(raw: &str) -> String {
+    let mut out = String::with_capacity(raw.len());
+    let mut chars = raw.chars().peekable();
+    let mut in_string = false;
+    while let Some(c) = chars.next() {
+        if in_string {
+            out.push(c);
+            if c == '\\' {
+                if let Some(next) = chars.next() {
+                    out.push(next);
+                }
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                out.push(c);
+            }
+            ('/', Some('/')) => {
+                // Keep the newline so line numbers in errors stay right
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        out.push('\n');
+                        break;
+                    }
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut prev = ' ';
+                for next in chars.by_ref() {
+                    if next == '\n' {
+                        out.push('\n');
+                    }
+                    if prev == '*' && next == '/' {
+                        break;
+                    }
+                    prev = next;
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+fn strip_trailing_commas(raw: &str) -> String {
+    let chars: Vec<char> = raw.chars().collect();
+    let mut out = String::with_capacity(raw.len());
+    let mut in_string = false;
+    let mut escaped = false;
+    for (idx, &c) in chars.iter().enumerate() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+        } else if c == '"' {
+            in_string = true;
+        } else if c == ',' {
+            let next = chars[idx + 1..].iter().find(|c| !c.is_whitespace());
+            if matches!(next, Some('}') | Some(']')) {
+                continue;
+            }
+        }
+        out.push(c);
+    }
+    out
+}
+
 fn build_json_node(value: &serde_json::Value, label: String, arena: &mut Vec<Node>) -> usize {
     let kind = match value {
         serde_json::Value::Null => NodeKind::Null,
@@ -628,6 +1143,15 @@ fn kdl_value_to_json(value: &kdl::KdlValue) -> serde_json::Value {
     }
 }
 
+/// Collapsed-container summary such as `{12 keys}` or `[1 item]`
+fn container_preview(open: char, close: char, count: usize, noun: &str) -> String {
+    match count {
+        0 => format!("{}{}", open, close),
+        1 => format!("{}1 {}{}", open, noun, close),
+        n => format!("{}{} {}s{}", open, n, noun, close),
+    }
+}
+
 fn path_from_segments(segments: &[String]) -> String {
     let mut path = String::new();
     for (idx, seg) in segments.iter().enumerate() {
@@ -654,24 +1178,50 @@ fn next_top_level_index(flat: &[FlatNode], current: usize) -> Option<usize> {
     None
 }
 
-fn page_jump(view_height: usize) -> usize {
-    let half = view_height / 2;
-    if half == 0 { 1 } else { half }
+fn prev_top_level_index(flat: &[FlatNode], current: usize) -> Option<usize> {
+    (0..current.min(flat.len())).rev().find(|&idx| flat[idx].depth == 1)
+}
+
+/// The selected row followed by each of its ancestors, nearest first
+fn ancestors(flat: &[FlatNode], current: usize) -> impl Iterator<Item = usize> + '_ {
+    let mut next = (current < flat.len()).then_some(current);
+    std::iter::from_fn(move || {
+        let idx = next?;
+        let depth = flat[idx].depth;
+        next = (0..idx).rev().find(|&i| flat[i].depth < depth);
+        Some(idx)
+    })
+}
+
+/// Next/previous sibling of the innermost array element containing `current`
+fn sibling_element_index(flat: &[FlatNode], current: usize, forward: bool) -> Option<usize> {
+    let element = ancestors(flat, current).find(|&idx| flat[idx].label.starts_with('['))?;
+    let depth = flat[element].depth;
+    let sibling = if forward {
+        flat[element + 1..]
+            .iter()
+            .position(|f| f.depth <= depth)
+            .map(|offset| element + 1 + offset)
+    } else {
+        flat[..element].iter().rposition(|f| f.depth <= depth)
+    };
+    sibling.filter(|&idx| flat[idx].depth == depth)
 }
 
 impl TreeEngine {
-    pub fn search_next(&mut self, query: &str, forward: bool) {
+    pub fn search_next(&mut self, query: &str, forward: bool) -> bool {
         let trimmed = query.trim();
         if trimmed.is_empty() {
-            return;
+            return false;
         }
-        self.rebuild_flat();
+        self.ensure_flat();
         let lower = trimmed.to_lowercase();
+        let mut found = false;
         let total = self.flat.len().max(1);
         let start = if forward {
             (self.selection + 1) % total
         } else {
-            self.selection.saturating_sub(1)
+            (self.selection + total - 1) % total
         };
         for offset in 0..self.flat.len() {
             let idx = if forward {
@@ -680,21 +1230,201 @@ impl TreeEngine {
                 (start + total - offset % total) % total
             };
             let flat = &self.flat[idx];
-            if flat.label.to_lowercase().contains(&lower)
-                || flat.value_preview.to_lowercase().contains(&lower)
+            if search_matches(&flat.label, &lower)
+                || search_matches(&flat.value_preview, &lower)
             {
                 self.selection = idx;
+                found = true;
                 break;
             }
         }
         self.last_match = Some(trimmed.to_string());
+        found
     }
 }
 
+/// A string value on one row: line breaks shown as `\n` (CRLF counts as one),
+/// so multi-line values from CRLF and LF files preview the same
+fn escape_line_breaks(value: &str) -> String {
+    value.replace("\r\n", "\n").replace('\n', "\\n").replace('\r', "\\r")
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn crlf_documents_render_like_lf() {
+        let lf = b"a = 1\ntext = \"\"\"\nfirst\nsecond\"\"\"\n";
+        let crlf = b"a = 1\r\ntext = \"\"\"\r\nfirst\r\nsecond\"\"\"\r\n";
+        let mut lf = TreeEngine::from_bytes(Path::new("doc.toml"), lf).unwrap();
+        let mut crlf = TreeEngine::from_bytes(Path::new("doc.toml"), crlf).unwrap();
+        assert_eq!(crlf.render_plain_lines(80), lf.render_plain_lines(80));
+        assert_eq!(escape_line_breaks("first\r\nsecond"), "first\\nsecond");
+    }
+
+    fn press(engine: &mut TreeEngine, code: KeyCode) {
+        engine.handle_key(KeyEvent::new(code, crossterm::event::KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn arrays_of_objects_toggle_into_a_table() {
+        let json = br#"[{"id": 1, "name": "a"}, {"id": 2, "name": "b"}]"#;
+        let mut engine = TreeEngine::from_bytes(Path::new("users.json"), json).unwrap();
+        assert_eq!(engine.name(), "TreeEngine");
+        // Only the first `s` builds the table
+        assert!(engine.records && engine.table.is_none());
+
+        press(&mut engine, KeyCode::Char('s'));
+        assert_eq!(engine.name(), "TableEngine");
+        assert_eq!(engine.get_selected_line().as_deref(), Some("id\tname"));
+        press(&mut engine, KeyCode::Char('s'));
+        assert_eq!(engine.name(), "TreeEngine");
+
+        // Anything else keeps `s` unbound
+        let mut engine = TreeEngine::from_bytes(Path::new("mixed.json"), br#"[{"id": 1}, 2]"#).unwrap();
+        press(&mut engine, KeyCode::Char('s'));
+        assert_eq!(engine.name(), "TreeEngine");
+    }
+
+    #[test]
+    fn brackets_and_colon_navigate_array_elements() {
+        let json = br#"{"items": [{"id": 0, "tags": ["a"]}, {"id": 1}, {"id": 2}]}"#;
+        let mut engine = TreeEngine::from_bytes(Path::new("data.json"), json).unwrap();
+        let label = |engine: &TreeEngine| engine.flat[engine.selection].breadcrumb.clone();
+
+        // From inside the first element, `]` hops to the second element's header
+        engine.selection = engine.flat.iter().position(|f| f.label == "id").unwrap();
+        press(&mut engine, KeyCode::Char(']'));
+        assert_eq!(label(&engine), "root > items > [1]");
+        press(&mut engine, KeyCode::Char('['));
+        assert_eq!(label(&engine), "root > items > [0]");
+
+        for code in [KeyCode::Char(':'), KeyCode::Char('2'), KeyCode::Enter] {
+            press(&mut engine, code);
+        }
+        assert_eq!(label(&engine), "root > items > [2]");
+        press(&mut engine, KeyCode::Char(']'));
+        assert_eq!(label(&engine), "root > items > [2]");
+    }
+
+    #[test]
+    fn sticky_path_pins_ancestors_of_scrolled_selection() {
+        let json = br#"{"outer": {"inner": {"a": 1, "b": 2, "c": 3, "d": 4, "e": 5, "f": 6}}}"#;
+        let mut engine = TreeEngine::from_bytes(Path::new("data.json"), json).unwrap();
+        engine.selection = engine.flat.len() - 1;
+
+        let backend = ratatui::backend::TestBackend::new(40, 4);
+        let mut terminal = ratatui::Terminal::new(backend).unwrap();
+        terminal.draw(|frame| engine.render(frame, frame.size())).unwrap();
+
+        let buffer = terminal.backend().buffer();
+        let top: String = (0..40).map(|x| buffer.get(x, 0).symbol().to_string()).collect();
+        assert!(top.contains("root > outer > inner"), "{}", top);
+        assert!(engine.selection < engine.scroll + 3);
+    }
+
+    #[test]
+    fn openapi_specs_are_outlined_by_path_and_method() {
+        let spec = serde_json::json!({
+            "openapi": "3.0.0",
+            "info": { "title": "Pets", "version": "1.0" },
+            "paths": {
+                "/pets": {
+                    "parameters": [],
+                    "get": {
+                        "summary": "List pets",
+                        "parameters": [{ "name": "limit", "in": "query" }],
+                        "responses": { "200": { "description": "ok" } }
+                    }
+                }
+            }
+        });
+        let outline = openapi_outline(&spec).unwrap();
+        assert_eq!(outline["title"], "Pets");
+        let op = &outline["paths"]["/pets"]["GET — List pets"];
+        assert_eq!(op["parameters"][0], "limit (query)");
+        assert_eq!(op["responses"]["200"], "ok");
+        assert!(outline["paths"]["/pets"].get("parameters").is_none());
+        assert!(openapi_outline(&serde_json::json!({ "paths": {} })).is_none());
+    }
+
+    #[test]
+    fn jsonc_allows_comments_and_trailing_commas() {
+        let raw = br#"{
+            // compiler options
+            "compilerOptions": { "strict": true, /* inline */ },
+            "paths": ["a//b", "c/*d*/",],
+        }"#;
+        let value = parse_value("jsonc", raw).unwrap();
+        assert_eq!(value["compilerOptions"]["strict"], true);
+        assert_eq!(value["paths"][0], "a//b");
+        assert_eq!(value["paths"][1], "c/*d*/");
+        assert!(parse_value("json", raw).is_ok());
+    }
+
+    #[test]
+    fn json5_accepts_unquoted_keys_single_quotes_and_hex() {
+        let raw = br#"{
+            // comment
+            name: 'vat',
+            port: 0x1F,
+            ratio: .5,
+            tags: ['a', 'b',],
+        }"#;
+        let value = parse_value("json5", raw).unwrap();
+        assert_eq!(value["name"], "vat");
+        assert_eq!(value["port"], 31);
+        assert_eq!(value["ratio"], 0.5);
+        assert_eq!(value["tags"][1], "b");
+        assert!(parse_value("jsonc", raw).is_err());
+    }
+
+    #[test]
+    fn container_rows_show_child_counts() {
+        let json = br#"{"tags": ["a", "b", "c"], "owner": {"id": 1}, "empty": []}"#;
+        let engine = TreeEngine::from_bytes(Path::new("data.json"), json).unwrap();
+        let preview = |label: &str| engine.flat.iter().find(|f| f.label == label).unwrap().value_preview.clone();
+        assert_eq!(engine.flat[0].value_preview, "{3 keys}");
+        assert_eq!(preview("tags"), "[3 items]");
+        assert_eq!(preview("owner"), "{1 key}");
+        assert_eq!(preview("empty"), "[]");
+    }
+
+    #[test]
+    fn collapse_to_depth_folds_nested_containers() {
+        let json = br#"{"server": {"tls": {"cert": "a.pem"}}, "ports": [80, 443], "name": "vat"}"#;
+        let mut engine = TreeEngine::from_bytes(Path::new("config.json"), json).unwrap();
+        engine.collapse_to_depth(1);
+        let mut labels: Vec<&str> = engine.flat.iter().map(|f| f.label.as_str()).collect();
+        labels.sort_unstable();
+        assert_eq!(labels, ["name", "ports", "root", "server"]);
+
+        engine.collapse_to_depth(0);
+        assert_eq!(engine.flat.len(), 1);
+    }
+
+    #[test]
+    fn folds_round_trip_and_drop_stale_paths() {
+        let json = br#"{"server": {"tls": {"cert": "a.pem"}}, "ports": [80, 443]}"#;
+        let mut engine = TreeEngine::from_bytes(Path::new("config.json"), json).unwrap();
+        engine.collapse_to_depth(2);
+        let folds = engine.folds().unwrap();
+        assert_eq!(folds, ["root.server.tls"]);
+
+        let mut reopened = TreeEngine::from_bytes(Path::new("config.json"), json).unwrap();
+        reopened.restore_folds(&[folds[0].clone(), "root.gone".to_string()]);
+        assert_eq!(reopened.folds(), Some(folds));
+        assert_eq!(reopened.flat.len(), engine.flat.len());
+    }
+
+    #[test]
+    fn dump_rebuilds_the_parsed_value() {
+        let yaml = b"name: vat\nports: [80, 443]\ntls: null\n";
+        let mut engine = TreeEngine::from_bytes(Path::new("config.yaml"), yaml).unwrap();
+        assert_eq!(engine.to_json(), json!({ "name": "vat", "ports": [80, 443], "tls": null }));
+    }
+
     #[test]
     fn path_segments_handle_arrays() {
         let segments = vec![
@@ -0,0 +1,315 @@
+use std::borrow::Cow;
+use std::fs::File;
+use std::ops::Deref;
+use std::path::Path;
+
+use anyhow::Result;
+use memmap2::Mmap;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::text::Span;
+
+/// Raw contents for the line-indexed engines: memory-mapped from disk,
+/// or owned when the engine is built from in-memory bytes.
+pub(crate) enum Source {
+    Mapped(Mmap),
+    Owned(Vec<u8>),
+}
+
+impl Source {
+    pub(crate) fn map(path: &Path) -> Result<Self> {
+        let file = File::open(path)?;
+        Ok(Source::Mapped(unsafe { Mmap::map(&file)? }))
+    }
+
+    /// Add bytes to the end, copying a mapped file into memory first
+    pub(crate) fn extend(&mut self, bytes: &[u8]) {
+        match self {
+            Source::Owned(data) => data.extend_from_slice(bytes),
+            Source::Mapped(mmap) => {
+                let mut data = mmap.to_vec();
+                data.extend_from_slice(bytes);
+                *self = Source::Owned(data);
+            }
+        }
+    }
+}
+
+impl Deref for Source {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        match self {
+            Source::Mapped(mmap) => mmap,
+            Source::Owned(bytes) => bytes,
+        }
+    }
+}
+
+/// A line's bytes as text without its `\n` or `\r\n` ending, so CRLF files show
+/// the same as LF ones; None when the line isn't UTF-8
+pub(crate) fn line_text(line: &[u8]) -> Option<&str> {
+    std::str::from_utf8(strip_line_ending(line)).ok()
+}
+
+/// Like `line_text`, but bytes that aren't UTF-8 show as U+FFFD instead of
+/// losing the whole line
+pub(crate) fn line_text_lossy(line: &[u8]) -> Cow<'_, str> {
+    String::from_utf8_lossy(strip_line_ending(line))
+}
+
+fn strip_line_ending(line: &[u8]) -> &[u8] {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+/// Whether `text` contains the lowercased search `query`, ignoring case. A query
+/// wrapped in `\<`…`\>` (what `*` and `#` search for) only matches whole words, as in vim
+pub(crate) fn search_matches(text: &str, query: &str) -> bool {
+    let text = text.to_lowercase();
+    let Some(word) = query.strip_prefix("\\<").and_then(|q| q.strip_suffix("\\>")) else {
+        return text.contains(query);
+    };
+    let is_word = |c: char| c.is_alphanumeric() || c == '_';
+    !word.is_empty()
+        && text.match_indices(word).any(|(start, _)| {
+            !text[..start].chars().next_back().is_some_and(is_word)
+                && !text[start + word.len()..].chars().next().is_some_and(is_word)
+        })
+}
+
+/// Shorten `value` to at most `max` characters, ending in "..." when cut.
+/// Counts chars rather than bytes so multibyte text is never split mid-character.
+pub(crate) fn truncate(value: &str, max: usize) -> String {
+    if value.chars().count() <= max {
+        return value.to_string();
+    }
+    let mut out = value.chars().take(max.saturating_sub(3)).collect::<String>();
+    out.push_str("...");
+    out
+}
+
+/// Byte count in binary units with one decimal, e.g. `512B`, `1.5KB`, `2.0GB`
+pub(crate) fn format_size(bytes: u64) -> String {
+    const KB: u64 = 1024;
+    const MB: u64 = KB * 1024;
+    const GB: u64 = MB * 1024;
+
+    if bytes >= GB {
+        format!("{:.1}GB", bytes as f64 / GB as f64)
+    } else if bytes >= MB {
+        format!("{:.1}MB", bytes as f64 / MB as f64)
+    } else if bytes >= KB {
+        format!("{:.1}KB", bytes as f64 / KB as f64)
+    } else {
+        format!("{}B", bytes)
+    }
+}
+
+/// A GitHub-flavored markdown table; `|` in cells is escaped and line breaks
+/// become `<br>` so every row stays on one line
+pub(crate) fn markdown_table(headers: &[&str], rows: &[Vec<String>]) -> String {
+    let cell = |value: &str| value.replace('|', "\\|").replace("\r\n", "<br>").replace('\n', "<br>");
+    let line = |cells: Vec<String>| format!("| {} |", cells.join(" | "));
+    let mut out = vec![
+        line(headers.iter().map(|h| cell(h)).collect()),
+        line(headers.iter().map(|_| "---".to_string()).collect()),
+    ];
+    out.extend(rows.iter().map(|row| line(row.iter().map(|v| cell(v)).collect())));
+    out.join("\n")
+}
+
+/// Split `text` into spans that are safe to draw: escape sequences are dropped
+/// (or, with `colors`, their SGR codes become styles layered on `base`) and other
+/// control characters are shown in caret notation, so they can't move the cursor
+/// or recolor the rest of the screen
+pub(crate) fn ansi_spans(text: &str, base: Style, colors: bool) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut style = base;
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            push_visible(&mut current, c);
+            continue;
+        }
+        match chars.next() {
+            // CSI: parameters up to a final byte in @..~
+            Some('[') => {
+                let mut params = String::new();
+                let mut fin = None;
+                for c in chars.by_ref() {
+                    if ('@'..='~').contains(&c) {
+                        fin = Some(c);
+                        break;
+                    }
+                    params.push(c);
+                }
+                if colors && fin == Some('m') {
+                    let next = apply_sgr(style, base, &params);
+                    if next != style && !current.is_empty() {
+                        spans.push(Span::styled(std::mem::take(&mut current), style));
+                    }
+                    style = next;
+                }
+            }
+            // OSC (titles, hyperlinks): ends at BEL or ESC \
+            Some(']') => {
+                while let Some(c) = chars.next() {
+                    if c == '\x07' || (c == '\x1b' && chars.next_if_eq(&'\\').is_some()) {
+                        break;
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    if !current.is_empty() || spans.is_empty() {
+        spans.push(Span::styled(current, style));
+    }
+    spans
+}
+
+/// `text` with escape sequences dropped and control characters made visible
+pub(crate) fn strip_controls(text: &str) -> Cow<'_, str> {
+    if !text.chars().any(|c| c.is_control() && c != '\t') {
+        return Cow::Borrowed(text);
+    }
+    Cow::Owned(ansi_spans(text, Style::default(), false).into_iter().map(|span| span.content).collect())
+}
+
+/// Append `c`, writing control characters as `^X` (or `\u{..}` outside ASCII);
+/// tabs are kept for `expand_tabs`
+fn push_visible(out: &mut String, c: char) {
+    match c {
+        '\t' => out.push(c),
+        '\x7f' => out.push_str("^?"),
+        c if c.is_ascii_control() => {
+            out.push('^');
+            out.push((c as u8 ^ 0x40) as char);
+        }
+        c if c.is_control() => out.extend(c.escape_unicode()),
+        c => out.push(c),
+    }
+}
+
+/// Apply SGR parameters like `1;31` or `38;5;208` to `style`; `0` (or none) resets to `base`
+fn apply_sgr(mut style: Style, base: Style, params: &str) -> Style {
+    let mut codes = params.split([';', ':']).map(|p| p.parse::<u16>().unwrap_or(0));
+    while let Some(code) = codes.next() {
+        style = match code {
+            0 => base,
+            1 => style.add_modifier(Modifier::BOLD),
+            2 => style.add_modifier(Modifier::DIM),
+            3 => style.add_modifier(Modifier::ITALIC),
+            4 => style.add_modifier(Modifier::UNDERLINED),
+            7 => style.add_modifier(Modifier::REVERSED),
+            9 => style.add_modifier(Modifier::CROSSED_OUT),
+            22 => style.remove_modifier(Modifier::BOLD | Modifier::DIM),
+            23 => style.remove_modifier(Modifier::ITALIC),
+            24 => style.remove_modifier(Modifier::UNDERLINED),
+            27 => style.remove_modifier(Modifier::REVERSED),
+            29 => style.remove_modifier(Modifier::CROSSED_OUT),
+            30..=37 => style.fg(ansi_color(code - 30)),
+            90..=97 => style.fg(ansi_color(code - 90 + 8)),
+            40..=47 => style.bg(ansi_color(code - 40)),
+            100..=107 => style.bg(ansi_color(code - 100 + 8)),
+            39 => Style { fg: base.fg, ..style },
+            49 => Style { bg: base.bg, ..style },
+            38 | 48 => {
+                let color = match codes.next() {
+                    Some(5) => codes.next().map(|n| Color::Indexed(n as u8)),
+                    Some(2) => match (codes.next(), codes.next(), codes.next()) {
+                        (Some(r), Some(g), Some(b)) => Some(Color::Rgb(r as u8, g as u8, b as u8)),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                match (code, color) {
+                    (38, Some(color)) => style.fg(color),
+                    (_, Some(color)) => style.bg(color),
+                    _ => style,
+                }
+            }
+            _ => style,
+        };
+    }
+    style
+}
+
+/// The 16 standard terminal colors, in SGR order
+fn ansi_color(index: u16) -> Color {
+    match index {
+        0 => Color::Black,
+        1 => Color::Red,
+        2 => Color::Green,
+        3 => Color::Yellow,
+        4 => Color::Blue,
+        5 => Color::Magenta,
+        6 => Color::Cyan,
+        7 => Color::Gray,
+        8 => Color::DarkGray,
+        9 => Color::LightRed,
+        10 => Color::LightGreen,
+        11 => Color::LightYellow,
+        12 => Color::LightBlue,
+        13 => Color::LightMagenta,
+        14 => Color::LightCyan,
+        _ => Color::White,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sizes_use_binary_units() {
+        assert_eq!(format_size(512), "512B");
+        assert_eq!(format_size(1536), "1.5KB");
+        assert_eq!(format_size(3 * 1024 * 1024 * 1024), "3.0GB");
+    }
+
+    #[test]
+    fn markdown_table_escapes_pipes_and_newlines() {
+        let rows = vec![vec!["a|b".to_string(), "one\ntwo".to_string()]];
+        assert_eq!(
+            markdown_table(&["name", "note"], &rows),
+            "| name | note |\n| --- | --- |\n| a\\|b | one<br>two |"
+        );
+    }
+
+    #[test]
+    fn bracketed_queries_match_whole_words() {
+        assert!(search_matches("let Foo_bar = foo;", "foo"));
+        assert!(search_matches("let Foo_bar = foo;", "\\<foo\\>"));
+        assert!(!search_matches("let Foo_bar = food;", "\\<foo\\>"));
+        assert!(search_matches("foo_bar", "\\<foo_bar\\>"));
+        assert!(!search_matches("anything", "\\<\\>"));
+    }
+
+    #[test]
+    fn truncate_counts_chars_not_bytes() {
+        // 5 chars but 15 bytes: fits without truncation
+        assert_eq!(truncate("日本語テキ", 5), "日本語テキ");
+        assert_eq!(truncate("日本語テキスト", 5), "日本...");
+        assert_eq!(truncate("🎉🎉🎉🎉🎉🎉", 4), "🎉...");
+        assert_eq!(truncate("short", 10), "short");
+    }
+
+    #[test]
+    fn ansi_spans_drop_escapes_unless_colors_are_on() {
+        let line = "\x1b[1;31mERROR\x1b[0m disk \x07full\x1b]0;title\x07";
+        assert_eq!(strip_controls(line), "ERROR disk ^Gfull");
+
+        let base = Style::default().fg(Color::White);
+        let spans = ansi_spans(line, base, true);
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].content, "ERROR");
+        assert_eq!(spans[0].style, base.fg(Color::Red).add_modifier(Modifier::BOLD));
+        assert_eq!(spans[1].content, " disk ^Gfull");
+        assert_eq!(spans[1].style, base);
+
+        let spans = ansi_spans("\x1b[38;5;208mwarm\x1b[48;2;1;2;3m", base, true);
+        assert_eq!(spans[0].style, base.fg(Color::Indexed(208)));
+    }
+}
@@ -1,4 +1,5 @@
-use std::collections::HashSet;
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::path::Path;
 
 use anyhow::Result;
@@ -7,6 +8,13 @@ use ratatui::layout::Rect;
 use ratatui::style::{Color, Style, Stylize};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Paragraph};
+use serde_json::json;
+
+use super::util::{search_matches, truncate};
+use super::Engine;
+
+/// Elements nested deeper than this are summarized on their ancestor's row
+const MAX_XML_DEPTH: usize = 256;
 
 #[derive(Clone)]
 struct XmlNode {
@@ -15,6 +23,8 @@ struct XmlNode {
     attributes: Vec<(String, String)>,
     text: Option<String>,
     has_children: bool,
+    /// Descendant elements past the depth limit, summarized instead of listed
+    hidden: usize,
     node_index: usize,
 }
 
@@ -34,14 +44,19 @@ pub struct XmlEngine {
 
 impl XmlEngine {
     pub fn from_path(path: &Path) -> Result<Self> {
-        let content = std::fs::read_to_string(path)?;
+        Self::from_bytes(path, &std::fs::read(path)?)
+    }
+
+    /// Create from in-memory bytes; `path` only supplies the name and extension
+    pub fn from_bytes(path: &Path, bytes: &[u8]) -> Result<Self> {
+        let content = std::str::from_utf8(bytes)?;
         let file_name = path
             .file_name()
             .and_then(|s| s.to_str())
             .unwrap_or("")
             .to_string();
 
-        let nodes = parse_xml(&content)?;
+        let nodes = parse_xml(content, MAX_XML_DEPTH)?;
 
         Ok(Self {
             nodes,
@@ -76,24 +91,131 @@ impl XmlEngine {
         visible
     }
 
-    pub fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
+    /// Row of the selected element's last visible descendant, where its closing tag
+    /// would sit; None for leaves and collapsed elements
+    fn closing_row(&self, visible: &[usize]) -> Option<usize> {
+        let depth = self.nodes[*visible.get(self.selection)?].depth;
+        let last = visible[self.selection + 1..]
+            .iter()
+            .take_while(|&&idx| self.nodes[idx].depth > depth)
+            .count();
+        (last > 0).then_some(self.selection + last)
+    }
+
+    /// `/root[1]/item[2]`-style path of every node, which survives edits elsewhere
+    /// in the document better than its index
+    fn element_paths(&self) -> Vec<String> {
+        let mut paths = Vec::with_capacity(self.nodes.len());
+        // Path and per-tag child counts of each open ancestor, the document first
+        let mut ancestors: Vec<(String, HashMap<&str, usize>)> = vec![(String::new(), HashMap::new())];
+        for node in &self.nodes {
+            ancestors.truncate(node.depth + 1);
+            let (parent, counts) = ancestors.last_mut().expect("document entry is never truncated");
+            let nth = counts.entry(node.tag.as_str()).or_insert(0);
+            *nth += 1;
+            let path = format!("{}/{}[{}]", parent, node.tag, nth);
+            ancestors.push((path.clone(), HashMap::new()));
+            paths.push(path);
+        }
+        paths
+    }
+
+    /// XPath of a node, e.g. `/root/config/server[2]`; positions are only given
+    /// where siblings share the tag
+    fn xpath(&self, node_idx: usize) -> String {
+        let mut steps = Vec::new();
+        let mut current = node_idx;
+        loop {
+            let node = &self.nodes[current];
+            let parent = (0..current).rev().find(|&idx| self.nodes[idx].depth < node.depth);
+            let namesakes: Vec<usize> = match parent {
+                Some(parent) => (parent + 1..self.nodes.len())
+                    .take_while(|&idx| self.nodes[idx].depth >= node.depth)
+                    .filter(|&idx| self.nodes[idx].depth == node.depth && self.nodes[idx].tag == node.tag)
+                    .collect(),
+                None => vec![current],
+            };
+            match namesakes.iter().position(|&idx| idx == current) {
+                Some(nth) if namesakes.len() > 1 => steps.push(format!("{}[{}]", node.tag, nth + 1)),
+                _ => steps.push(node.tag.clone()),
+            }
+            match parent {
+                Some(parent) => current = parent,
+                None => break,
+            }
+        }
+        steps.reverse();
+        format!("/{}", steps.join("/"))
+    }
+
+    fn search_next(&mut self, query: &str, forward: bool) -> bool {
+        let lower = query.to_lowercase();
+        let mut found = false;
+        let visible = self.visible_nodes();
+        let total = visible.len().max(1);
+        let start = if forward {
+            (self.selection + 1) % total
+        } else {
+            (self.selection + total - 1) % total
+        };
+
+        for offset in 0..total {
+            let idx = if forward {
+                (start + offset) % total
+            } else {
+                (start + total - offset % total) % total
+            };
+            if let Some(&node_idx) = visible.get(idx) {
+                let node = &self.nodes[node_idx];
+                let searchable = format!(
+                    "{} {} {}",
+                    node.tag,
+                    node.attributes.iter().map(|(k, v)| format!("{}={}", k, v)).collect::<Vec<_>>().join(" "),
+                    node.text.as_deref().unwrap_or("")
+                );
+                if search_matches(&searchable, &lower) {
+                    self.selection = idx;
+                    found = true;
+                    break;
+                }
+            }
+        }
+        self.last_match = Some(query.to_string());
+        found
+    }
+}
+
+impl Engine for XmlEngine {
+    fn name(&self) -> &'static str {
+        "XmlEngine"
+    }
+
+    fn set_visual_range(&mut self, range: Option<(usize, usize)>) {
+        self.visual_range = range;
+    }
+
+    fn render(&mut self, frame: &mut ratatui::Frame, area: Rect) {
         let height = area.height as usize;
         self.last_view_height = height;
 
         let visible = self.visible_nodes();
         let total = visible.len();
 
+        if total == 0 {
+            super::render_placeholder(frame, area, "(empty document)");
+            return;
+        }
+
         if self.selection >= total && total > 0 {
             self.selection = total - 1;
         }
 
-        if self.selection < self.scroll {
-            self.scroll = self.selection;
-        } else if self.selection >= self.scroll + height {
-            self.scroll = self.selection.saturating_sub(height - 1);
-        }
+        self.scroll = super::follow_selection(self.scroll, self.selection, height, total);
 
         let line_no_width = self.nodes.len().max(1).to_string().len().max(2);
+        let closing = self
+            .closing_row(&visible)
+            .map(|row| (row, self.nodes[visible[self.selection]].tag.clone()));
 
         let display: Vec<Line> = visible
             .iter()
@@ -113,8 +235,10 @@ impl XmlEngine {
                 } else {
                     Style::default().fg(Color::LightYellow)
                 };
-                spans.push(Span::styled(line_no, line_no_style));
-                spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
+                if super::line_numbers() {
+                    spans.push(Span::styled(line_no, line_no_style));
+                    spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
+                }
 
                 // Indentation
                 let indent = "  ".repeat(node.depth);
@@ -160,7 +284,7 @@ impl XmlEngine {
                         Style::default().fg(Color::Yellow)
                     };
                     spans.push(Span::styled(format!(" {}=", key), attr_style));
-                    spans.push(Span::styled(format!("\"{}\"", truncate(value, 20)), val_style));
+                    spans.push(Span::styled(format!("\"{}\"", truncate(value, super::preview_len(20))), val_style));
                 }
 
                 spans.push(Span::styled(">", bracket_style));
@@ -172,7 +296,16 @@ impl XmlEngine {
                     } else {
                         Style::default().fg(Color::Yellow)
                     };
-                    spans.push(Span::styled(format!(" {}", truncate(text, 40)), text_style));
+                    spans.push(Span::styled(format!(" {}", truncate(text, super::preview_len(40))), text_style));
+                }
+                if node.hidden > 0 {
+                    let note_style = Style::default().fg(Color::DarkGray).italic();
+                    spans.push(Span::styled(depth_limit_note(node.hidden), note_style));
+                }
+                if let Some((_, tag)) = closing.as_ref().filter(|(closing_row, _)| *closing_row == row) {
+                    let close_style = Style::default().fg(Color::Black).bg(Color::LightMagenta).bold();
+                    spans.push(Span::raw(" "));
+                    spans.push(Span::styled(format!("</{}>", tag), close_style));
                 }
 
                 Line::from(spans)
@@ -183,7 +316,8 @@ impl XmlEngine {
         frame.render_widget(Paragraph::new(display).block(block), area);
     }
 
-    pub fn handle_key(&mut self, key: KeyEvent) {
+    #[allow(clippy::collapsible_match)]
+    fn handle_key(&mut self, key: KeyEvent) {
         match key.code {
             KeyCode::Char('g') => {
                 if self.pending_g {
@@ -212,12 +346,10 @@ impl XmlEngine {
                 self.selection = self.selection.saturating_sub(1);
             }
             KeyCode::Char('u') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let jump = page_jump(self.last_view_height).min(self.selection);
-                self.selection = self.selection.saturating_sub(jump);
+                self.selection = super::half_page_up(self.selection, self.last_view_height);
             }
             KeyCode::Char('d') if key.modifiers.contains(KeyModifiers::CONTROL) => {
-                let jump = page_jump(self.last_view_height).min(total.saturating_sub(1));
-                self.selection = (self.selection + jump).min(total.saturating_sub(1));
+                self.selection = super::half_page_down(self.selection, self.last_view_height, total);
             }
             KeyCode::Char('G') => {
                 if total > 0 {
@@ -250,25 +382,26 @@ impl XmlEngine {
         }
     }
 
-    pub fn apply_search(&mut self, query: &str) {
+    fn apply_search(&mut self, query: &str, forward: bool) -> bool {
         let trimmed = query.trim();
         if trimmed.is_empty() {
-            return;
+            return false;
         }
         self.last_query = Some(trimmed.to_string());
-        self.search_next(trimmed, true);
+        let found = self.search_next(trimmed, forward);
         self.last_match = Some(trimmed.to_string());
+        found
     }
 
-    pub fn apply_filter(&mut self, query: &str) {
-        self.apply_search(query);
+    fn apply_filter(&mut self, query: &str) -> bool {
+        self.apply_search(query, true)
     }
 
-    pub fn clear_filter(&mut self) {
+    fn clear_filter(&mut self) {
         self.last_query = None;
     }
 
-    pub fn breadcrumbs(&self) -> String {
+    fn breadcrumbs(&self) -> String {
         let visible = self.visible_nodes();
         if let Some(&node_idx) = visible.get(self.selection) {
             let node = &self.nodes[node_idx];
@@ -278,25 +411,31 @@ impl XmlEngine {
         }
     }
 
-    pub fn status_line(&self) -> String {
+    fn status_line(&self) -> String {
         let query = self
             .last_query
             .as_ref()
             .map(|q| format!(" | search: {}", q))
             .unwrap_or_default();
         format!(
-            "j/k move | gg/G jump | Ctrl+u/d half-page | Enter fold | n/N next/prev | / search{}",
+            "j/k move | gg/G jump | Ctrl+u/d half-page | Enter fold | n/N next/prev | y copy XPath | / search{}",
             query
         )
     }
 
-    #[allow(dead_code)]
-    pub fn selected_path(&self) -> Option<String> {
-        None
+    /// Keys specific to this view, listed in the help overlay
+    fn help_lines(&self) -> Vec<Line<'static>> {
+        vec![
+            Line::from("  Enter        Expand/collapse element"),
+        ]
+    }
+
+    fn selected_path(&self) -> Option<String> {
+        self.visible_nodes().get(self.selection).map(|&node_idx| self.xpath(node_idx))
     }
 
     /// Get the content of the currently selected line
-    pub fn get_selected_line(&self) -> Option<String> {
+    fn get_selected_line(&self) -> Option<String> {
         let visible = self.visible_nodes();
         visible.get(self.selection).map(|&node_idx| {
             let node = &self.nodes[node_idx];
@@ -306,7 +445,7 @@ impl XmlEngine {
     }
 
     /// Get lines in a range (inclusive), skipping children of selected parents
-    pub fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
+    fn get_lines_range(&self, start: usize, end: usize) -> Option<String> {
         let (start, end) = if start <= end { (start, end) } else { (end, start) };
         let visible = self.visible_nodes();
         let total = visible.len();
@@ -343,26 +482,77 @@ impl XmlEngine {
     }
 
     /// Get current selection index (for visual mode)
-    pub fn selection(&self) -> usize {
+    fn selection(&self) -> usize {
         self.selection
     }
 
-    pub fn content_height(&self) -> usize {
+    fn select(&mut self, row: usize) {
+        self.selection = row.min(self.visible_nodes().len().saturating_sub(1));
+    }
+
+    /// Scroll so the selection sits at the top, middle or bottom of the view (`zt`/`zz`/`zb`)
+    fn align_scroll(&mut self, align: super::ScrollAlign) {
+        self.scroll = super::aligned_scroll(self.selection, self.last_view_height, align);
+    }
+
+    /// Element paths of the folded nodes, for the state file
+    fn folds(&self) -> Option<Vec<String>> {
+        let paths = self.element_paths();
+        let mut folds: Vec<String> = self.collapsed.iter().map(|&idx| paths[idx].clone()).collect();
+        folds.sort();
+        Some(folds)
+    }
+
+    /// Fold the elements saved by `folds`; paths no longer in the document are dropped
+    fn restore_folds(&mut self, folds: &[String]) {
+        let saved: HashSet<&str> = folds.iter().map(String::as_str).collect();
+        self.collapsed = self
+            .element_paths()
+            .iter()
+            .enumerate()
+            .filter(|(idx, path)| self.nodes[*idx].has_children && saved.contains(path.as_str()))
+            .map(|(idx, _)| idx)
+            .collect();
+    }
+
+    fn content_height(&mut self) -> usize {
         self.visible_nodes().len()
     }
 
-    pub fn render_plain_lines(&self, _width: u16) -> Vec<Line<'static>> {
+    /// Parsed structure for `--dump`
+    fn to_json(&mut self) -> serde_json::Value {
+        let elements: Vec<_> = self
+            .nodes
+            .iter()
+            .map(|node| {
+                let attributes: serde_json::Map<String, serde_json::Value> =
+                    node.attributes.iter().map(|(k, v)| (k.clone(), json!(v))).collect();
+                json!({
+                    "depth": node.depth,
+                    "tag": node.tag,
+                    "attributes": attributes,
+                    "text": node.text,
+                    "hidden_descendants": node.hidden,
+                })
+            })
+            .collect();
+        json!({ "elements": elements })
+    }
+
+    fn render_plain_lines(&mut self, _width: u16) -> Vec<Line<'static>> {
         let line_no_width = self.nodes.len().max(1).to_string().len().max(2);
         self.nodes
             .iter()
             .enumerate()
             .map(|(idx, node)| {
                 let mut spans = Vec::new();
-                spans.push(Span::styled(
-                    format!("{:>width$} ", idx + 1, width = line_no_width),
-                    Style::default().fg(Color::LightYellow),
-                ));
-                spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
+                if super::line_numbers() {
+                    spans.push(Span::styled(
+                        format!("{:>width$} ", idx + 1, width = line_no_width),
+                        Style::default().fg(Color::LightYellow),
+                    ));
+                    spans.push(Span::styled("│ ", Style::default().fg(Color::LightBlue)));
+                }
                 spans.push(Span::raw("  ".repeat(node.depth)));
                 spans.push(Span::styled(
                     format!("<{}", node.tag),
@@ -376,97 +566,233 @@ impl XmlEngine {
                 if let Some(text) = &node.text {
                     spans.push(Span::styled(format!(" {}", text), Style::default().fg(Color::White)));
                 }
+                if node.hidden > 0 {
+                    spans.push(Span::styled(depth_limit_note(node.hidden), Style::default().fg(Color::DarkGray)));
+                }
                 Line::from(spans)
             })
             .collect()
     }
+}
 
-    fn search_next(&mut self, query: &str, forward: bool) {
-        let lower = query.to_lowercase();
-        let visible = self.visible_nodes();
-        let total = visible.len().max(1);
-        let start = if forward {
-            (self.selection + 1) % total
+fn parse_xml(content: &str, max_depth: usize) -> Result<Vec<XmlNode>> {
+    // roxmltree parses elements recursively, so subtrees past the limit are cut
+    // out before it sees them and only their element counts are kept
+    let (content, cut_counts) = prune_deep(content, max_depth);
+    let mut cut_counts = cut_counts.into_iter();
+    let doc = roxmltree::Document::parse(&content)?;
+    let mut nodes = Vec::new();
+    let mut stack = vec![(doc.root_element(), 0)];
+
+    while let Some((node, depth)) = stack.pop() {
+        let tag = node.tag_name().name().to_string();
+        let attributes: Vec<(String, String)> = node
+            .attributes()
+            .map(|a| (a.name().to_string(), a.value().to_string()))
+            .collect();
+
+        let text = node
+            .children()
+            .find(|c| c.is_text())
+            .and_then(|c| c.text())
+            .map(|t| t.trim().to_string())
+            .filter(|t| !t.is_empty());
+
+        let children: Vec<_> = node.children().filter(|c| c.is_element()).collect();
+        // Elements at the limit are met in document order, like `prune_deep` counted them
+        let hidden = if depth == max_depth { cut_counts.next().unwrap_or(0) } else { 0 };
+
+        nodes.push(XmlNode {
+            depth,
+            tag,
+            attributes,
+            text,
+            has_children: !children.is_empty(),
+            hidden,
+            node_index: nodes.len(),
+        });
+
+        stack.extend(children.into_iter().rev().map(|child| (child, depth + 1)));
+    }
+    Ok(nodes)
+}
+
+/// Remove every element nested below `max_depth` with a flat scan of the tags.
+/// Returns the remaining document and, for each element at `max_depth` in
+/// document order, how many elements were removed from inside it
+fn prune_deep(content: &str, max_depth: usize) -> (Cow<'_, str>, Vec<usize>) {
+    let bytes = content.as_bytes();
+    let find = |from: usize, needle: &[u8]| {
+        bytes[from..].windows(needle.len()).position(|w| w == needle).map(|pos| from + pos + needle.len())
+    };
+    let mut pruned = String::new();
+    let mut kept_from = 0;
+    let mut counts = Vec::new();
+    let mut cut_start = None;
+    // Elements currently open
+    let mut depth = 0usize;
+    let mut i = 0;
+
+    while let Some(pos) = bytes[i..].iter().position(|&b| b == b'<') {
+        let start = i + pos;
+        let rest = &bytes[start..];
+        let end = if rest.starts_with(b"<!--") {
+            find(start, b"-->")
+        } else if rest.starts_with(b"<![CDATA[") {
+            find(start, b"]]>")
+        } else if rest.starts_with(b"<?") {
+            find(start, b"?>")
         } else {
-            self.selection.saturating_sub(1)
+            tag_end(bytes, start)
         };
+        let Some(end) = end else { break };
+        i = end;
+        if rest.starts_with(b"<!") || rest.starts_with(b"<?") {
+            continue;
+        }
 
-        for offset in 0..total {
-            let idx = if forward {
-                (start + offset) % total
-            } else {
-                (start + total - offset % total) % total
-            };
-            if let Some(&node_idx) = visible.get(idx) {
-                let node = &self.nodes[node_idx];
-                let searchable = format!(
-                    "{} {} {}",
-                    node.tag,
-                    node.attributes.iter().map(|(k, v)| format!("{}={}", k, v)).collect::<Vec<_>>().join(" "),
-                    node.text.as_deref().unwrap_or("")
-                );
-                if searchable.to_lowercase().contains(&lower) {
-                    self.selection = idx;
-                    break;
-                }
+        if rest.starts_with(b"</") {
+            depth = depth.saturating_sub(1);
+            if depth != max_depth + 1 {
+                continue;
+            }
+        } else {
+            let element_depth = depth;
+            if bytes[end - 2] != b'/' {
+                depth += 1;
+            }
+            if element_depth == max_depth {
+                counts.push(0);
+            }
+            if element_depth <= max_depth {
+                continue;
+            }
+            if let Some(count) = counts.last_mut() {
+                *count += 1;
+            }
+            if element_depth == max_depth + 1 {
+                cut_start = Some(start);
+            }
+            if depth != max_depth + 1 {
+                continue;
             }
         }
-        self.last_match = Some(query.to_string());
+        // A child of an element at the limit just closed
+        if let Some(cut) = cut_start.take() {
+            pruned.push_str(&content[kept_from..cut]);
+            kept_from = end;
+        }
+    }
+
+    if kept_from == 0 {
+        return (Cow::Borrowed(content), counts);
     }
+    pruned.push_str(&content[kept_from..]);
+    (Cow::Owned(pruned), counts)
 }
 
-fn parse_xml(content: &str) -> Result<Vec<XmlNode>> {
-    let doc = roxmltree::Document::parse(content)?;
-    let mut nodes = Vec::new();
-    let mut node_index = 0;
-
-    fn visit(node: roxmltree::Node, depth: usize, nodes: &mut Vec<XmlNode>, node_index: &mut usize) {
-        if node.is_element() {
-            let tag = node.tag_name().name().to_string();
-            let attributes: Vec<(String, String)> = node
-                .attributes()
-                .map(|a| (a.name().to_string(), a.value().to_string()))
-                .collect();
-
-            let text = node
-                .children()
-                .find(|c| c.is_text())
-                .and_then(|c| c.text())
-                .map(|t| t.trim().to_string())
-                .filter(|t| !t.is_empty());
-
-            let has_children = node.children().any(|c| c.is_element());
-
-            nodes.push(XmlNode {
-                depth,
-                tag,
-                attributes,
-                text,
-                has_children,
-                node_index: *node_index,
-            });
-            *node_index += 1;
-
-            for child in node.children() {
-                visit(child, depth + 1, nodes, node_index);
+/// Byte after the `>` closing the tag or declaration at `start`, skipping quoted
+/// attribute values and a DOCTYPE's internal subset
+fn tag_end(bytes: &[u8], start: usize) -> Option<usize> {
+    let mut brackets = 0usize;
+    let mut i = start + 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            quote @ (b'"' | b'\'') => {
+                i += bytes[i + 1..].iter().position(|&b| b == quote)? + 1;
             }
+            b'[' => brackets += 1,
+            b']' => brackets = brackets.saturating_sub(1),
+            b'>' if brackets == 0 => return Some(i + 1),
+            _ => {}
         }
+        i += 1;
     }
+    None
+}
 
-    visit(doc.root_element(), 0, &mut nodes, &mut node_index);
-    Ok(nodes)
+/// Row suffix for an element whose subtree was cut off at the depth limit
+fn depth_limit_note(hidden: usize) -> String {
+    format!(" … {} nested element{} too deep to show", hidden, if hidden == 1 { "" } else { "s" })
 }
 
-fn truncate(value: &str, max: usize) -> String {
-    if value.len() <= max {
-        return value.to_string();
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dump_lists_elements_in_document_order() {
+        let xml = br#"<feed lang="en"><item id="1">hello</item></feed>"#;
+        let mut engine = XmlEngine::from_bytes(Path::new("feed.xml"), xml).unwrap();
+        assert_eq!(
+            engine.to_json(),
+            json!({ "elements": [
+                { "depth": 0, "tag": "feed", "attributes": { "lang": "en" }, "text": null, "hidden_descendants": 0 },
+                { "depth": 1, "tag": "item", "attributes": { "id": "1" }, "text": "hello", "hidden_descendants": 0 },
+            ]})
+        );
+    }
+
+    #[test]
+    fn parse_keeps_document_order() {
+        let nodes = parse_xml("<a><b><c/></b><d/></a>", MAX_XML_DEPTH).unwrap();
+        let tags: Vec<(&str, usize)> = nodes.iter().map(|n| (n.tag.as_str(), n.depth)).collect();
+        assert_eq!(tags, [("a", 0), ("b", 1), ("c", 2), ("d", 1)]);
+        assert!(nodes[0].has_children && !nodes[2].has_children);
+    }
+
+    #[test]
+    fn deep_nesting_stops_at_the_depth_limit() {
+        let depth = 20_000;
+        let content = format!("{}{}", "<n>".repeat(depth), "</n>".repeat(depth));
+        let nodes = parse_xml(&content, 3).unwrap();
+        assert_eq!(nodes.len(), 4);
+        assert_eq!(nodes[3].hidden, depth - 4);
+        assert!(!nodes[3].has_children);
     }
-    let mut out = value.chars().take(max.saturating_sub(3)).collect::<String>();
-    out.push_str("...");
-    out
-}
 
-fn page_jump(view_height: usize) -> usize {
-    let half = view_height / 2;
-    if half == 0 { 1 } else { half }
+    #[test]
+    fn pruning_skips_markup_that_only_looks_like_tags() {
+        let content = r#"<a><b t="1>2"><!-- <c> --><c/>x<c><![CDATA[<d>]]><d/></c></b><b/></a>"#;
+        let nodes = parse_xml(content, 1).unwrap();
+        let rows: Vec<(&str, usize, usize)> = nodes.iter().map(|n| (n.tag.as_str(), n.depth, n.hidden)).collect();
+        assert_eq!(rows, [("a", 0, 0), ("b", 1, 3), ("b", 1, 0)]);
+        assert_eq!(nodes[1].text.as_deref(), Some("x"));
+    }
+
+    #[test]
+    fn folds_are_saved_as_element_paths() {
+        let content = b"<a><b><c/></b><b><c/></b></a>";
+        let mut engine = XmlEngine::from_bytes(Path::new("doc.xml"), content).unwrap();
+        engine.collapsed.insert(3);
+        assert_eq!(engine.folds().unwrap(), ["/a[1]/b[2]"]);
+
+        let mut reopened = XmlEngine::from_bytes(Path::new("doc.xml"), content).unwrap();
+        reopened.restore_folds(&["/a[1]/b[2]".to_string(), "/a[1]/b[2]/c[1]".to_string()]);
+        assert_eq!(reopened.collapsed, HashSet::from([3]));
+    }
+
+    #[test]
+    fn selected_path_is_an_xpath() {
+        let content = b"<root><config><server/><server><port/></server></config></root>";
+        let mut engine = XmlEngine::from_bytes(Path::new("doc.xml"), content).unwrap();
+        assert_eq!(engine.selected_path().as_deref(), Some("/root"));
+        engine.selection = 3;
+        assert_eq!(engine.selected_path().as_deref(), Some("/root/config/server[2]"));
+        engine.selection = 4;
+        assert_eq!(engine.selected_path().as_deref(), Some("/root/config/server[2]/port"));
+    }
+
+    #[test]
+    fn closing_tag_follows_the_last_visible_descendant() {
+        let content = b"<a><b><c/><c/></b><d/></a>";
+        let mut engine = XmlEngine::from_bytes(Path::new("doc.xml"), content).unwrap();
+        let visible = engine.visible_nodes();
+        assert_eq!(engine.closing_row(&visible), Some(4));
+
+        engine.selection = 1;
+        assert_eq!(engine.closing_row(&visible), Some(3));
+        engine.collapsed.insert(engine.nodes[1].node_index);
+        assert_eq!(engine.closing_row(&engine.visible_nodes()), None);
+    }
 }
@@ -1,27 +1,91 @@
-use std::io::{self, Read, Write};
-use std::path::PathBuf;
+use std::collections::HashMap;
+use std::io::{self, BufRead, IsTerminal, Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
 
 use anyhow::{anyhow, Result};
 use clap::{Parser, ValueEnum};
 
 mod analyzer;
 mod app;
+mod config;
 mod engines;
+mod state;
+
+/// How long opening a file may take before "Loading…" is shown
+const SPLASH_DELAY: Duration = Duration::from_millis(150);
 
 #[derive(Parser, Debug)]
 #[command(name = "vat", version, about = "Semantic file viewer")]
 struct Args {
     /// Path to the file to view (use "-" for stdin)
-    path: String,
+    #[arg(required_unless_present_any = ["diff", "recent"])]
+    path: Option<String>,
+    /// Pick a recently viewed file to open (lists them when stdout isn't a terminal)
+    #[arg(long, conflicts_with_all = ["path", "diff"])]
+    recent: bool,
+    /// Show a unified diff of two files (JSON/YAML/TOML are compared as normalized trees)
+    #[arg(long, num_args = 2, value_names = ["OLD", "NEW"], conflicts_with = "path")]
+    diff: Option<Vec<PathBuf>>,
     /// Paging mode: auto, always, never (bat-compatible)
     #[arg(long, value_enum, default_value = "auto")]
     paging: Paging,
+    /// Print formatted output without the pager (same as --paging=never)
+    #[arg(short = 'P', long, conflicts_with = "paging")]
+    no_paging: bool,
+    /// Open the pager even when stdin or stdout isn't a terminal
+    #[arg(long, conflicts_with_all = ["no_paging", "plain"])]
+    force_tui: bool,
     /// Output raw file content without formatting (useful for piping)
     #[arg(short = 'p', long)]
     plain: bool,
     /// Language/format hint for stdin (e.g., json, yaml, csv, jsonl)
     #[arg(short = 'l', long)]
     language: Option<String>,
+    /// Navigation key preset: vim, less (Space/b paging), emacs (Ctrl+n/p)
+    #[arg(long, value_enum, default_value = "vim")]
+    keys: Keys,
+    /// Render piped output as formatted, boxed text at N columns (defaults to $COLUMNS)
+    #[arg(long, value_name = "N")]
+    width: Option<u16>,
+    /// Gutter numbering in text, code and tree views: none, absolute, relative
+    /// (distance from the selected line); overrides `line_numbers` in the config
+    #[arg(long, value_enum, value_name = "STYLE")]
+    line_numbers: Option<Numbering>,
+    /// Wrap long lines in text and code views (toggle with `w`)
+    #[arg(long, overrides_with = "no_wrap")]
+    wrap: bool,
+    /// Cut long lines off at the screen edge (the default)
+    #[arg(long, overrides_with = "wrap")]
+    no_wrap: bool,
+    /// Tab width for text and code views
+    #[arg(long, value_name = "N", default_value_t = 4)]
+    tabs: usize,
+    /// Color text and log lines by their embedded ANSI escape codes (dropped otherwise)
+    #[arg(long)]
+    ansi: bool,
+    /// Open at the end of the file with the last N lines in view
+    #[arg(long, value_name = "N")]
+    tail: Option<usize>,
+    /// Open tree views folded below N levels (0 shows only the root)
+    #[arg(long, value_name = "N")]
+    depth: Option<usize>,
+    /// Print the parsed structure as JSON instead of viewing the file
+    #[arg(long)]
+    dump: bool,
+    /// Compare a .env file's keys with another, e.g. `.env --compare .env.example`
+    #[arg(long, value_name = "FILE")]
+    compare: Option<PathBuf>,
+    /// Open with a specific engine instead of detecting one (syntax, tree, table, log, hex, ...)
+    #[arg(long, value_name = "NAME", conflicts_with = "diff")]
+    engine: Option<String>,
+    /// Hide the border, header and status footer (toggle with Ctrl+t)
+    #[arg(long)]
+    no_chrome: bool,
+    /// Write the formatted view to a file instead of showing it
+    #[arg(short = 'o', long, value_name = "PATH", conflicts_with = "dump")]
+    output: Option<PathBuf>,
 }
 
 #[derive(ValueEnum, Clone, Debug)]
@@ -31,25 +95,334 @@ enum Paging {
     Never,
 }
 
+#[derive(ValueEnum, Clone, Copy, Debug)]
+enum Numbering {
+    None,
+    Absolute,
+    Relative,
+}
+
+#[derive(ValueEnum, Clone, Debug)]
+enum Keys {
+    Vim,
+    Less,
+    Emacs,
+}
+
+/// Less-style startup commands: `+N` opens at line N, `+/pattern` opens at the first match
+#[derive(Default)]
+struct StartAt {
+    line: Option<usize>,
+    search: Option<String>,
+}
+
+impl StartAt {
+    /// Take `+N` / `+/pattern` out of argv before clap sees them
+    fn split_args(args: impl Iterator<Item = String>) -> (Vec<String>, Self) {
+        let mut start = Self::default();
+        let mut rest = Vec::new();
+        for arg in args {
+            if let Some(pattern) = arg.strip_prefix("+/") {
+                start.search = Some(pattern.to_string());
+            } else if let Some(line) = arg.strip_prefix('+').and_then(|n| n.parse::<usize>().ok()) {
+                start.line = Some(line);
+            } else {
+                rest.push(arg);
+            }
+        }
+        (rest, start)
+    }
+
+    fn apply(&self, engine: &mut engines::EngineState) {
+        if let Some(line) = self.line {
+            engine.jump_to_line(line.saturating_sub(1));
+        }
+        if let Some(pattern) = self.search.as_deref().filter(|p| !p.is_empty()) {
+            engine.apply_search(pattern, true);
+        }
+    }
+}
+
 fn main() -> Result<()> {
-    let args = Args::parse();
+    let (argv, start) = StartAt::split_args(std::env::args());
+    let mut args = Args::parse_from(argv);
+    let width = render_width(&args);
+    let config = config::Config::load()?;
+    engines::set_scroll_off(config.scroll_off);
+    engines::set_line_numbers(config.line_numbers);
+    if let Some(numbering) = args.line_numbers {
+        engines::set_line_numbers(!matches!(numbering, Numbering::None));
+        engines::set_relative_numbers(matches!(numbering, Numbering::Relative));
+    }
+    engines::set_page_fraction(config.half_page);
+    if let Some(chars) = config.preview_len {
+        engines::set_preview_len(chars);
+    }
+    engines::set_ansi(args.ansi);
+
+    // Files picked from the recent list (`--recent`, `Ctrl+o`) open one after another
+    let mut next = match &args.diff {
+        Some(files) => {
+            let diff = diff_files(&files[0], &files[1])?;
+            let display_path = format!("{} → {}", files[0].display(), files[1].display());
+            let mut engine = engines::EngineState::new(
+                engines::DiffEngine::from_bytes(Path::new("changes.diff"), diff.as_bytes())?
+                    .with_title(display_path.clone()),
+            );
+            if args.dump {
+                return dump(&mut engine);
+            }
+            start.apply(&mut engine);
+            let paging = paging_mode(&args, false);
+            let mut app = app::App::new(engine, display_path, PathBuf::new(), paging, args.plain, args.keys.clone().into())
+                .with_raw_content(diff.into_bytes())
+                .with_width(width)
+                .with_output(args.output.clone())
+                .with_chrome(!args.no_chrome)
+                .with_force_tui(args.force_tui)
+                .with_layout(text_layout(&args));
+            app.run()?;
+            app.reopen().map(|file| file.display().to_string())
+        }
+        None if args.recent => pick_recent(&args)?,
+        None => view(&args, &config, args.path.clone().unwrap_or_default(), &start, width)?,
+    };
+    while let Some(path) = next {
+        // Options aimed at the file on the command line don't carry over
+        args.compare = None;
+        args.engine = None;
+        args.depth = None;
+        args.tail = None;
+        next = view(&args, &config, path, &StartAt::default(), width)?;
+    }
+    Ok(())
+}
+
+/// View one file (or stdin for "-"); returns the file picked from the recent list to open next
+fn view(
+    args: &Args,
+    config: &config::Config,
+    arg_path: String,
+    start: &StartAt,
+    width: Option<u16>,
+) -> Result<Option<String>> {
+    // Logs and text piped into a terminal view show up as they arrive
+    if arg_path == "-" {
+        if let Some(engine) = streaming_engine(args) {
+            let display_path = format!("<stdin>.{}", args.language.as_deref().unwrap_or_default());
+            let paging = paging_mode(args, true);
+            let mut app = app::App::new(engine, display_path, PathBuf::new(), paging, false, args.keys.clone().into())
+                .with_stream(stream_stdin())
+                .with_chrome(!args.no_chrome)
+                .with_layout(text_layout(args));
+            app.run()?;
+            return Ok(app.reopen().map(|file| file.display().to_string()));
+        }
+    }
+
+    let reads_stdin = arg_path == "-";
 
     // Handle stdin
-    let (path, _temp_file) = if args.path == "-" {
+    let (path, _temp_file) = if arg_path == "-" {
         read_stdin_to_temp(&args.language)?
     } else {
-        (PathBuf::from(&args.path), None)
+        (PathBuf::from(&arg_path), None)
     };
 
-    let display_path = if args.path == "-" {
+    // Folds are remembered per file on disk, so not for stdin
+    let state_file = (arg_path != "-").then(|| path.clone());
+
+    let display_path = if arg_path == "-" {
         format!("<stdin>{}", args.language.as_ref().map(|l| format!(".{}", l)).unwrap_or_default())
     } else {
-        args.path.clone()
+        arg_path
     };
 
-    let engine = analyzer::analyze(&path)?;
-    let mut app = app::App::new(engine, display_path, path, args.paging.into(), args.plain);
-    app.run()
+    let mut engine = analyze_with_splash(&path, &display_path, &config.engines, args.engine.as_deref())?;
+    if let Some(other) = &args.compare {
+        match engine.downcast_mut::<engines::EnvEngine>() {
+            Some(env) => env.compare_with(other)?,
+            None => return Err(anyhow!("--compare only works on .env files")),
+        }
+    }
+    if args.dump {
+        dump(&mut engine)?;
+        return Ok(None);
+    }
+    // An explicit --depth wins over folds saved last time, which win over the config
+    let saved_folds = state_file
+        .as_deref()
+        .and_then(|file| state::State::load().folds(file).map(<[String]>::to_vec));
+    match saved_folds.filter(|_| args.depth.is_none()) {
+        Some(folds) => engine.restore_folds(&folds),
+        None => {
+            if let (Some(depth), Some(tree)) = (args.depth.or(config.tree_depth), engine.downcast_mut::<engines::TreeEngine>()) {
+                tree.collapse_to_depth(depth);
+            }
+        }
+    }
+    start.apply(&mut engine);
+    if let Some(lines) = args.tail {
+        engine.tail(lines);
+    }
+    let opening_folds = engine.folds();
+    let paging = paging_mode(args, reads_stdin);
+    let mut app = app::App::new(engine, display_path, path, paging, args.plain, args.keys.clone().into())
+        .with_width(width)
+        .with_output(args.output.clone())
+        .with_chrome(!args.no_chrome)
+        .with_force_tui(args.force_tui)
+        .with_comments(config.comments.clone())
+        .with_layout(text_layout(args));
+    app.run()?;
+    // Printed and piped runs aren't visits worth listing, and can't change folds
+    if let Some(file) = state_file.as_deref().filter(|_| app.interactive()) {
+        let folds = app.engine().folds().filter(|folds| Some(folds) != opening_folds.as_ref());
+        remember_session(file, folds);
+    }
+    Ok(app.reopen().map(|file| file.display().to_string()))
+}
+
+/// `--recent`: a picker of recently viewed files in a terminal, else the list itself
+fn pick_recent(args: &Args) -> Result<Option<String>> {
+    let files = state::State::load().recent();
+    if !io::stdout().is_terminal() {
+        let mut out = io::stdout().lock();
+        for file in &files {
+            writeln!(out, "{}", file.display())?;
+        }
+        return Ok(None);
+    }
+    if files.is_empty() {
+        return Err(anyhow!("No recently viewed files"));
+    }
+    let engine = engines::EngineState::new(engines::TextEngine::from_bytes(Path::new("<recent>"), b"")?);
+    let keys = args.keys.clone().into();
+    let mut app = app::App::new(engine, "Recent files".to_string(), PathBuf::new(), app::Paging::Always, false, keys)
+        .with_chrome(!args.no_chrome)
+        .with_recent_picker(files);
+    app.run()?;
+    Ok(app.reopen().map(|file| file.display().to_string()))
+}
+
+/// Put `file` at the front of the recent list and save the folds left open in
+/// the viewer, if they changed; a failed write only means neither is remembered
+fn remember_session(file: &Path, folds: Option<Vec<String>>) {
+    let _ = state::State::update(|state| {
+        state.add_recent(file);
+        if let Some(folds) = folds {
+            state.set_folds(file, folds);
+        }
+    });
+}
+
+/// Build the engine, printing "Loading…" to the terminal when that takes long
+/// enough to look like a hang (big SQLite databases, lockfiles, parquet).
+/// `forced` is an `--engine` name, which skips detection and config overrides.
+fn analyze_with_splash(
+    path: &Path,
+    display_path: &str,
+    overrides: &HashMap<String, String>,
+    forced: Option<&str>,
+) -> Result<engines::EngineState> {
+    let analyze = || match forced {
+        Some(name) => analyzer::analyze_as(path, name),
+        None => analyzer::analyze(path, overrides),
+    };
+    if !io::stderr().is_terminal() {
+        return analyze();
+    }
+    let (done, wait) = mpsc::channel::<()>();
+    let label = display_path.to_string();
+    let splash = std::thread::spawn(move || {
+        if let Err(mpsc::RecvTimeoutError::Timeout) = wait.recv_timeout(SPLASH_DELAY) {
+            eprint!("Loading {}…", label);
+            let _ = io::stderr().flush();
+            let _ = wait.recv();
+            // Clear the message so plain output starts on a clean line
+            eprint!("\r\x1b[2K");
+            let _ = io::stderr().flush();
+        }
+    });
+    let engine = analyze();
+    let _ = done.send(());
+    let _ = splash.join();
+    engine
+}
+
+/// `--dump`: write what the engine parsed to stdout as pretty-printed JSON
+fn dump(engine: &mut engines::EngineState) -> Result<()> {
+    let mut out = io::stdout().lock();
+    serde_json::to_writer_pretty(&mut out, &engine.to_json())?;
+    writeln!(out)?;
+    Ok(())
+}
+
+/// Width for non-TTY rendering: `--width`, else `$COLUMNS` unless `--plain` asked for raw output
+fn render_width(args: &Args) -> Option<u16> {
+    args.width.filter(|&w| w > 0).or_else(|| {
+        if args.plain {
+            return None;
+        }
+        std::env::var("COLUMNS").ok()?.trim().parse::<u16>().ok().filter(|&w| w > 0)
+    })
+}
+
+/// Wrap and tab width for text and code views (`--wrap`/`--no-wrap`, `--tabs`)
+fn text_layout(args: &Args) -> engines::TextLayout {
+    engines::TextLayout { wrap: args.wrap && !args.no_wrap, tab_width: args.tabs.max(1) }
+}
+
+/// The paging mode after shortcuts and environment: `-P`, `$PAGER=cat` (when
+/// `--paging` is left on auto), and a stdin that isn't a terminal all print
+/// instead of paging, unless `--force-tui` asks for the pager anyway.
+/// `reads_stdin` is set when stdin carries the file, so it was never the keyboard.
+fn paging_mode(args: &Args, reads_stdin: bool) -> app::Paging {
+    if args.force_tui {
+        return app::Paging::Always;
+    }
+    let pager_is_cat = std::env::var("PAGER").is_ok_and(|pager| pager.trim() == "cat");
+    let interactive = reads_stdin || io::stdin().is_terminal();
+    match args.paging {
+        _ if args.no_paging || !interactive => app::Paging::Never,
+        Paging::Auto if pager_is_cat => app::Paging::Never,
+        ref paging => paging.clone().into(),
+    }
+}
+
+/// An empty engine to stream stdin into, when the `-l` hint names a line-based
+/// format and the view is interactive; anything else reads all of stdin first
+fn streaming_engine(args: &Args) -> Option<engines::EngineState> {
+    let interactive = io::stdout().is_terminal()
+        && !args.plain
+        && !args.dump
+        && args.output.is_none()
+        && !matches!(paging_mode(args, true), app::Paging::Never);
+    if !interactive || io::stdin().is_terminal() {
+        return None;
+    }
+    let path = Path::new("<stdin>");
+    match args.language.as_deref()? {
+        "log" => engines::LogEngine::from_bytes(path, b"").ok().map(engines::EngineState::new),
+        "txt" | "text" => engines::TextEngine::from_bytes(path, b"").ok().map(engines::EngineState::new),
+        _ => None,
+    }
+}
+
+/// Read stdin line by line on a background thread; the channel closes at end of input
+fn stream_stdin() -> mpsc::Receiver<Vec<u8>> {
+    let (tx, rx) = mpsc::channel();
+    std::thread::spawn(move || {
+        let mut stdin = io::stdin().lock();
+        loop {
+            let mut line = Vec::new();
+            let read = stdin.read_until(b'\n', &mut line);
+            if !matches!(read, Ok(n) if n > 0) || tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    rx
 }
 
 /// Read stdin to a temporary file, return path and handle (to keep file alive)
@@ -75,6 +448,36 @@ fn read_stdin_to_temp(language: &Option<String>) -> Result<(PathBuf, Option<temp
     Ok((path, Some(temp)))
 }
 
+/// Unified diff of two files
+fn diff_files(old: &Path, new: &Path) -> Result<String> {
+    let old_text = read_for_diff(old)?;
+    let new_text = read_for_diff(new)?;
+    Ok(engines::unified_diff(
+        &old.display().to_string(),
+        &old_text,
+        &new.display().to_string(),
+        &new_text,
+    ))
+}
+
+/// Read a file for diffing; structured formats are normalized to pretty JSON
+/// with sorted keys so that formatting and key order do not show up as changes
+fn read_for_diff(path: &Path) -> Result<String> {
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| anyhow!("{}: {}", path.display(), e))?;
+    let ext = path.extension().and_then(|s| s.to_str()).unwrap_or("").to_lowercase();
+    let value: Option<serde_json::Value> = match ext.as_str() {
+        "json" => serde_json::from_str(&content).ok(),
+        "yaml" | "yml" => serde_yaml::from_str(&content).ok(),
+        "toml" => toml::from_str(&content).ok(),
+        _ => None,
+    };
+    match value.and_then(|v| serde_json::to_string_pretty(&v).ok()) {
+        Some(pretty) => Ok(pretty + "\n"),
+        None => Ok(content),
+    }
+}
+
 /// Try to detect format from content
 fn detect_format(content: &[u8]) -> String {
     let text = String::from_utf8_lossy(content);
@@ -136,3 +539,13 @@ impl From<Paging> for app::Paging {
         }
     }
 }
+
+impl From<Keys> for app::KeyPreset {
+    fn from(value: Keys) -> Self {
+        match value {
+            Keys::Vim => app::KeyPreset::Vim,
+            Keys::Less => app::KeyPreset::Less,
+            Keys::Emacs => app::KeyPreset::Emacs,
+        }
+    }
+}
@@ -0,0 +1,161 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Result};
+use fs2::FileExt;
+use serde::{Deserialize, Serialize};
+
+/// Most files kept in the recent-files list; folds are kept only for these files
+const RECENT_LIMIT: usize = 50;
+
+/// What vat remembers about files between runs, kept in `<state dir>/vat/state.json`
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct State {
+    /// Canonical file path → folded node paths in its tree or XML view, for
+    /// files on the recent list
+    folds: HashMap<String, Vec<String>>,
+    /// Canonical paths of files viewed lately, most recent first
+    recent: Vec<String>,
+}
+
+impl State {
+    /// Load the state file to read from; a missing or unreadable file starts
+    /// fresh, since losing remembered folds is better than refusing to open the file
+    pub fn load() -> Self {
+        Self::path().and_then(|path| Self::read(&path).ok()).unwrap_or_default()
+    }
+
+    /// Load, `change` and save the state file while holding a lock beside it, so
+    /// vat sessions closing at once keep each other's entries. A file that
+    /// exists but doesn't parse is an error and is left as it is.
+    pub fn update(change: impl FnOnce(&mut State)) -> Result<()> {
+        match Self::path() {
+            Some(path) => Self::update_at(&path, change),
+            None => Ok(()),
+        }
+    }
+
+    fn update_at(path: &Path, change: impl FnOnce(&mut State)) -> Result<()> {
+        let dir = path.parent().unwrap_or(Path::new("."));
+        std::fs::create_dir_all(dir)?;
+        let lock = std::fs::File::create(path.with_extension("lock"))?;
+        lock.lock_exclusive()?;
+        let mut state = Self::read(path)?;
+        change(&mut state);
+        let State { folds, recent } = &mut state;
+        folds.retain(|file, _| recent.contains(file));
+        // Readers never see a half-written file: write beside it, then rename over it
+        let mut temp = tempfile::NamedTempFile::new_in(dir)?;
+        serde_json::to_writer(&mut temp, &state)?;
+        temp.persist(path)?;
+        Ok(())
+    }
+
+    /// Read a state file; a missing one is empty
+    fn read(path: &Path) -> Result<Self> {
+        match std::fs::read(path) {
+            Ok(raw) => serde_json::from_slice(&raw).map_err(|e| anyhow!("{}: {}", path.display(), e)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(anyhow!("{}: {}", path.display(), e)),
+        }
+    }
+
+    pub fn path() -> Option<PathBuf> {
+        dirs::state_dir()
+            .or_else(dirs::data_local_dir)
+            .map(|dir| dir.join("vat").join("state.json"))
+    }
+
+    pub fn folds(&self, file: &Path) -> Option<&[String]> {
+        self.folds.get(&Self::key(file)?).map(Vec::as_slice)
+    }
+
+    /// Remember `folds` for `file`, which must be on the recent list to be
+    /// saved; an empty list forgets the file
+    pub fn set_folds(&mut self, file: &Path, folds: Vec<String>) {
+        let Some(key) = Self::key(file) else {
+            return;
+        };
+        if folds.is_empty() {
+            self.folds.remove(&key);
+        } else {
+            self.folds.insert(key, folds);
+        }
+    }
+
+    /// Files viewed lately that still exist, most recent first
+    pub fn recent(&self) -> Vec<PathBuf> {
+        self.recent.iter().map(PathBuf::from).filter(|file| file.exists()).collect()
+    }
+
+    /// Move `file` to the front of the recent list, dropping the oldest past the limit
+    pub fn add_recent(&mut self, file: &Path) {
+        let Some(key) = Self::key(file) else {
+            return;
+        };
+        self.recent.retain(|recent| *recent != key);
+        self.recent.insert(0, key);
+        self.recent.truncate(RECENT_LIMIT);
+    }
+
+    fn key(file: &Path) -> Option<String> {
+        Some(file.canonicalize().ok()?.to_string_lossy().into_owned())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files(dir: &Path, count: usize) -> Vec<PathBuf> {
+        (0..count)
+            .map(|idx| {
+                let file = dir.join(format!("{}.json", idx));
+                std::fs::write(&file, "{}").unwrap();
+                file
+            })
+            .collect()
+    }
+
+    #[test]
+    fn add_recent_moves_files_to_the_front_and_drops_the_oldest() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = files(dir.path(), RECENT_LIMIT + 2);
+        let mut state = State::default();
+        for file in &files {
+            state.add_recent(file);
+        }
+        state.add_recent(&files[1]);
+        let recent = state.recent();
+        assert_eq!(recent.len(), RECENT_LIMIT);
+        assert_eq!(recent[0], files[1].canonicalize().unwrap());
+        assert_eq!(recent[1], files[RECENT_LIMIT + 1].canonicalize().unwrap());
+        // files[0] was the oldest left once files[1] moved up
+        assert!(!recent.contains(&files[0].canonicalize().unwrap()));
+    }
+
+    #[test]
+    fn update_keeps_folds_only_for_recent_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let files = files(dir.path(), RECENT_LIMIT + 1);
+        let path = dir.path().join("state.json");
+        State::update_at(&path, |state| {
+            state.add_recent(&files[0]);
+            state.set_folds(&files[0], vec!["a".into()]);
+        })
+        .unwrap();
+        assert_eq!(State::read(&path).unwrap().folds(&files[0]), Some(&["a".to_string()][..]));
+        State::update_at(&path, |state| files[1..].iter().for_each(|file| state.add_recent(file))).unwrap();
+        assert_eq!(State::read(&path).unwrap().folds(&files[0]), None);
+    }
+
+    #[test]
+    fn update_leaves_an_unparseable_file_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("state.json");
+        std::fs::write(&path, "{ not json").unwrap();
+        assert!(State::update_at(&path, |state| state.add_recent(&path)).is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{ not json");
+    }
+}